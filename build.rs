@@ -228,6 +228,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "src/shaders/line.wesl",
         "src/shaders/point.wesl",
         "src/shaders/orbital_paths.wesl",
+        "src/shaders/rings.wesl",
+        "src/shaders/comet_tail.wesl",
     ];
 
     // Process each shader
@@ -10,8 +10,14 @@ use winit::{
 };
 
 use crate::{
-    AstrariaResult, assets::AssetManager, input::InputHandler, physics::PhysicsSimulation,
-    renderer::Renderer, scenario::BodyType, ui::UserInterface,
+    AstrariaResult,
+    assets::AssetManager,
+    input::InputHandler,
+    math::{Body, LAGRANGE_POINT_NAMES, LagrangePoints},
+    physics::PhysicsSimulation,
+    renderer::Renderer,
+    scenario::{BodyType, mpc::MpcImporter},
+    ui::UserInterface,
 };
 
 #[cfg(feature = "web")]
@@ -35,6 +41,19 @@ pub struct AstrariaApp {
     last_frame_time: f64,
     scenario_file: String,
     focus_body_index: usize,
+    barycentric_override: Option<bool>,
+    /// TLE file to load as a satellite layer once the renderer exists
+    tle_file: Option<String>,
+    /// Refuse scenarios that fail validation instead of skipping bad bodies
+    strict_validation: bool,
+    /// Loaded instead of `scenario_file` when set
+    generated_scenario: Option<crate::scenario::Scenario>,
+    /// MPC element file contents appended around the Sun of every scenario read at startup
+    /// or on reload
+    mpc_orbits: Option<(MpcImporter, String)>,
+    /// Watches the scenario file, its includes and textures for edits
+    #[cfg(feature = "native")]
+    scenario_watcher: Option<crate::scenario::watch::ScenarioWatcher>,
     #[cfg(feature = "web")]
     initialized: bool,
 }
@@ -63,6 +82,12 @@ impl AstrariaApp {
             last_frame_time: std::time::Instant::now(),
             scenario_file,
             focus_body_index,
+            barycentric_override: None,
+            tle_file: None,
+            strict_validation: false,
+            generated_scenario: None,
+            mpc_orbits: None,
+            scenario_watcher: None,
         })
     }
 
@@ -81,10 +106,45 @@ impl AstrariaApp {
             last_frame_time: 0.0,
             scenario_file,
             focus_body_index,
+            barycentric_override: None,
+            tle_file: None,
+            strict_validation: false,
+            generated_scenario: None,
+            mpc_orbits: None,
             initialized: false,
         })
     }
 
+    /// Force barycentric normalization on or off, overriding the scenario file
+    pub fn with_barycentric_override(mut self, barycentric: Option<bool>) -> Self {
+        self.barycentric_override = barycentric;
+        self
+    }
+
+    /// Refuse to load a scenario with any validation error
+    pub fn with_strict_validation(mut self, strict: bool) -> Self {
+        self.strict_validation = strict;
+        self
+    }
+
+    /// Start with a generated scenario instead of reading the scenario file
+    pub fn with_generated_scenario(mut self, scenario: Option<crate::scenario::Scenario>) -> Self {
+        self.generated_scenario = scenario;
+        self
+    }
+
+    /// Add the small bodies of an MPC element file to the scenario, at its epoch
+    pub fn with_mpc_orbits(mut self, orbits: Option<(MpcImporter, String)>) -> Self {
+        self.mpc_orbits = orbits;
+        self
+    }
+
+    /// Load two-line element sets as a satellite layer around the Earth
+    pub fn with_satellites(mut self, tle_file: Option<String>) -> Self {
+        self.tle_file = tle_file;
+        self
+    }
+
     #[cfg(feature = "native")]
     pub fn run(mut self) -> Result<()> {
         let event_loop = EventLoop::new()?;
@@ -216,7 +276,9 @@ impl AstrariaApp {
         }
 
         // Initialize physics simulation
-        self.physics = Some(PhysicsSimulation::new());
+        let mut physics = PhysicsSimulation::new();
+        physics.set_barycentric_override(self.barycentric_override);
+        self.physics = Some(physics);
 
         // Initialize input handler
         self.input_handler = Some(InputHandler::new());
@@ -288,7 +350,9 @@ impl AstrariaApp {
         }
 
         // Initialize physics simulation
-        self.physics = Some(PhysicsSimulation::new());
+        let mut physics = PhysicsSimulation::new();
+        physics.set_barycentric_override(self.barycentric_override);
+        self.physics = Some(physics);
 
         // Initialize input handler
         self.input_handler = Some(InputHandler::new());
@@ -303,79 +367,326 @@ impl AstrariaApp {
         // Position camera to focus on the specified body
         self.position_camera_on_focus_body().await?;
 
+        // Satellites are optional: a bad TLE file should not stop the app
+        #[cfg(not(target_arch = "wasm32"))]
+        if let (Some(tle_file), Some(renderer)) = (&self.tle_file, &mut self.renderer) {
+            match crate::satellites::SatelliteLayer::load(tle_file) {
+                Ok(layer) => renderer.set_satellites(Some(layer)),
+                Err(e) => log::warn!("Failed to load satellites: {}", e),
+            }
+        }
+
         log::info!("Astraria initialization complete!");
         Ok(())
     }
 
     async fn load_default_scenario(&mut self) -> AstrariaResult<()> {
+        if let Some(mut scenario) = self.generated_scenario.take() {
+            log::info!(
+                "App: Loading generated scenario with {} bodies",
+                scenario.bodies.len()
+            );
+            Self::add_mpc_orbits(self.mpc_orbits.as_ref(), &mut scenario);
+            return self.load_parsed_scenario(scenario).await;
+        }
+
         // Try to load the specified scenario file
         log::info!(
             "App: Attempting to load scenario file: {}",
             self.scenario_file
         );
-        if let Some(asset_manager) = &mut self.asset_manager {
-            if let Ok(scenario_data) = asset_manager.load_scenario(&self.scenario_file).await {
-                log::info!("App: Successfully loaded scenario data, parsing...");
+        #[cfg(feature = "native")]
+        {
+            self.scenario_watcher = Some(crate::scenario::watch::ScenarioWatcher::new(
+                &self.scenario_file,
+            ));
+        }
+        let Some(asset_manager) = &mut self.asset_manager else {
+            log::error!("App: Asset manager not initialized when loading scenario");
+            return Ok(());
+        };
+        let Ok(mut scenario) = asset_manager.load_scenario(&self.scenario_file).await else {
+            log::warn!(
+                "App: Could not load scenario '{}', starting with empty simulation",
+                self.scenario_file
+            );
+            return Ok(());
+        };
+        log::info!("App: Parsed scenario with {} bodies", scenario.bodies.len());
+
+        // Strict loading checks each file as written, so bad bodies in includes are caught too
+        if self.strict_validation {
+            let (diagnostics, errors) =
+                Self::validate_scenario(asset_manager, &self.scenario_file).await?;
+            for diagnostic in &diagnostics {
+                log::warn!("Scenario {}", diagnostic);
+            }
+            if errors > 0 {
+                return Err(crate::AstrariaError::ParseError(format!(
+                    "Scenario failed validation:\n{}\n{} error(s)",
+                    diagnostics.join("\n"),
+                    errors
+                )));
+            }
+        }
+        Self::add_mpc_orbits(self.mpc_orbits.as_ref(), &mut scenario);
+        self.load_parsed_scenario(scenario).await?;
+        log::info!("App: Physics loaded scenario: {}", self.scenario_file);
+        Ok(())
+    }
 
-                // Parse the scenario
-                let scenario = crate::scenario::ScenarioParser::parse(&scenario_data)?;
-                log::info!("App: Parsed scenario with {} bodies", scenario.bodies.len());
+    /// Append the `--mpc` small bodies around the scenario's Sun, placed at its epoch
+    fn add_mpc_orbits(
+        mpc_orbits: Option<&(MpcImporter, String)>,
+        scenario: &mut crate::scenario::Scenario,
+    ) {
+        let Some((importer, content)) = mpc_orbits else {
+            return;
+        };
+        let importer = match scenario.metadata.epoch {
+            Some(epoch) => importer.clone().with_epoch(epoch),
+            None => importer.clone(),
+        };
+        match importer.add_to(scenario, content) {
+            Ok(count) => log::info!("App: Added {} MPC orbits to the scenario", count),
+            Err(e) => log::warn!("App: Could not add MPC orbits: {}", e),
+        }
+    }
 
-                // Load textures for all bodies in the scenario
-                if let Some(renderer) = &mut self.renderer {
-                    log::info!("App: Loading scenario textures...");
-                    let (textures_before, models_before, cubemaps_before) =
-                        asset_manager.cache_stats();
-                    log::info!(
-                        "App: AssetManager cache before scenario textures: textures={}, models={}, cubemaps={}",
-                        textures_before,
-                        models_before,
-                        cubemaps_before
-                    );
+    /// Load the textures of a scenario's bodies and hand it to the physics simulation
+    async fn load_parsed_scenario(
+        &mut self,
+        scenario: crate::scenario::Scenario,
+    ) -> AstrariaResult<()> {
+        // Load textures for all bodies in the scenario
+        if let (Some(asset_manager), Some(renderer)) = (&mut self.asset_manager, &mut self.renderer)
+        {
+            renderer.set_scenario_epoch(scenario.metadata.epoch);
+            log::info!("App: Loading scenario textures...");
+            let (textures_before, models_before, cubemaps_before) = asset_manager.cache_stats();
+            log::info!(
+                "App: AssetManager cache before scenario textures: textures={}, models={}, cubemaps={}",
+                textures_before,
+                models_before,
+                cubemaps_before
+            );
+
+            renderer
+                .main_renderer()
+                .load_scenario_textures(asset_manager, &scenario)
+                .await?;
 
-                    renderer
-                        .main_renderer()
-                        .load_scenario_textures(asset_manager, &scenario)
-                        .await?;
+            let (textures_after, models_after, cubemaps_after) = asset_manager.cache_stats();
+            log::info!(
+                "App: AssetManager cache after scenario textures: textures={}, models={}, cubemaps={}",
+                textures_after,
+                models_after,
+                cubemaps_after
+            );
+            log::info!(
+                "App: Scenario textures loaded successfully - added {} textures",
+                textures_after - textures_before
+            );
+        } else {
+            log::error!("App: Renderer not initialized when loading scenario textures");
+        }
 
-                    let (textures_after, models_after, cubemaps_after) =
-                        asset_manager.cache_stats();
-                    log::info!(
-                        "App: AssetManager cache after scenario textures: textures={}, models={}, cubemaps={}",
-                        textures_after,
-                        models_after,
-                        cubemaps_after
-                    );
-                    log::info!(
-                        "App: Scenario textures loaded successfully - added {} textures",
-                        textures_after - textures_before
-                    );
-                } else {
-                    log::error!("App: Renderer not initialized when loading scenario textures");
-                }
+        if let Some(physics) = &mut self.physics {
+            physics.load_parsed_scenario(scenario)?;
+        } else {
+            log::error!("App: Physics system not initialized when loading scenario");
+        }
+        Ok(())
+    }
 
-                // Pass the raw scenario data to physics (it will re-parse, but that's OK for now)
-                if let Some(physics) = &mut self.physics {
-                    physics.load_scenario(scenario_data)?;
-                    log::info!("App: Physics loaded scenario: {}", self.scenario_file);
-                } else {
-                    log::error!("App: Physics system not initialized when loading scenario");
-                }
+    /// Pick up edits to the scenario file, the files it includes and its textures.
+    /// Diagnostics go to the UI; a scenario that fails to load leaves the running one in place.
+    #[cfg(feature = "native")]
+    fn reload_scenario(
+        &mut self,
+        changes: crate::scenario::watch::WatchChanges,
+    ) -> AstrariaResult<()> {
+        log::info!("App: Scenario files changed on disk: {:?}", changes);
+        let (Some(asset_manager), Some(renderer), Some(watcher)) = (
+            &mut self.asset_manager,
+            &mut self.renderer,
+            &mut self.scenario_watcher,
+        ) else {
+            return Ok(());
+        };
+        for texture in &changes.textures {
+            asset_manager.forget_texture(texture);
+            renderer
+                .main_renderer()
+                .asset_manager
+                .forget_texture(texture);
+        }
+        if changes.scenario {
+            watcher.refresh();
+        }
+
+        // Report problems per file, as `--check` does
+        let (diagnostics, errors) =
+            pollster::block_on(Self::validate_scenario(asset_manager, &self.scenario_file))
+                .unwrap_or_default();
+
+        let loaded = if self.strict_validation && errors > 0 {
+            Err(crate::AstrariaError::ParseError(format!(
+                "{} validation error(s)",
+                errors
+            )))
+        } else {
+            pollster::block_on(asset_manager.load_scenario(&self.scenario_file))
+        }
+        .and_then(|scenario| {
+            // A file saved mid-edit may have no bodies yet; keep what is running
+            if scenario.bodies.is_empty() {
+                Err(crate::AstrariaError::ParseError(
+                    "scenario has no bodies".to_string(),
+                ))
             } else {
-                log::warn!(
-                    "App: Could not load scenario '{}', starting with empty simulation",
-                    self.scenario_file
-                );
+                Ok(scenario)
+            }
+        });
+        let mut scenario = match loaded {
+            Ok(scenario) => scenario,
+            Err(e) => {
+                log::warn!("App: Keeping the running scenario, reload failed: {}", e);
+                if let Some(ui) = &mut self.ui {
+                    ui.report_scenario_reload(
+                        format!("Reload of {} failed: {}", self.scenario_file, e),
+                        diagnostics,
+                        true,
+                    );
+                }
+                return Ok(());
             }
+        };
+
+        Self::add_mpc_orbits(self.mpc_orbits.as_ref(), &mut scenario);
+        let body_count = scenario.bodies.len();
+        if changes.scenario {
+            self.swap_scenario(scenario)?;
         } else {
-            log::error!("App: Asset manager not initialized when loading scenario");
+            // Only textures changed, so the simulation carries on
+            pollster::block_on(
+                renderer
+                    .main_renderer()
+                    .load_scenario_textures(asset_manager, &scenario),
+            )?;
+        }
+        if let Some(ui) = &mut self.ui {
+            ui.report_scenario_reload(
+                format!("Reloaded {} with {} bodies", self.scenario_file, body_count),
+                diagnostics,
+                false,
+            );
+        }
+        Ok(())
+    }
+
+    /// Replace the running bodies with a reloaded scenario. The focused body and viewing frame
+    /// are found again by name, and the camera keeps its pose relative to the focused body.
+    #[cfg(feature = "native")]
+    fn swap_scenario(&mut self, scenario: crate::scenario::Scenario) -> AstrariaResult<()> {
+        let Some(physics) = &self.physics else {
+            return Ok(());
+        };
+        let old_bodies = physics.get_view_bodies()?;
+        let old_frame = physics.get_reference_frame()?;
+        let focused = self
+            .ui
+            .as_ref()
+            .and_then(|ui| ui.selected_object())
+            .and_then(|index| old_bodies.get(index))
+            .map(|body| (body.name.clone(), body.position));
+
+        pollster::block_on(self.load_parsed_scenario(scenario))?;
+
+        let Some(physics) = &self.physics else {
+            return Ok(());
+        };
+        let new_bodies = physics.get_bodies()?;
+        let index_of = |name: &str| new_bodies.iter().position(|body| body.name == name);
+        let frame = old_frame
+            .remap(|index| old_bodies.get(index).and_then(|body| index_of(&body.name)))
+            .unwrap_or_default();
+        physics.set_reference_frame(frame)?;
+
+        let focus_index = focused.as_ref().and_then(|(name, _)| index_of(name));
+        if let Some(ui) = &mut self.ui {
+            ui.set_reference_frame(frame);
+            ui.select_object(focus_index);
+        }
+        if let (Some((_, old_position)), Some(index), Some(renderer)) =
+            (focused, focus_index, &mut self.renderer)
+        {
+            let new_position = physics.get_view_bodies()?[index].position;
+            let camera_position = renderer.camera().position();
+            renderer.set_camera_position(camera_position + new_position - old_position);
+        }
+        Ok(())
+    }
+
+    /// Replace the running simulation with a generated scenario and show its first bookmark.
+    /// On the web textures cannot be awaited here, so bodies use the default texture until
+    /// theirs is cached.
+    fn load_generated_scenario(
+        &mut self,
+        scenario: crate::scenario::Scenario,
+    ) -> AstrariaResult<()> {
+        log::info!(
+            "App: Loading generated scenario '{}'",
+            scenario.metadata.name.as_deref().unwrap_or("unnamed")
+        );
+        let has_bookmarks = !scenario.metadata.cameras.is_empty();
+
+        #[cfg(feature = "native")]
+        pollster::block_on(self.load_parsed_scenario(scenario))?;
+        #[cfg(feature = "web")]
+        if let Some(physics) = &mut self.physics {
+            physics.load_parsed_scenario(scenario)?;
+        }
+
+        if has_bookmarks {
+            self.apply_camera_bookmark(0)?;
         }
         Ok(())
     }
 
+    /// Validate the scenario file and each file it includes. Returns every diagnostic,
+    /// prefixed with the file it was found in, and the number of errors among them.
+    async fn validate_scenario(
+        asset_manager: &AssetManager,
+        path: &str,
+    ) -> AstrariaResult<(Vec<String>, usize)> {
+        let reports = asset_manager
+            .validate_scenario(path, &Self::scenario_validator())
+            .await?;
+        let errors = reports.iter().map(|(_, report)| report.error_count()).sum();
+        let diagnostics = reports
+            .iter()
+            .flat_map(|(file, report)| {
+                report
+                    .diagnostics
+                    .iter()
+                    .map(move |diagnostic| format!("{}: {}", file, diagnostic))
+            })
+            .collect();
+        Ok((diagnostics, errors))
+    }
+
+    /// Validator for strict loading; textures can only be checked on disk natively
+    fn scenario_validator() -> crate::scenario::validation::ScenarioValidator {
+        let validator = crate::scenario::validation::ScenarioValidator::new();
+        #[cfg(feature = "native")]
+        let validator = validator.with_texture_check(AssetManager::texture_exists);
+        validator
+    }
+
     async fn position_camera_on_focus_body(&mut self) -> AstrariaResult<()> {
         if let (Some(physics), Some(renderer)) = (&self.physics, &mut self.renderer) {
-            let bodies = physics.get_bodies()?;
+            let bodies = physics.get_view_bodies()?;
             if let Some(focus_body) = bodies.get(self.focus_body_index) {
                 log::info!(
                     "Positioning camera to focus on body '{}' at index {}",
@@ -389,6 +700,8 @@ impl AstrariaApp {
                     BodyType::Star { radius, .. } => radius,
                     BodyType::PlanetAtmo { radius, .. } => radius,
                     BodyType::BlackHole { radius } => radius,
+                    BodyType::Spacecraft { radius, .. } => radius,
+                    BodyType::Comet { radius, .. } => radius,
                 };
 
                 // Position camera at 3x radius distance for good view
@@ -422,6 +735,16 @@ impl AstrariaApp {
             physics.update(delta_time)?;
         }
 
+        // Reload the scenario when it is edited on disk
+        #[cfg(feature = "native")]
+        if let Some(changes) = self
+            .scenario_watcher
+            .as_mut()
+            .and_then(|watcher| watcher.poll())
+        {
+            self.reload_scenario(changes)?;
+        }
+
         // Update input handler
         if let Some(input_handler) = &mut self.input_handler {
             input_handler.update(delta_time);
@@ -481,11 +804,139 @@ impl AstrariaApp {
                 // Camera focus is cleared - user can now move freely
                 // No specific action needed as the camera will respond to user input
             }
+            UiAction::SetReferenceFrame(frame) => {
+                log::info!("Switching reference frame to {:?}", frame);
+
+                if let Some(physics) = &self.physics {
+                    physics.set_reference_frame(frame)?;
+
+                    // Camera coordinates are frame-relative, so re-aim at the frame's anchor
+                    let bodies = physics.get_view_bodies()?;
+                    let anchor = frame.anchor_body().unwrap_or(self.focus_body_index);
+                    if let (Some(body), Some(renderer)) = (bodies.get(anchor), &mut self.renderer) {
+                        let radius = match &body.body_type {
+                            BodyType::Planet { radius, .. } => *radius,
+                            BodyType::Star { radius, .. } => *radius,
+                            BodyType::PlanetAtmo { radius, .. } => *radius,
+                            BodyType::BlackHole { radius } => *radius,
+                            BodyType::Spacecraft { radius, .. } => *radius,
+                            BodyType::Comet { radius, .. } => *radius,
+                        };
+                        let camera_distance = (radius as f64 * 3.0).max(1000.0);
+                        renderer.set_camera_look_at(body.position, camera_distance);
+                    }
+                }
+            }
+            UiAction::SetManeuvers {
+                body_index,
+                maneuvers,
+            } => {
+                if let Some(physics) = &self.physics {
+                    physics.set_maneuvers(body_index, maneuvers)?;
+                }
+            }
+            UiAction::LoadGeneratedScenario(scenario) => {
+                self.load_generated_scenario(*scenario)?;
+            }
+            UiAction::SpawnBody(body) => {
+                if let Some(physics) = &self.physics {
+                    log::info!("Spawning '{}'", body.name);
+                    physics.spawn_body(*body)?;
+                }
+            }
+            UiAction::SpawnAtLagrangePoint {
+                primary,
+                secondary,
+                point,
+            } => {
+                if let Some(physics) = &self.physics {
+                    let bodies = physics.get_bodies()?;
+                    let Some(points) = LagrangePoints::for_pair(&bodies, primary, secondary)
+                    else {
+                        log::warn!(
+                            "Cannot compute Lagrange points for bodies {} and {}",
+                            primary,
+                            secondary
+                        );
+                        return Ok(());
+                    };
+
+                    // Spacecraft: light enough not to disturb the pair
+                    let name = format!("{} {}", bodies[secondary].name, LAGRANGE_POINT_NAMES[point]);
+                    let body = Body::new_with_properties(
+                        1.0e3,
+                        points.positions[point],
+                        points.velocity(point),
+                        name.clone(),
+                        BodyType::Spacecraft {
+                            radius: 10.0,
+                            maneuvers: Vec::new(),
+                        },
+                        [0.9, 0.9, 0.3, 1.0],
+                        (0.0, 0.0, 0.0, 0.0),
+                    );
+
+                    log::info!(
+                        "Spawning '{}' at ({:.2e}, {:.2e}, {:.2e})",
+                        name,
+                        points.positions[point].x,
+                        points.positions[point].y,
+                        points.positions[point].z
+                    );
+                    physics.spawn_body(body)?;
+                }
+            }
+            UiAction::ApplyCameraBookmark(index) => self.apply_camera_bookmark(index)?,
         }
 
         Ok(())
     }
 
+    /// Move the camera to a bookmark of the current scenario, resolving its focus body by name
+    fn apply_camera_bookmark(&mut self, index: usize) -> AstrariaResult<()> {
+        let (Some(physics), Some(renderer)) = (&self.physics, &mut self.renderer) else {
+            return Ok(());
+        };
+        let Some(bookmark) = physics.scenario_metadata().cameras.get(index) else {
+            log::warn!("No camera bookmark {}", index);
+            return Ok(());
+        };
+
+        let bodies = physics.get_view_bodies()?;
+        let (target, radius) = match &bookmark.focus {
+            Some(name) => {
+                let Some(body) = bodies.iter().find(|body| &body.name == name) else {
+                    log::warn!(
+                        "Camera bookmark '{}' looks at unknown body '{}'",
+                        bookmark.name,
+                        name
+                    );
+                    return Ok(());
+                };
+                let radius = match &body.body_type {
+                    BodyType::Planet { radius, .. } => *radius,
+                    BodyType::Star { radius, .. } => *radius,
+                    BodyType::PlanetAtmo { radius, .. } => *radius,
+                    BodyType::BlackHole { radius } => *radius,
+                    BodyType::Spacecraft { radius, .. } => *radius,
+                    BodyType::Comet { radius, .. } => *radius,
+                };
+                (body.position, radius as f64)
+            }
+            None => (glam::DVec3::ZERO, 0.0),
+        };
+
+        log::info!("Applying camera bookmark '{}'", bookmark.name);
+        match bookmark.offset {
+            Some(offset) => renderer.set_camera_look_from(target + offset, target),
+            None => {
+                let distance = bookmark.distance.unwrap_or(radius * 3.0).max(1000.0);
+                renderer.set_camera_look_at(target, distance);
+            }
+        }
+        Ok(())
+    }
+
     fn render(&mut self) -> AstrariaResult<()> {
         if let (Some(renderer), Some(physics), Some(asset_manager), Some(ui), Some(window)) = (
             &mut self.renderer,
@@ -757,3 +1208,43 @@ impl Drop for AstrariaApp {
         // (Rust's drop semantics will handle the rest)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{scenario::generators::Generator, ui::UiAction};
+
+    #[test]
+    fn test_new_scenario_dialog_replaces_running_simulation() {
+        let mut app = AstrariaApp::new().unwrap();
+        app.physics = Some(PhysicsSimulation::new());
+
+        for (name, seed) in [("figure-eight", 1), ("planetary", 2)] {
+            let scenario = Generator::from_name(name).unwrap().generate(seed);
+            let body_count = scenario.bodies.len();
+            app.handle_ui_action(UiAction::LoadGeneratedScenario(Box::new(scenario)))
+                .unwrap();
+            let physics = app.physics.as_ref().unwrap();
+            assert_eq!(physics.get_bodies().unwrap().len(), body_count);
+        }
+        app.physics.as_mut().unwrap().shutdown();
+    }
+
+    #[test]
+    fn test_mpc_orbits_are_added_around_the_star() {
+        let ceres = "00001    3.34  0.15 K2555 188.70269   73.27343   80.25221   10.58780  0.0794013  0.21424651   2.7660512  0 E2024-V47  7330 125 1801-2024 0.80 M-v 30k MPCLINUX   0000 (1) Ceres                   20241101\n";
+        let scenario = Generator::from_name("planetary").unwrap().generate(3);
+        let body_count = scenario.bodies.len();
+        let mut app = AstrariaApp::new()
+            .unwrap()
+            .with_generated_scenario(Some(scenario))
+            .with_mpc_orbits(Some((MpcImporter::new(), ceres.to_string())));
+        app.physics = Some(PhysicsSimulation::new());
+
+        pollster::block_on(app.load_default_scenario()).unwrap();
+        let bodies = app.physics.as_ref().unwrap().get_bodies().unwrap();
+        assert_eq!(bodies.len(), body_count + 1);
+        assert_eq!(bodies[body_count].name, "Ceres");
+        app.physics.as_mut().unwrap().shutdown();
+    }
+}
@@ -5,7 +5,14 @@ use std::collections::HashMap;
 use std::sync::{Arc, Weak};
 use wgpu::{Buffer, Device, Queue, Texture, TextureView, util::DeviceExt};
 
-use crate::{AstrariaError, AstrariaResult};
+use crate::{
+    AstrariaError, AstrariaResult,
+    scenario::{
+        Scenario, ScenarioParser,
+        validation::{ScenarioValidator, ValidationReport},
+    },
+};
+use futures::future::LocalBoxFuture;
 
 #[cfg(feature = "native")]
 use crate::generated_shaders::common::VertexInput;
@@ -288,39 +295,121 @@ impl AssetManager {
         })
     }
 
-    #[cfg(feature = "native")]
-    pub async fn load_scenario(&self, path: &str) -> AstrariaResult<String> {
-        use std::fs;
+    /// Load and parse a scenario file with its `include:` directives resolved.
+    /// Include paths are relative to the including file.
+    pub async fn load_scenario(&self, path: &str) -> AstrariaResult<Scenario> {
+        let scenario = ScenarioParser::parse(&self.load_scenario_file(path).await?)?;
+        if scenario.includes.is_empty() {
+            return Ok(scenario);
+        }
+        self.resolve_includes(path, scenario, &mut vec![path.to_string()])
+            .await
+    }
 
-        // Try to load from assets/examples/ directory first
-        let full_path = format!("assets/examples/{}", path);
+    /// Validate a scenario file and every file it includes, each against its own lines.
+    /// Includes of a file that does not parse cannot be followed; its report says why.
+    pub async fn validate_scenario(
+        &self,
+        path: &str,
+        validator: &ScenarioValidator,
+    ) -> AstrariaResult<Vec<(String, ValidationReport)>> {
+        let mut reports: Vec<(String, ValidationReport)> = Vec::new();
+        let mut pending = vec![path.to_string()];
+        while let Some(file) = pending.pop() {
+            if reports.iter().any(|(known, _)| *known == file) {
+                continue;
+            }
+            let content = self.load_scenario_file(&file).await?;
+            if let Ok(scenario) = ScenarioParser::parse(&content) {
+                // Reversed so the files are reported in the order they are included
+                pending.extend(
+                    scenario
+                        .includes
+                        .iter()
+                        .rev()
+                        .map(|include| include.resolve_path(&file)),
+                );
+            }
+            reports.push((file, validator.validate(&content)));
+        }
+        Ok(reports)
+    }
+
+    /// Replace the include directives of `scenario` (read from `path`) with the included bodies.
+    /// `chain` holds the files being resolved, to catch files that include themselves.
+    fn resolve_includes<'a>(
+        &'a self,
+        path: &'a str,
+        mut scenario: Scenario,
+        chain: &'a mut Vec<String>,
+    ) -> LocalBoxFuture<'a, AstrariaResult<Scenario>> {
+        Box::pin(async move {
+            // Back to front, so the insertion points of earlier includes stay valid
+            for include in std::mem::take(&mut scenario.includes).into_iter().rev() {
+                let include_path = include.resolve_path(path);
+                if chain.contains(&include_path) {
+                    return Err(AstrariaError::ParseError(format!(
+                        "Scenario '{}' includes itself through '{}'",
+                        include_path, path
+                    )));
+                }
+                let content = self.load_scenario_file(&include_path).await?;
+                let included = ScenarioParser::parse(&content).map_err(|e| {
+                    AstrariaError::ParseError(format!(
+                        "In '{}' included from '{}': {}",
+                        include_path, path, e
+                    ))
+                })?;
+
+                chain.push(include_path.clone());
+                let included = self.resolve_includes(&include_path, included, chain).await;
+                chain.pop();
+                let included = included?;
+
+                log::info!(
+                    "Including {} bodies from '{}'",
+                    included.bodies.len(),
+                    include.path
+                );
+                scenario.insert_included(&include, included);
+            }
+            Ok(scenario)
+        })
+    }
 
-        match fs::read_to_string(&full_path) {
+    #[cfg(feature = "native")]
+    /// Read one scenario file as stored, without resolving its includes
+    pub async fn load_scenario_file(&self, path: &str) -> AstrariaResult<String> {
+        let full_path = Self::scenario_file_path(path);
+        match std::fs::read_to_string(&full_path) {
             Ok(content) => {
-                log::info!("Loaded scenario file: {}", full_path);
+                log::info!("Loaded scenario file: {}", full_path.display());
                 Ok(content)
             }
-            Err(_) => {
-                // Try direct path if not found in examples
-                match fs::read_to_string(path) {
-                    Ok(content) => {
-                        log::info!("Loaded scenario file: {}", path);
-                        Ok(content)
-                    }
-                    Err(e) => {
-                        log::error!("Failed to load scenario file '{}': {}", path, e);
-                        Err(crate::AstrariaError::AssetLoading(format!(
-                            "Failed to load scenario file '{}': {}",
-                            path, e
-                        )))
-                    }
-                }
+            Err(e) => {
+                log::error!("Failed to load scenario file '{}': {}", path, e);
+                Err(crate::AstrariaError::AssetLoading(format!(
+                    "Failed to load scenario file '{}': {}",
+                    path, e
+                )))
             }
         }
     }
 
+    /// File a scenario path refers to: in `assets/examples/` if it exists there, else as given
+    #[cfg(feature = "native")]
+    pub fn scenario_file_path(path: &str) -> std::path::PathBuf {
+        let in_examples = std::path::Path::new("assets/examples").join(path);
+        if in_examples.exists() {
+            in_examples
+        } else {
+            std::path::PathBuf::from(path)
+        }
+    }
+
     #[cfg(feature = "web")]
-    pub async fn load_scenario(&self, path: &str) -> AstrariaResult<String> {
+    /// Read one scenario file as stored, without resolving its includes
+    pub async fn load_scenario_file(&self, path: &str) -> AstrariaResult<String> {
         // On web, fetch from HTTP
         let full_path = format!("assets/examples/{}", path);
         Self::fetch_text(&full_path).await
@@ -624,6 +713,17 @@ impl AssetManager {
         self.textures.get(path).map(Arc::clone)
     }
 
+    /// Drop a cached texture so the next load reads it from disk again
+    #[cfg(feature = "native")]
+    pub fn forget_texture(&mut self, path: &str) {
+        for key in [path.to_string(), Self::resolve_texture_path(path)] {
+            if self.textures.remove(&key).is_some() {
+                log::debug!("Forgot cached texture: {}", key);
+            }
+            self.texture_handles.remove(&key);
+        }
+    }
+
     /// Clean up unused assets (remove assets with no active handles)
     pub fn cleanup_unused_assets(&mut self) {
         // Clean up unused textures
@@ -812,10 +912,16 @@ impl AssetManager {
         self.cubemaps.get(name).map(Arc::clone)
     }
 
+    /// Whether a scenario texture path resolves to a file on disk
+    #[cfg(feature = "native")]
+    pub fn texture_exists(path: &str) -> bool {
+        std::path::Path::new(&Self::resolve_texture_path(path)).exists()
+    }
+
     /// Resolve Java-style texture paths to actual file system paths
     /// Converts "./Planet Textures/filename.jpg" -> "assets/Planet Textures/filename.jpg"
     #[cfg(feature = "native")]
-    fn resolve_texture_path(path: &str) -> String {
+    pub(crate) fn resolve_texture_path(path: &str) -> String {
         if path.starts_with("./") {
             // Java-style relative path, convert to assets directory
             let resolved = format!("assets/{}", &path[2..]);
@@ -3,11 +3,11 @@
 use astraria_rust::{
     AstrariaError, AstrariaResult,
     renderer::{
-        MainRenderer,
+        MainRenderer, StarLight,
         core::{MeshType, RenderCommand, *},
     },
 };
-use glam::{DVec3, Mat4, Vec3, Vec4};
+use glam::{DVec3, Mat3, Mat4, Vec3, Vec4};
 use image::{ImageBuffer, Rgba};
 use std::fs;
 
@@ -93,8 +93,12 @@ async fn save_render(
                     ambient_texture_path: Some(
                         "assets/Planet Textures/earth.jpg".to_string(),
                     ),
-                    planet_position: DVec3::ZERO,
-                    sun_position: DVec3::new(1.0, 0.0, 0.0),
+                    lights: vec![StarLight {
+                        direction: Vec3::X,
+                        intensity: 1.0,
+                    }],
+                    rings: None,
+                    orientation: Mat3::IDENTITY,
                 };
                 renderer.begin_frame();
                 renderer.prepare_render_command(
@@ -0,0 +1,136 @@
+/// Headless stability analysis
+/// Exports MEGNO / Lyapunov histories of scenario bodies and renders stability maps
+use anyhow::{Context, Result, bail};
+use astraria_rust::{
+    assets::AssetManager,
+    scenario::Scenario,
+    stability::{DEFAULT_CHAOS_SAMPLE_INTERVAL, IndicatorRun, StabilityMap, StabilityMapSettings},
+};
+use std::{env, path::Path};
+
+const USAGE: &str = "\
+Usage:
+  stability indicators <scenario> <body>[,<body>...] --duration <s> --dt <s> [--interval <s>] [--output <file.csv>]
+  stability map <scenario> --a <min m> <max m> --e <min> <max> --duration <s> --dt <s>
+                [--host <body>] [--size <width>x<height>] [--output <file.png>]
+
+The map places a massless test planet at periapsis around the host (or the
+system barycentre if no host is given) and colours each cell by mean MEGNO.";
+
+fn main() -> Result<()> {
+    env_logger::init();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("indicators") => indicators(&args[1..]),
+        Some("map") => map(&args[1..]),
+        _ => bail!("{USAGE}"),
+    }
+}
+
+fn load_scenario(path: &str) -> Result<Scenario> {
+    Ok(pollster::block_on(async {
+        AssetManager::new().await?.load_scenario(path).await
+    })?)
+}
+
+/// Value(s) following `--name`, if present
+fn option<'a>(args: &'a [String], name: &str, count: usize) -> Result<Option<&'a [String]>> {
+    match args.iter().position(|arg| arg == name) {
+        Some(index) if index + count < args.len() => Ok(Some(&args[index + 1..=index + count])),
+        Some(_) => bail!("{name} expects {count} value(s)\n{USAGE}"),
+        None => Ok(None),
+    }
+}
+
+fn number(args: &[String], name: &str) -> Result<Option<f64>> {
+    option(args, name, 1)?
+        .map(|values| {
+            values[0]
+                .parse::<f64>()
+                .with_context(|| format!("invalid {name}"))
+        })
+        .transpose()
+}
+
+fn required(args: &[String], name: &str) -> Result<f64> {
+    number(args, name)?.with_context(|| format!("missing {name}\n{USAGE}"))
+}
+
+fn range(args: &[String], name: &str) -> Result<(f64, f64)> {
+    let values = option(args, name, 2)?.with_context(|| format!("missing {name}\n{USAGE}"))?;
+    Ok((
+        values[0]
+            .parse()
+            .with_context(|| format!("invalid {name}"))?,
+        values[1]
+            .parse()
+            .with_context(|| format!("invalid {name}"))?,
+    ))
+}
+
+fn indicators(args: &[String]) -> Result<()> {
+    let (Some(scenario_path), Some(bodies)) = (args.first(), args.get(1)) else {
+        bail!("{USAGE}");
+    };
+    let scenario = load_scenario(scenario_path)?;
+    let names: Vec<String> = bodies
+        .split(',')
+        .map(|name| name.trim().to_string())
+        .collect();
+    let duration = required(args, "--duration")?;
+    let time_step = required(args, "--dt")?;
+    let interval = number(args, "--interval")?.unwrap_or(DEFAULT_CHAOS_SAMPLE_INTERVAL);
+    let output = option(args, "--output", 1)?.map_or("chaos_indicators.csv", |values| &values[0]);
+
+    let run = IndicatorRun::compute(&scenario, &names, duration, time_step, interval)?;
+    run.write_csv(Path::new(output))?;
+
+    if let Some(last) = run.samples.last() {
+        for (name, sample) in run.names.iter().zip(last) {
+            println!(
+                "{name}: <Y> = {:.4}, lambda = {:.4e} /s",
+                sample.mean_megno, sample.lyapunov_exponent
+            );
+        }
+    }
+    println!("Wrote {} samples to {output}", run.samples.len());
+    Ok(())
+}
+
+fn map(args: &[String]) -> Result<()> {
+    let Some(scenario_path) = args.first() else {
+        bail!("{USAGE}");
+    };
+    let scenario = load_scenario(scenario_path)?;
+    let resolution = match option(args, "--size", 1)? {
+        Some(values) => {
+            let (width, height) = values[0]
+                .split_once('x')
+                .with_context(|| format!("invalid --size\n{USAGE}"))?;
+            (width.parse()?, height.parse()?)
+        }
+        None => (64, 64),
+    };
+    let settings = StabilityMapSettings {
+        host: option(args, "--host", 1)?.map(|values| values[0].clone()),
+        semi_major_axis: range(args, "--a")?,
+        eccentricity: range(args, "--e")?,
+        resolution,
+        duration: required(args, "--duration")?,
+        time_step: required(args, "--dt")?,
+    };
+    let output = option(args, "--output", 1)?.map_or("stability_map.png", |values| &values[0]);
+
+    let map = StabilityMap::compute(&scenario, &settings)?;
+    map.save_png(Path::new(output))?;
+
+    let chaotic = map.mean_megno.iter().filter(|value| **value > 4.0).count();
+    println!(
+        "Wrote {}x{} map to {output} ({chaotic} of {} cells with <Y> > 4)",
+        resolution.0,
+        resolution.1,
+        map.mean_megno.len()
+    );
+    Ok(())
+}
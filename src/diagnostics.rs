@@ -0,0 +1,298 @@
+/// Conservation diagnostics
+/// Samples total energy, linear momentum and angular momentum while the simulation runs
+/// and reports their drift relative to the values at load time
+use std::collections::VecDeque;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use glam::DVec3;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::AstrariaResult;
+use crate::math::BodyCollection;
+
+/// Default simulated seconds between samples
+pub const DEFAULT_SAMPLE_INTERVAL: f64 = 1.0;
+
+/// Number of drift samples kept for plotting
+const MAX_HISTORY: usize = 5000;
+
+/// Conserved quantities of the whole system at one instant
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConservationSample {
+    /// Simulation time in seconds
+    pub time: f64,
+    /// Total kinetic + potential energy (J)
+    pub energy: f64,
+    /// Total linear momentum (kg m/s)
+    pub momentum: DVec3,
+    /// Total angular momentum about the origin (kg m^2/s)
+    pub angular_momentum: DVec3,
+    /// Sum of individual momentum magnitudes, used to normalise momentum drift
+    pub momentum_scale: f64,
+    /// Sum of individual angular momentum magnitudes, used to normalise angular momentum drift
+    pub angular_momentum_scale: f64,
+}
+
+impl ConservationSample {
+    /// Measure the current state of a body collection
+    pub fn from_collection(collection: &BodyCollection, time: f64) -> Self {
+        let mut momentum_scale = 0.0;
+        let mut angular_momentum_scale = 0.0;
+        for body_ref in collection.bodies() {
+            if let Ok(body) = body_ref.read() {
+                momentum_scale += body.momentum().length();
+                angular_momentum_scale += body.position.cross(body.momentum()).length();
+            }
+        }
+
+        Self {
+            time,
+            energy: collection.total_energy(),
+            momentum: collection.total_momentum(),
+            angular_momentum: collection.total_angular_momentum(),
+            momentum_scale,
+            angular_momentum_scale,
+        }
+    }
+
+    /// Relative drift of this sample from a baseline
+    pub fn drift_from(&self, baseline: &ConservationSample) -> ConservationDrift {
+        let relative = |change: f64, scale: f64| if scale > 0.0 { change / scale } else { 0.0 };
+
+        ConservationDrift {
+            time: self.time,
+            energy: relative(self.energy - baseline.energy, baseline.energy.abs()),
+            momentum: relative(
+                (self.momentum - baseline.momentum).length(),
+                baseline.momentum_scale,
+            ),
+            angular_momentum: relative(
+                (self.angular_momentum - baseline.angular_momentum).length(),
+                baseline.angular_momentum_scale,
+            ),
+        }
+    }
+}
+
+/// Relative change of the conserved quantities since the baseline
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ConservationDrift {
+    pub time: f64,
+    /// (E - E0) / |E0|
+    pub energy: f64,
+    /// |P - P0| / sum |p_i|
+    pub momentum: f64,
+    /// |L - L0| / sum |l_i|
+    pub angular_momentum: f64,
+}
+
+/// Periodic sampler with drift history and optional CSV log
+pub struct ConservationDiagnostics {
+    interval: f64,
+    last_sample_time: Option<f64>,
+    baseline: Option<ConservationSample>,
+    latest: Option<ConservationSample>,
+    history: VecDeque<ConservationDrift>,
+    #[cfg(not(target_arch = "wasm32"))]
+    csv: Option<(PathBuf, BufWriter<File>)>,
+}
+
+impl Default for ConservationDiagnostics {
+    fn default() -> Self {
+        Self::new(DEFAULT_SAMPLE_INTERVAL)
+    }
+}
+
+impl ConservationDiagnostics {
+    pub fn new(interval: f64) -> Self {
+        Self {
+            interval,
+            last_sample_time: None,
+            baseline: None,
+            latest: None,
+            history: VecDeque::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            csv: None,
+        }
+    }
+
+    /// Forget all samples; the next sample becomes the new baseline
+    pub fn reset(&mut self) {
+        self.last_sample_time = None;
+        self.baseline = None;
+        self.latest = None;
+        self.history.clear();
+    }
+
+    /// Simulated seconds between samples
+    pub fn interval(&self) -> f64 {
+        self.interval
+    }
+
+    pub fn set_interval(&mut self, interval: f64) {
+        self.interval = interval.max(0.0);
+    }
+
+    /// Check whether a sample should be taken at this simulation time
+    pub fn is_due(&self, time: f64) -> bool {
+        self.last_sample_time
+            .is_none_or(|last| time - last >= self.interval)
+    }
+
+    /// Record a sample, returning its drift from the baseline
+    pub fn record(&mut self, sample: ConservationSample) -> ConservationDrift {
+        let baseline = *self.baseline.get_or_insert(sample);
+        let drift = sample.drift_from(&baseline);
+
+        self.last_sample_time = Some(sample.time);
+        self.latest = Some(sample);
+        if self.history.len() >= MAX_HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(drift);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        self.write_csv_row(&sample, &drift);
+
+        drift
+    }
+
+    /// Values at load time (or after the last reset)
+    pub fn baseline(&self) -> Option<&ConservationSample> {
+        self.baseline.as_ref()
+    }
+
+    /// Most recent sample
+    pub fn latest(&self) -> Option<&ConservationSample> {
+        self.latest.as_ref()
+    }
+
+    /// Most recent drift
+    pub fn latest_drift(&self) -> Option<ConservationDrift> {
+        self.history.back().copied()
+    }
+
+    /// Drift samples, oldest first
+    pub fn history(&self) -> &VecDeque<ConservationDrift> {
+        &self.history
+    }
+
+    /// Start appending samples to a CSV file (overwrites an existing file)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start_csv(&mut self, path: &Path) -> AstrariaResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(
+            writer,
+            "time_s,energy_J,momentum_x,momentum_y,momentum_z,angular_momentum_x,angular_momentum_y,angular_momentum_z,energy_drift,momentum_drift,angular_momentum_drift"
+        )?;
+        writer.flush()?;
+        log::info!("Logging conservation diagnostics to {}", path.display());
+        self.csv = Some((path.to_path_buf(), writer));
+        Ok(())
+    }
+
+    /// Stop logging to CSV
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn stop_csv(&mut self) {
+        if let Some((path, mut writer)) = self.csv.take() {
+            if let Err(e) = writer.flush() {
+                log::warn!("Failed to flush {}: {}", path.display(), e);
+            }
+            log::info!(
+                "Stopped logging conservation diagnostics to {}",
+                path.display()
+            );
+        }
+    }
+
+    /// File currently being logged to, if any
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn csv_path(&self) -> Option<&Path> {
+        self.csv.as_ref().map(|(path, _)| path.as_path())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write_csv_row(&mut self, sample: &ConservationSample, drift: &ConservationDrift) {
+        let Some((path, writer)) = &mut self.csv else {
+            return;
+        };
+        let result = writeln!(
+            writer,
+            "{},{:e},{:e},{:e},{:e},{:e},{:e},{:e},{:e},{:e},{:e}",
+            sample.time,
+            sample.energy,
+            sample.momentum.x,
+            sample.momentum.y,
+            sample.momentum.z,
+            sample.angular_momentum.x,
+            sample.angular_momentum.y,
+            sample.angular_momentum.z,
+            drift.energy,
+            drift.momentum,
+            drift.angular_momentum
+        )
+        .and_then(|_| writer.flush());
+
+        if let Err(e) = result {
+            log::warn!("Failed to write diagnostics to {}: {}", path.display(), e);
+            self.csv = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Body;
+
+    fn two_body_collection(velocity: f64) -> BodyCollection {
+        let mut collection = BodyCollection::new();
+        collection.add_body(Body::new(
+            1.0e24,
+            DVec3::ZERO,
+            DVec3::new(0.0, -velocity, 0.0),
+        ));
+        collection.add_body(Body::new(
+            1.0e24,
+            DVec3::new(1.0e7, 0.0, 0.0),
+            DVec3::new(0.0, velocity, 0.0),
+        ));
+        collection.update_collection();
+        collection
+    }
+
+    #[test]
+    fn test_drift_relative_to_first_sample() {
+        let mut diagnostics = ConservationDiagnostics::new(10.0);
+        assert!(diagnostics.is_due(0.0));
+
+        let first = diagnostics.record(ConservationSample::from_collection(
+            &two_body_collection(100.0),
+            0.0,
+        ));
+        assert_eq!(first.energy, 0.0);
+        assert_eq!(first.momentum, 0.0);
+        assert!(!diagnostics.is_due(5.0));
+        assert!(diagnostics.is_due(10.0));
+
+        // Speeding one body up changes every conserved quantity
+        let collection = two_body_collection(100.0);
+        collection.bodies()[1].write().unwrap().velocity.y = 110.0;
+        let drift = diagnostics.record(ConservationSample::from_collection(&collection, 10.0));
+
+        // Momentum scale is 2 * 1e24 * 100, change is 1e24 * 10
+        assert!((drift.momentum - 0.05).abs() < 1e-12);
+        assert!(drift.energy != 0.0);
+        assert!(drift.angular_momentum > 0.0);
+        assert_eq!(diagnostics.history().len(), 2);
+
+        diagnostics.reset();
+        assert!(diagnostics.baseline().is_none());
+        assert!(diagnostics.history().is_empty());
+    }
+}
@@ -0,0 +1,571 @@
+/// Ephemeris accuracy comparison
+/// Loads reference trajectories from CSV and measures how far the running simulation
+/// drifts from them, per body, so integrator and timestep changes can be regressed
+use std::collections::VecDeque;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use glam::DVec3;
+
+use crate::{AstrariaError, AstrariaResult, math::AU_TO_METERS};
+
+/// Default simulated seconds between comparisons
+pub const DEFAULT_COMPARISON_INTERVAL: f64 = 3600.0;
+
+/// Number of error samples kept per body for plotting
+const MAX_HISTORY: usize = 5000;
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// One reference state, in meters and m/s
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReferenceState {
+    /// Seconds since simulation start
+    pub time: f64,
+    pub position: DVec3,
+    pub velocity: DVec3,
+}
+
+/// Reference states of one body, sorted by time
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferenceBody {
+    pub name: String,
+    pub states: Vec<ReferenceState>,
+}
+
+impl ReferenceBody {
+    /// Time span covered by the reference
+    pub fn span(&self) -> Option<(f64, f64)> {
+        Some((self.states.first()?.time, self.states.last()?.time))
+    }
+
+    /// Position and velocity at a time, cubic Hermite interpolated between samples.
+    /// `None` outside the covered span.
+    pub fn state_at(&self, time: f64) -> Option<(DVec3, DVec3)> {
+        let (start, end) = self.span()?;
+        if time < start || time > end {
+            return None;
+        }
+        if self.states.len() == 1 {
+            let state = self.states[0];
+            return Some((state.position, state.velocity));
+        }
+        let upper = self
+            .states
+            .partition_point(|state| state.time < time)
+            .clamp(1, self.states.len() - 1);
+        let (a, b) = (self.states[upper - 1], self.states[upper]);
+        let h = b.time - a.time;
+        if h <= 0.0 {
+            return Some((b.position, b.velocity));
+        }
+
+        let s = (time - a.time) / h;
+        let (s2, s3) = (s * s, s * s * s);
+        let position = a.position * (2.0 * s3 - 3.0 * s2 + 1.0)
+            + a.velocity * (h * (s3 - 2.0 * s2 + s))
+            + b.position * (-2.0 * s3 + 3.0 * s2)
+            + b.velocity * (h * (s3 - s2));
+        let velocity = (b.position - a.position) * (6.0 * (s - s2) / h)
+            + a.velocity * (3.0 * s2 - 4.0 * s + 1.0)
+            + b.velocity * (3.0 * s2 - 2.0 * s);
+        Some((position, velocity))
+    }
+}
+
+/// Reference trajectories for any number of bodies
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReferenceTrajectory {
+    pub bodies: Vec<ReferenceBody>,
+}
+
+impl ReferenceTrajectory {
+    /// Parse a CSV file with one state per row.
+    ///
+    /// The header names the columns, in any order: `body`, `time`, `x`, `y`, `z`, `vx`, `vy`, `vz`.
+    /// Units follow the name, e.g. `time_d`, `x_km`, `vx_km_s`; the defaults are s, m and m/s.
+    /// Lengths may be m, km or au, times s or d. Blank lines and lines starting with `#` are skipped.
+    pub fn parse_csv(content: &str) -> AstrariaResult<Self> {
+        let mut rows = content
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+        let Some((header_line, header)) = rows.next() else {
+            return Err(AstrariaError::ParseError(
+                "Reference trajectory is empty".to_string(),
+            ));
+        };
+        let columns = Columns::parse(header, header_line)?;
+
+        let mut bodies: Vec<ReferenceBody> = Vec::new();
+        for (line_number, line) in rows {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let field = |index: usize| -> AstrariaResult<f64> {
+                let text = fields.get(index).copied().unwrap_or("");
+                text.parse().map_err(|_| {
+                    AstrariaError::ParseError(format!(
+                        "Invalid number '{}' at line {}",
+                        text, line_number
+                    ))
+                })
+            };
+            let vector = |indices: [usize; 3], scale: f64| -> AstrariaResult<DVec3> {
+                Ok(DVec3::new(field(indices[0])?, field(indices[1])?, field(indices[2])?) * scale)
+            };
+
+            let name = fields
+                .get(columns.body)
+                .copied()
+                .unwrap_or("")
+                .trim_matches('"');
+            if name.is_empty() {
+                return Err(AstrariaError::ParseError(format!(
+                    "Missing body name at line {}",
+                    line_number
+                )));
+            }
+            let state = ReferenceState {
+                time: field(columns.time)? * columns.time_scale,
+                position: vector(columns.position, columns.length_scale)?,
+                velocity: vector(columns.velocity, columns.velocity_scale)?,
+            };
+            match bodies.iter_mut().find(|body| body.name == name) {
+                Some(body) => body.states.push(state),
+                None => bodies.push(ReferenceBody {
+                    name: name.to_string(),
+                    states: vec![state],
+                }),
+            }
+        }
+
+        if bodies.is_empty() {
+            return Err(AstrariaError::ParseError(
+                "Reference trajectory has no states".to_string(),
+            ));
+        }
+        for body in &mut bodies {
+            body.states.sort_by(|a, b| a.time.total_cmp(&b.time));
+        }
+        Ok(Self { bodies })
+    }
+
+    /// Read a reference CSV from disk
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: &Path) -> AstrariaResult<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            AstrariaError::AssetLoading(format!(
+                "Failed to read reference trajectory '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let reference = Self::parse_csv(&content)?;
+        log::info!(
+            "Loaded reference trajectories for {} bodies from {}",
+            reference.bodies.len(),
+            path.display()
+        );
+        Ok(reference)
+    }
+
+    pub fn body(&self, name: &str) -> Option<&ReferenceBody> {
+        self.bodies.iter().find(|body| body.name == name)
+    }
+}
+
+/// Column indices and unit scales of a reference CSV
+struct Columns {
+    body: usize,
+    time: usize,
+    position: [usize; 3],
+    velocity: [usize; 3],
+    time_scale: f64,
+    length_scale: f64,
+    velocity_scale: f64,
+}
+
+impl Columns {
+    fn parse(header: &str, line_number: usize) -> AstrariaResult<Self> {
+        // "x (km)", "x[km]" and "x_km" all become ("x", "km"); "km/s" becomes "km_s"
+        let names: Vec<(String, String)> = header
+            .split(',')
+            .map(|name| {
+                let name: String = name
+                    .trim()
+                    .trim_matches('"')
+                    .to_lowercase()
+                    .chars()
+                    .filter_map(|c| match c {
+                        '(' | '[' | '/' => Some('_'),
+                        ')' | ']' | ' ' => None,
+                        c => Some(c),
+                    })
+                    .collect();
+                match name.split_once('_') {
+                    Some((base, unit)) => (base.to_string(), unit.to_string()),
+                    None => (name, String::new()),
+                }
+            })
+            .collect();
+
+        let find = |aliases: &[&str]| {
+            names
+                .iter()
+                .position(|(base, _)| aliases.contains(&base.as_str()))
+                .ok_or_else(|| {
+                    AstrariaError::ParseError(format!(
+                        "Reference header at line {} has no '{}' column",
+                        line_number, aliases[0]
+                    ))
+                })
+        };
+        let unit_error = |unit: &str| {
+            AstrariaError::ParseError(format!(
+                "Unknown unit '{}' in reference header at line {}",
+                unit, line_number
+            ))
+        };
+
+        let time = find(&["time", "t"])?;
+        let position = [find(&["x"])?, find(&["y"])?, find(&["z"])?];
+        let velocity = [find(&["vx"])?, find(&["vy"])?, find(&["vz"])?];
+
+        let time_scale = match names[time].1.as_str() {
+            "" | "s" => 1.0,
+            "d" | "day" | "days" => SECONDS_PER_DAY,
+            unit => return Err(unit_error(unit)),
+        };
+        let length_scale = match names[position[0]].1.as_str() {
+            "" | "m" => 1.0,
+            "km" => 1000.0,
+            "au" => AU_TO_METERS,
+            unit => return Err(unit_error(unit)),
+        };
+        let velocity_scale = match names[velocity[0]].1.as_str() {
+            "" | "m_s" => 1.0,
+            "km_s" => 1000.0,
+            "au_d" => AU_TO_METERS / SECONDS_PER_DAY,
+            unit => return Err(unit_error(unit)),
+        };
+
+        Ok(Self {
+            body: find(&["body", "name"])?,
+            time,
+            position,
+            velocity,
+            time_scale,
+            length_scale,
+            velocity_scale,
+        })
+    }
+}
+
+/// Position and velocity error of one body at one instant
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EphemerisError {
+    pub time: f64,
+    /// |r_sim - r_ref| (m)
+    pub position: f64,
+    /// |v_sim - v_ref| (m/s)
+    pub velocity: f64,
+}
+
+/// Error statistics of one body over the whole comparison
+#[derive(Debug, Clone, PartialEq)]
+pub struct EphemerisSummary {
+    pub name: String,
+    pub samples: usize,
+    pub max_position: f64,
+    pub rms_position: f64,
+    pub final_position: f64,
+    pub max_velocity: f64,
+    pub rms_velocity: f64,
+    pub final_velocity: f64,
+}
+
+impl EphemerisSummary {
+    /// Write per-body error summaries as CSV
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_csv(summaries: &[Self], path: &Path) -> AstrariaResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(
+            writer,
+            "body,samples,max_position_error_m,rms_position_error_m,final_position_error_m,max_velocity_error_m_s,rms_velocity_error_m_s,final_velocity_error_m_s"
+        )?;
+        for summary in summaries {
+            writeln!(
+                writer,
+                "{},{},{:e},{:e},{:e},{:e},{:e},{:e}",
+                summary.name,
+                summary.samples,
+                summary.max_position,
+                summary.rms_position,
+                summary.final_position,
+                summary.max_velocity,
+                summary.rms_velocity,
+                summary.final_velocity
+            )?;
+        }
+        writer.flush()?;
+        log::info!("Wrote ephemeris comparison summary to {}", path.display());
+        Ok(())
+    }
+}
+
+/// Error history of one simulated body that has a reference
+#[derive(Debug, Clone)]
+pub struct ComparedBody {
+    pub name: String,
+    history: VecDeque<EphemerisError>,
+    samples: usize,
+    max: (f64, f64),
+    sum_squares: (f64, f64),
+}
+
+impl ComparedBody {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            history: VecDeque::new(),
+            samples: 0,
+            max: (0.0, 0.0),
+            sum_squares: (0.0, 0.0),
+        }
+    }
+
+    /// Error samples, oldest first
+    pub fn history(&self) -> &VecDeque<EphemerisError> {
+        &self.history
+    }
+
+    pub fn latest(&self) -> Option<&EphemerisError> {
+        self.history.back()
+    }
+
+    /// Statistics over every sample, including ones dropped from the plotted history
+    pub fn summary(&self) -> EphemerisSummary {
+        let count = self.samples.max(1) as f64;
+        let latest = self.latest();
+        EphemerisSummary {
+            name: self.name.clone(),
+            samples: self.samples,
+            max_position: self.max.0,
+            rms_position: (self.sum_squares.0 / count).sqrt(),
+            final_position: latest.map_or(0.0, |error| error.position),
+            max_velocity: self.max.1,
+            rms_velocity: (self.sum_squares.1 / count).sqrt(),
+            final_velocity: latest.map_or(0.0, |error| error.velocity),
+        }
+    }
+
+    fn record(&mut self, error: EphemerisError) {
+        self.samples += 1;
+        self.max = (
+            self.max.0.max(error.position),
+            self.max.1.max(error.velocity),
+        );
+        self.sum_squares.0 += error.position * error.position;
+        self.sum_squares.1 += error.velocity * error.velocity;
+        if self.history.len() >= MAX_HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(error);
+    }
+}
+
+/// Periodic comparison of the simulation against a reference trajectory
+pub struct EphemerisComparison {
+    reference: Option<ReferenceTrajectory>,
+    interval: f64,
+    /// Compare states relative to this body (e.g. "Sun" for heliocentric references)
+    pub relative_to: Option<String>,
+    last_sample_time: Option<f64>,
+    bodies: Vec<ComparedBody>,
+}
+
+impl Default for EphemerisComparison {
+    fn default() -> Self {
+        Self::new(DEFAULT_COMPARISON_INTERVAL)
+    }
+}
+
+impl EphemerisComparison {
+    /// New comparison, idle until a reference is set
+    pub fn new(interval: f64) -> Self {
+        Self {
+            reference: None,
+            interval,
+            relative_to: None,
+            last_sample_time: None,
+            bodies: Vec::new(),
+        }
+    }
+
+    pub fn reference(&self) -> Option<&ReferenceTrajectory> {
+        self.reference.as_ref()
+    }
+
+    /// Compare against a new reference, discarding earlier errors
+    pub fn set_reference(&mut self, reference: Option<ReferenceTrajectory>) {
+        self.reference = reference;
+        self.reset();
+    }
+
+    /// Forget all errors, keeping the reference
+    pub fn reset(&mut self) {
+        self.last_sample_time = None;
+        self.bodies.clear();
+    }
+
+    /// Simulated seconds between comparisons
+    pub fn interval(&self) -> f64 {
+        self.interval
+    }
+
+    pub fn set_interval(&mut self, interval: f64) {
+        self.interval = interval.max(0.0);
+    }
+
+    /// Check whether a comparison should be made at this simulation time
+    pub fn is_due(&self, time: f64) -> bool {
+        self.reference.is_some()
+            && self
+                .last_sample_time
+                .is_none_or(|last| time - last >= self.interval)
+    }
+
+    /// Bodies compared so far, in the order they were first matched
+    pub fn bodies(&self) -> &[ComparedBody] {
+        &self.bodies
+    }
+
+    pub fn summaries(&self) -> Vec<EphemerisSummary> {
+        self.bodies.iter().map(ComparedBody::summary).collect()
+    }
+
+    /// Compare simulated states with the reference at a simulation time.
+    /// Bodies are matched by name; ones without reference coverage at this time are skipped.
+    pub fn record(
+        &mut self,
+        time: f64,
+        names: &[String],
+        positions: &[DVec3],
+        velocities: &[DVec3],
+    ) {
+        self.last_sample_time = Some(time);
+        let Some(reference) = &self.reference else {
+            return;
+        };
+
+        // Origin states; a reference without the origin body is taken to be relative to it already
+        let (simulated_origin, reference_origin) = match &self.relative_to {
+            Some(origin) => {
+                let Some(index) = names.iter().position(|name| name == origin) else {
+                    return;
+                };
+                let reference_origin = match reference.body(origin) {
+                    Some(body) => match body.state_at(time) {
+                        Some(state) => state,
+                        None => return,
+                    },
+                    None => (DVec3::ZERO, DVec3::ZERO),
+                };
+                ((positions[index], velocities[index]), reference_origin)
+            }
+            None => ((DVec3::ZERO, DVec3::ZERO), (DVec3::ZERO, DVec3::ZERO)),
+        };
+
+        for (index, name) in names.iter().enumerate() {
+            if self.relative_to.as_ref() == Some(name) {
+                continue;
+            }
+            let Some((position, velocity)) =
+                reference.body(name).and_then(|body| body.state_at(time))
+            else {
+                continue;
+            };
+            let error = EphemerisError {
+                time,
+                position: ((positions[index] - simulated_origin.0)
+                    - (position - reference_origin.0))
+                    .length(),
+                velocity: ((velocities[index] - simulated_origin.1)
+                    - (velocity - reference_origin.1))
+                    .length(),
+            };
+            let compared = match self.bodies.iter().position(|body| body.name == *name) {
+                Some(existing) => &mut self.bodies[existing],
+                None => {
+                    self.bodies.push(ComparedBody::new(name.clone()));
+                    self.bodies.last_mut().expect("just pushed")
+                }
+            };
+            compared.record(error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REFERENCE: &str = "\
+# Uniform circular motion sampled every quarter turn
+body, time_d, x_km, y_km, z_km, vx_km_s, vy_km_s, vz_km_s
+Probe, 0, 1000, 0, 0, 0, 1, 0
+Probe, 1, 0, 1000, 0, -1, 0, 0
+Origin, 0, 0, 0, 0, 0, 0, 0
+Origin, 1, 0, 0, 0, 0, 0, 0
+";
+
+    #[test]
+    fn test_parse_and_interpolate_reference() {
+        let reference = ReferenceTrajectory::parse_csv(REFERENCE).unwrap();
+        assert_eq!(reference.bodies.len(), 2);
+        let probe = reference.body("Probe").unwrap();
+        assert_eq!(probe.span(), Some((0.0, SECONDS_PER_DAY)));
+        assert_eq!(probe.states[1].velocity, DVec3::new(-1000.0, 0.0, 0.0));
+
+        // Hermite interpolation reproduces the endpoints and their velocities
+        let (position, velocity) = probe.state_at(SECONDS_PER_DAY).unwrap();
+        assert!((position - DVec3::new(0.0, 1e6, 0.0)).length() < 1e-6);
+        assert!((velocity - DVec3::new(-1000.0, 0.0, 0.0)).length() < 1e-9);
+        assert!(probe.state_at(-1.0).is_none());
+
+        let bad = "body,time,x_parsec,y,z,vx,vy,vz\nA,0,0,0,0,0,0,0\n";
+        assert!(ReferenceTrajectory::parse_csv(bad).is_err());
+    }
+
+    #[test]
+    fn test_records_errors_and_summary() {
+        let reference = ReferenceTrajectory::parse_csv(REFERENCE).unwrap();
+        let mut comparison = EphemerisComparison::new(10.0);
+        assert!(!comparison.is_due(0.0));
+        comparison.set_reference(Some(reference));
+        comparison.relative_to = Some("Origin".to_string());
+
+        let names = ["Origin", "Probe", "Unreferenced"].map(String::from);
+        // Everything shifted by the same offset: relative errors come only from the probe
+        let offset = DVec3::new(5.0e9, 0.0, 0.0);
+        let positions = [offset, offset + DVec3::new(1.0e6 + 3.0, 4.0, 0.0), offset];
+        let velocities = [DVec3::ZERO, DVec3::new(0.0, 1000.0, 2.0), DVec3::ZERO];
+        comparison.record(0.0, &names, &positions, &velocities);
+        assert!(!comparison.is_due(5.0));
+
+        assert_eq!(comparison.bodies().len(), 1);
+        let probe = &comparison.bodies()[0];
+        assert_eq!(probe.name, "Probe");
+        let error = probe.latest().unwrap();
+        assert!((error.position - 5.0).abs() < 1e-6);
+        assert!((error.velocity - 2.0).abs() < 1e-9);
+
+        let summary = probe.summary();
+        assert_eq!(summary.samples, 1);
+        assert!((summary.rms_position - 5.0).abs() < 1e-6);
+    }
+}
@@ -1,12 +1,19 @@
 pub mod app;
 pub mod assets;
+pub mod diagnostics;
+pub mod ephemeris;
 pub mod generated_shaders;
 pub mod graphics;
 pub mod input;
 pub mod math;
+pub mod photometry;
 pub mod physics;
 pub mod renderer;
+pub mod resonance;
+pub mod satellites;
 pub mod scenario;
+pub mod spk;
+pub mod stability;
 pub mod ui;
 
 pub use app::AstrariaApp;
@@ -1,5 +1,15 @@
 use anyhow::Result;
-use astraria_rust::AstrariaApp;
+use astraria_rust::{
+    AstrariaApp,
+    assets::AssetManager,
+    scenario::{
+        ScenarioWriter,
+        generators::Generator,
+        horizons::HorizonsImporter,
+        mpc::{MpcFilter, MpcImporter, OrbitClass},
+        validation::ScenarioValidator,
+    },
+};
 use std::env;
 
 fn main() -> Result<()> {
@@ -7,23 +17,207 @@ fn main() -> Result<()> {
 
     log::info!("Starting Astraria Rust port...");
 
-    // Parse command line arguments
-    let args: Vec<String> = env::args().collect();
-    let scenario_file = if args.len() > 1 {
-        args[1].clone()
+    // Parse command line arguments: flags anywhere, then positional arguments
+    let mut args: Vec<String> = Vec::new();
+    let mut barycentric = None;
+    let mut tle_file = None;
+    let mut convert = None;
+    let mut check = false;
+    let mut strict = false;
+    let mut generator = None;
+    let mut seed = 1;
+    let mut output = None;
+    let mut mpc_file = None;
+    let mut mpc_filter = MpcFilter::default();
+    let mut horizons_files = Vec::new();
+    let mut horizons_sidecar = None;
+    let mut cli = env::args().skip(1);
+    while let Some(arg) = cli.next() {
+        match arg.as_str() {
+            "--barycentric" => barycentric = Some(true),
+            "--no-barycentric" => barycentric = Some(false),
+            "--check" => check = true,
+            "--strict" => strict = true,
+            "--tle" => match cli.next() {
+                Some(path) => tle_file = Some(path),
+                None => anyhow::bail!("--tle needs a file of two-line element sets"),
+            },
+            "--mpc" => match cli.next() {
+                Some(path) => mpc_file = Some(path),
+                None => anyhow::bail!("--mpc needs an MPC orbit file (MPCORB or comet elements)"),
+            },
+            "--max-h" => match cli.next().and_then(|value| value.parse().ok()) {
+                Some(value) => mpc_filter.max_magnitude = Some(value),
+                None => anyhow::bail!("--max-h needs an absolute magnitude"),
+            },
+            "--class" => match cli.next().as_deref().and_then(OrbitClass::from_name) {
+                Some(class) => mpc_filter.classes.push(class),
+                None => anyhow::bail!(
+                    "--class needs one of: {}",
+                    OrbitClass::ALL.map(OrbitClass::label).join(", ")
+                ),
+            },
+            "--limit" => match cli.next().and_then(|value| value.parse().ok()) {
+                Some(value) => mpc_filter.limit = Some(value),
+                None => anyhow::bail!("--limit needs a number of orbits"),
+            },
+            "--horizons" => match cli.next() {
+                Some(path) => horizons_files.push(path),
+                None => anyhow::bail!("--horizons needs a saved JPL Horizons vector table"),
+            },
+            "--horizons-sidecar" => match cli.next() {
+                Some(path) => horizons_sidecar = Some(path),
+                None => anyhow::bail!("--horizons-sidecar needs a body catalog file"),
+            },
+            "--generate" => match cli.next().as_deref().map(Generator::from_name) {
+                Some(Some(kind)) => generator = Some(kind),
+                _ => anyhow::bail!("--generate needs one of: {}", Generator::NAMES.join(", ")),
+            },
+            "--seed" => match cli.next().and_then(|value| value.parse().ok()) {
+                Some(value) => seed = value,
+                None => anyhow::bail!("--seed needs a non-negative integer"),
+            },
+            "--output" => match cli.next() {
+                Some(path) => output = Some(path),
+                None => anyhow::bail!("--output needs a file to write the scenario to"),
+            },
+            "--convert" => match (cli.next(), cli.next()) {
+                (Some(input), Some(output)) => convert = Some((input, output)),
+                _ => anyhow::bail!("--convert needs a scenario file and an output path"),
+            },
+            _ if arg.starts_with("--") => {
+                anyhow::bail!(
+                    "Unknown option: {}\nUsage: astraria [--barycentric | --no-barycentric] [--check | --strict] [--tle file] [--mpc file [--max-h n] [--class name]... [--limit n]] [--horizons file]... [--horizons-sidecar file] [--convert scenario output.ron] [--generate kind [--seed n] [--output file]] [scenario] [focus index]",
+                    arg
+                );
+            }
+            _ => args.push(arg),
+        }
+    }
+
+    if let Some((input, output)) = convert {
+        let content = std::fs::read_to_string(&input)?;
+        let converted = astraria_rust::scenario::ScenarioParser::convert_to_v4(&content)?;
+        std::fs::write(&output, converted)?;
+        log::info!("Converted {} to {}", input, output);
+        return Ok(());
+    }
+
+    if horizons_files.is_empty() && horizons_sidecar.is_some() {
+        anyhow::bail!("--horizons-sidecar is only used with --horizons");
+    }
+    let generated = match generator {
+        Some(_) if !horizons_files.is_empty() => {
+            anyhow::bail!("--generate and --horizons cannot be used together")
+        }
+        Some(generator) => {
+            let scenario = generator.generate(seed);
+            log::info!(
+                "Generated {} bodies with seed {}",
+                scenario.bodies.len(),
+                seed
+            );
+            Some(scenario)
+        }
+        None if !horizons_files.is_empty() => {
+            let mut importer = HorizonsImporter::new();
+            if let Some(path) = &horizons_sidecar {
+                importer = importer.with_sidecar(&std::fs::read_to_string(path)?)?;
+            }
+            let scenario = importer.import_files(&horizons_files)?;
+            log::info!(
+                "Imported {} bodies from {} Horizons export(s)",
+                scenario.bodies.len(),
+                horizons_files.len()
+            );
+            Some(scenario)
+        }
+        None => None,
+    };
+    if let Some(scenario) = &generated {
+        if let Some(output) = &output {
+            ScenarioWriter::save(scenario, std::path::Path::new(output))?;
+            log::info!("Wrote scenario to {}", output);
+            return Ok(());
+        }
+    } else if output.is_some() {
+        anyhow::bail!("--output is only used with --generate or --horizons");
+    }
+
+    let mpc_orbits = match mpc_file {
+        Some(path) => {
+            let content = std::fs::read_to_string(&path)?;
+            log::info!("Adding small bodies from: {}", path);
+            Some((MpcImporter::new().with_filter(mpc_filter), content))
+        }
+        None if mpc_filter != MpcFilter::default() => {
+            anyhow::bail!("--max-h, --class and --limit are only used with --mpc")
+        }
+        None => None,
+    };
+
+    let scenario_file = if !args.is_empty() {
+        args[0].clone()
     } else {
         "Solar_System_2K.txt".to_string()
     };
 
-    let focus_index = if args.len() > 2 {
-        args[2].parse::<usize>().unwrap_or(0)
+    let focus_index = if args.len() > 1 {
+        args[1].parse::<usize>().unwrap_or(0)
     } else {
         0 // Default to first body (usually the sun)
     };
 
+    if check {
+        return check_scenario(&scenario_file);
+    }
+
     log::info!("Using scenario file: {}", scenario_file);
     log::info!("Focusing on body index: {}", focus_index);
 
-    let app = AstrariaApp::new_with_scenario_and_focus(scenario_file, focus_index)?;
+    if let Some(barycentric) = barycentric {
+        log::info!(
+            "Barycentric normalization forced {}",
+            if barycentric { "on" } else { "off" }
+        );
+    }
+
+    if let Some(tle_file) = &tle_file {
+        log::info!("Loading satellites from: {}", tle_file);
+    }
+
+    let app = AstrariaApp::new_with_scenario_and_focus(scenario_file, focus_index)?
+        .with_barycentric_override(barycentric)
+        .with_strict_validation(strict)
+        .with_generated_scenario(generated)
+        .with_mpc_orbits(mpc_orbits)
+        .with_satellites(tle_file);
     app.run()
 }
+
+/// Print every problem in a scenario file and the files it includes, without opening a window;
+/// fails if any is an error
+fn check_scenario(scenario_file: &str) -> Result<()> {
+    let asset_manager = pollster::block_on(AssetManager::new())?;
+    let validator = ScenarioValidator::new().with_texture_check(AssetManager::texture_exists);
+    let reports = pollster::block_on(asset_manager.validate_scenario(scenario_file, &validator))?;
+
+    let mut errors = 0;
+    for (path, report) in &reports {
+        for diagnostic in &report.diagnostics {
+            println!("{}: {}", path, diagnostic);
+        }
+        println!(
+            "{}: {} bodies, {} error(s), {} warning(s)",
+            path,
+            report.body_count,
+            report.error_count(),
+            report.warning_count()
+        );
+        errors += report.error_count();
+    }
+    if errors > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -1,8 +1,11 @@
-use crate::{scenario::BodyType, renderer::orbital_paths::OrbitTrail};
+use crate::{
+    renderer::orbital_paths::OrbitTrail,
+    scenario::{BodyType, RotationMode},
+};
 use bytemuck::{Pod, Zeroable};
 /// Physics body representation for N-body simulation
 /// Ported from the original Java Body.java with Rust safety improvements
-use glam::DVec3;
+use glam::{DQuat, DVec3};
 use std::sync::{Arc, RwLock};
 // Removed serde for now - can be added back when needed
 
@@ -36,6 +39,9 @@ pub struct Body {
     /// Rotation parameters (incTilt, axisRightAsc, rotPeriod, offset) in radians
     pub rotation_params: (f32, f32, f32, f32),
 
+    /// Whether the body spins freely or keeps facing another body
+    pub rotation_mode: RotationMode,
+
     /// Simple orbital trail (like Java Orbit.java)
     pub orbit_trail: OrbitTrail,
 }
@@ -53,9 +59,11 @@ impl Body {
             body_type: BodyType::Planet {
                 radius: 1000.0,
                 texture_path: "default.jpg".to_string(),
+                rings: None,
             },
             orbit_color: [1.0, 1.0, 1.0, 1.0],
             rotation_params: (0.0, 0.0, 0.0, 0.0),
+            rotation_mode: RotationMode::Fixed,
             orbit_trail: OrbitTrail::new([1.0, 1.0, 1.0, 1.0]), // Default white trail
         }
     }
@@ -80,6 +88,7 @@ impl Body {
             body_type,
             orbit_color,
             rotation_params,
+            rotation_mode: RotationMode::Fixed,
             orbit_trail: OrbitTrail::new(orbit_color), // Use body's orbit color
         }
     }
@@ -133,6 +142,33 @@ impl Body {
         self.acceleration += acceleration;
     }
 
+    /// Orientation of the body's equator in world coordinates
+    ///
+    /// The spin axis starts along +Z, is tilted by `incTilt` about X and then turned by
+    /// `axisRightAsc` about Z.
+    pub fn equatorial_orientation(&self) -> DQuat {
+        let (tilt, right_ascension, _, _) = self.rotation_params;
+        DQuat::from_rotation_z(right_ascension as f64) * DQuat::from_rotation_x(tilt as f64)
+    }
+
+    /// Orientation of the body's surface in world coordinates at `time` seconds
+    ///
+    /// Longitude 0 lies along +X of the equatorial frame and turns about the spin axis with
+    /// the rotation rate (per day) from the rotation offset. A tidally locked body instead turns
+    /// it towards `parent_offset`, the parent's position relative to the body, when given.
+    pub fn surface_orientation(&self, time: f64, parent_offset: Option<DVec3>) -> DQuat {
+        let equatorial = self.equatorial_orientation();
+        let (_, _, rate, offset) = self.rotation_params;
+        let spin = match (&self.rotation_mode, parent_offset) {
+            (RotationMode::TidallyLocked { .. }, Some(parent_offset)) => {
+                let local = equatorial.inverse() * parent_offset;
+                local.y.atan2(local.x)
+            }
+            _ => offset as f64 + rate as f64 * time / crate::math::SECONDS_PER_DAY,
+        };
+        equatorial * DQuat::from_rotation_z(spin)
+    }
+
     /// Update orbital trail with current position (like Java SimulationObject.prepare())
     pub fn update_orbit_trail(&mut self) {
         log::trace!("Updating orbital trail for '{}' at position ({:.2e}, {:.2e}, {:.2e})", 
@@ -140,6 +176,12 @@ impl Body {
         self.orbit_trail.update_position(self.position);
     }
 
+    /// Update orbital trail with the current position expressed in a viewing frame
+    pub fn update_orbit_trail_in_frame(&mut self, transform: &crate::math::FrameTransform) {
+        let frame_position = transform.position_to_frame(self.position);
+        self.orbit_trail.update_position(frame_position);
+    }
+
     /// Get mutable reference to orbit trail for GPU buffer updates
     pub fn get_orbit_trail_mut(&mut self) -> &mut OrbitTrail {
         &mut self.orbit_trail
@@ -169,6 +211,7 @@ impl Clone for Body {
             body_type: self.body_type.clone(),
             orbit_color: self.orbit_color,
             rotation_params: self.rotation_params,
+            rotation_mode: self.rotation_mode.clone(),
             orbit_trail: OrbitTrail::new(self.orbit_color), // Create new trail
         }
     }
@@ -185,6 +228,7 @@ impl PartialEq for Body {
             && self.body_type == other.body_type
             && self.orbit_color == other.orbit_color
             && self.rotation_params == other.rotation_params
+            && self.rotation_mode == other.rotation_mode
         // Note: orbit_trail is not compared as it contains GPU resources
     }
 }
@@ -322,6 +366,32 @@ impl BodyCollection {
         kinetic_energy + potential_energy
     }
 
+    /// Calculate total linear momentum of the system
+    pub fn total_momentum(&self) -> DVec3 {
+        let mut momentum = DVec3::ZERO;
+
+        for body_ref in &self.bodies {
+            if let Ok(body) = body_ref.read() {
+                momentum += body.momentum();
+            }
+        }
+
+        momentum
+    }
+
+    /// Calculate total angular momentum of the system about the origin
+    pub fn total_angular_momentum(&self) -> DVec3 {
+        let mut angular_momentum = DVec3::ZERO;
+
+        for body_ref in &self.bodies {
+            if let Ok(body) = body_ref.read() {
+                angular_momentum += body.position.cross(body.momentum());
+            }
+        }
+
+        angular_momentum
+    }
+
     /// Calculate center of mass of the system
     pub fn center_of_mass(&self) -> DVec3 {
         let mut total_mass = 0.0;
@@ -340,6 +410,47 @@ impl BodyCollection {
             DVec3::ZERO
         }
     }
+
+    /// Total mass of the system
+    pub fn total_mass(&self) -> f64 {
+        self.bodies
+            .iter()
+            .filter_map(|body_ref| body_ref.read().ok().map(|body| body.mass))
+            .sum()
+    }
+
+    /// Shift every body so the centre of mass sits at rest at the origin
+    ///
+    /// Returns the offsets that were subtracted from all positions and velocities.
+    pub fn shift_to_barycentre(&mut self) -> BarycentricCorrection {
+        let total_mass = self.total_mass();
+        if total_mass <= 0.0 {
+            return BarycentricCorrection::default();
+        }
+
+        let correction = BarycentricCorrection {
+            position_offset: self.center_of_mass(),
+            velocity_offset: self.total_momentum() / total_mass,
+        };
+
+        for body_ref in &self.bodies {
+            if let Ok(mut body) = body_ref.write() {
+                body.position -= correction.position_offset;
+                body.velocity -= correction.velocity_offset;
+            }
+        }
+
+        correction
+    }
+}
+
+/// Offsets removed from a system when moving it into the barycentric frame
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BarycentricCorrection {
+    /// Original centre of mass position (m)
+    pub position_offset: DVec3,
+    /// Original centre of mass velocity (m/s)
+    pub velocity_offset: DVec3,
 }
 
 impl Default for BodyCollection {
@@ -404,4 +515,49 @@ mod tests {
         assert_eq!(render_body.velocity[1], 2e4 as f32);
         assert_eq!(render_body.mass, 1e24 as f32);
     }
+
+    #[test]
+    fn test_surface_orientation_spins_or_faces_parent() {
+        let mut moon = Body::new(7.3e22, DVec3::ZERO, DVec3::ZERO);
+        // 90 degrees per day from an offset of 90 degrees
+        moon.rotation_params = (0.0, 0.0, 90f32.to_radians(), 90f32.to_radians());
+        let prime_meridian = |orientation: DQuat| orientation * DVec3::X;
+
+        let spun = prime_meridian(moon.surface_orientation(86400.0, None));
+        assert!((spun - DVec3::NEG_X).length() < 1e-6);
+
+        moon.rotation_mode = RotationMode::TidallyLocked {
+            parent: "Earth".to_string(),
+        };
+        let parent_offset = DVec3::new(-3.0e8, 2.0e8, 5.0e7);
+        let facing = prime_meridian(moon.surface_orientation(86400.0, Some(parent_offset)));
+        let expected = parent_offset.with_z(0.0).normalize();
+        assert!((facing - expected).length() < 1e-9);
+
+        // Without the parent in view, locked bodies keep their fixed spin
+        let fallback = prime_meridian(moon.surface_orientation(86400.0, None));
+        assert!((fallback - spun).length() < 1e-12);
+    }
+
+    #[test]
+    fn test_shift_to_barycentre() {
+        let mut collection = BodyCollection::new();
+        collection.add_body(Body::new(
+            3.0,
+            DVec3::new(1.0, 0.0, 0.0),
+            DVec3::new(0.0, 2.0, 0.0),
+        ));
+        collection.add_body(Body::new(
+            1.0,
+            DVec3::new(5.0, 0.0, 0.0),
+            DVec3::new(0.0, -2.0, 4.0),
+        ));
+        collection.update_collection();
+
+        let correction = collection.shift_to_barycentre();
+        assert_eq!(correction.position_offset, DVec3::new(2.0, 0.0, 0.0));
+        assert_eq!(correction.velocity_offset, DVec3::new(0.0, 1.0, 1.0));
+        assert!(collection.center_of_mass().length() < 1e-12);
+        assert!(collection.total_momentum().length() < 1e-12);
+    }
 }
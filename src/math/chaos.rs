@@ -0,0 +1,383 @@
+/// Chaos indicators from the variational equations
+/// A tangent vector is integrated alongside the N-body state with the same velocity-Verlet
+/// scheme; its growth rate gives MEGNO and the maximum Lyapunov exponent
+use glam::DVec3;
+
+use crate::math::GRAVITATIONAL_CONSTANT;
+
+/// Gravitational acceleration of every body
+pub fn nbody_accelerations(masses: &[f64], positions: &[DVec3]) -> Vec<DVec3> {
+    let mut accelerations = vec![DVec3::ZERO; positions.len()];
+    for (i, acceleration) in accelerations.iter_mut().enumerate() {
+        for (j, other) in positions.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            let displacement = *other - positions[i];
+            let distance_squared = displacement.length_squared();
+            if distance_squared > 0.0 {
+                *acceleration += displacement
+                    * (GRAVITATIONAL_CONSTANT * masses[j]
+                        / (distance_squared * distance_squared.sqrt()));
+            }
+        }
+    }
+    accelerations
+}
+
+/// Linearised change of every acceleration when the positions are displaced by `offsets`
+pub fn variational_accelerations(
+    masses: &[f64],
+    positions: &[DVec3],
+    offsets: &[DVec3],
+) -> Vec<DVec3> {
+    let mut accelerations = vec![DVec3::ZERO; positions.len()];
+    for (i, acceleration) in accelerations.iter_mut().enumerate() {
+        for (j, other) in positions.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            let displacement = *other - positions[i];
+            let offset = offsets[j] - offsets[i];
+            let distance_squared = displacement.length_squared();
+            if distance_squared > 0.0 {
+                let distance = distance_squared.sqrt();
+                let inverse_cube = 1.0 / (distance_squared * distance);
+                // Gradient of r / |r|^3 applied to the relative offset
+                *acceleration += (offset
+                    - displacement * (3.0 * displacement.dot(offset) / distance_squared))
+                    * (GRAVITATIONAL_CONSTANT * masses[j] * inverse_cube);
+            }
+        }
+    }
+    accelerations
+}
+
+/// Characteristic time of a body's motion: distance over relative speed to the body
+/// that pulls on it hardest. Used to weigh velocity against position deviations.
+pub fn characteristic_time_scale(
+    masses: &[f64],
+    positions: &[DVec3],
+    velocities: &[DVec3],
+    body: usize,
+) -> f64 {
+    let dominant = (0..positions.len())
+        .filter(|&other| other != body && masses[other] > 0.0)
+        .map(|other| {
+            let distance_squared = (positions[other] - positions[body]).length_squared();
+            (
+                other,
+                masses[other] / distance_squared.max(f64::MIN_POSITIVE),
+            )
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1));
+
+    let Some((other, _)) = dominant else {
+        return 1.0;
+    };
+    let distance = (positions[other] - positions[body]).length();
+    let speed = (velocities[other] - velocities[body]).length();
+    if distance > 0.0 && speed > 0.0 {
+        distance / speed
+    } else {
+        1.0
+    }
+}
+
+/// Running MEGNO and Lyapunov estimates for one tangent vector
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ChaosIndicator {
+    time: f64,
+    log_growth: f64,
+    megno_integral: f64,
+    mean_megno_integral: f64,
+}
+
+impl ChaosIndicator {
+    /// Account for the tangent vector growing by `growth` (natural log) over `delta_time`
+    pub fn record(&mut self, growth: f64, delta_time: f64) {
+        if delta_time <= 0.0 || !growth.is_finite() {
+            return;
+        }
+        // d(ln |delta|)/dt weighted by the time at the middle of the step
+        self.megno_integral += growth * (self.time + 0.5 * delta_time);
+        self.log_growth += growth;
+        self.time += delta_time;
+        self.mean_megno_integral += self.megno() * delta_time;
+    }
+
+    /// Integration time covered so far (s)
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Instantaneous MEGNO, Y(t)
+    pub fn megno(&self) -> f64 {
+        if self.time > 0.0 {
+            2.0 * self.megno_integral / self.time
+        } else {
+            0.0
+        }
+    }
+
+    /// Time-averaged MEGNO, <Y>(t): tends to 2 for quasi-periodic orbits and grows
+    /// linearly for chaotic ones
+    pub fn mean_megno(&self) -> f64 {
+        if self.time > 0.0 {
+            self.mean_megno_integral / self.time
+        } else {
+            0.0
+        }
+    }
+
+    /// Finite-time estimate of the maximum Lyapunov exponent (1/s)
+    pub fn lyapunov_exponent(&self) -> f64 {
+        if self.time > 0.0 {
+            self.log_growth / self.time
+        } else {
+            0.0
+        }
+    }
+
+    /// Lyapunov time (s), infinite if no exponential growth was measured
+    pub fn lyapunov_time(&self) -> f64 {
+        let exponent = self.lyapunov_exponent();
+        if exponent > 0.0 {
+            1.0 / exponent
+        } else {
+            f64::INFINITY
+        }
+    }
+}
+
+/// Deviation of the whole system from its reference trajectory, seeded by
+/// perturbing one body
+#[derive(Debug, Clone)]
+pub struct TangentVector {
+    /// Body whose state was perturbed initially
+    pub body: usize,
+    offsets: Vec<DVec3>,
+    velocities: Vec<DVec3>,
+    accelerations: Option<Vec<DVec3>>,
+    /// Seconds; converts velocity deviations into lengths for the norm
+    time_scale: f64,
+    pub indicator: ChaosIndicator,
+}
+
+impl TangentVector {
+    pub fn new(body: usize, body_count: usize, time_scale: f64) -> Self {
+        let mut offsets = vec![DVec3::ZERO; body_count];
+        let mut velocities = vec![DVec3::ZERO; body_count];
+        // Fixed, generic direction so runs are reproducible
+        offsets[body] = DVec3::new(0.6, -0.3, 0.2);
+        velocities[body] = DVec3::new(0.2, 0.5, -0.4) / time_scale;
+
+        let mut tangent = Self {
+            body,
+            offsets,
+            velocities,
+            accelerations: None,
+            time_scale,
+            indicator: ChaosIndicator::default(),
+        };
+        tangent.rescale(1.0 / tangent.norm());
+        tangent
+    }
+
+    /// Number of bodies in the system this vector belongs to
+    pub fn body_count(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Phase-space length of the deviation
+    pub fn norm(&self) -> f64 {
+        let positions: f64 = self.offsets.iter().map(|o| o.length_squared()).sum();
+        let velocities: f64 = self.velocities.iter().map(|v| v.length_squared()).sum();
+        (positions + velocities * self.time_scale * self.time_scale).sqrt()
+    }
+
+    /// Advance alongside one velocity-Verlet step of the reference trajectory
+    /// from `old_positions` to `new_positions`
+    pub fn step(
+        &mut self,
+        masses: &[f64],
+        old_positions: &[DVec3],
+        new_positions: &[DVec3],
+        delta_time: f64,
+    ) {
+        let accelerations = self
+            .accelerations
+            .take()
+            .unwrap_or_else(|| variational_accelerations(masses, old_positions, &self.offsets));
+
+        for ((offset, velocity), acceleration) in self
+            .offsets
+            .iter_mut()
+            .zip(&self.velocities)
+            .zip(&accelerations)
+        {
+            *offset += *velocity * delta_time + *acceleration * (0.5 * delta_time * delta_time);
+        }
+
+        let new_accelerations = variational_accelerations(masses, new_positions, &self.offsets);
+        for ((velocity, old), new) in self
+            .velocities
+            .iter_mut()
+            .zip(&accelerations)
+            .zip(&new_accelerations)
+        {
+            *velocity += (*old + *new) * (0.5 * delta_time);
+        }
+        self.accelerations = Some(new_accelerations);
+
+        // The equations are linear, so renormalise every step and keep only the growth
+        let norm = self.norm();
+        if norm > 0.0 && norm.is_finite() {
+            self.indicator.record(norm.ln(), delta_time);
+            self.rescale(1.0 / norm);
+        }
+    }
+
+    fn rescale(&mut self, factor: f64) {
+        self.offsets.iter_mut().for_each(|o| *o *= factor);
+        self.velocities.iter_mut().for_each(|v| *v *= factor);
+        if let Some(accelerations) = &mut self.accelerations {
+            accelerations.iter_mut().for_each(|a| *a *= factor);
+        }
+    }
+}
+
+/// Stand-alone N-body integrator carrying tangent vectors, for headless runs
+#[derive(Debug, Clone)]
+pub struct VariationalIntegrator {
+    pub masses: Vec<f64>,
+    pub positions: Vec<DVec3>,
+    pub velocities: Vec<DVec3>,
+    accelerations: Vec<DVec3>,
+    tangents: Vec<TangentVector>,
+    time: f64,
+}
+
+impl VariationalIntegrator {
+    pub fn new(masses: Vec<f64>, positions: Vec<DVec3>, velocities: Vec<DVec3>) -> Self {
+        let accelerations = nbody_accelerations(&masses, &positions);
+        Self {
+            masses,
+            positions,
+            velocities,
+            accelerations,
+            tangents: Vec::new(),
+            time: 0.0,
+        }
+    }
+
+    /// Start measuring chaos for a body, returning the tangent vector index
+    pub fn track(&mut self, body: usize) -> usize {
+        let time_scale =
+            characteristic_time_scale(&self.masses, &self.positions, &self.velocities, body);
+        self.tangents
+            .push(TangentVector::new(body, self.masses.len(), time_scale));
+        self.tangents.len() - 1
+    }
+
+    pub fn tangents(&self) -> &[TangentVector] {
+        &self.tangents
+    }
+
+    /// Seconds integrated so far
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// One velocity-Verlet step of the bodies and all tangent vectors
+    pub fn step(&mut self, delta_time: f64) {
+        let old_positions = self.positions.clone();
+        for ((position, velocity), acceleration) in self
+            .positions
+            .iter_mut()
+            .zip(&self.velocities)
+            .zip(&self.accelerations)
+        {
+            *position += *velocity * delta_time + *acceleration * (0.5 * delta_time * delta_time);
+        }
+
+        let new_accelerations = nbody_accelerations(&self.masses, &self.positions);
+        for ((velocity, old), new) in self
+            .velocities
+            .iter_mut()
+            .zip(&self.accelerations)
+            .zip(&new_accelerations)
+        {
+            *velocity += (*old + *new) * (0.5 * delta_time);
+        }
+        self.accelerations = new_accelerations;
+
+        for tangent in &mut self.tangents {
+            tangent.step(&self.masses, &old_positions, &self.positions, delta_time);
+        }
+        self.time += delta_time;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variational_matches_finite_difference() {
+        let masses = [2.0e24, 5.0e23, 1.0e23];
+        let positions = [
+            DVec3::ZERO,
+            DVec3::new(1.0e7, 2.0e6, 0.0),
+            DVec3::new(-4.0e6, 8.0e6, 1.0e6),
+        ];
+        let offsets = [
+            DVec3::new(1.0, 0.0, 0.0),
+            DVec3::new(0.0, -2.0, 1.0),
+            DVec3::new(0.5, 0.5, -1.5),
+        ];
+
+        let epsilon = 10.0;
+        let displaced: Vec<DVec3> = positions
+            .iter()
+            .zip(&offsets)
+            .map(|(p, o)| *p + *o * epsilon)
+            .collect();
+        let base = nbody_accelerations(&masses, &positions);
+        let shifted = nbody_accelerations(&masses, &displaced);
+        let linear = variational_accelerations(&masses, &positions, &offsets);
+
+        for i in 0..3 {
+            let numeric = (shifted[i] - base[i]) / epsilon;
+            assert!((numeric - linear[i]).length() < 1e-4 * linear[i].length());
+        }
+    }
+
+    #[test]
+    fn test_kepler_orbit_is_regular() {
+        // Massless particle on an eccentric orbit with G*M = 1
+        let masses = vec![1.0 / GRAVITATIONAL_CONSTANT, 0.0];
+        let eccentricity: f64 = 0.3;
+        let periapsis_speed = (1.0 + eccentricity).sqrt() / (1.0 - eccentricity).sqrt();
+        let mut integrator = VariationalIntegrator::new(
+            masses,
+            vec![DVec3::ZERO, DVec3::new(1.0 - eccentricity, 0.0, 0.0)],
+            vec![DVec3::ZERO, DVec3::new(0.0, periapsis_speed, 0.0)],
+        );
+        integrator.track(1);
+
+        let period = 2.0 * std::f64::consts::PI;
+        while integrator.time() < 50.0 * period {
+            integrator.step(2.0e-3);
+        }
+
+        // Deviations grow linearly, so <Y> tends to 2 and the exponent to zero
+        let indicator = integrator.tangents()[0].indicator;
+        assert!(
+            (indicator.mean_megno() - 2.0).abs() < 0.3,
+            "{}",
+            indicator.mean_megno()
+        );
+        assert!(indicator.lyapunov_exponent() < 0.05);
+    }
+}
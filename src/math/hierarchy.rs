@@ -0,0 +1,224 @@
+/// Orbital hierarchy detection for N-body systems
+/// Works out which body each body orbits using Hill-sphere tests over the current state
+use glam::DVec3;
+
+use crate::math::{Body, GRAVITATIONAL_CONSTANT};
+
+/// Parent/child relationships between bodies (e.g. Sun -> Earth -> Moon)
+///
+/// Indices refer to positions in the body slice the hierarchy was built from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrbitalHierarchy {
+    parents: Vec<Option<usize>>,
+    children: Vec<Vec<usize>>,
+    roots: Vec<usize>,
+    hill_radii: Vec<f64>,
+}
+
+impl OrbitalHierarchy {
+    /// Build the hierarchy from the current state of the simulation bodies
+    pub fn from_bodies(bodies: &[Body]) -> Self {
+        let masses: Vec<f64> = bodies.iter().map(|body| body.mass).collect();
+        let positions: Vec<DVec3> = bodies.iter().map(|body| body.position).collect();
+        let velocities: Vec<DVec3> = bodies.iter().map(|body| body.velocity).collect();
+        Self::from_states(&masses, &positions, &velocities)
+    }
+
+    /// Build the hierarchy from raw masses, positions and velocities
+    ///
+    /// Bodies are visited from heaviest to lightest. Each body is assigned to the
+    /// heavier body with the smallest Hill sphere that contains it; bodies outside
+    /// every Hill sphere become roots. Roots have an unbounded Hill sphere, so a body
+    /// only joins a root when its two-body orbit around it is bound.
+    pub fn from_states(masses: &[f64], positions: &[DVec3], velocities: &[DVec3]) -> Self {
+        let count = masses.len().min(positions.len()).min(velocities.len());
+        let mut parents = vec![None; count];
+        let mut hill_radii = vec![0.0; count];
+        let mut roots = Vec::new();
+
+        let mut order: Vec<usize> = (0..count).collect();
+        order.sort_by(|&a, &b| masses[b].total_cmp(&masses[a]).then(a.cmp(&b)));
+
+        for (rank, &index) in order.iter().enumerate() {
+            let mut best: Option<(usize, f64)> = None;
+
+            for &candidate in &order[..rank] {
+                // Massless and equal-mass bodies cannot capture anything
+                if masses[candidate] <= 0.0 || masses[candidate] <= masses[index] {
+                    continue;
+                }
+
+                let offset = positions[index] - positions[candidate];
+                let distance = offset.length();
+                let hill = hill_radii[candidate];
+                if distance >= hill || best.is_some_and(|(_, best_hill)| hill >= best_hill) {
+                    continue;
+                }
+                if hill.is_infinite() {
+                    let speed = (velocities[index] - velocities[candidate]).length();
+                    let mu = GRAVITATIONAL_CONSTANT * (masses[candidate] + masses[index]);
+                    if distance == 0.0 || 0.5 * speed * speed - mu / distance >= 0.0 {
+                        continue;
+                    }
+                }
+                best = Some((candidate, hill));
+            }
+
+            match best {
+                Some((parent, _)) => {
+                    parents[index] = Some(parent);
+                    hill_radii[index] = Self::hill_radius(
+                        masses[index],
+                        masses[parent],
+                        (positions[index] - positions[parent]).length(),
+                    );
+                }
+                None => {
+                    roots.push(index);
+                    hill_radii[index] = f64::INFINITY;
+                }
+            }
+        }
+
+        let mut children = vec![Vec::new(); count];
+        for (index, parent) in parents.iter().enumerate() {
+            if let Some(parent) = parent {
+                children[*parent].push(index);
+            }
+        }
+        roots.sort_unstable();
+
+        Self {
+            parents,
+            children,
+            roots,
+            hill_radii,
+        }
+    }
+
+    /// Hill radius r_H = a * cbrt(m / 3M) of a body of mass m orbiting mass M at distance a
+    pub fn hill_radius(mass: f64, parent_mass: f64, distance: f64) -> f64 {
+        if parent_mass <= 0.0 {
+            return f64::INFINITY;
+        }
+        distance * (mass / (3.0 * parent_mass)).cbrt()
+    }
+
+    /// Number of bodies covered by the hierarchy
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    /// Check if the hierarchy is empty
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Body this body orbits, if any
+    pub fn parent(&self, index: usize) -> Option<usize> {
+        self.parents.get(index).copied().flatten()
+    }
+
+    /// Bodies orbiting this body directly
+    pub fn children(&self, index: usize) -> &[usize] {
+        self.children.get(index).map_or(&[], Vec::as_slice)
+    }
+
+    /// Bodies that orbit nothing (usually the central star)
+    pub fn roots(&self) -> &[usize] {
+        &self.roots
+    }
+
+    /// Hill radius used for this body (infinite for roots)
+    pub fn hill_radius_of(&self, index: usize) -> Option<f64> {
+        self.hill_radii.get(index).copied()
+    }
+
+    /// Number of ancestors above this body (0 for roots)
+    pub fn depth(&self, index: usize) -> usize {
+        let mut depth = 0;
+        let mut current = self.parent(index);
+        while let Some(parent) = current {
+            depth += 1;
+            current = self.parent(parent);
+        }
+        depth
+    }
+
+    /// Check if two bodies orbit the same parent
+    pub fn are_siblings(&self, a: usize, b: usize) -> bool {
+        a != b && self.parent(a).is_some() && self.parent(a) == self.parent(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{AU_TO_METERS, EARTH_MASS, SOLAR_MASS};
+
+    /// Circular speed around a mass at a distance
+    fn circular_speed(mass: f64, distance: f64) -> f64 {
+        (crate::math::GRAVITATIONAL_CONSTANT * mass / distance).sqrt()
+    }
+
+    #[test]
+    fn test_sun_earth_moon_hierarchy() {
+        let masses = [EARTH_MASS, SOLAR_MASS, 7.342e22, 1.898e27];
+        let positions = [
+            DVec3::new(AU_TO_METERS, 0.0, 0.0),
+            DVec3::ZERO,
+            DVec3::new(AU_TO_METERS + 3.844e8, 0.0, 0.0),
+            DVec3::new(0.0, 5.2 * AU_TO_METERS, 0.0),
+        ];
+        let earth_speed = circular_speed(SOLAR_MASS, AU_TO_METERS);
+        let velocities = [
+            DVec3::new(0.0, earth_speed, 0.0),
+            DVec3::ZERO,
+            DVec3::new(0.0, earth_speed + circular_speed(EARTH_MASS, 3.844e8), 0.0),
+            DVec3::new(-circular_speed(SOLAR_MASS, 5.2 * AU_TO_METERS), 0.0, 0.0),
+        ];
+
+        let hierarchy = OrbitalHierarchy::from_states(&masses, &positions, &velocities);
+
+        assert_eq!(hierarchy.roots(), &[1]);
+        assert_eq!(hierarchy.parent(0), Some(1));
+        assert_eq!(hierarchy.parent(2), Some(0));
+        assert_eq!(hierarchy.parent(3), Some(1));
+        assert_eq!(hierarchy.children(1), &[0, 3]);
+        assert_eq!(hierarchy.depth(2), 2);
+        assert!(hierarchy.are_siblings(0, 3));
+    }
+
+    #[test]
+    fn test_only_bound_bodies_join_a_root() {
+        // A passing star, a planet on a circular orbit, a hyperbolic flyby and a
+        // massless probe next to a massless marker
+        let masses = [SOLAR_MASS, SOLAR_MASS * 0.5, EARTH_MASS, 1.0e15, 0.0, 0.0];
+        let positions = [
+            DVec3::ZERO,
+            DVec3::new(1.0e6 * AU_TO_METERS, 0.0, 0.0),
+            DVec3::new(AU_TO_METERS, 0.0, 0.0),
+            DVec3::new(0.0, 2.0 * AU_TO_METERS, 0.0),
+            DVec3::new(AU_TO_METERS + 1.0e7, 0.0, 0.0),
+            DVec3::new(AU_TO_METERS + 1.0e7 + 1.0, 0.0, 0.0),
+        ];
+        let earth_speed = circular_speed(SOLAR_MASS, AU_TO_METERS);
+        let velocities = [
+            DVec3::ZERO,
+            DVec3::new(0.0, 1000.0, 0.0),
+            DVec3::new(0.0, earth_speed, 0.0),
+            DVec3::new(2.0 * earth_speed, 0.0, 0.0),
+            DVec3::new(0.0, earth_speed, 0.0),
+            DVec3::new(0.0, earth_speed, 0.0),
+        ];
+
+        let hierarchy = OrbitalHierarchy::from_states(&masses, &positions, &velocities);
+
+        // The passing star and the flyby are not bound to the heavier star
+        assert_eq!(hierarchy.roots(), &[0, 1, 3]);
+        assert_eq!(hierarchy.parent(2), Some(0));
+        // Massless bodies orbit the planet, never each other
+        assert_eq!(hierarchy.parent(4), Some(2));
+        assert_eq!(hierarchy.parent(5), Some(2));
+    }
+}
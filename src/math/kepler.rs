@@ -0,0 +1,315 @@
+/// Two-body (Keplerian) orbit propagation
+/// Universal-variable formulation, valid for elliptic, parabolic and hyperbolic orbits
+use glam::DVec3;
+
+/// Stumpff function C(z)
+pub fn stumpff_c(z: f64) -> f64 {
+    if z > 1e-6 {
+        (1.0 - z.sqrt().cos()) / z
+    } else if z < -1e-6 {
+        ((-z).sqrt().cosh() - 1.0) / -z
+    } else {
+        // Series expansion near zero avoids catastrophic cancellation
+        1.0 / 2.0 - z / 24.0 + z * z / 720.0
+    }
+}
+
+/// Stumpff function S(z)
+pub fn stumpff_s(z: f64) -> f64 {
+    if z > 1e-6 {
+        let root = z.sqrt();
+        (root - root.sin()) / (root * root * root)
+    } else if z < -1e-6 {
+        let root = (-z).sqrt();
+        (root.sinh() - root) / (root * root * root)
+    } else {
+        1.0 / 6.0 - z / 120.0 + z * z / 5040.0
+    }
+}
+
+/// Propagate a state relative to a central body of gravitational parameter `mu`
+/// (G * M, m^3/s^2) by `delta_time` seconds, which may be negative.
+///
+/// Returns the new relative position and velocity.
+pub fn propagate_kepler(
+    position: DVec3,
+    velocity: DVec3,
+    mu: f64,
+    delta_time: f64,
+) -> (DVec3, DVec3) {
+    let r0 = position.length();
+    if r0 == 0.0 || mu <= 0.0 || delta_time == 0.0 {
+        return (position, velocity);
+    }
+
+    let sqrt_mu = mu.sqrt();
+    let radial_velocity = position.dot(velocity) / r0;
+    // Reciprocal of the semi-major axis (negative for hyperbolic orbits)
+    let alpha = 2.0 / r0 - velocity.length_squared() / mu;
+
+    // Solve the universal Kepler equation for chi with Newton's method
+    let mut chi = sqrt_mu * alpha.abs() * delta_time;
+    if alpha.abs() < 1e-12 || !chi.is_finite() || chi == 0.0 {
+        chi = sqrt_mu * delta_time / r0;
+    }
+    for _ in 0..100 {
+        let z = alpha * chi * chi;
+        let c = stumpff_c(z);
+        let s = stumpff_s(z);
+        let f = r0 * radial_velocity / sqrt_mu * chi * chi * c
+            + (1.0 - alpha * r0) * chi * chi * chi * s
+            + r0 * chi
+            - sqrt_mu * delta_time;
+        let derivative = r0 * radial_velocity / sqrt_mu * chi * (1.0 - z * s)
+            + (1.0 - alpha * r0) * chi * chi * c
+            + r0;
+        let step = f / derivative;
+        chi -= step;
+        if step.abs() < 1e-12 * chi.abs().max(1.0) {
+            break;
+        }
+    }
+
+    // Lagrange coefficients
+    let z = alpha * chi * chi;
+    let c = stumpff_c(z);
+    let s = stumpff_s(z);
+    let f = 1.0 - chi * chi / r0 * c;
+    let g = delta_time - chi * chi * chi * s / sqrt_mu;
+    let new_position = position * f + velocity * g;
+    let r = new_position.length();
+    let f_dot = sqrt_mu / (r * r0) * (z * chi * s - chi);
+    let g_dot = 1.0 - chi * chi / r * c;
+    let new_velocity = position * f_dot + velocity * g_dot;
+
+    (new_position, new_velocity)
+}
+
+/// Relative state on a conic given its periapsis distance, eccentricity and orientation
+/// (angles in radians, reference plane xy) at `time_since_periapsis` seconds.
+///
+/// Ellipses are solved through Kepler's equation so long spans do not lose accuracy;
+/// parabolas and hyperbolas start at periapsis and use the universal-variable propagator.
+#[allow(clippy::too_many_arguments)]
+pub fn conic_state(
+    periapsis_distance: f64,
+    eccentricity: f64,
+    inclination: f64,
+    longitude_of_node: f64,
+    argument_of_periapsis: f64,
+    time_since_periapsis: f64,
+    mu: f64,
+) -> (DVec3, DVec3) {
+    let (sin_node, cos_node) = longitude_of_node.sin_cos();
+    let (sin_arg, cos_arg) = argument_of_periapsis.sin_cos();
+    let (sin_inc, cos_inc) = inclination.sin_cos();
+    // Unit vectors towards periapsis and 90° ahead of it in the orbital plane
+    let p = DVec3::new(
+        cos_node * cos_arg - sin_node * sin_arg * cos_inc,
+        sin_node * cos_arg + cos_node * sin_arg * cos_inc,
+        sin_arg * sin_inc,
+    );
+    let q = DVec3::new(
+        -cos_node * sin_arg - sin_node * cos_arg * cos_inc,
+        -sin_node * sin_arg + cos_node * cos_arg * cos_inc,
+        cos_arg * sin_inc,
+    );
+
+    if eccentricity < 1.0 {
+        let a = periapsis_distance / (1.0 - eccentricity);
+        let mean_motion = (mu / (a * a * a)).sqrt();
+        let tau = std::f64::consts::TAU;
+        let mean_anomaly = (mean_motion * time_since_periapsis + std::f64::consts::PI)
+            .rem_euclid(tau)
+            - std::f64::consts::PI;
+        let mut e_anomaly = if eccentricity > 0.8 {
+            std::f64::consts::PI.copysign(mean_anomaly)
+        } else {
+            mean_anomaly
+        };
+        for _ in 0..50 {
+            let step = (e_anomaly - eccentricity * e_anomaly.sin() - mean_anomaly)
+                / (1.0 - eccentricity * e_anomaly.cos());
+            e_anomaly -= step;
+            if step.abs() < 1e-14 {
+                break;
+            }
+        }
+        let (sin_e, cos_e) = e_anomaly.sin_cos();
+        let root = (1.0 - eccentricity * eccentricity).sqrt();
+        let r = a * (1.0 - eccentricity * cos_e);
+        let position = p * (a * (cos_e - eccentricity)) + q * (a * root * sin_e);
+        let velocity = (p * -sin_e + q * (root * cos_e)) * ((mu * a).sqrt() / r);
+        (position, velocity)
+    } else {
+        let speed = (mu * (1.0 + eccentricity) / periapsis_distance).sqrt();
+        propagate_kepler(p * periapsis_distance, q * speed, mu, time_since_periapsis)
+    }
+}
+
+/// Semi-major axis of the orbit through the given relative state (negative if hyperbolic)
+pub fn semi_major_axis(position: DVec3, velocity: DVec3, mu: f64) -> f64 {
+    1.0 / (2.0 / position.length() - velocity.length_squared() / mu)
+}
+
+/// Eccentricity vector of the orbit through the given relative state
+pub fn eccentricity_vector(position: DVec3, velocity: DVec3, mu: f64) -> DVec3 {
+    let r = position.length();
+    (position * (velocity.length_squared() - mu / r) - velocity * position.dot(velocity)) / mu
+}
+
+/// Classical elements of a bound orbit, with angles measured from the x axis of the
+/// reference (xy) plane
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitalElements {
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    pub inclination: f64,
+    /// Longitude of the ascending node, Ω
+    pub longitude_of_node: f64,
+    /// Longitude of periapsis, ϖ = Ω + ω
+    pub longitude_of_periapsis: f64,
+    /// Mean longitude, λ = ϖ + M
+    pub mean_longitude: f64,
+    /// Mean motion n (rad/s)
+    pub mean_motion: f64,
+}
+
+impl OrbitalElements {
+    /// Elements of the orbit through a relative state; `None` unless it is a bound ellipse
+    pub fn from_state(position: DVec3, velocity: DVec3, mu: f64) -> Option<Self> {
+        let angular_momentum = position.cross(velocity);
+        let normal = angular_momentum.try_normalize()?;
+        let semi_major_axis = semi_major_axis(position, velocity, mu);
+        if !(semi_major_axis > 0.0 && semi_major_axis.is_finite()) {
+            return None;
+        }
+        let eccentricity_vector = eccentricity_vector(position, velocity, mu);
+        let eccentricity = eccentricity_vector.length();
+        if eccentricity >= 1.0 {
+            return None;
+        }
+
+        // Node line, or the x axis for orbits in the reference plane
+        let node = DVec3::Z.cross(normal).try_normalize();
+        let longitude_of_node = node.map_or(0.0, |node| node.y.atan2(node.x));
+        let node = node.unwrap_or(DVec3::X);
+        let in_plane = normal.cross(node);
+        let angle_from_node = |vector: DVec3| vector.dot(in_plane).atan2(vector.dot(node));
+
+        let argument_of_latitude = angle_from_node(position);
+        let argument_of_periapsis = if eccentricity > 1e-12 {
+            angle_from_node(eccentricity_vector)
+        } else {
+            0.0
+        };
+        let true_anomaly = argument_of_latitude - argument_of_periapsis;
+        let eccentric_anomaly = ((1.0 - eccentricity * eccentricity).sqrt() * true_anomaly.sin())
+            .atan2(eccentricity + true_anomaly.cos());
+        let mean_anomaly = eccentric_anomaly - eccentricity * eccentric_anomaly.sin();
+
+        let tau = std::f64::consts::TAU;
+        let longitude_of_periapsis = longitude_of_node + argument_of_periapsis;
+        Some(Self {
+            semi_major_axis,
+            eccentricity,
+            inclination: normal.z.clamp(-1.0, 1.0).acos(),
+            longitude_of_node: longitude_of_node.rem_euclid(tau),
+            longitude_of_periapsis: longitude_of_periapsis.rem_euclid(tau),
+            mean_longitude: (longitude_of_periapsis + mean_anomaly).rem_euclid(tau),
+            mean_motion: (mu / semi_major_axis.powi(3)).sqrt(),
+        })
+    }
+
+    /// Orbital period (s)
+    pub fn period(&self) -> f64 {
+        std::f64::consts::TAU / self.mean_motion
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circular_orbit_returns_after_one_period() {
+        let mu: f64 = 3.986004418e14;
+        let radius = 7.0e6;
+        let speed = (mu / radius).sqrt();
+        let period = 2.0 * std::f64::consts::PI * (radius * radius * radius / mu).sqrt();
+
+        let start = DVec3::new(radius, 0.0, 0.0);
+        let start_velocity = DVec3::new(0.0, speed, 0.0);
+
+        let (quarter, _) = propagate_kepler(start, start_velocity, mu, period / 4.0);
+        assert!((quarter - DVec3::new(0.0, radius, 0.0)).length() < 1e-3);
+
+        let (end, end_velocity) = propagate_kepler(start, start_velocity, mu, period);
+        assert!((end - start).length() < 1e-3);
+        assert!((end_velocity - start_velocity).length() < 1e-6);
+
+        // Propagating backwards undoes a forward step
+        let (back, back_velocity) =
+            propagate_kepler(quarter, DVec3::new(-speed, 0.0, 0.0), mu, -period / 4.0);
+        assert!((back - start).length() < 1e-3);
+        assert!((back_velocity - start_velocity).length() < 1e-6);
+    }
+
+    #[test]
+    fn test_hyperbolic_energy_is_conserved() {
+        let mu: f64 = 1.0;
+        let position = DVec3::new(1.0, 0.0, 0.0);
+        let velocity = DVec3::new(0.0, 2.0, 0.0);
+        let energy = |r: DVec3, v: DVec3| v.length_squared() / 2.0 - mu / r.length();
+
+        let (r, v) = propagate_kepler(position, velocity, mu, 10.0);
+        assert!((energy(r, v) - energy(position, velocity)).abs() < 1e-9);
+        assert!(semi_major_axis(position, velocity, mu) < 0.0);
+        assert!((eccentricity_vector(position, velocity, mu).length() - 3.0).abs() < 1e-12);
+        assert!(OrbitalElements::from_state(position, velocity, mu).is_none());
+    }
+
+    #[test]
+    fn test_orbital_elements_longitudes() {
+        let mu: f64 = 1.0;
+        let eccentricity: f64 = 0.2;
+        let periapsis_angle: f64 = 1.0;
+        let direction = DVec3::new(periapsis_angle.cos(), periapsis_angle.sin(), 0.0);
+        let speed = ((1.0 + eccentricity) / (1.0 - eccentricity)).sqrt();
+        let position = direction * (1.0 - eccentricity);
+        let velocity = DVec3::Z.cross(direction) * speed;
+
+        // At periapsis the mean longitude equals the longitude of periapsis
+        let elements = OrbitalElements::from_state(position, velocity, mu).unwrap();
+        assert!((elements.semi_major_axis - 1.0).abs() < 1e-12);
+        assert!((elements.eccentricity - eccentricity).abs() < 1e-12);
+        assert!((elements.longitude_of_periapsis - periapsis_angle).abs() < 1e-9);
+        assert!((elements.mean_longitude - periapsis_angle).abs() < 1e-9);
+        assert!((elements.period() - std::f64::consts::TAU).abs() < 1e-9);
+
+        // Mean longitude advances uniformly with time
+        let (later_position, later_velocity) = propagate_kepler(position, velocity, mu, 1.5);
+        let later = OrbitalElements::from_state(later_position, later_velocity, mu).unwrap();
+        assert!((later.mean_longitude - (periapsis_angle + 1.5)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_conic_state_matches_elements_and_propagation() {
+        let mu = 1.0;
+        let (inclination, node, argument) = (0.3, 1.1, -0.7);
+        let (position, velocity) = conic_state(0.6, 0.4, inclination, node, argument, 2.5, mu);
+
+        let elements = OrbitalElements::from_state(position, velocity, mu).unwrap();
+        assert!((elements.semi_major_axis - 1.0).abs() < 1e-12);
+        assert!((elements.eccentricity - 0.4).abs() < 1e-12);
+        assert!((elements.inclination - inclination).abs() < 1e-12);
+        assert!((elements.longitude_of_node - node).abs() < 1e-12);
+
+        // A hyperbola starts at periapsis and goes through the universal propagator
+        let (periapsis, periapsis_velocity) = conic_state(0.6, 1.4, 0.0, 0.0, 0.0, 0.0, mu);
+        assert!((periapsis - DVec3::new(0.6, 0.0, 0.0)).length() < 1e-12);
+        let (later, _) = conic_state(0.6, 1.4, 0.0, 0.0, 0.0, 3.0, mu);
+        let (expected, _) = propagate_kepler(periapsis, periapsis_velocity, mu, 3.0);
+        assert!((later - expected).length() < 1e-9);
+    }
+}
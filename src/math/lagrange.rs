@@ -0,0 +1,194 @@
+/// Lagrange point computation for a two-body pair
+/// Uses the circular restricted three-body approximation on the pair's current state
+use glam::DVec3;
+
+use crate::math::{Body, FrameTransform, ReferenceFrame};
+
+/// Names of the five points, in the order they are stored
+pub const LAGRANGE_POINT_NAMES: [&str; 5] = ["L1", "L2", "L3", "L4", "L5"];
+
+/// L1-L5 of a primary/secondary pair at one instant
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LagrangePoints {
+    /// World positions of L1..L5
+    pub positions: [DVec3; 5],
+    /// World position of the secondary the points were computed from
+    pub secondary_position: DVec3,
+    /// Co-rotating frame of the pair, used to derive velocities
+    frame: FrameTransform,
+}
+
+impl LagrangePoints {
+    /// Compute the points for two bodies of a simulation
+    pub fn for_pair(bodies: &[Body], primary: usize, secondary: usize) -> Option<Self> {
+        let primary_body = bodies.get(primary)?;
+        let secondary_body = bodies.get(secondary)?;
+        Self::compute(
+            primary_body.mass,
+            primary_body.position,
+            primary_body.velocity,
+            secondary_body.mass,
+            secondary_body.position,
+            secondary_body.velocity,
+        )
+    }
+
+    /// Compute the points from the pair's masses and state vectors
+    ///
+    /// Returns `None` if the pair is degenerate (no separation, no relative
+    /// angular momentum or no mass).
+    pub fn compute(
+        primary_mass: f64,
+        primary_position: DVec3,
+        primary_velocity: DVec3,
+        secondary_mass: f64,
+        secondary_position: DVec3,
+        secondary_velocity: DVec3,
+    ) -> Option<Self> {
+        let total_mass = primary_mass + secondary_mass;
+        let separation = (secondary_position - primary_position).length();
+        if total_mass <= 0.0 || separation == 0.0 {
+            return None;
+        }
+
+        let frame = ReferenceFrame::CoRotating {
+            primary: 0,
+            secondary: 1,
+        }
+        .transform_from_states(
+            &[primary_mass, secondary_mass],
+            &[primary_position, secondary_position],
+            &[primary_velocity, secondary_velocity],
+        );
+        if frame.angular_velocity == DVec3::ZERO {
+            return None;
+        }
+
+        // Normalised rotating frame: primary at -mu, secondary at 1 - mu on the x axis
+        let mu = secondary_mass / total_mass;
+        let collinear = Self::collinear_points(mu);
+        let half_root_three = 3.0_f64.sqrt() / 2.0;
+        let normalised = [
+            DVec3::new(collinear[0], 0.0, 0.0),
+            DVec3::new(collinear[1], 0.0, 0.0),
+            DVec3::new(collinear[2], 0.0, 0.0),
+            DVec3::new(0.5 - mu, half_root_three, 0.0),
+            DVec3::new(0.5 - mu, -half_root_three, 0.0),
+        ];
+
+        let positions = normalised.map(|point| frame.position_to_world(point * separation));
+
+        Some(Self {
+            positions,
+            secondary_position,
+            frame,
+        })
+    }
+
+    /// Velocity a body at the given point needs to co-rotate with the pair
+    pub fn velocity(&self, index: usize) -> DVec3 {
+        let position = self.positions[index];
+        self.frame
+            .velocity_to_world(self.frame.position_to_frame(position), DVec3::ZERO)
+    }
+
+    /// Distance from the secondary to the given point
+    pub fn distance_from_secondary(&self, index: usize) -> f64 {
+        (self.positions[index] - self.secondary_position).length()
+    }
+
+    /// Positions of L1, L2 and L3 along the normalised x axis
+    fn collinear_points(mu: f64) -> [f64; 3] {
+        // Hill-sphere estimate is a good starting guess for L1/L2 when mu is small
+        let hill = (mu / 3.0).cbrt();
+        [
+            Self::solve_collinear(mu, 1.0 - mu - hill, -mu, 1.0 - mu),
+            Self::solve_collinear(mu, 1.0 - mu + hill, 1.0 - mu, f64::INFINITY),
+            Self::solve_collinear(mu, -1.0 - 5.0 * mu / 12.0, f64::NEG_INFINITY, -mu),
+        ]
+    }
+
+    /// Solve the collinear equilibrium condition with Newton's method, kept inside (lower, upper)
+    fn solve_collinear(mu: f64, guess: f64, lower: f64, upper: f64) -> f64 {
+        let r1_sign = |x: f64| (x + mu).signum();
+        let r2_sign = |x: f64| (x - 1.0 + mu).signum();
+
+        // Net acceleration along x in the rotating frame and its derivative
+        let acceleration = |x: f64| {
+            let d1 = (x + mu).abs();
+            let d2 = (x - 1.0 + mu).abs();
+            x - (1.0 - mu) * r1_sign(x) / (d1 * d1) - mu * r2_sign(x) / (d2 * d2)
+        };
+        let derivative = |x: f64| {
+            let d1 = (x + mu).abs();
+            let d2 = (x - 1.0 + mu).abs();
+            1.0 + 2.0 * (1.0 - mu) / (d1 * d1 * d1) + 2.0 * mu / (d2 * d2 * d2)
+        };
+
+        let span = 1e-12;
+        let mut x = guess.clamp(lower + span, upper - span);
+        for _ in 0..100 {
+            let step = acceleration(x) / derivative(x);
+            let next = (x - step).clamp(lower + span, upper - span);
+            if (next - x).abs() < 1e-15 {
+                return next;
+            }
+            x = next;
+        }
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{AU_TO_METERS, EARTH_MASS, GRAVITATIONAL_CONSTANT, SOLAR_MASS};
+
+    fn sun_earth() -> LagrangePoints {
+        let earth_speed =
+            (GRAVITATIONAL_CONSTANT * (SOLAR_MASS + EARTH_MASS) / AU_TO_METERS).sqrt();
+        LagrangePoints::compute(
+            SOLAR_MASS,
+            DVec3::ZERO,
+            DVec3::ZERO,
+            EARTH_MASS,
+            DVec3::new(AU_TO_METERS, 0.0, 0.0),
+            DVec3::new(0.0, earth_speed, 0.0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_sun_earth_collinear_points() {
+        let points = sun_earth();
+        let earth = DVec3::new(AU_TO_METERS, 0.0, 0.0);
+
+        // L1 and L2 sit about 1.5 million km either side of Earth
+        let l1 = (points.positions[0] - earth).length();
+        let l2 = (points.positions[1] - earth).length();
+        assert!((l1 / 1.5e9 - 1.0).abs() < 0.02, "L1 distance {l1}");
+        assert!((l2 / 1.5e9 - 1.0).abs() < 0.02, "L2 distance {l2}");
+        assert!(points.positions[0].x < earth.x && points.positions[1].x > earth.x);
+
+        // L3 is on the far side of the Sun at roughly 1 AU
+        assert!((points.positions[2].x / -AU_TO_METERS - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_triangular_points_lead_and_trail() {
+        let points = sun_earth();
+        let earth = DVec3::new(AU_TO_METERS, 0.0, 0.0);
+
+        for index in [3, 4] {
+            let to_earth = (points.positions[index] - earth).length();
+            assert!((to_earth / AU_TO_METERS - 1.0).abs() < 1e-3);
+        }
+        // L4 leads Earth along its direction of motion
+        assert!(points.positions[3].y > 0.0 && points.positions[4].y < 0.0);
+
+        // A body placed at L4 moves at Earth's orbital speed
+        let earth_speed =
+            (GRAVITATIONAL_CONSTANT * (SOLAR_MASS + EARTH_MASS) / AU_TO_METERS).sqrt();
+        assert!((points.velocity(3).length() / earth_speed - 1.0).abs() < 1e-3);
+    }
+}
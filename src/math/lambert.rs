@@ -0,0 +1,284 @@
+/// Lambert problem solver and transfer planning
+/// Finds the two-body orbit connecting two positions in a given time of flight
+use glam::DVec3;
+
+use crate::math::kepler::{
+    eccentricity_vector, propagate_kepler, semi_major_axis, stumpff_c, stumpff_s,
+};
+
+/// Solve Lambert's problem for a single-revolution transfer
+///
+/// `r1` and `r2` are positions relative to the central body, `mu` its gravitational
+/// parameter. The transfer goes the way round that is prograde about `orbit_normal`.
+/// Returns the velocities at departure and arrival, or `None` if no solution exists.
+pub fn solve_lambert(
+    r1: DVec3,
+    r2: DVec3,
+    time_of_flight: f64,
+    mu: f64,
+    orbit_normal: DVec3,
+) -> Option<(DVec3, DVec3)> {
+    let r1_length = r1.length();
+    let r2_length = r2.length();
+    if time_of_flight <= 0.0 || mu <= 0.0 || r1_length == 0.0 || r2_length == 0.0 {
+        return None;
+    }
+
+    // Short way if the sweep r1 -> r2 is prograde, long way otherwise
+    let cos_angle = (r1.dot(r2) / (r1_length * r2_length)).clamp(-1.0, 1.0);
+    let short_way = r1.cross(r2).dot(orbit_normal) >= 0.0;
+    let sign = if short_way { 1.0 } else { -1.0 };
+    let a = sign * (r1_length * r2_length * (1.0 + cos_angle)).sqrt();
+    if a.abs() < 1e-12 * (r1_length + r2_length) {
+        return None; // 180 degree transfer: plane undefined
+    }
+
+    let y = |z: f64| r1_length + r2_length + a * (z * stumpff_s(z) - 1.0) / stumpff_c(z).sqrt();
+    let flight_time = |z: f64, y: f64| {
+        let x = (y / stumpff_c(z)).sqrt();
+        (x * x * x * stumpff_s(z) + a * y.sqrt()) / mu.sqrt()
+    };
+
+    // Time of flight increases monotonically with z, so bisect
+    let mut lower = -1.0e4;
+    let mut upper = 4.0 * std::f64::consts::PI * std::f64::consts::PI - 1e-9;
+    let mut z = 0.0;
+    for _ in 0..200 {
+        z = 0.5 * (lower + upper);
+        let y_value = y(z);
+        if y_value < 0.0 || flight_time(z, y_value) < time_of_flight {
+            lower = z;
+        } else {
+            upper = z;
+        }
+        if upper - lower < 1e-12 {
+            break;
+        }
+    }
+
+    let y_value = y(z);
+    if y_value <= 0.0 || !y_value.is_finite() {
+        return None;
+    }
+    // No solution if the bisection hit the limits of the bracket
+    let achieved = flight_time(z, y_value);
+    if ((achieved - time_of_flight) / time_of_flight).abs() > 1e-6 {
+        return None;
+    }
+
+    // Lagrange coefficients
+    let f = 1.0 - y_value / r1_length;
+    let g = a * (y_value / mu).sqrt();
+    let g_dot = 1.0 - y_value / r2_length;
+
+    let v1 = (r2 - r1 * f) / g;
+    let v2 = (r2 * g_dot - r1) / g;
+    Some((v1, v2))
+}
+
+/// A transfer between two bodies orbiting the same central body
+///
+/// All positions and velocities are relative to the central body; times are
+/// seconds from the epoch the initial states were taken at.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transfer {
+    pub departure_time: f64,
+    pub time_of_flight: f64,
+    /// Transfer orbit state just after departure
+    pub departure_position: DVec3,
+    pub departure_velocity: DVec3,
+    /// Transfer orbit state just before arrival
+    pub arrival_position: DVec3,
+    pub arrival_velocity: DVec3,
+    /// Velocity change needed to leave the departure body's orbit
+    pub departure_delta_v: DVec3,
+    /// Velocity change needed to match the arrival body's orbit
+    pub arrival_delta_v: DVec3,
+    /// Gravitational parameter of the central body
+    pub mu: f64,
+}
+
+impl Transfer {
+    /// Plan a transfer from the departure body to the arrival body
+    ///
+    /// Both bodies are propagated on Kepler orbits from their current relative
+    /// states to the departure and arrival times.
+    pub fn plan(
+        mu: f64,
+        departure_state: (DVec3, DVec3),
+        arrival_state: (DVec3, DVec3),
+        departure_time: f64,
+        time_of_flight: f64,
+    ) -> Option<Self> {
+        let (r1, v_body1) =
+            propagate_kepler(departure_state.0, departure_state.1, mu, departure_time);
+        let (r2, v_body2) = propagate_kepler(
+            arrival_state.0,
+            arrival_state.1,
+            mu,
+            departure_time + time_of_flight,
+        );
+
+        let orbit_normal = departure_state.0.cross(departure_state.1);
+        let (v1, v2) = solve_lambert(r1, r2, time_of_flight, mu, orbit_normal)?;
+
+        Some(Self {
+            departure_time,
+            time_of_flight,
+            departure_position: r1,
+            departure_velocity: v1,
+            arrival_position: r2,
+            arrival_velocity: v2,
+            departure_delta_v: v1 - v_body1,
+            arrival_delta_v: v_body2 - v2,
+            mu,
+        })
+    }
+
+    /// Sum of departure and arrival delta-v magnitudes (m/s)
+    pub fn total_delta_v(&self) -> f64 {
+        self.departure_delta_v.length() + self.arrival_delta_v.length()
+    }
+
+    /// Position and velocity on the transfer orbit at any time (may be before departure)
+    pub fn state_at(&self, time: f64) -> (DVec3, DVec3) {
+        propagate_kepler(
+            self.departure_position,
+            self.departure_velocity,
+            self.mu,
+            time - self.departure_time,
+        )
+    }
+
+    /// Semi-major axis of the transfer orbit (negative if hyperbolic)
+    pub fn semi_major_axis(&self) -> f64 {
+        semi_major_axis(self.departure_position, self.departure_velocity, self.mu)
+    }
+
+    /// Eccentricity of the transfer orbit
+    pub fn eccentricity(&self) -> f64 {
+        eccentricity_vector(self.departure_position, self.departure_velocity, self.mu).length()
+    }
+}
+
+/// Total delta-v over a grid of departure and arrival times
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PorkchopPlot {
+    pub departure_times: Vec<f64>,
+    pub arrival_times: Vec<f64>,
+    /// Row-major by arrival time; NaN where no transfer exists
+    pub delta_v: Vec<f64>,
+}
+
+impl PorkchopPlot {
+    /// Sweep `steps` x `steps` departure and arrival times over the given ranges
+    pub fn compute(
+        mu: f64,
+        departure_state: (DVec3, DVec3),
+        arrival_state: (DVec3, DVec3),
+        departure_range: (f64, f64),
+        arrival_range: (f64, f64),
+        steps: usize,
+    ) -> Self {
+        let sweep = |(start, end): (f64, f64)| -> Vec<f64> {
+            (0..steps)
+                .map(|i| start + (end - start) * i as f64 / (steps.max(2) - 1) as f64)
+                .collect()
+        };
+        let departure_times = sweep(departure_range);
+        let arrival_times = sweep(arrival_range);
+
+        let mut delta_v = Vec::with_capacity(steps * steps);
+        for &arrival in &arrival_times {
+            for &departure in &departure_times {
+                let total = Transfer::plan(
+                    mu,
+                    departure_state,
+                    arrival_state,
+                    departure,
+                    arrival - departure,
+                )
+                .map_or(f64::NAN, |transfer| transfer.total_delta_v());
+                delta_v.push(total);
+            }
+        }
+
+        Self {
+            departure_times,
+            arrival_times,
+            delta_v,
+        }
+    }
+
+    /// Total delta-v at a grid cell, if a transfer exists there
+    pub fn get(&self, departure_index: usize, arrival_index: usize) -> Option<f64> {
+        self.delta_v
+            .get(arrival_index * self.departure_times.len() + departure_index)
+            .copied()
+            .filter(|value| value.is_finite())
+    }
+
+    /// Cheapest cell as (departure index, arrival index, delta-v)
+    pub fn minimum(&self) -> Option<(usize, usize, f64)> {
+        let width = self.departure_times.len();
+        self.delta_v
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| value.is_finite())
+            .min_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(index, value)| (index % width, index / width, *value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{AU_TO_METERS, GRAVITATIONAL_CONSTANT, SOLAR_MASS};
+
+    #[test]
+    fn test_lambert_reproduces_kepler_arc() {
+        let mu = GRAVITATIONAL_CONSTANT * SOLAR_MASS;
+        let r1 = DVec3::new(AU_TO_METERS, 0.0, 0.0);
+        let v1 = DVec3::new(0.0, 33.0e3, 1.0e3);
+        let time_of_flight = 120.0 * 86400.0;
+        let (r2, v2) = propagate_kepler(r1, v1, mu, time_of_flight);
+
+        let (solved_v1, solved_v2) =
+            solve_lambert(r1, r2, time_of_flight, mu, r1.cross(v1)).unwrap();
+        assert!((solved_v1 - v1).length() < 1e-3, "{solved_v1} vs {v1}");
+        assert!((solved_v2 - v2).length() < 1e-3, "{solved_v2} vs {v2}");
+    }
+
+    #[test]
+    fn test_hohmann_transfer_delta_v() {
+        let mu = GRAVITATIONAL_CONSTANT * SOLAR_MASS;
+        let inner = AU_TO_METERS;
+        let outer = 1.524 * AU_TO_METERS;
+        let circular = |radius: f64| (mu / radius).sqrt();
+        let transfer_time = std::f64::consts::PI * (((inner + outer) / 2.0).powi(3) / mu).sqrt();
+
+        // Place the outer body so it arrives at the apoapsis 180 degrees away
+        // (slightly less than 180, which leaves the transfer plane defined)
+        let angle_at_arrival = std::f64::consts::PI - 1e-4;
+        let outer_angle = angle_at_arrival - transfer_time * circular(outer) / outer;
+        let departure = (
+            DVec3::new(inner, 0.0, 0.0),
+            DVec3::new(0.0, circular(inner), 0.0),
+        );
+        let arrival = (
+            DVec3::new(outer_angle.cos(), outer_angle.sin(), 0.0) * outer,
+            DVec3::new(-outer_angle.sin(), outer_angle.cos(), 0.0) * circular(outer),
+        );
+
+        let transfer = Transfer::plan(mu, departure, arrival, 0.0, transfer_time).unwrap();
+
+        // Classic Earth-Mars Hohmann figures: about 2.9 km/s and 2.6 km/s
+        assert!((transfer.departure_delta_v.length() / 2945.0 - 1.0).abs() < 0.02);
+        assert!((transfer.arrival_delta_v.length() / 2649.0 - 1.0).abs() < 0.02);
+        assert!((transfer.semi_major_axis() / ((inner + outer) / 2.0) - 1.0).abs() < 1e-3);
+
+        // The transfer orbit passes back through the departure point
+        let (start, _) = transfer.state_at(0.0);
+        assert!((start - departure.0).length() < 1.0);
+    }
+}
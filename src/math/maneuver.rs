@@ -0,0 +1,157 @@
+/// Impulsive spacecraft maneuvers
+/// Burns are given in the prograde/normal/radial frame of the craft's orbit
+/// around a reference body and applied as instantaneous velocity changes
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use glam::DVec3;
+
+static NEXT_MANEUVER_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A scheduled impulsive burn
+#[derive(Debug, Clone)]
+pub struct Maneuver {
+    /// Identifies the node across copies of the maneuver list while the app runs
+    pub id: u64,
+    /// Simulation time of the burn in seconds
+    pub time: f64,
+    /// Name of the body the burn directions are measured against
+    pub reference_body: String,
+    /// Delta-v along the velocity relative to the reference body (m/s)
+    pub prograde: f64,
+    /// Delta-v along the orbit normal, r x v (m/s)
+    pub normal: f64,
+    /// Delta-v away from the reference body within the orbital plane (m/s)
+    pub radial: f64,
+    /// Whether the burn has already been applied
+    pub executed: bool,
+}
+
+impl Maneuver {
+    pub fn new(time: f64, reference_body: String, prograde: f64, normal: f64, radial: f64) -> Self {
+        Self {
+            id: NEXT_MANEUVER_ID.fetch_add(1, Ordering::Relaxed),
+            time,
+            reference_body,
+            prograde,
+            normal,
+            radial,
+            executed: false,
+        }
+    }
+
+    /// The nodes of `edited` merged into the `live` list of a running craft at time `now`.
+    ///
+    /// Whether a burn has fired is taken from the live list, matched by id, so an edit made
+    /// from an older copy cannot schedule it again. Pending burns cannot be moved to or before
+    /// `now`, where they would fire at once: they keep their live time, and new ones are dropped.
+    pub fn merge_edits(live: &[Maneuver], edited: Vec<Maneuver>, now: f64) -> Vec<Maneuver> {
+        edited
+            .into_iter()
+            .filter_map(|maneuver| {
+                let current = live.iter().find(|current| current.id == maneuver.id);
+                match current {
+                    Some(current) if current.executed => Some(current.clone()),
+                    _ if maneuver.time > now => Some(Maneuver {
+                        executed: false,
+                        ..maneuver
+                    }),
+                    Some(current) => Some(Maneuver {
+                        time: current.time,
+                        executed: false,
+                        ..maneuver
+                    }),
+                    None => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Total delta-v of the burn (m/s)
+    pub fn magnitude(&self) -> f64 {
+        DVec3::new(self.prograde, self.normal, self.radial).length()
+    }
+
+    /// Convert the burn into a world-space velocity change for the craft's current state
+    ///
+    /// Returns `None` if the orbit frame is undefined (craft at rest relative to
+    /// the reference body, or moving straight at it).
+    pub fn delta_v(
+        &self,
+        craft_position: DVec3,
+        craft_velocity: DVec3,
+        reference_position: DVec3,
+        reference_velocity: DVec3,
+    ) -> Option<DVec3> {
+        let relative_position = craft_position - reference_position;
+        let relative_velocity = craft_velocity - reference_velocity;
+
+        let prograde = relative_velocity.try_normalize()?;
+        let normal = relative_position.cross(relative_velocity).try_normalize()?;
+        // Completes the right-handed frame; points away from the reference body
+        let radial = prograde.cross(normal);
+
+        Some(prograde * self.prograde + normal * self.normal + radial * self.radial)
+    }
+}
+
+/// Ids are left out, so parsed and written copies of a scenario compare equal
+impl PartialEq for Maneuver {
+    fn eq(&self, other: &Self) -> bool {
+        self.time == other.time
+            && self.reference_body == other.reference_body
+            && self.prograde == other.prograde
+            && self.normal == other.normal
+            && self.radial == other.radial
+            && self.executed == other.executed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burn_directions() {
+        // Circular orbit: craft on +x moving along +y, orbit normal is +z
+        let position = DVec3::new(7.0e6, 0.0, 0.0);
+        let velocity = DVec3::new(0.0, 7.5e3, 0.0);
+        let burn = |prograde, normal, radial| {
+            Maneuver::new(0.0, "Earth".to_string(), prograde, normal, radial)
+                .delta_v(position, velocity, DVec3::ZERO, DVec3::ZERO)
+                .unwrap()
+        };
+
+        assert!((burn(10.0, 0.0, 0.0) - DVec3::new(0.0, 10.0, 0.0)).length() < 1e-12);
+        assert!((burn(0.0, 10.0, 0.0) - DVec3::new(0.0, 0.0, 10.0)).length() < 1e-12);
+        assert!((burn(0.0, 0.0, 10.0) - DVec3::new(10.0, 0.0, 0.0)).length() < 1e-12);
+    }
+
+    #[test]
+    fn test_degenerate_frame() {
+        let burn = Maneuver::new(0.0, "Earth".to_string(), 1.0, 0.0, 0.0);
+        let at_rest = burn.delta_v(DVec3::X, DVec3::ZERO, DVec3::ZERO, DVec3::ZERO);
+        assert!(at_rest.is_none());
+    }
+
+    #[test]
+    fn test_merge_edits_keeps_live_execution_and_future_times() {
+        let mut fired = Maneuver::new(100.0, "Earth".to_string(), 1.0, 0.0, 0.0);
+        let pending = Maneuver::new(500.0, "Earth".to_string(), 2.0, 0.0, 0.0);
+        // The planner's copy was taken before the first burn fired
+        let mut snapshot = vec![fired.clone(), pending.clone()];
+        fired.executed = true;
+        let live = vec![fired, pending];
+
+        snapshot[0].prograde = 5.0;
+        snapshot[1].time = 150.0; // dragged to before now
+        snapshot.push(Maneuver::new(120.0, "Earth".to_string(), 3.0, 0.0, 0.0));
+        snapshot.push(Maneuver::new(900.0, "Earth".to_string(), 4.0, 0.0, 0.0));
+
+        let merged = Maneuver::merge_edits(&live, snapshot, 200.0);
+        assert_eq!(merged.len(), 3);
+        assert!(merged[0].executed);
+        assert_eq!(merged[0].prograde, 1.0);
+        assert_eq!((merged[1].time, merged[1].executed), (500.0, false));
+        assert_eq!(merged[2].time, 900.0);
+    }
+}
@@ -1,7 +1,23 @@
 pub mod body;
+pub mod chaos;
+pub mod hierarchy;
+pub mod kepler;
+pub mod lagrange;
+pub mod lambert;
+pub mod maneuver;
+pub mod reference_frame;
+pub mod sgp4;
 pub mod units;
 
 pub use body::*;
+pub use chaos::*;
+pub use hierarchy::*;
+pub use kepler::*;
+pub use lagrange::*;
+pub use lambert::*;
+pub use maneuver::*;
+pub use reference_frame::*;
+pub use sgp4::*;
 pub use units::*;
 
 use glam::{DVec3, Mat4, Quat, Vec3};
@@ -0,0 +1,278 @@
+/// Viewing reference frames
+/// Physics always integrates in a single inertial frame; these frames only change
+/// how positions, velocities and trails are presented to the renderer
+use glam::{DMat3, DVec3};
+
+use crate::math::Body;
+
+/// Frame the scene is viewed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReferenceFrame {
+    /// The frame physics integrates in
+    #[default]
+    Inertial,
+    /// Origin at the centre of mass of the whole system, axes fixed
+    Barycentric,
+    /// Origin at a chosen body, axes fixed
+    BodyCentred { body: usize },
+    /// Origin at the barycentre of a pair, x axis along primary -> secondary,
+    /// z axis along the pair's orbital angular momentum (e.g. the Sun-Earth line)
+    CoRotating { primary: usize, secondary: usize },
+}
+
+impl ReferenceFrame {
+    /// Short name for display in the UI
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReferenceFrame::Inertial => "Inertial",
+            ReferenceFrame::Barycentric => "Barycentric",
+            ReferenceFrame::BodyCentred { .. } => "Body-centred",
+            ReferenceFrame::CoRotating { .. } => "Co-rotating pair",
+        }
+    }
+
+    /// Body the frame is anchored to, if any (used to re-aim the camera)
+    pub fn anchor_body(&self) -> Option<usize> {
+        match self {
+            ReferenceFrame::Inertial | ReferenceFrame::Barycentric => None,
+            ReferenceFrame::BodyCentred { body } => Some(*body),
+            ReferenceFrame::CoRotating { secondary, .. } => Some(*secondary),
+        }
+    }
+
+    /// The same frame after the bodies were renumbered; `None` if a body it uses is gone
+    pub fn remap(&self, new_index: impl Fn(usize) -> Option<usize>) -> Option<Self> {
+        Some(match *self {
+            ReferenceFrame::Inertial | ReferenceFrame::Barycentric => *self,
+            ReferenceFrame::BodyCentred { body } => ReferenceFrame::BodyCentred {
+                body: new_index(body)?,
+            },
+            ReferenceFrame::CoRotating { primary, secondary } => ReferenceFrame::CoRotating {
+                primary: new_index(primary)?,
+                secondary: new_index(secondary)?,
+            },
+        })
+    }
+
+    /// Compute the world -> frame transform for the current state of the bodies
+    pub fn transform(&self, bodies: &[Body]) -> FrameTransform {
+        let masses: Vec<f64> = bodies.iter().map(|body| body.mass).collect();
+        let positions: Vec<DVec3> = bodies.iter().map(|body| body.position).collect();
+        let velocities: Vec<DVec3> = bodies.iter().map(|body| body.velocity).collect();
+        self.transform_from_states(&masses, &positions, &velocities)
+    }
+
+    /// Compute the world -> frame transform from raw state vectors
+    ///
+    /// Frames that reference a missing body fall back to the inertial frame.
+    pub fn transform_from_states(
+        &self,
+        masses: &[f64],
+        positions: &[DVec3],
+        velocities: &[DVec3],
+    ) -> FrameTransform {
+        match *self {
+            ReferenceFrame::Inertial => FrameTransform::IDENTITY,
+            ReferenceFrame::Barycentric => {
+                let total_mass: f64 = masses.iter().sum();
+                if total_mass <= 0.0 {
+                    return FrameTransform::IDENTITY;
+                }
+                let origin = masses
+                    .iter()
+                    .zip(positions)
+                    .fold(DVec3::ZERO, |acc, (m, p)| acc + *p * *m)
+                    / total_mass;
+                let origin_velocity = masses
+                    .iter()
+                    .zip(velocities)
+                    .fold(DVec3::ZERO, |acc, (m, v)| acc + *v * *m)
+                    / total_mass;
+                FrameTransform::translation(origin, origin_velocity)
+            }
+            ReferenceFrame::BodyCentred { body } => {
+                match (positions.get(body), velocities.get(body)) {
+                    (Some(position), Some(velocity)) => {
+                        FrameTransform::translation(*position, *velocity)
+                    }
+                    _ => FrameTransform::IDENTITY,
+                }
+            }
+            ReferenceFrame::CoRotating { primary, secondary } => {
+                let (Some(&m1), Some(&m2)) = (masses.get(primary), masses.get(secondary)) else {
+                    return FrameTransform::IDENTITY;
+                };
+                let (Some(&r1), Some(&r2)) = (positions.get(primary), positions.get(secondary))
+                else {
+                    return FrameTransform::IDENTITY;
+                };
+                let (Some(&v1), Some(&v2)) = (velocities.get(primary), velocities.get(secondary))
+                else {
+                    return FrameTransform::IDENTITY;
+                };
+
+                let relative_position = r2 - r1;
+                let relative_velocity = v2 - v1;
+                let angular_momentum = relative_position.cross(relative_velocity);
+                let separation_squared = relative_position.length_squared();
+                if primary == secondary
+                    || separation_squared == 0.0
+                    || angular_momentum.length_squared() == 0.0
+                {
+                    return FrameTransform::IDENTITY;
+                }
+
+                let total_mass = m1 + m2;
+                let (origin, origin_velocity) = if total_mass > 0.0 {
+                    (
+                        (r1 * m1 + r2 * m2) / total_mass,
+                        (v1 * m1 + v2 * m2) / total_mass,
+                    )
+                } else {
+                    ((r1 + r2) * 0.5, (v1 + v2) * 0.5)
+                };
+
+                let x_axis = relative_position.normalize();
+                let z_axis = angular_momentum.normalize();
+                let y_axis = z_axis.cross(x_axis);
+
+                FrameTransform {
+                    origin,
+                    origin_velocity,
+                    // Rows are the frame axes, so this maps world vectors into the frame
+                    rotation: DMat3::from_cols(x_axis, y_axis, z_axis).transpose(),
+                    angular_velocity: angular_momentum / separation_squared,
+                }
+            }
+        }
+    }
+}
+
+/// Rigid world -> frame transform captured at one instant
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameTransform {
+    /// Frame origin in world coordinates
+    pub origin: DVec3,
+    /// Velocity of the frame origin in world coordinates
+    pub origin_velocity: DVec3,
+    /// Rotation taking world vectors into frame axes
+    pub rotation: DMat3,
+    /// Angular velocity of the frame axes in world coordinates (rad/s)
+    pub angular_velocity: DVec3,
+}
+
+impl FrameTransform {
+    /// The inertial frame
+    pub const IDENTITY: Self = Self {
+        origin: DVec3::ZERO,
+        origin_velocity: DVec3::ZERO,
+        rotation: DMat3::IDENTITY,
+        angular_velocity: DVec3::ZERO,
+    };
+
+    /// Non-rotating frame moving with the given origin
+    pub fn translation(origin: DVec3, origin_velocity: DVec3) -> Self {
+        Self {
+            origin,
+            origin_velocity,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Convert a world position into frame coordinates
+    pub fn position_to_frame(&self, world_position: DVec3) -> DVec3 {
+        self.rotation * (world_position - self.origin)
+    }
+
+    /// Convert a frame position back into world coordinates
+    pub fn position_to_world(&self, frame_position: DVec3) -> DVec3 {
+        self.rotation.transpose() * frame_position + self.origin
+    }
+
+    /// Convert a world velocity into the velocity seen in the frame
+    pub fn velocity_to_frame(&self, world_position: DVec3, world_velocity: DVec3) -> DVec3 {
+        let offset = world_position - self.origin;
+        self.rotation
+            * (world_velocity - self.origin_velocity - self.angular_velocity.cross(offset))
+    }
+
+    /// Convert a velocity seen in the frame back into a world velocity
+    pub fn velocity_to_world(&self, frame_position: DVec3, frame_velocity: DVec3) -> DVec3 {
+        let offset = self.rotation.transpose() * frame_position;
+        self.rotation.transpose() * frame_velocity
+            + self.origin_velocity
+            + self.angular_velocity.cross(offset)
+    }
+
+    /// Rewrite a body's position and velocity into frame coordinates
+    pub fn apply_to_body(&self, body: &mut Body) {
+        body.velocity = self.velocity_to_frame(body.position, body.velocity);
+        body.position = self.position_to_frame(body.position);
+    }
+}
+
+impl Default for FrameTransform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_co_rotating_frame_puts_pair_on_x_axis() {
+        let masses = [3.0, 1.0];
+        let positions = [DVec3::new(0.0, 0.0, 0.0), DVec3::new(0.0, 4.0, 0.0)];
+        let velocities = [DVec3::ZERO, DVec3::new(-2.0, 0.0, 0.0)];
+
+        let frame = ReferenceFrame::CoRotating {
+            primary: 0,
+            secondary: 1,
+        };
+        let transform = frame.transform_from_states(&masses, &positions, &velocities);
+
+        let primary = transform.position_to_frame(positions[0]);
+        let secondary = transform.position_to_frame(positions[1]);
+        assert!((primary - DVec3::new(-1.0, 0.0, 0.0)).length() < 1e-12);
+        assert!((secondary - DVec3::new(3.0, 0.0, 0.0)).length() < 1e-12);
+
+        // A circular pair is at rest in its own co-rotating frame
+        let barycentre_velocity = (velocities[0] * 3.0 + velocities[1]) / 4.0;
+        let circular_primary = barycentre_velocity + DVec3::new(0.5, 0.0, 0.0);
+        let v_primary = transform.velocity_to_frame(positions[0], circular_primary);
+        let v_secondary = transform.velocity_to_frame(positions[1], velocities[1]);
+        assert!(v_secondary.length() < 1e-12);
+        assert!(v_primary.length() < 1e-12);
+    }
+
+    #[test]
+    fn test_round_trip_and_missing_body_fallback() {
+        let masses = [1.0, 2.0];
+        let positions = [DVec3::new(1.0, 2.0, 3.0), DVec3::new(-4.0, 0.5, 2.0)];
+        let velocities = [DVec3::new(0.1, 0.0, 0.3), DVec3::new(0.0, -1.0, 0.2)];
+
+        let transform = ReferenceFrame::CoRotating {
+            primary: 0,
+            secondary: 1,
+        }
+        .transform_from_states(&masses, &positions, &velocities);
+
+        let point = DVec3::new(7.0, -3.0, 1.5);
+        let velocity = DVec3::new(0.3, 0.2, -0.1);
+        let frame_point = transform.position_to_frame(point);
+        let frame_velocity = transform.velocity_to_frame(point, velocity);
+        assert!((transform.position_to_world(frame_point) - point).length() < 1e-12);
+        assert!(
+            (transform.velocity_to_world(frame_point, frame_velocity) - velocity).length() < 1e-12
+        );
+
+        let missing = ReferenceFrame::BodyCentred { body: 5 }.transform_from_states(
+            &masses,
+            &positions,
+            &velocities,
+        );
+        assert_eq!(missing, FrameTransform::IDENTITY);
+    }
+}
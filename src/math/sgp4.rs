@@ -0,0 +1,1422 @@
+/// SGP4/SDP4 propagation of two-line element sets
+/// Port of the Spacetrack Report #3 model as revised by Vallado et al. (2006), WGS-72 constants
+use glam::DVec3;
+
+use crate::{AstrariaError, AstrariaResult};
+
+const TWO_PI: f64 = 2.0 * std::f64::consts::PI;
+const X2O3: f64 = 2.0 / 3.0;
+const TEMP4: f64 = 1.5e-12;
+
+/// WGS-72 gravitational parameter (km³/s²)
+pub const SGP4_MU: f64 = 398600.8;
+/// WGS-72 equatorial radius (km)
+pub const SGP4_EARTH_RADIUS: f64 = 6378.135;
+const J2: f64 = 0.001082616;
+const J3: f64 = -0.00000253881;
+const J4: f64 = -0.00000165597;
+const J3OJ2: f64 = J3 / J2;
+
+/// Minutes per day over radians per revolution, converts rev/day to rad/min
+const XPDOTP: f64 = 1440.0 / TWO_PI;
+
+/// Earth rotation rate (rad/min)
+const RPTIM: f64 = 4.375_269_088_011_3e-3;
+
+/// Orbits with a period of at least this many minutes use the deep-space (SDP4) branch
+const DEEP_SPACE_PERIOD: f64 = 225.0;
+
+/// sqrt(mu / R³) in units of Earth radii per minute
+fn xke() -> f64 {
+    60.0 / (SGP4_EARTH_RADIUS * SGP4_EARTH_RADIUS * SGP4_EARTH_RADIUS / SGP4_MU).sqrt()
+}
+
+/// Greenwich mean sidereal time (radians) at a UT1 Julian date
+pub fn gmst(jd_ut1: f64) -> f64 {
+    let t = (jd_ut1 - 2451545.0) / 36525.0;
+    let seconds = -6.2e-6 * t * t * t
+        + 0.093104 * t * t
+        + (876600.0 * 3600.0 + 8640184.812866) * t
+        + 67310.54841;
+    (seconds.to_radians() / 240.0).rem_euclid(TWO_PI)
+}
+
+/// Julian date of 0h on January 0 (i.e. December 31) of a year
+fn julian_date_of_year(year: i32) -> f64 {
+    let year = year as f64;
+    367.0 * year - (7.0 * year * 0.25).floor() + 30.0 + 1721013.5
+}
+
+/// One satellite's mean elements, as read from a two-line element set
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tle {
+    pub name: String,
+    pub catalog_number: u32,
+    pub international_designator: String,
+    /// Epoch of the elements (UTC Julian date)
+    pub epoch: f64,
+    /// First derivative of mean motion over two (rev/day²)
+    pub mean_motion_dot: f64,
+    /// Second derivative of mean motion over six (rev/day³)
+    pub mean_motion_ddot: f64,
+    /// Drag term (1 / Earth radii)
+    pub bstar: f64,
+    /// Inclination (degrees)
+    pub inclination: f64,
+    /// Right ascension of the ascending node (degrees)
+    pub raan: f64,
+    pub eccentricity: f64,
+    /// Argument of perigee (degrees)
+    pub arg_perigee: f64,
+    /// Mean anomaly (degrees)
+    pub mean_anomaly: f64,
+    /// Kozai mean motion (rev/day)
+    pub mean_motion: f64,
+    pub revolution_number: u32,
+}
+
+impl Tle {
+    /// Parse a two-line element set, with an optional name line in front
+    pub fn parse(name: Option<&str>, line1: &str, line2: &str) -> AstrariaResult<Self> {
+        let line1 = line1.trim_end();
+        let line2 = line2.trim_end();
+        if line1.len() < 68 || !line1.starts_with('1') || !line1.is_ascii() {
+            return Err(AstrariaError::ParseError(format!(
+                "Invalid TLE line 1: '{}'",
+                line1
+            )));
+        }
+        if line2.len() < 68 || !line2.starts_with('2') || !line2.is_ascii() {
+            return Err(AstrariaError::ParseError(format!(
+                "Invalid TLE line 2: '{}'",
+                line2
+            )));
+        }
+        for line in [line1, line2] {
+            if let Some(expected) = line[68..].chars().next().and_then(|c| c.to_digit(10))
+                && checksum(line) != expected
+            {
+                return Err(AstrariaError::ParseError(format!(
+                    "TLE checksum mismatch (expected {}, computed {}): '{}'",
+                    expected,
+                    checksum(line),
+                    line
+                )));
+            }
+        }
+
+        let catalog_number = parse_field::<u32>(line1, 2..7, "catalog number")?;
+        if parse_field::<u32>(line2, 2..7, "catalog number")? != catalog_number {
+            return Err(AstrariaError::ParseError(format!(
+                "TLE lines belong to different satellites: '{}' / '{}'",
+                line1, line2
+            )));
+        }
+
+        let epoch_year = parse_field::<i32>(line1, 18..20, "epoch year")?;
+        let epoch_year = if epoch_year < 57 {
+            2000 + epoch_year
+        } else {
+            1900 + epoch_year
+        };
+        let epoch_day = parse_field::<f64>(line1, 20..32, "epoch day")?;
+
+        let name = name
+            .map(|name| name.trim().trim_start_matches("0 ").trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| catalog_number.to_string());
+
+        Ok(Self {
+            name,
+            catalog_number,
+            international_designator: line1[9..17].trim().to_string(),
+            epoch: julian_date_of_year(epoch_year) + epoch_day,
+            mean_motion_dot: parse_field(line1, 33..43, "mean motion derivative")?,
+            mean_motion_ddot: parse_exponent_field(&line1[44..52])?,
+            bstar: parse_exponent_field(&line1[53..61])?,
+            inclination: parse_field(line2, 8..16, "inclination")?,
+            raan: parse_field(line2, 17..25, "right ascension of node")?,
+            eccentricity: parse_field::<f64>(
+                &format!("0.{}", line2[26..33].trim()),
+                0..,
+                "eccentricity",
+            )?,
+            arg_perigee: parse_field(line2, 34..42, "argument of perigee")?,
+            mean_anomaly: parse_field(line2, 43..51, "mean anomaly")?,
+            mean_motion: parse_field(line2, 52..63, "mean motion")?,
+            revolution_number: line2[63..68].trim().parse().unwrap_or(0),
+        })
+    }
+
+    /// Parse every element set in a text file (two-line or three-line format)
+    ///
+    /// Element sets that fail to parse are logged and skipped.
+    pub fn parse_all(text: &str) -> Vec<Tle> {
+        let mut elements = Vec::new();
+        let mut name: Option<&str> = None;
+        let mut lines = text
+            .lines()
+            .map(str::trim_end)
+            .filter(|l| !l.trim().is_empty());
+        while let Some(line) = lines.next() {
+            if line.starts_with("1 ") {
+                let Some(line2) = lines.next() else {
+                    log::warn!("TLE line 1 without line 2: '{}'", line);
+                    break;
+                };
+                match Tle::parse(name.take(), line, line2) {
+                    Ok(tle) => elements.push(tle),
+                    Err(e) => log::warn!("Skipping element set: {}", e),
+                }
+            } else {
+                name = Some(line);
+            }
+        }
+        elements
+    }
+
+    /// Orbital period from the mean motion (minutes)
+    pub fn period_minutes(&self) -> f64 {
+        1440.0 / self.mean_motion
+    }
+}
+
+/// Modulo-10 checksum of the first 68 columns: digits count their value, minus signs count one
+fn checksum(line: &str) -> u32 {
+    line.chars()
+        .take(68)
+        .map(|c| match c {
+            '-' => 1,
+            c => c.to_digit(10).unwrap_or(0),
+        })
+        .sum::<u32>()
+        % 10
+}
+
+fn parse_field<T: std::str::FromStr>(
+    line: &str,
+    range: impl std::slice::SliceIndex<str, Output = str>,
+    what: &str,
+) -> AstrariaResult<T> {
+    let field = line.get(range).unwrap_or("").trim();
+    field
+        .parse()
+        .map_err(|_| AstrariaError::ParseError(format!("Invalid TLE {}: '{}'", what, field)))
+}
+
+/// Parse a field with an implied leading decimal point and a power-of-ten suffix, e.g. " 28098-4"
+fn parse_exponent_field(field: &str) -> AstrariaResult<f64> {
+    let field = field.trim();
+    let invalid = || AstrariaError::ParseError(format!("Invalid TLE exponent field: '{}'", field));
+    if field.len() < 3 {
+        return Err(invalid());
+    }
+    let (mantissa, exponent) = field.split_at(field.len() - 2);
+    let (sign, digits) = match mantissa.strip_prefix('-') {
+        Some(digits) => (-1.0, digits),
+        None => (1.0, mantissa.trim_start_matches('+')),
+    };
+    let mantissa: f64 = format!("0.{}", digits.trim())
+        .parse()
+        .map_err(|_| invalid())?;
+    let exponent: i32 = exponent
+        .trim_start_matches('+')
+        .parse()
+        .map_err(|_| invalid())?;
+    Ok(sign * mantissa * 10f64.powi(exponent))
+}
+
+/// Lunar-solar coefficients and resonance state of the deep-space (SDP4) branch
+#[derive(Debug, Clone, Default)]
+struct DeepSpace {
+    // Long-period periodics (dscom/dpper)
+    e3: f64,
+    ee2: f64,
+    se2: f64,
+    se3: f64,
+    sgh2: f64,
+    sgh3: f64,
+    sgh4: f64,
+    sh2: f64,
+    sh3: f64,
+    si2: f64,
+    si3: f64,
+    sl2: f64,
+    sl3: f64,
+    sl4: f64,
+    xgh2: f64,
+    xgh3: f64,
+    xgh4: f64,
+    xh2: f64,
+    xh3: f64,
+    xi2: f64,
+    xi3: f64,
+    xl2: f64,
+    xl3: f64,
+    xl4: f64,
+    zmol: f64,
+    zmos: f64,
+    // Secular rates (dsinit)
+    dedt: f64,
+    didt: f64,
+    dmdt: f64,
+    dnodt: f64,
+    domdt: f64,
+    // Resonance terms: 0 none, 1 synchronous, 2 half-day
+    irez: u8,
+    d2201: f64,
+    d2211: f64,
+    d3210: f64,
+    d3222: f64,
+    d4410: f64,
+    d4422: f64,
+    d5220: f64,
+    d5232: f64,
+    d5421: f64,
+    d5433: f64,
+    del1: f64,
+    del2: f64,
+    del3: f64,
+    xfact: f64,
+    xlamo: f64,
+    // Resonance integrator state
+    atime: f64,
+    xli: f64,
+    xni: f64,
+}
+
+/// Intermediate values from `dscom` that `dsinit` needs
+struct DeepSpaceCommon {
+    sinim: f64,
+    cosim: f64,
+    emsq: f64,
+    s: [f64; 5],
+    ss: [f64; 5],
+    z1: f64,
+    z3: f64,
+    z11: f64,
+    z13: f64,
+    z21: f64,
+    z23: f64,
+    z31: f64,
+    z33: f64,
+    sz1: f64,
+    sz3: f64,
+    sz11: f64,
+    sz13: f64,
+    sz21: f64,
+    sz23: f64,
+    sz31: f64,
+    sz33: f64,
+}
+
+/// SGP4/SDP4 propagator initialised from one element set
+#[derive(Debug, Clone)]
+pub struct Sgp4 {
+    /// Epoch of the elements (UTC Julian date)
+    pub epoch: f64,
+    bstar: f64,
+    inclo: f64,
+    nodeo: f64,
+    ecco: f64,
+    argpo: f64,
+    mo: f64,
+    /// Un-Kozai'd mean motion (rad/min)
+    no: f64,
+    isimp: bool,
+    aycof: f64,
+    con41: f64,
+    cc1: f64,
+    cc4: f64,
+    cc5: f64,
+    d2: f64,
+    d3: f64,
+    d4: f64,
+    delmo: f64,
+    eta: f64,
+    argpdot: f64,
+    omgcof: f64,
+    sinmao: f64,
+    t2cof: f64,
+    t3cof: f64,
+    t4cof: f64,
+    t5cof: f64,
+    x1mth2: f64,
+    x7thm1: f64,
+    mdot: f64,
+    nodedot: f64,
+    xlcof: f64,
+    xmcof: f64,
+    nodecf: f64,
+    gsto: f64,
+    deep: Option<DeepSpace>,
+}
+
+impl Sgp4 {
+    /// Initialise the propagator (sgp4init)
+    pub fn new(tle: &Tle) -> AstrariaResult<Self> {
+        let xke = xke();
+        let ecco = tle.eccentricity;
+        let inclo = tle.inclination.to_radians();
+        let nodeo = tle.raan.to_radians();
+        let argpo = tle.arg_perigee.to_radians();
+        let mo = tle.mean_anomaly.to_radians();
+        let no_kozai = tle.mean_motion / XPDOTP;
+        let bstar = tle.bstar;
+        if no_kozai <= 0.0 || !(0.0..1.0).contains(&ecco) {
+            return Err(AstrariaError::Physics(format!(
+                "Satellite '{}' has unusable elements (n = {}, e = {})",
+                tle.name, tle.mean_motion, ecco
+            )));
+        }
+
+        // initl: recover the original mean motion and semi-major axis
+        let eccsq = ecco * ecco;
+        let omeosq = 1.0 - eccsq;
+        let rteosq = omeosq.sqrt();
+        let cosio = inclo.cos();
+        let cosio2 = cosio * cosio;
+        let ak = (xke / no_kozai).powf(X2O3);
+        let d1 = 0.75 * J2 * (3.0 * cosio2 - 1.0) / (rteosq * omeosq);
+        let del = d1 / (ak * ak);
+        let adel = ak * (1.0 - del * del - del * (1.0 / 3.0 + 134.0 * del * del / 81.0));
+        let del = d1 / (adel * adel);
+        let no = no_kozai / (1.0 + del);
+        let ao = (xke / no).powf(X2O3);
+        let sinio = inclo.sin();
+        let po = ao * omeosq;
+        let con42 = 1.0 - 5.0 * cosio2;
+        let con41 = -con42 - cosio2 - cosio2;
+        let posq = po * po;
+        let rp = ao * (1.0 - ecco);
+        let gsto = gmst(tle.epoch);
+
+        let ss = 78.0 / SGP4_EARTH_RADIUS + 1.0;
+        let qzms2t = ((120.0 - 78.0) / SGP4_EARTH_RADIUS).powi(4);
+
+        let mut isimp = rp < 220.0 / SGP4_EARTH_RADIUS + 1.0;
+        let mut sfour = ss;
+        let mut qzms24 = qzms2t;
+        let perige = (rp - 1.0) * SGP4_EARTH_RADIUS;
+        if perige < 156.0 {
+            sfour = if perige < 98.0 { 20.0 } else { perige - 78.0 };
+            qzms24 = ((120.0 - sfour) / SGP4_EARTH_RADIUS).powi(4);
+            sfour = sfour / SGP4_EARTH_RADIUS + 1.0;
+        }
+        let pinvsq = 1.0 / posq;
+        let tsi = 1.0 / (ao - sfour);
+        let eta = ao * ecco * tsi;
+        let etasq = eta * eta;
+        let eeta = ecco * eta;
+        let psisq = (1.0 - etasq).abs();
+        let coef = qzms24 * tsi.powi(4);
+        let coef1 = coef / psisq.powf(3.5);
+        let cc2 = coef1
+            * no
+            * (ao * (1.0 + 1.5 * etasq + eeta * (4.0 + etasq))
+                + 0.375 * J2 * tsi / psisq * con41 * (8.0 + 3.0 * etasq * (8.0 + etasq)));
+        let cc1 = bstar * cc2;
+        let cc3 = if ecco > 1.0e-4 {
+            -2.0 * coef * tsi * J3OJ2 * no * sinio / ecco
+        } else {
+            0.0
+        };
+        let x1mth2 = 1.0 - cosio2;
+        let cc4 = 2.0
+            * no
+            * coef1
+            * ao
+            * omeosq
+            * (eta * (2.0 + 0.5 * etasq) + ecco * (0.5 + 2.0 * etasq)
+                - J2 * tsi / (ao * psisq)
+                    * (-3.0 * con41 * (1.0 - 2.0 * eeta + etasq * (1.5 - 0.5 * eeta))
+                        + 0.75
+                            * x1mth2
+                            * (2.0 * etasq - eeta * (1.0 + etasq))
+                            * (2.0 * argpo).cos()));
+        let cc5 = 2.0 * coef1 * ao * omeosq * (1.0 + 2.75 * (etasq + eeta) + eeta * etasq);
+        let cosio4 = cosio2 * cosio2;
+        let temp1 = 1.5 * J2 * pinvsq * no;
+        let temp2 = 0.5 * temp1 * J2 * pinvsq;
+        let temp3 = -0.46875 * J4 * pinvsq * pinvsq * no;
+        let mdot = no
+            + 0.5 * temp1 * rteosq * con41
+            + 0.0625 * temp2 * rteosq * (13.0 - 78.0 * cosio2 + 137.0 * cosio4);
+        let argpdot = -0.5 * temp1 * con42
+            + 0.0625 * temp2 * (7.0 - 114.0 * cosio2 + 395.0 * cosio4)
+            + temp3 * (3.0 - 36.0 * cosio2 + 49.0 * cosio4);
+        let xhdot1 = -temp1 * cosio;
+        let nodedot = xhdot1
+            + (0.5 * temp2 * (4.0 - 19.0 * cosio2) + 2.0 * temp3 * (3.0 - 7.0 * cosio2)) * cosio;
+        let xpidot = argpdot + nodedot;
+        let omgcof = bstar * cc3 * argpo.cos();
+        let xmcof = if ecco > 1.0e-4 {
+            -X2O3 * coef * bstar / eeta
+        } else {
+            0.0
+        };
+        let nodecf = 3.5 * omeosq * xhdot1 * cc1;
+        let t2cof = 1.5 * cc1;
+        let xlcof = -0.25 * J3OJ2 * sinio * (3.0 + 5.0 * cosio) / non_zero(1.0 + cosio);
+        let aycof = -0.5 * J3OJ2 * sinio;
+        let delmo = (1.0 + eta * mo.cos()).powi(3);
+        let sinmao = mo.sin();
+        let x7thm1 = 7.0 * cosio2 - 1.0;
+
+        let mut deep = None;
+        if TWO_PI / no >= DEEP_SPACE_PERIOD {
+            isimp = true;
+            let epoch = tle.epoch - 2433281.5;
+            let mut ds = DeepSpace::default();
+            let common = ds.dscom(epoch, ecco, argpo, 0.0, inclo, nodeo, no);
+            ds.dsinit(
+                &common, argpo, 0.0, gsto, mo, mdot, no, nodeo, nodedot, xpidot, ecco, eccsq, inclo,
+            );
+            deep = Some(ds);
+        }
+
+        let (mut d2, mut d3, mut d4) = (0.0, 0.0, 0.0);
+        let (mut t3cof, mut t4cof, mut t5cof) = (0.0, 0.0, 0.0);
+        if !isimp {
+            let cc1sq = cc1 * cc1;
+            d2 = 4.0 * ao * tsi * cc1sq;
+            let temp = d2 * tsi * cc1 / 3.0;
+            d3 = (17.0 * ao + sfour) * temp;
+            d4 = 0.5 * temp * ao * tsi * (221.0 * ao + 31.0 * sfour) * cc1;
+            t3cof = d2 + 2.0 * cc1sq;
+            t4cof = 0.25 * (3.0 * d3 + cc1 * (12.0 * d2 + 10.0 * cc1sq));
+            t5cof = 0.2
+                * (3.0 * d4 + 12.0 * cc1 * d3 + 6.0 * d2 * d2 + 15.0 * cc1sq * (2.0 * d2 + cc1sq));
+        }
+
+        let mut sgp4 = Self {
+            epoch: tle.epoch,
+            bstar,
+            inclo,
+            nodeo,
+            ecco,
+            argpo,
+            mo,
+            no,
+            isimp,
+            aycof,
+            con41,
+            cc1,
+            cc4,
+            cc5,
+            d2,
+            d3,
+            d4,
+            delmo,
+            eta,
+            argpdot,
+            omgcof,
+            sinmao,
+            t2cof,
+            t3cof,
+            t4cof,
+            t5cof,
+            x1mth2,
+            x7thm1,
+            mdot,
+            nodedot,
+            xlcof,
+            xmcof,
+            nodecf,
+            gsto,
+            deep,
+        };
+        // A propagation to the epoch catches elements that cannot be propagated at all
+        sgp4.propagate(0.0).map_err(|e| {
+            AstrariaError::Physics(format!(
+                "Satellite '{}' failed to initialise: {}",
+                tle.name, e
+            ))
+        })?;
+        Ok(sgp4)
+    }
+
+    /// Whether this satellite uses the deep-space (SDP4) branch
+    pub fn is_deep_space(&self) -> bool {
+        self.deep.is_some()
+    }
+
+    /// Position (km) and velocity (km/s) in the TEME frame, `minutes` after the element epoch
+    pub fn propagate(&mut self, minutes: f64) -> AstrariaResult<(DVec3, DVec3)> {
+        let xke = xke();
+        let vkmpersec = SGP4_EARTH_RADIUS * xke / 60.0;
+        let t = minutes;
+
+        // Secular gravity and atmospheric drag
+        let xmdf = self.mo + self.mdot * t;
+        let argpdf = self.argpo + self.argpdot * t;
+        let nodedf = self.nodeo + self.nodedot * t;
+        let mut argpm = argpdf;
+        let mut mm = xmdf;
+        let t2 = t * t;
+        let mut nodem = nodedf + self.nodecf * t2;
+        let mut tempa = 1.0 - self.cc1 * t;
+        let mut tempe = self.bstar * self.cc4 * t;
+        let mut templ = self.t2cof * t2;
+        if !self.isimp {
+            let delomg = self.omgcof * t;
+            let delmtemp = 1.0 + self.eta * xmdf.cos();
+            let delm = self.xmcof * (delmtemp * delmtemp * delmtemp - self.delmo);
+            let temp = delomg + delm;
+            mm = xmdf + temp;
+            argpm = argpdf - temp;
+            let t3 = t2 * t;
+            let t4 = t3 * t;
+            tempa = tempa - self.d2 * t2 - self.d3 * t3 - self.d4 * t4;
+            tempe += self.bstar * self.cc5 * (mm.sin() - self.sinmao);
+            templ += self.t3cof * t3 + t4 * (self.t4cof + t * self.t5cof);
+        }
+
+        let mut nm = self.no;
+        let mut em = self.ecco;
+        let mut inclm = self.inclo;
+        if let Some(deep) = &mut self.deep {
+            deep.dspace(
+                t,
+                self.gsto,
+                self.argpo,
+                self.argpdot,
+                self.no,
+                &mut em,
+                &mut argpm,
+                &mut inclm,
+                &mut mm,
+                &mut nodem,
+                &mut nm,
+            );
+        }
+        if nm <= 0.0 {
+            return Err(AstrariaError::Physics(format!(
+                "mean motion {} is not positive",
+                nm
+            )));
+        }
+        let am = (xke / nm).powf(X2O3) * tempa * tempa;
+        nm = xke / am.powf(1.5);
+        em -= tempe;
+        if !(-0.001..1.0).contains(&em) {
+            return Err(AstrariaError::Physics(format!(
+                "mean eccentricity {} out of range",
+                em
+            )));
+        }
+        em = em.max(1.0e-6);
+        mm += self.no * templ;
+        let xlm = mm + argpm + nodem;
+        nodem %= TWO_PI;
+        argpm %= TWO_PI;
+        let xlm = xlm % TWO_PI;
+        mm = (xlm - argpm - nodem) % TWO_PI;
+
+        // Lunar-solar periodics
+        let mut ep = em;
+        let mut xincp = inclm;
+        let mut argpp = argpm;
+        let mut nodep = nodem;
+        let mut mp = mm;
+        let mut aycof = self.aycof;
+        let mut xlcof = self.xlcof;
+        let mut con41 = self.con41;
+        let mut x1mth2 = self.x1mth2;
+        let mut x7thm1 = self.x7thm1;
+        if let Some(deep) = &self.deep {
+            deep.dpper(t, &mut ep, &mut xincp, &mut nodep, &mut argpp, &mut mp);
+            if xincp < 0.0 {
+                xincp = -xincp;
+                nodep += std::f64::consts::PI;
+                argpp -= std::f64::consts::PI;
+            }
+            if !(0.0..=1.0).contains(&ep) {
+                return Err(AstrariaError::Physics(format!(
+                    "perturbed eccentricity {} out of range",
+                    ep
+                )));
+            }
+            let sinip = xincp.sin();
+            let cosip = xincp.cos();
+            aycof = -0.5 * J3OJ2 * sinip;
+            xlcof = -0.25 * J3OJ2 * sinip * (3.0 + 5.0 * cosip) / non_zero(1.0 + cosip);
+            let cosisq = cosip * cosip;
+            con41 = 3.0 * cosisq - 1.0;
+            x1mth2 = 1.0 - cosisq;
+            x7thm1 = 7.0 * cosisq - 1.0;
+        }
+        let sinip = xincp.sin();
+        let cosip = xincp.cos();
+
+        // Long-period periodics
+        let axnl = ep * argpp.cos();
+        let temp = 1.0 / (am * (1.0 - ep * ep));
+        let aynl = ep * argpp.sin() + temp * aycof;
+        let xl = mp + argpp + nodep + temp * xlcof * axnl;
+
+        // Kepler's equation in the modified equinoctial form
+        let u = (xl - nodep) % TWO_PI;
+        let mut eo1 = u;
+        let mut tem5: f64 = 9999.9;
+        let (mut sineo1, mut coseo1) = (0.0, 0.0);
+        let mut iterations = 1;
+        while tem5.abs() >= 1.0e-12 && iterations <= 10 {
+            sineo1 = eo1.sin();
+            coseo1 = eo1.cos();
+            tem5 = 1.0 - coseo1 * axnl - sineo1 * aynl;
+            tem5 = (u - aynl * coseo1 + axnl * sineo1 - eo1) / tem5;
+            tem5 = tem5.clamp(-0.95, 0.95);
+            eo1 += tem5;
+            iterations += 1;
+        }
+
+        // Short-period periodics
+        let ecose = axnl * coseo1 + aynl * sineo1;
+        let esine = axnl * sineo1 - aynl * coseo1;
+        let el2 = axnl * axnl + aynl * aynl;
+        let pl = am * (1.0 - el2);
+        if pl < 0.0 {
+            return Err(AstrariaError::Physics(format!(
+                "semi-latus rectum {} is negative",
+                pl
+            )));
+        }
+        let rl = am * (1.0 - ecose);
+        let rdotl = am.sqrt() * esine / rl;
+        let rvdotl = pl.sqrt() / rl;
+        let betal = (1.0 - el2).sqrt();
+        let temp = esine / (1.0 + betal);
+        let sinu = am / rl * (sineo1 - aynl - axnl * temp);
+        let cosu = am / rl * (coseo1 - axnl + aynl * temp);
+        let mut su = sinu.atan2(cosu);
+        let sin2u = (cosu + cosu) * sinu;
+        let cos2u = 1.0 - 2.0 * sinu * sinu;
+        let temp = 1.0 / pl;
+        let temp1 = 0.5 * J2 * temp;
+        let temp2 = temp1 * temp;
+
+        let mrt = rl * (1.0 - 1.5 * temp2 * betal * con41) + 0.5 * temp1 * x1mth2 * cos2u;
+        su -= 0.25 * temp2 * x7thm1 * sin2u;
+        let xnode = nodep + 1.5 * temp2 * cosip * sin2u;
+        let xinc = xincp + 1.5 * temp2 * cosip * sinip * cos2u;
+        let mvt = rdotl - nm * temp1 * x1mth2 * sin2u / xke;
+        let rvdot = rvdotl + nm * temp1 * (x1mth2 * cos2u + 1.5 * con41) / xke;
+
+        // Orientation vectors
+        let (sinsu, cossu) = su.sin_cos();
+        let (snod, cnod) = xnode.sin_cos();
+        let (sini, cosi) = xinc.sin_cos();
+        let xmx = -snod * cosi;
+        let xmy = cnod * cosi;
+        let u = DVec3::new(
+            xmx * sinsu + cnod * cossu,
+            xmy * sinsu + snod * cossu,
+            sini * sinsu,
+        );
+        let v = DVec3::new(
+            xmx * cossu - cnod * sinsu,
+            xmy * cossu - snod * sinsu,
+            sini * cossu,
+        );
+
+        if mrt < 1.0 {
+            return Err(AstrariaError::Physics("satellite has decayed".to_string()));
+        }
+        Ok((
+            mrt * u * SGP4_EARTH_RADIUS,
+            (mvt * u + rvdot * v) * vkmpersec,
+        ))
+    }
+}
+
+/// Guard the `1 + cos i` divisor for retrograde equatorial orbits
+fn non_zero(value: f64) -> f64 {
+    if value.abs() > TEMP4 { value } else { TEMP4 }
+}
+
+impl DeepSpace {
+    /// Lunar and solar perturbation coefficients (dscom)
+    #[allow(clippy::too_many_arguments)]
+    fn dscom(
+        &mut self,
+        epoch: f64,
+        ep: f64,
+        argpp: f64,
+        tc: f64,
+        inclp: f64,
+        nodep: f64,
+        np: f64,
+    ) -> DeepSpaceCommon {
+        const ZES: f64 = 0.01675;
+        const ZEL: f64 = 0.05490;
+        const C1SS: f64 = 2.9864797e-6;
+        const C1L: f64 = 4.7968065e-7;
+        const ZSINIS: f64 = 0.39785416;
+        const ZCOSIS: f64 = 0.91744867;
+        const ZCOSGS: f64 = 0.1945905;
+        const ZSINGS: f64 = -0.98088458;
+
+        let em = ep;
+        let snodm = nodep.sin();
+        let cnodm = nodep.cos();
+        let sinomm = argpp.sin();
+        let cosomm = argpp.cos();
+        let sinim = inclp.sin();
+        let cosim = inclp.cos();
+        let emsq = em * em;
+        let betasq = 1.0 - emsq;
+        let rtemsq = betasq.sqrt();
+
+        let day = epoch + 18261.5 + tc / 1440.0;
+        let xnodce = (4.5236020 - 9.2422029e-4 * day) % TWO_PI;
+        let stem = xnodce.sin();
+        let ctem = xnodce.cos();
+        let zcosil = 0.91375164 - 0.03568096 * ctem;
+        let zsinil = (1.0 - zcosil * zcosil).sqrt();
+        let zsinhl = 0.089683511 * stem / zsinil;
+        let zcoshl = (1.0 - zsinhl * zsinhl).sqrt();
+        let gam = 5.8351514 + 0.0019443680 * day;
+        let zx = 0.39785416 * stem / zsinil;
+        let zy = zcoshl * ctem + 0.91744867 * zsinhl * stem;
+        let zx = gam + zx.atan2(zy) - xnodce;
+        let zcosgl = zx.cos();
+        let zsingl = zx.sin();
+
+        // First pass: solar terms, second pass: lunar terms
+        let mut zcosg = ZCOSGS;
+        let mut zsing = ZSINGS;
+        let mut zcosi = ZCOSIS;
+        let mut zsini = ZSINIS;
+        let mut zcosh = cnodm;
+        let mut zsinh = snodm;
+        let mut cc = C1SS;
+        let xnoi = 1.0 / np;
+
+        let mut solar = [0.0; 19];
+        let mut lunar = [0.0; 19];
+        for pass in 0..2 {
+            let a1 = zcosg * zcosh + zsing * zcosi * zsinh;
+            let a3 = -zsing * zcosh + zcosg * zcosi * zsinh;
+            let a7 = -zcosg * zsinh + zsing * zcosi * zcosh;
+            let a8 = zsing * zsini;
+            let a9 = zsing * zsinh + zcosg * zcosi * zcosh;
+            let a10 = zcosg * zsini;
+            let a2 = cosim * a7 + sinim * a8;
+            let a4 = cosim * a9 + sinim * a10;
+            let a5 = -sinim * a7 + cosim * a8;
+            let a6 = -sinim * a9 + cosim * a10;
+
+            let x1 = a1 * cosomm + a2 * sinomm;
+            let x2 = a3 * cosomm + a4 * sinomm;
+            let x3 = -a1 * sinomm + a2 * cosomm;
+            let x4 = -a3 * sinomm + a4 * cosomm;
+            let x5 = a5 * sinomm;
+            let x6 = a6 * sinomm;
+            let x7 = a5 * cosomm;
+            let x8 = a6 * cosomm;
+
+            let z31 = 12.0 * x1 * x1 - 3.0 * x3 * x3;
+            let z32 = 24.0 * x1 * x2 - 6.0 * x3 * x4;
+            let z33 = 12.0 * x2 * x2 - 3.0 * x4 * x4;
+            let mut z1 = 3.0 * (a1 * a1 + a2 * a2) + z31 * emsq;
+            let mut z2 = 6.0 * (a1 * a3 + a2 * a4) + z32 * emsq;
+            let mut z3 = 3.0 * (a3 * a3 + a4 * a4) + z33 * emsq;
+            let z11 = -6.0 * a1 * a5 + emsq * (-24.0 * x1 * x7 - 6.0 * x3 * x5);
+            let z12 = -6.0 * (a1 * a6 + a3 * a5)
+                + emsq * (-24.0 * (x2 * x7 + x1 * x8) - 6.0 * (x3 * x6 + x4 * x5));
+            let z13 = -6.0 * a3 * a6 + emsq * (-24.0 * x2 * x8 - 6.0 * x4 * x6);
+            let z21 = 6.0 * a2 * a5 + emsq * (24.0 * x1 * x5 - 6.0 * x3 * x7);
+            let z22 = 6.0 * (a4 * a5 + a2 * a6)
+                + emsq * (24.0 * (x2 * x5 + x1 * x6) - 6.0 * (x4 * x7 + x3 * x8));
+            let z23 = 6.0 * a4 * a6 + emsq * (24.0 * x2 * x6 - 6.0 * x4 * x8);
+            z1 = z1 + z1 + betasq * z31;
+            z2 = z2 + z2 + betasq * z32;
+            z3 = z3 + z3 + betasq * z33;
+            let s3 = cc * xnoi;
+            let s2 = -0.5 * s3 / rtemsq;
+            let s4 = s3 * rtemsq;
+            let s1 = -15.0 * em * s4;
+            let s5 = x1 * x3 + x2 * x4;
+            let s6 = x2 * x3 + x1 * x4;
+            let s7 = x2 * x4 - x1 * x3;
+
+            let terms = [
+                s1, s2, s3, s4, s5, s6, s7, z1, z2, z3, z11, z12, z13, z21, z22, z23, z31, z32, z33,
+            ];
+            if pass == 0 {
+                solar = terms;
+                zcosg = zcosgl;
+                zsing = zsingl;
+                zcosi = zcosil;
+                zsini = zsinil;
+                zcosh = zcoshl * cnodm + zsinhl * snodm;
+                zsinh = snodm * zcoshl - cnodm * zsinhl;
+                cc = C1L;
+            } else {
+                lunar = terms;
+            }
+        }
+        let [
+            ss1,
+            ss2,
+            ss3,
+            ss4,
+            ss5,
+            ss6,
+            ss7,
+            sz1,
+            sz2,
+            sz3,
+            sz11,
+            sz12,
+            sz13,
+            sz21,
+            sz22,
+            sz23,
+            sz31,
+            sz32,
+            sz33,
+        ] = solar;
+        let [
+            s1,
+            s2,
+            s3,
+            s4,
+            s5,
+            s6,
+            s7,
+            z1,
+            z2,
+            z3,
+            z11,
+            z12,
+            z13,
+            z21,
+            z22,
+            z23,
+            z31,
+            z32,
+            z33,
+        ] = lunar;
+
+        self.zmol = (4.7199672 + 0.22997150 * day - gam) % TWO_PI;
+        self.zmos = (6.2565837 + 0.017201977 * day) % TWO_PI;
+
+        // Solar terms
+        self.se2 = 2.0 * ss1 * ss6;
+        self.se3 = 2.0 * ss1 * ss7;
+        self.si2 = 2.0 * ss2 * sz12;
+        self.si3 = 2.0 * ss2 * (sz13 - sz11);
+        self.sl2 = -2.0 * ss3 * sz2;
+        self.sl3 = -2.0 * ss3 * (sz3 - sz1);
+        self.sl4 = -2.0 * ss3 * (-21.0 - 9.0 * emsq) * ZES;
+        self.sgh2 = 2.0 * ss4 * sz32;
+        self.sgh3 = 2.0 * ss4 * (sz33 - sz31);
+        self.sgh4 = -18.0 * ss4 * ZES;
+        self.sh2 = -2.0 * ss2 * sz22;
+        self.sh3 = -2.0 * ss2 * (sz23 - sz21);
+
+        // Lunar terms
+        self.ee2 = 2.0 * s1 * s6;
+        self.e3 = 2.0 * s1 * s7;
+        self.xi2 = 2.0 * s2 * z12;
+        self.xi3 = 2.0 * s2 * (z13 - z11);
+        self.xl2 = -2.0 * s3 * z2;
+        self.xl3 = -2.0 * s3 * (z3 - z1);
+        self.xl4 = -2.0 * s3 * (-21.0 - 9.0 * emsq) * ZEL;
+        self.xgh2 = 2.0 * s4 * z32;
+        self.xgh3 = 2.0 * s4 * (z33 - z31);
+        self.xgh4 = -18.0 * s4 * ZEL;
+        self.xh2 = -2.0 * s2 * z22;
+        self.xh3 = -2.0 * s2 * (z23 - z21);
+
+        DeepSpaceCommon {
+            sinim,
+            cosim,
+            emsq,
+            s: [s1, s2, s3, s4, s5],
+            ss: [ss1, ss2, ss3, ss4, ss5],
+            z1,
+            z3,
+            z11,
+            z13,
+            z21,
+            z23,
+            z31,
+            z33,
+            sz1,
+            sz3,
+            sz11,
+            sz13,
+            sz21,
+            sz23,
+            sz31,
+            sz33,
+        }
+    }
+
+    /// Secular rates and resonance coefficients (dsinit)
+    #[allow(clippy::too_many_arguments)]
+    fn dsinit(
+        &mut self,
+        c: &DeepSpaceCommon,
+        argpo: f64,
+        tc: f64,
+        gsto: f64,
+        mo: f64,
+        mdot: f64,
+        no: f64,
+        nodeo: f64,
+        nodedot: f64,
+        xpidot: f64,
+        ecco: f64,
+        eccsq: f64,
+        inclm: f64,
+    ) {
+        const Q22: f64 = 1.7891679e-6;
+        const Q31: f64 = 2.1460748e-6;
+        const Q33: f64 = 2.2123015e-7;
+        const ROOT22: f64 = 1.7891679e-6;
+        const ROOT44: f64 = 7.3636953e-9;
+        const ROOT54: f64 = 2.1765803e-9;
+        const ROOT32: f64 = 3.7393792e-7;
+        const ROOT52: f64 = 1.1428639e-7;
+        const ZNL: f64 = 1.5835218e-4;
+        const ZNS: f64 = 1.19459e-5;
+
+        let nm = no;
+        let em = ecco;
+        let [s1, s2, s3, s4, s5] = c.s;
+        let [ss1, ss2, ss3, ss4, ss5] = c.ss;
+        let (sinim, cosim, emsq) = (c.sinim, c.cosim, c.emsq);
+
+        self.irez = 0;
+        if nm < 0.0052359877 && nm > 0.0034906585 {
+            self.irez = 1;
+        }
+        if (8.26e-3..=9.24e-3).contains(&nm) && em >= 0.5 {
+            self.irez = 2;
+        }
+
+        // Solar terms
+        let ses = ss1 * ZNS * ss5;
+        let sis = ss2 * ZNS * (c.sz11 + c.sz13);
+        let sls = -ZNS * ss3 * (c.sz1 + c.sz3 - 14.0 - 6.0 * emsq);
+        let sghs = ss4 * ZNS * (c.sz31 + c.sz33 - 6.0);
+        let near_equatorial =
+            !(5.2359877e-2..=std::f64::consts::PI - 5.2359877e-2).contains(&inclm);
+        let mut shs = -ZNS * ss2 * (c.sz21 + c.sz23);
+        if near_equatorial {
+            shs = 0.0;
+        }
+        if sinim != 0.0 {
+            shs /= sinim;
+        }
+        let sgs = sghs - cosim * shs;
+
+        // Lunar terms
+        self.dedt = ses + s1 * ZNL * s5;
+        self.didt = sis + s2 * ZNL * (c.z11 + c.z13);
+        self.dmdt = sls - ZNL * s3 * (c.z1 + c.z3 - 14.0 - 6.0 * emsq);
+        let sghl = s4 * ZNL * (c.z31 + c.z33 - 6.0);
+        let mut shll = -ZNL * s2 * (c.z21 + c.z23);
+        if near_equatorial {
+            shll = 0.0;
+        }
+        self.domdt = sgs + sghl;
+        self.dnodt = shs;
+        if sinim != 0.0 {
+            self.domdt -= cosim / sinim * shll;
+            self.dnodt += shll / sinim;
+        }
+
+        // Geopotential resonance
+        let theta = (gsto + tc * RPTIM) % TWO_PI;
+        if self.irez == 0 {
+            return;
+        }
+        let aonv = (nm / xke()).powf(X2O3);
+
+        if self.irez == 2 {
+            // Half-day (Molniya-type) orbits
+            let cosisq = cosim * cosim;
+            let em = ecco;
+            let emsq = eccsq;
+            let eoc = em * emsq;
+            let g201 = -0.306 - (em - 0.64) * 0.440;
+            let (g211, g310, g322, g410, g422, g520);
+            if em <= 0.65 {
+                g211 = 3.616 - 13.2470 * em + 16.2900 * emsq;
+                g310 = -19.302 + 117.3900 * em - 228.4190 * emsq + 156.5910 * eoc;
+                g322 = -18.9068 + 109.7927 * em - 214.6334 * emsq + 146.5816 * eoc;
+                g410 = -41.122 + 242.6940 * em - 471.0940 * emsq + 313.9530 * eoc;
+                g422 = -146.407 + 841.8800 * em - 1629.014 * emsq + 1083.4350 * eoc;
+                g520 = -532.114 + 3017.977 * em - 5740.032 * emsq + 3708.2760 * eoc;
+            } else {
+                g211 = -72.099 + 331.819 * em - 508.738 * emsq + 266.724 * eoc;
+                g310 = -346.844 + 1582.851 * em - 2415.925 * emsq + 1246.113 * eoc;
+                g322 = -342.585 + 1554.908 * em - 2366.899 * emsq + 1215.972 * eoc;
+                g410 = -1052.797 + 4758.686 * em - 7193.992 * emsq + 3651.957 * eoc;
+                g422 = -3581.690 + 16178.110 * em - 24462.770 * emsq + 12422.520 * eoc;
+                g520 = if em > 0.715 {
+                    -5149.66 + 29936.92 * em - 54087.36 * emsq + 31324.56 * eoc
+                } else {
+                    1464.74 - 4664.75 * em + 3763.64 * emsq
+                };
+            }
+            let (g533, g521, g532);
+            if em < 0.7 {
+                g533 = -919.22770 + 4988.6100 * em - 9064.7700 * emsq + 5542.21 * eoc;
+                g521 = -822.71072 + 4568.6173 * em - 8491.4146 * emsq + 5337.524 * eoc;
+                g532 = -853.66600 + 4690.2500 * em - 8624.7700 * emsq + 5341.4 * eoc;
+            } else {
+                g533 = -37995.780 + 161616.52 * em - 229838.20 * emsq + 109377.94 * eoc;
+                g521 = -51752.104 + 218913.95 * em - 309468.16 * emsq + 146349.42 * eoc;
+                g532 = -40023.880 + 170470.89 * em - 242699.48 * emsq + 115605.82 * eoc;
+            }
+
+            let sini2 = sinim * sinim;
+            let f220 = 0.75 * (1.0 + 2.0 * cosim + cosisq);
+            let f221 = 1.5 * sini2;
+            let f321 = 1.875 * sinim * (1.0 - 2.0 * cosim - 3.0 * cosisq);
+            let f322 = -1.875 * sinim * (1.0 + 2.0 * cosim - 3.0 * cosisq);
+            let f441 = 35.0 * sini2 * f220;
+            let f442 = 39.3750 * sini2 * sini2;
+            let f522 = 9.84375
+                * sinim
+                * (sini2 * (1.0 - 2.0 * cosim - 5.0 * cosisq)
+                    + 0.33333333 * (-2.0 + 4.0 * cosim + 6.0 * cosisq));
+            let f523 = sinim
+                * (4.92187512 * sini2 * (-2.0 - 4.0 * cosim + 10.0 * cosisq)
+                    + 6.56250012 * (1.0 + 2.0 * cosim - 3.0 * cosisq));
+            let f542 = 29.53125
+                * sinim
+                * (2.0 - 8.0 * cosim + cosisq * (-12.0 + 8.0 * cosim + 10.0 * cosisq));
+            let f543 = 29.53125
+                * sinim
+                * (-2.0 - 8.0 * cosim + cosisq * (12.0 + 8.0 * cosim - 10.0 * cosisq));
+
+            let xno2 = nm * nm;
+            let ainv2 = aonv * aonv;
+            let mut temp1 = 3.0 * xno2 * ainv2;
+            let mut temp = temp1 * ROOT22;
+            self.d2201 = temp * f220 * g201;
+            self.d2211 = temp * f221 * g211;
+            temp1 *= aonv;
+            temp = temp1 * ROOT32;
+            self.d3210 = temp * f321 * g310;
+            self.d3222 = temp * f322 * g322;
+            temp1 *= aonv;
+            temp = 2.0 * temp1 * ROOT44;
+            self.d4410 = temp * f441 * g410;
+            self.d4422 = temp * f442 * g422;
+            temp1 *= aonv;
+            temp = temp1 * ROOT52;
+            self.d5220 = temp * f522 * g520;
+            self.d5232 = temp * f523 * g532;
+            temp = 2.0 * temp1 * ROOT54;
+            self.d5421 = temp * f542 * g521;
+            self.d5433 = temp * f543 * g533;
+            self.xlamo = (mo + nodeo + nodeo - theta - theta) % TWO_PI;
+            self.xfact = mdot + self.dmdt + 2.0 * (nodedot + self.dnodt - RPTIM) - no;
+        }
+
+        if self.irez == 1 {
+            // Synchronous (geostationary-type) orbits
+            let g200 = 1.0 + emsq * (-2.5 + 0.8125 * emsq);
+            let g310 = 1.0 + 2.0 * emsq;
+            let g300 = 1.0 + emsq * (-6.0 + 6.60937 * emsq);
+            let f220 = 0.75 * (1.0 + cosim) * (1.0 + cosim);
+            let f311 = 0.9375 * sinim * sinim * (1.0 + 3.0 * cosim) - 0.75 * (1.0 + cosim);
+            let f330 = 1.0 + cosim;
+            let f330 = 1.875 * f330 * f330 * f330;
+            let del1 = 3.0 * nm * nm * aonv * aonv;
+            self.del2 = 2.0 * del1 * f220 * g200 * Q22;
+            self.del3 = 3.0 * del1 * f330 * g300 * Q33 * aonv;
+            self.del1 = del1 * f311 * g310 * Q31 * aonv;
+            self.xlamo = (mo + nodeo + argpo - theta) % TWO_PI;
+            self.xfact = mdot + xpidot - RPTIM + self.dmdt + self.domdt + self.dnodt - no;
+        }
+
+        self.xli = self.xlamo;
+        self.xni = no;
+        self.atime = 0.0;
+    }
+
+    /// Lunar-solar long-period periodics (dpper)
+    fn dpper(
+        &self,
+        t: f64,
+        ep: &mut f64,
+        inclp: &mut f64,
+        nodep: &mut f64,
+        argpp: &mut f64,
+        mp: &mut f64,
+    ) {
+        const ZNS: f64 = 1.19459e-5;
+        const ZES: f64 = 0.01675;
+        const ZNL: f64 = 1.5835218e-4;
+        const ZEL: f64 = 0.05490;
+
+        let periodics = |zm: f64, ze: f64| {
+            let zf = zm + 2.0 * ze * zm.sin();
+            let sinzf = zf.sin();
+            let f2 = 0.5 * sinzf * sinzf - 0.25;
+            let f3 = -0.5 * sinzf * zf.cos();
+            (sinzf, f2, f3)
+        };
+        let (sinzf, f2, f3) = periodics(self.zmos + ZNS * t, ZES);
+        let ses = self.se2 * f2 + self.se3 * f3;
+        let sis = self.si2 * f2 + self.si3 * f3;
+        let sls = self.sl2 * f2 + self.sl3 * f3 + self.sl4 * sinzf;
+        let sghs = self.sgh2 * f2 + self.sgh3 * f3 + self.sgh4 * sinzf;
+        let shs = self.sh2 * f2 + self.sh3 * f3;
+        let (sinzf, f2, f3) = periodics(self.zmol + ZNL * t, ZEL);
+        let sel = self.ee2 * f2 + self.e3 * f3;
+        let sil = self.xi2 * f2 + self.xi3 * f3;
+        let sll = self.xl2 * f2 + self.xl3 * f3 + self.xl4 * sinzf;
+        let sghl = self.xgh2 * f2 + self.xgh3 * f3 + self.xgh4 * sinzf;
+        let shll = self.xh2 * f2 + self.xh3 * f3;
+
+        // The values at epoch are zero in this revision of the model, so no offsets are removed
+        let pe = ses + sel;
+        let pinc = sis + sil;
+        let pl = sls + sll;
+        let mut pgh = sghs + sghl;
+        let mut ph = shs + shll;
+
+        *inclp += pinc;
+        *ep += pe;
+        let sinip = inclp.sin();
+        let cosip = inclp.cos();
+        if *inclp >= 0.2 {
+            ph /= sinip;
+            pgh -= cosip * ph;
+            *argpp += pgh;
+            *nodep += ph;
+            *mp += pl;
+        } else {
+            // Lyddane modification for low inclinations
+            let sinop = nodep.sin();
+            let cosop = nodep.cos();
+            let alfdp = sinip * sinop + ph * cosop + pinc * cosip * sinop;
+            let betdp = sinip * cosop - ph * sinop + pinc * cosip * cosop;
+            *nodep %= TWO_PI;
+            let xls = *mp + *argpp + cosip * *nodep + pl + pgh - pinc * *nodep * sinip;
+            let xnoh = *nodep;
+            *nodep = alfdp.atan2(betdp);
+            if (xnoh - *nodep).abs() > std::f64::consts::PI {
+                if *nodep < xnoh {
+                    *nodep += TWO_PI;
+                } else {
+                    *nodep -= TWO_PI;
+                }
+            }
+            *mp += pl;
+            *argpp = xls - *mp - cosip * *nodep;
+        }
+    }
+
+    /// Deep-space secular effects and resonance integration (dspace)
+    #[allow(clippy::too_many_arguments)]
+    fn dspace(
+        &mut self,
+        t: f64,
+        gsto: f64,
+        argpo: f64,
+        argpdot: f64,
+        no: f64,
+        em: &mut f64,
+        argpm: &mut f64,
+        inclm: &mut f64,
+        mm: &mut f64,
+        nodem: &mut f64,
+        nm: &mut f64,
+    ) {
+        const FASX2: f64 = 0.13130908;
+        const FASX4: f64 = 2.8843198;
+        const FASX6: f64 = 0.37448087;
+        const G22: f64 = 5.7686396;
+        const G32: f64 = 0.95240898;
+        const G44: f64 = 1.8014998;
+        const G52: f64 = 1.0508330;
+        const G54: f64 = 4.4108898;
+        const STEPP: f64 = 720.0;
+        const STEPN: f64 = -720.0;
+        const STEP2: f64 = 259200.0;
+
+        let theta = (gsto + t * RPTIM) % TWO_PI;
+        *em += self.dedt * t;
+        *inclm += self.didt * t;
+        *argpm += self.domdt * t;
+        *nodem += self.dnodt * t;
+        *mm += self.dmdt * t;
+
+        if self.irez == 0 {
+            return;
+        }
+
+        // Restart the integration from epoch when the request is on the other side of it
+        // or closer to it than the last integrated time
+        if self.atime == 0.0 || t * self.atime <= 0.0 || t.abs() < self.atime.abs() {
+            self.atime = 0.0;
+            self.xni = no;
+            self.xli = self.xlamo;
+        }
+        let delt = if t > 0.0 { STEPP } else { STEPN };
+
+        let (mut xndt, mut xldot, mut xnddt);
+        let ft = loop {
+            if self.irez != 2 {
+                let xli = self.xli;
+                xndt = self.del1 * (xli - FASX2).sin()
+                    + self.del2 * (2.0 * (xli - FASX4)).sin()
+                    + self.del3 * (3.0 * (xli - FASX6)).sin();
+                xldot = self.xni + self.xfact;
+                xnddt = self.del1 * (xli - FASX2).cos()
+                    + 2.0 * self.del2 * (2.0 * (xli - FASX4)).cos()
+                    + 3.0 * self.del3 * (3.0 * (xli - FASX6)).cos();
+                xnddt *= xldot;
+            } else {
+                let xomi = argpo + argpdot * self.atime;
+                let x2omi = xomi + xomi;
+                let xli = self.xli;
+                let x2li = xli + xli;
+                xndt = self.d2201 * (x2omi + xli - G22).sin()
+                    + self.d2211 * (xli - G22).sin()
+                    + self.d3210 * (xomi + xli - G32).sin()
+                    + self.d3222 * (-xomi + xli - G32).sin()
+                    + self.d4410 * (x2omi + x2li - G44).sin()
+                    + self.d4422 * (x2li - G44).sin()
+                    + self.d5220 * (xomi + xli - G52).sin()
+                    + self.d5232 * (-xomi + xli - G52).sin()
+                    + self.d5421 * (xomi + x2li - G54).sin()
+                    + self.d5433 * (-xomi + x2li - G54).sin();
+                xldot = self.xni + self.xfact;
+                xnddt = self.d2201 * (x2omi + xli - G22).cos()
+                    + self.d2211 * (xli - G22).cos()
+                    + self.d3210 * (xomi + xli - G32).cos()
+                    + self.d3222 * (-xomi + xli - G32).cos()
+                    + self.d5220 * (xomi + xli - G52).cos()
+                    + self.d5232 * (-xomi + xli - G52).cos()
+                    + 2.0
+                        * (self.d4410 * (x2omi + x2li - G44).cos()
+                            + self.d4422 * (x2li - G44).cos()
+                            + self.d5421 * (xomi + x2li - G54).cos()
+                            + self.d5433 * (-xomi + x2li - G54).cos());
+                xnddt *= xldot;
+            }
+
+            if (t - self.atime).abs() < STEPP {
+                break t - self.atime;
+            }
+            self.xli += xldot * delt + xndt * STEP2;
+            self.xni += xndt * delt + xnddt * STEP2;
+            self.atime += delt;
+        };
+
+        *nm = self.xni + xndt * ft + xnddt * ft * ft * 0.5;
+        let xl = self.xli + xldot * ft + xndt * ft * ft * 0.5;
+        *mm = if self.irez != 1 {
+            xl - 2.0 * *nodem + 2.0 * theta
+        } else {
+            xl - *nodem - *argpm + theta
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: DVec3, expected: DVec3, tolerance: f64) {
+        assert!(
+            (actual - expected).length() < tolerance,
+            "expected {:?}, got {:?}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_near_earth_verification_case() {
+        // Vanguard 1 from the SGP4 verification set (Vallado et al. 2006)
+        let tle = Tle::parse(
+            Some("VANGUARD 1"),
+            "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753",
+            "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667",
+        )
+        .unwrap();
+        assert_eq!(tle.catalog_number, 5);
+        assert!((tle.bstar - 2.8098e-5).abs() < 1e-12);
+        assert!((tle.eccentricity - 0.1859667).abs() < 1e-12);
+
+        let mut sgp4 = Sgp4::new(&tle).unwrap();
+        assert!(!sgp4.is_deep_space());
+
+        let (r, v) = sgp4.propagate(0.0).unwrap();
+        assert_close(
+            r,
+            DVec3::new(7022.46529266, -1400.08296755, 0.03995155),
+            1e-3,
+        );
+        assert_close(v, DVec3::new(1.893841015, 6.405893759, 4.534807250), 1e-6);
+
+        let (r, v) = sgp4.propagate(360.0).unwrap();
+        assert_close(
+            r,
+            DVec3::new(-7154.03120202, -3783.17682504, -3536.19412294),
+            1e-3,
+        );
+        assert_close(v, DVec3::new(4.741887409, -4.151817765, -2.093935425), 1e-6);
+    }
+
+    #[test]
+    fn test_deep_space_verification_case() {
+        // Molniya 2-14, a half-day resonant orbit exercising the SDP4 branch
+        let tle = Tle::parse(
+            None,
+            "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813",
+            "2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656",
+        )
+        .unwrap();
+        let mut sgp4 = Sgp4::new(&tle).unwrap();
+        assert!(sgp4.is_deep_space());
+
+        let (r, v) = sgp4.propagate(0.0).unwrap();
+        assert_close(
+            r,
+            DVec3::new(2349.89483350, -14785.93811562, 0.02119378),
+            1e-3,
+        );
+        assert_close(v, DVec3::new(2.721488096, -3.256811655, 4.498416672), 1e-6);
+
+        // Later vectors depend on the deep-space secular and resonance terms
+        let (r, v) = sgp4.propagate(120.0).unwrap();
+        assert_close(
+            r,
+            DVec3::new(15223.91713658, -17852.95881835, 25280.39558204),
+            1e-3,
+        );
+        assert_close(v, DVec3::new(1.079041732, 0.875187372, 2.485682813), 1e-6);
+
+        let (r, v) = sgp4.propagate(1440.0).unwrap();
+        assert_close(
+            r,
+            DVec3::new(2890.80638268, -15446.43952300, 948.77010176),
+            1e-3,
+        );
+        assert_close(v, DVec3::new(2.654407490, -2.909344895, 4.486437362), 1e-6);
+    }
+
+    #[test]
+    fn test_checksum_rejects_corrupted_line() {
+        let corrupted = Tle::parse(
+            None,
+            "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4754",
+            "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667",
+        );
+        assert!(corrupted.is_err());
+    }
+}
@@ -0,0 +1,580 @@
+/// Transit and eclipse photometry
+/// Synthetic light curves of the stars in a system as seen by an observer, with
+/// quadratic limb darkening and partial overlaps of any number of occulting disks
+use std::f64::consts::TAU;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use glam::{DVec2, DVec3};
+
+use crate::{
+    AstrariaError, AstrariaResult,
+    math::{Body, VariationalIntegrator},
+    scenario::{BodyType, ScenarioBody},
+};
+
+/// Rings the stellar disk is split into when integrating the blocked light
+const DISK_RINGS: usize = 200;
+
+/// Upper limit on integration steps of a single prediction
+const MAX_STEPS: usize = 5_000_000;
+
+/// Quadratic limb-darkening law I(μ) / I(1) = 1 - u1 (1 - μ) - u2 (1 - μ)²,
+/// where μ is the cosine of the angle between the line of sight and the surface normal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LimbDarkening {
+    pub u1: f64,
+    pub u2: f64,
+}
+
+impl LimbDarkening {
+    /// Roughly the Sun in visible light
+    pub const SOLAR: Self = Self { u1: 0.44, u2: 0.23 };
+
+    /// Uniform disk
+    pub const NONE: Self = Self { u1: 0.0, u2: 0.0 };
+
+    /// Relative intensity at a distance `r` from the disk centre, in stellar radii
+    pub fn intensity(&self, r: f64) -> f64 {
+        let one_minus_mu = 1.0 - (1.0 - r * r).max(0.0).sqrt();
+        1.0 - self.u1 * one_minus_mu - self.u2 * one_minus_mu * one_minus_mu
+    }
+}
+
+impl Default for LimbDarkening {
+    fn default() -> Self {
+        Self::SOLAR
+    }
+}
+
+/// Fraction of a limb-darkened star's light hidden by occulting disks, given as
+/// (centre, radius) in units of the stellar radius relative to the star's centre
+pub fn blocked_fraction(occulters: &[(DVec2, f64)], limb_darkening: &LimbDarkening) -> f64 {
+    if occulters.is_empty() {
+        return 0.0;
+    }
+
+    let mut blocked = 0.0;
+    let mut disk = 0.0;
+    let mut arcs = Vec::with_capacity(occulters.len() * 2);
+    for ring in 0..DISK_RINGS {
+        let r = (ring as f64 + 0.5) / DISK_RINGS as f64;
+        disk += limb_darkening.intensity(r) * TAU * r;
+
+        // Angular intervals of this ring covered by each occulter
+        arcs.clear();
+        for (centre, radius) in occulters {
+            let distance = centre.length();
+            if r + distance <= *radius {
+                arcs.push((0.0, TAU));
+                continue;
+            }
+            if r >= distance + radius || r <= distance - radius {
+                continue;
+            }
+            let half_angle = ((r * r + distance * distance - radius * radius)
+                / (2.0 * r * distance))
+                .clamp(-1.0, 1.0)
+                .acos();
+            let start = (centre.y.atan2(centre.x) - half_angle).rem_euclid(TAU);
+            let end = start + 2.0 * half_angle;
+            if end > TAU {
+                arcs.push((start, TAU));
+                arcs.push((0.0, end - TAU));
+            } else {
+                arcs.push((start, end));
+            }
+        }
+        if arcs.is_empty() {
+            continue;
+        }
+
+        // Length of the union of the intervals
+        arcs.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut covered = 0.0;
+        let (mut current_start, mut current_end) = arcs[0];
+        for &(start, end) in &arcs[1..] {
+            if start > current_end {
+                covered += current_end - current_start;
+                current_start = start;
+            }
+            current_end = current_end.max(end);
+        }
+        covered += current_end - current_start;
+
+        blocked += limb_darkening.intensity(r) * covered * r;
+    }
+
+    // Ring area is r dr dθ, so the common factor dr cancels
+    blocked / disk
+}
+
+/// Where the light curve is observed from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Observer {
+    /// From the centre of a body, which is itself ignored as an occulter
+    Body(usize),
+    /// From far away in this direction (from the system towards the observer)
+    Direction(DVec3),
+}
+
+/// Size, emission and state of a body as used for photometry
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhotometricBody {
+    pub name: String,
+    pub mass: f64,
+    pub position: DVec3,
+    pub velocity: DVec3,
+    /// Meters
+    pub radius: f64,
+    /// Effective temperature of stars in Kelvin; other bodies emit nothing
+    pub temperature: Option<f64>,
+}
+
+impl PhotometricBody {
+    fn new(name: &str, mass: f64, position: DVec3, velocity: DVec3, body_type: &BodyType) -> Self {
+        let (radius, temperature) = match body_type {
+            BodyType::Planet { radius, .. } => (*radius, None),
+            BodyType::Star {
+                radius,
+                temperature,
+                ..
+            } => (*radius, Some(*temperature as f64)),
+            BodyType::PlanetAtmo { radius, .. } => (*radius, None),
+            BodyType::BlackHole { radius } => (*radius, None),
+            BodyType::Spacecraft { radius, .. } => (*radius, None),
+            BodyType::Comet { radius, .. } => (*radius, None),
+        };
+        Self {
+            name: name.to_string(),
+            mass,
+            position,
+            velocity,
+            radius: radius as f64,
+            temperature,
+        }
+    }
+
+    /// Current state of a body of the running simulation
+    pub fn from_body(body: &Body) -> Self {
+        Self::new(
+            &body.name,
+            body.mass,
+            body.position,
+            body.velocity,
+            &body.body_type,
+        )
+    }
+
+    /// Initial state of a scenario body
+    pub fn from_scenario_body(body: &ScenarioBody) -> Self {
+        Self::new(
+            &body.name,
+            body.mass,
+            body.position,
+            body.velocity,
+            &body.body_type,
+        )
+    }
+}
+
+/// Light of one star hidden by one body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Occultation {
+    pub star: usize,
+    pub occulter: usize,
+    /// Fraction of the total flux of all stars hidden by this occulter alone
+    pub flux_loss: f64,
+    /// Whether the occulter looks larger than the star
+    pub occulter_larger: bool,
+}
+
+/// Relative brightness of the stars of a system and what is blocking them
+#[derive(Debug, Clone, PartialEq)]
+pub struct FluxSample {
+    /// Received flux divided by the flux with nothing in the way
+    pub relative_flux: f64,
+    pub occultations: Vec<Occultation>,
+}
+
+/// Flux received by an observer from every star, with overlaps between bodies
+/// and the stars' limb darkening. Bodies without a radius never block anything.
+pub fn observed_flux(
+    bodies: &[PhotometricBody],
+    observer: Observer,
+    limb_darkening: &LimbDarkening,
+) -> FluxSample {
+    let mut total = 0.0;
+    let mut received = 0.0;
+    let mut occultations = Vec::new();
+
+    for (star, star_body) in bodies.iter().enumerate() {
+        let Some(temperature) = star_body.temperature else {
+            continue;
+        };
+        if star_body.radius <= 0.0 || observer == Observer::Body(star) {
+            continue;
+        }
+
+        // Sky-plane frame centred on the star, with lengths in stellar radii
+        let (axis, distance) = match observer {
+            Observer::Body(index) => {
+                let offset = star_body.position - bodies[index].position;
+                (offset.normalize_or_zero(), offset.length())
+            }
+            Observer::Direction(direction) => (-direction.normalize_or_zero(), 1.0),
+        };
+        if axis == DVec3::ZERO
+            || matches!(observer, Observer::Body(_)) && distance <= star_body.radius
+        {
+            continue;
+        }
+        let (east, north) = axis.any_orthonormal_pair();
+        // Luminosity ∝ R² T⁴, diluted with distance for an observer inside the system
+        let weight = star_body.radius.powi(2) * temperature.powi(4) / distance.powi(2);
+        total += weight;
+
+        let mut disks = Vec::new();
+        let mut occulter_indices = Vec::new();
+        for (occulter, body) in bodies.iter().enumerate() {
+            if occulter == star || body.radius <= 0.0 || observer == Observer::Body(occulter) {
+                continue;
+            }
+            let disk = match observer {
+                Observer::Body(index) => {
+                    // Angles as seen by the observer, scaled by the star's angular radius
+                    let offset = body.position - bodies[index].position;
+                    let along = offset.dot(axis);
+                    if along <= 0.0 || along >= distance {
+                        continue;
+                    }
+                    let star_angle = (star_body.radius / distance).asin();
+                    let angle = offset.angle_between(axis);
+                    let occulter_angle = (body.radius / offset.length()).min(1.0).asin();
+                    let direction = DVec2::new(offset.dot(east), offset.dot(north))
+                        .try_normalize()
+                        .unwrap_or(DVec2::X);
+                    (
+                        direction * (angle / star_angle),
+                        occulter_angle / star_angle,
+                    )
+                }
+                Observer::Direction(_) => {
+                    let offset = body.position - star_body.position;
+                    if offset.dot(axis) >= 0.0 {
+                        continue;
+                    }
+                    (
+                        DVec2::new(offset.dot(east), offset.dot(north)) / star_body.radius,
+                        body.radius / star_body.radius,
+                    )
+                }
+            };
+            if disk.0.length() < 1.0 + disk.1 {
+                disks.push(disk);
+                occulter_indices.push(occulter);
+            }
+        }
+
+        let blocked = blocked_fraction(&disks, limb_darkening);
+        received += weight * (1.0 - blocked);
+        for (disk, occulter) in disks.iter().zip(occulter_indices) {
+            occultations.push(Occultation {
+                star,
+                occulter,
+                flux_loss: weight * blocked_fraction(std::slice::from_ref(disk), limb_darkening),
+                occulter_larger: disk.1 >= 1.0,
+            });
+        }
+    }
+
+    if total <= 0.0 {
+        return FluxSample {
+            relative_flux: 1.0,
+            occultations,
+        };
+    }
+    for occultation in &mut occultations {
+        occultation.flux_loss /= total;
+    }
+    FluxSample {
+        relative_flux: received / total,
+        occultations,
+    }
+}
+
+/// Transits are by bodies that look smaller than the star, eclipses by ones that
+/// cover all of it at once or by another star
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Transit,
+    Eclipse,
+}
+
+/// One passage of a body in front of a star
+#[derive(Debug, Clone, PartialEq)]
+pub struct EclipseEvent {
+    pub kind: EventKind,
+    pub star: String,
+    pub occulter: String,
+    /// First and last integration step with any overlap (s)
+    pub start: f64,
+    pub end: f64,
+    /// Time of the deepest point (s)
+    pub mid: f64,
+    /// Largest fraction of the system's total flux hidden by this occulter
+    pub depth: f64,
+}
+
+/// One point of a light curve
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightCurveSample {
+    pub time: f64,
+    pub relative_flux: f64,
+}
+
+/// Predicted light curve and transit / eclipse events over a time span
+#[derive(Debug, Clone, Default)]
+pub struct LightCurve {
+    pub samples: Vec<LightCurveSample>,
+    pub events: Vec<EclipseEvent>,
+}
+
+impl LightCurve {
+    /// Integrate the bodies forward from `start_time` for `duration` seconds.
+    /// Events are resolved to the integration step; the curve keeps one sample
+    /// every `sample_interval`, plus the deepest point of every event.
+    pub fn compute(
+        bodies: &[PhotometricBody],
+        observer: Observer,
+        limb_darkening: &LimbDarkening,
+        start_time: f64,
+        duration: f64,
+        time_step: f64,
+        sample_interval: f64,
+    ) -> AstrariaResult<Self> {
+        if time_step <= 0.0 || duration <= 0.0 {
+            return Err(AstrariaError::Physics(
+                "Duration and time step must be positive".to_string(),
+            ));
+        }
+        if duration / time_step > MAX_STEPS as f64 {
+            return Err(AstrariaError::Physics(format!(
+                "Prediction would take more than {} steps; increase the time step",
+                MAX_STEPS
+            )));
+        }
+        if let Observer::Body(index) = observer
+            && index >= bodies.len()
+        {
+            return Err(AstrariaError::Physics(format!(
+                "No body with index {}",
+                index
+            )));
+        }
+
+        let mut integrator = VariationalIntegrator::new(
+            bodies.iter().map(|body| body.mass).collect(),
+            bodies.iter().map(|body| body.position).collect(),
+            bodies.iter().map(|body| body.velocity).collect(),
+        );
+        let mut state = bodies.to_vec();
+
+        let mut curve = Self::default();
+        // Events still in progress: (star, occulter) -> event
+        let mut open: Vec<((usize, usize), EclipseEvent, f64)> = Vec::new();
+        let mut next_sample = 0.0;
+        loop {
+            let elapsed = integrator.time();
+            let time = start_time + elapsed;
+            for (body, (position, velocity)) in state
+                .iter_mut()
+                .zip(integrator.positions.iter().zip(&integrator.velocities))
+            {
+                body.position = *position;
+                body.velocity = *velocity;
+            }
+            let sample = observed_flux(&state, observer, limb_darkening);
+
+            let mut deepest = false;
+            for occultation in &sample.occultations {
+                let key = (occultation.star, occultation.occulter);
+                let kind = if occultation.occulter_larger
+                    || state[occultation.occulter].temperature.is_some()
+                {
+                    EventKind::Eclipse
+                } else {
+                    EventKind::Transit
+                };
+                match open.iter_mut().find(|(open_key, ..)| *open_key == key) {
+                    Some((_, event, seen)) => {
+                        event.end = time;
+                        *seen = time;
+                        if occultation.flux_loss > event.depth {
+                            event.depth = occultation.flux_loss;
+                            event.mid = time;
+                            event.kind = kind;
+                            deepest = true;
+                        }
+                    }
+                    None => open.push((
+                        key,
+                        EclipseEvent {
+                            kind,
+                            star: state[occultation.star].name.clone(),
+                            occulter: state[occultation.occulter].name.clone(),
+                            start: time,
+                            end: time,
+                            mid: time,
+                            depth: occultation.flux_loss,
+                        },
+                        time,
+                    )),
+                }
+            }
+            // Anything not seen this step has ended
+            let mut index = 0;
+            while index < open.len() {
+                if open[index].2 < time {
+                    curve.events.push(open.remove(index).1);
+                } else {
+                    index += 1;
+                }
+            }
+
+            if elapsed >= next_sample || deepest || elapsed >= duration {
+                curve.samples.push(LightCurveSample {
+                    time,
+                    relative_flux: sample.relative_flux,
+                });
+                if elapsed >= next_sample {
+                    next_sample += sample_interval.max(time_step);
+                }
+            }
+            if elapsed >= duration {
+                break;
+            }
+            integrator.step(time_step.min(duration - elapsed));
+        }
+
+        // Events cut off by the end of the prediction
+        curve
+            .events
+            .extend(open.into_iter().map(|(_, event, _)| event));
+        curve.events.sort_by(|a, b| a.start.total_cmp(&b.start));
+        Ok(curve)
+    }
+
+    /// Write the light curve as CSV: time, relative flux
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_csv(&self, path: &Path) -> AstrariaResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "time_s,relative_flux")?;
+        for sample in &self.samples {
+            writeln!(writer, "{},{:.10}", sample.time, sample.relative_flux)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Write the events as CSV: kind, star, occulter, start, mid, end, depth
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_events_csv(&self, path: &Path) -> AstrariaResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "kind,star,occulter,start_s,mid_s,end_s,depth")?;
+        for event in &self.events {
+            writeln!(
+                writer,
+                "{:?},{},{},{},{},{},{:e}",
+                event.kind,
+                event.star,
+                event.occulter,
+                event.start,
+                event.mid,
+                event.end,
+                event.depth
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{AU_TO_METERS, GRAVITATIONAL_CONSTANT, SOLAR_MASS};
+
+    #[test]
+    fn test_blocked_fraction() {
+        // Uniform disk: blocked fraction is the area ratio
+        let small = [(DVec2::new(0.3, 0.2), 0.1)];
+        assert!((blocked_fraction(&small, &LimbDarkening::NONE) - 0.01).abs() < 2e-4);
+
+        // Limb darkening makes a central transit deeper than the area ratio
+        let central = [(DVec2::ZERO, 0.1)];
+        let LimbDarkening { u1, u2 } = LimbDarkening::SOLAR;
+        let expected = 0.01 / (1.0 - u1 / 3.0 - u2 / 6.0);
+        let darkened = blocked_fraction(&central, &LimbDarkening::SOLAR);
+        assert!((darkened - expected).abs() / expected < 0.01);
+
+        // A larger disk hides everything; two overlapping disks count once
+        let total = [(DVec2::new(0.2, 0.0), 1.5)];
+        assert!((blocked_fraction(&total, &LimbDarkening::SOLAR) - 1.0).abs() < 1e-9);
+        let overlapping = [(DVec2::ZERO, 0.1), (DVec2::ZERO, 0.1)];
+        assert!((blocked_fraction(&overlapping, &LimbDarkening::SOLAR) - darkened).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_transit_light_curve() {
+        let star = PhotometricBody {
+            name: "Star".to_string(),
+            mass: SOLAR_MASS,
+            position: DVec3::ZERO,
+            velocity: DVec3::ZERO,
+            radius: 7.0e8,
+            temperature: Some(5778.0),
+        };
+        let speed = (GRAVITATIONAL_CONSTANT * SOLAR_MASS / AU_TO_METERS).sqrt();
+        let planet = PhotometricBody {
+            name: "Planet".to_string(),
+            mass: 1.0,
+            position: DVec3::new(AU_TO_METERS, 0.0, 0.0),
+            velocity: DVec3::new(0.0, speed, 0.0),
+            radius: 7.0e7,
+            temperature: None,
+        };
+        // Observer far along +x sees the planet cross the star immediately
+        let curve = LightCurve::compute(
+            &[star, planet],
+            Observer::Direction(DVec3::X),
+            &LimbDarkening::NONE,
+            0.0,
+            2.0 * 86400.0,
+            60.0,
+            600.0,
+        )
+        .unwrap();
+
+        assert_eq!(curve.events.len(), 1);
+        let event = &curve.events[0];
+        assert_eq!(event.kind, EventKind::Transit);
+        assert_eq!(event.occulter, "Planet");
+        assert!((event.depth - 0.01).abs() < 2e-4);
+        // Half a transit: (R* + Rp) / v
+        let half = (7.0e8 + 7.0e7) / speed;
+        assert!((event.end - half).abs() < 120.0);
+
+        let minimum = curve
+            .samples
+            .iter()
+            .map(|sample| sample.relative_flux)
+            .fold(1.0, f64::min);
+        assert!((minimum - 0.99).abs() < 2e-4);
+        assert_eq!(curve.samples.last().unwrap().relative_flux, 1.0);
+    }
+}
@@ -11,9 +11,20 @@ use std::time::Instant;
 
 use crate::{
     AstrariaError, AstrariaResult,
-    math::{Body, BodyCollection, GRAVITATIONAL_CONSTANT},
+    diagnostics::{ConservationDiagnostics, ConservationSample},
+    ephemeris::EphemerisComparison,
+    math::{
+        BarycentricCorrection, Body, BodyCollection, FrameTransform, GRAVITATIONAL_CONSTANT,
+        Maneuver, ReferenceFrame,
+    },
+    resonance::ResonanceMonitor,
+    scenario::{BodyType, IntegratorSettings, ScenarioMetadata},
+    stability::ChaosTracker,
 };
 
+/// Longest single step in simulated seconds, unless the scenario sets its own
+pub const DEFAULT_MAX_TIME_STEP: f64 = 0.1;
+
 /// Physics simulation statistics
 #[derive(Default, Clone)]
 pub struct PhysicsStats {
@@ -27,13 +38,24 @@ pub struct PhysicsStats {
 pub struct VelocityVerlet {
     bodies: Arc<RwLock<BodyCollection>>,
     simulation_speed: Arc<RwLock<f32>>,
+    /// Frame times are clamped to this many simulated seconds to keep the integration stable
+    max_time_step: Arc<RwLock<f64>>,
+    /// Viewing frame that orbital trails are recorded in
+    reference_frame: Arc<RwLock<ReferenceFrame>>,
     terminate_flag: Arc<AtomicBool>,
     #[cfg(not(target_arch = "wasm32"))]
     thread_handle: Option<JoinHandle<()>>,
     stats: Arc<RwLock<PhysicsStats>>,
-    /// For web: accumulated simulation time for path recording
-    #[cfg(target_arch = "wasm32")]
-    simulation_time: f64,
+    /// Simulated seconds since the scenario was loaded
+    simulation_time: Arc<RwLock<f64>>,
+    /// Energy and momentum drift since load
+    diagnostics: Arc<RwLock<ConservationDiagnostics>>,
+    /// Variational equations for MEGNO / Lyapunov estimates of selected bodies
+    chaos: Arc<RwLock<ChaosTracker>>,
+    /// Period-ratio scan of sibling bodies and their resonant angles
+    resonances: Arc<RwLock<ResonanceMonitor>>,
+    /// Error against a loaded reference trajectory
+    ephemeris: Arc<RwLock<EphemerisComparison>>,
 }
 
 impl Default for VelocityVerlet {
@@ -47,35 +69,53 @@ impl VelocityVerlet {
         Self {
             bodies: Arc::new(RwLock::new(BodyCollection::new())),
             simulation_speed: Arc::new(RwLock::new(1.0)),
+            max_time_step: Arc::new(RwLock::new(DEFAULT_MAX_TIME_STEP)),
+            reference_frame: Arc::new(RwLock::new(ReferenceFrame::default())),
             terminate_flag: Arc::new(AtomicBool::new(false)),
             #[cfg(not(target_arch = "wasm32"))]
             thread_handle: None,
             stats: Arc::new(RwLock::new(PhysicsStats::default())),
-            #[cfg(target_arch = "wasm32")]
-            simulation_time: 0.0,
+            simulation_time: Arc::new(RwLock::new(0.0)),
+            diagnostics: Arc::new(RwLock::new(ConservationDiagnostics::default())),
+            chaos: Arc::new(RwLock::new(ChaosTracker::default())),
+            resonances: Arc::new(RwLock::new(ResonanceMonitor::default())),
+            ephemeris: Arc::new(RwLock::new(EphemerisComparison::default())),
         }
     }
 
     /// Start simulation - native version uses threads
     #[cfg(not(target_arch = "wasm32"))]
     pub fn start_simulation(&mut self) -> AstrariaResult<()> {
-        if self.thread_handle.is_some() {
+        if self
+            .thread_handle
+            .as_ref()
+            .is_some_and(|handle| !handle.is_finished())
+        {
             return Err(AstrariaError::Physics(
                 "Simulation already running".to_string(),
             ));
         }
+        // A thread that was stopped or ended on an error is joined before a new one starts
+        self.stop_simulation();
+        self.terminate_flag.store(false, Ordering::Relaxed);
 
         let bodies = Arc::clone(&self.bodies);
         let simulation_speed = Arc::clone(&self.simulation_speed);
+        let max_time_step = Arc::clone(&self.max_time_step);
+        let reference_frame = Arc::clone(&self.reference_frame);
         let terminate_flag = Arc::clone(&self.terminate_flag);
         let stats = Arc::clone(&self.stats);
+        let simulation_time = Arc::clone(&self.simulation_time);
+        let diagnostics = Arc::clone(&self.diagnostics);
+        let chaos = Arc::clone(&self.chaos);
+        let resonances = Arc::clone(&self.resonances);
+        let ephemeris = Arc::clone(&self.ephemeris);
 
         let handle = thread::spawn(move || {
             let mut last_time = Instant::now();
             let mut step_count = 0u64;
             let mut stats_update_timer = Instant::now();
             let mut delta_time_accumulator = 0.0;
-            let mut simulation_time = 0.0; // Track total simulation time for path recording
 
             while !terminate_flag.load(Ordering::Relaxed) {
                 let current_time = Instant::now();
@@ -88,15 +128,25 @@ impl VelocityVerlet {
                 }
 
                 // Limit delta time to prevent numerical instability
-                delta_time = delta_time.min(0.1);
-
-                // Update simulation time
-                simulation_time += delta_time;
+                if let Ok(max_step) = max_time_step.read() {
+                    delta_time = delta_time.min(*max_step);
+                }
 
-                // Run the integration step
-                if let Err(e) = Self::integration_step(&bodies, delta_time, simulation_time) {
-                    log::error!("Physics integration error: {e}");
-                    break;
+                // Run the integration step, stopping at any scheduled burns
+                let start_time = simulation_time.read().map(|time| *time).unwrap_or(0.0);
+                match Self::advance(&bodies, &reference_frame, &chaos, start_time, delta_time) {
+                    Ok(end_time) => {
+                        if let Ok(mut time) = simulation_time.write() {
+                            *time = end_time;
+                        }
+                        Self::sample_diagnostics(&bodies, &diagnostics, end_time);
+                        Self::sample_resonances(&bodies, &resonances, end_time);
+                        Self::sample_ephemeris(&bodies, &ephemeris, end_time);
+                    }
+                    Err(e) => {
+                        log::error!("Physics integration error: {e}");
+                        break;
+                    }
                 }
 
                 // Update statistics
@@ -136,6 +186,7 @@ impl VelocityVerlet {
     /// Start simulation - web version is a no-op (simulation is driven by frame updates)
     #[cfg(target_arch = "wasm32")]
     pub fn start_simulation(&mut self) -> AstrariaResult<()> {
+        self.terminate_flag.store(false, Ordering::Relaxed);
         log::info!("Physics simulation started (web mode - frame-driven)");
         Ok(())
     }
@@ -151,13 +202,25 @@ impl VelocityVerlet {
         }
 
         // Limit delta time to prevent numerical instability
-        dt = dt.min(0.1);
-
-        // Update simulation time
-        self.simulation_time += dt;
+        if let Ok(max_step) = self.max_time_step.read() {
+            dt = dt.min(*max_step);
+        }
 
-        // Run the integration step
-        Self::integration_step(&self.bodies, dt, self.simulation_time)?;
+        // Run the integration step, stopping at any scheduled burns
+        let start_time = self.get_simulation_time()?;
+        let end_time = Self::advance(
+            &self.bodies,
+            &self.reference_frame,
+            &self.chaos,
+            start_time,
+            dt,
+        )?;
+        if let Ok(mut time) = self.simulation_time.write() {
+            *time = end_time;
+        }
+        Self::sample_diagnostics(&self.bodies, &self.diagnostics, end_time);
+        Self::sample_resonances(&self.bodies, &self.resonances, end_time);
+        Self::sample_ephemeris(&self.bodies, &self.ephemeris, end_time);
 
         // Update stats
         if let Ok(mut stats_guard) = self.stats.write() {
@@ -174,8 +237,265 @@ impl VelocityVerlet {
         Ok(())
     }
 
+    /// Advance the simulation by `delta_time` seconds from `start_time`
+    /// Steps are split so that every maneuver is applied exactly at its scheduled time.
+    /// Returns the new simulation time.
+    fn advance(
+        bodies: &Arc<RwLock<BodyCollection>>,
+        reference_frame: &Arc<RwLock<ReferenceFrame>>,
+        chaos: &Arc<RwLock<ChaosTracker>>,
+        start_time: f64,
+        delta_time: f64,
+    ) -> AstrariaResult<f64> {
+        let end_time = start_time + delta_time;
+        let mut time = start_time;
+
+        while let Some(burn_time) = Self::next_maneuver_time(bodies)?.filter(|t| *t <= end_time) {
+            // Overdue burns (e.g. scheduled in the past from the UI) fire immediately
+            let burn_time = burn_time.max(time);
+            if burn_time > time {
+                Self::tracked_step(bodies, reference_frame, chaos, burn_time - time, burn_time)?;
+                time = burn_time;
+            }
+            Self::apply_due_maneuvers(bodies, time)?;
+        }
+
+        if end_time > time {
+            Self::tracked_step(bodies, reference_frame, chaos, end_time - time, end_time)?;
+        }
+        Ok(end_time)
+    }
+
+    /// Integration step that also advances the chaos indicator tangent vectors, if any
+    fn tracked_step(
+        bodies: &Arc<RwLock<BodyCollection>>,
+        reference_frame: &Arc<RwLock<ReferenceFrame>>,
+        chaos: &Arc<RwLock<ChaosTracker>>,
+        delta_time: f64,
+        end_time: f64,
+    ) -> AstrariaResult<()> {
+        let tracking = chaos
+            .read()
+            .map(|tracker| !tracker.is_empty())
+            .unwrap_or(false);
+        if !tracking {
+            return Self::integration_step(bodies, reference_frame, delta_time, end_time);
+        }
+
+        let (masses, old_positions) = Self::mass_and_position_snapshot(bodies)?;
+        Self::integration_step(bodies, reference_frame, delta_time, end_time)?;
+        let (_, new_positions) = Self::mass_and_position_snapshot(bodies)?;
+
+        if let Ok(mut tracker) = chaos.write() {
+            tracker.step(
+                &masses,
+                &old_positions,
+                &new_positions,
+                delta_time,
+                end_time,
+            );
+        }
+        Ok(())
+    }
+
+    fn mass_and_position_snapshot(
+        bodies: &Arc<RwLock<BodyCollection>>,
+    ) -> AstrariaResult<(Vec<f64>, Vec<glam::DVec3>)> {
+        let bodies_guard = bodies
+            .read()
+            .map_err(|_| AstrariaError::Physics("Failed to acquire read lock".to_string()))?;
+
+        let mut masses = Vec::with_capacity(bodies_guard.len());
+        let mut positions = Vec::with_capacity(bodies_guard.len());
+        for body_ref in bodies_guard.bodies() {
+            let body = body_ref.read().map_err(|_| {
+                AstrariaError::Physics("Failed to acquire body read lock".to_string())
+            })?;
+            masses.push(body.mass);
+            positions.push(body.position);
+        }
+        Ok((masses, positions))
+    }
+
+    /// Record conserved quantities if the sampling interval has elapsed
+    fn sample_diagnostics(
+        bodies: &Arc<RwLock<BodyCollection>>,
+        diagnostics: &Arc<RwLock<ConservationDiagnostics>>,
+        time: f64,
+    ) {
+        let Ok(mut diagnostics) = diagnostics.write() else {
+            return;
+        };
+        if !diagnostics.is_due(time) {
+            return;
+        }
+        if let Ok(bodies) = bodies.read()
+            && !bodies.is_empty()
+        {
+            diagnostics.record(ConservationSample::from_collection(&bodies, time));
+        }
+    }
+
+    /// Scan for resonances if enabled and the sampling interval has elapsed
+    fn sample_resonances(
+        bodies: &Arc<RwLock<BodyCollection>>,
+        resonances: &Arc<RwLock<ResonanceMonitor>>,
+        time: f64,
+    ) {
+        let Ok(mut resonances) = resonances.write() else {
+            return;
+        };
+        if !resonances.is_due(time) {
+            return;
+        }
+        let Ok(bodies) = bodies.read() else {
+            return;
+        };
+        let mut names = Vec::with_capacity(bodies.len());
+        let mut masses = Vec::with_capacity(bodies.len());
+        let mut positions = Vec::with_capacity(bodies.len());
+        let mut velocities = Vec::with_capacity(bodies.len());
+        for body_ref in bodies.bodies() {
+            let Ok(body) = body_ref.read() else {
+                return;
+            };
+            names.push(body.name.clone());
+            masses.push(body.mass);
+            positions.push(body.position);
+            velocities.push(body.velocity);
+        }
+        drop(bodies);
+        resonances.record(time, &names, &masses, &positions, &velocities);
+    }
+
+    /// Compare against the reference trajectory if one is loaded and the interval has elapsed
+    fn sample_ephemeris(
+        bodies: &Arc<RwLock<BodyCollection>>,
+        ephemeris: &Arc<RwLock<EphemerisComparison>>,
+        time: f64,
+    ) {
+        let Ok(mut ephemeris) = ephemeris.write() else {
+            return;
+        };
+        if !ephemeris.is_due(time) {
+            return;
+        }
+        let Ok(bodies) = bodies.read() else {
+            return;
+        };
+        let mut names = Vec::with_capacity(bodies.len());
+        let mut positions = Vec::with_capacity(bodies.len());
+        let mut velocities = Vec::with_capacity(bodies.len());
+        for body_ref in bodies.bodies() {
+            let Ok(body) = body_ref.read() else {
+                return;
+            };
+            names.push(body.name.clone());
+            positions.push(body.position);
+            velocities.push(body.velocity);
+        }
+        drop(bodies);
+        ephemeris.record(time, &names, &positions, &velocities);
+    }
+
+    /// Earliest pending maneuver time across all spacecraft
+    fn next_maneuver_time(bodies: &Arc<RwLock<BodyCollection>>) -> AstrariaResult<Option<f64>> {
+        let bodies_guard = bodies
+            .read()
+            .map_err(|_| AstrariaError::Physics("Failed to acquire read lock".to_string()))?;
+
+        let mut next: Option<f64> = None;
+        for body_ref in bodies_guard.bodies() {
+            let body = body_ref.read().map_err(|_| {
+                AstrariaError::Physics("Failed to acquire body read lock".to_string())
+            })?;
+            if let BodyType::Spacecraft { maneuvers, .. } = &body.body_type {
+                for maneuver in maneuvers.iter().filter(|maneuver| !maneuver.executed) {
+                    next = Some(next.map_or(maneuver.time, |t| t.min(maneuver.time)));
+                }
+            }
+        }
+        Ok(next)
+    }
+
+    /// Apply every pending maneuver scheduled at or before `time`
+    fn apply_due_maneuvers(bodies: &Arc<RwLock<BodyCollection>>, time: f64) -> AstrariaResult<()> {
+        let bodies_guard = bodies
+            .read()
+            .map_err(|_| AstrariaError::Physics("Failed to acquire read lock".to_string()))?;
+        let body_refs = bodies_guard.bodies();
+
+        // Snapshot states first so reference bodies can be looked up by name
+        let mut states = Vec::with_capacity(body_refs.len());
+        for body_ref in body_refs {
+            let body = body_ref.read().map_err(|_| {
+                AstrariaError::Physics("Failed to acquire body read lock".to_string())
+            })?;
+            states.push((body.name.clone(), body.position, body.velocity));
+        }
+
+        for (index, body_ref) in body_refs.iter().enumerate() {
+            let mut body = body_ref.write().map_err(|_| {
+                AstrariaError::Physics("Failed to acquire body write lock".to_string())
+            })?;
+            let (position, mut velocity) = (body.position, body.velocity);
+            let name = body.name.clone();
+
+            let BodyType::Spacecraft { maneuvers, .. } = &mut body.body_type else {
+                continue;
+            };
+            for maneuver in maneuvers
+                .iter_mut()
+                .filter(|maneuver| !maneuver.executed && maneuver.time <= time)
+            {
+                maneuver.executed = true;
+
+                let reference = states
+                    .iter()
+                    .enumerate()
+                    .find(|(other, (other_name, _, _))| {
+                        *other != index && *other_name == maneuver.reference_body
+                    })
+                    .map(|(_, state)| state);
+                let Some((_, reference_position, reference_velocity)) = reference else {
+                    log::warn!(
+                        "Skipping burn of '{}' at t={:.1}s: reference body '{}' not found",
+                        name,
+                        maneuver.time,
+                        maneuver.reference_body
+                    );
+                    continue;
+                };
+
+                match maneuver.delta_v(position, velocity, *reference_position, *reference_velocity)
+                {
+                    Some(delta_v) => {
+                        velocity += delta_v;
+                        log::info!(
+                            "'{}' burned {:.2} m/s at t={:.3}s relative to '{}'",
+                            name,
+                            maneuver.magnitude(),
+                            time,
+                            maneuver.reference_body
+                        );
+                    }
+                    None => log::warn!(
+                        "Skipping burn of '{}' at t={:.1}s: no orbit around '{}'",
+                        name,
+                        maneuver.time,
+                        maneuver.reference_body
+                    ),
+                }
+            }
+            body.velocity = velocity;
+        }
+
+        Ok(())
+    }
+
     fn integration_step(
         bodies: &Arc<RwLock<BodyCollection>>,
+        reference_frame: &Arc<RwLock<ReferenceFrame>>,
         delta_time: f64,
         _simulation_time: f64,
     ) -> AstrariaResult<()> {
@@ -275,14 +595,50 @@ impl VelocityVerlet {
             // Store new acceleration for next timestep
             body.acceleration = *new_acceleration;
             body.acceleration_initialized = false; // Reset for next iteration
+        }
 
-            // Update orbital trail (like Java SimulationObject.prepare())
-            body.update_orbit_trail();
+        // Phase 4: Record orbital trails in the viewing frame (like Java SimulationObject.prepare())
+        let frame = reference_frame
+            .read()
+            .map(|frame| *frame)
+            .unwrap_or_default();
+        let transform = if frame == ReferenceFrame::Inertial {
+            FrameTransform::IDENTITY
+        } else {
+            Self::frame_transform(body_refs, frame)?
+        };
+
+        for body_ref in body_refs {
+            let mut body = body_ref.write().map_err(|_| {
+                AstrariaError::Physics("Failed to acquire body write lock".to_string())
+            })?;
+            body.update_orbit_trail_in_frame(&transform);
         }
 
         Ok(())
     }
 
+    /// Compute the world -> frame transform for the current body states
+    fn frame_transform(
+        body_refs: &[crate::math::SharedBody],
+        frame: ReferenceFrame,
+    ) -> AstrariaResult<FrameTransform> {
+        let mut masses = Vec::with_capacity(body_refs.len());
+        let mut positions = Vec::with_capacity(body_refs.len());
+        let mut velocities = Vec::with_capacity(body_refs.len());
+
+        for body_ref in body_refs {
+            let body = body_ref.read().map_err(|_| {
+                AstrariaError::Physics("Failed to acquire body read lock".to_string())
+            })?;
+            masses.push(body.mass);
+            positions.push(body.position);
+            velocities.push(body.velocity);
+        }
+
+        Ok(frame.transform_from_states(&masses, &positions, &velocities))
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     pub fn stop_simulation(&mut self) {
         self.terminate_flag.store(true, Ordering::Relaxed);
@@ -338,6 +694,121 @@ impl VelocityVerlet {
         Ok(())
     }
 
+    /// Change the longest step the integrator may take (simulated seconds)
+    pub fn set_max_time_step(&self, max_time_step: f64) -> AstrariaResult<()> {
+        if !(max_time_step.is_finite() && max_time_step > 0.0) {
+            return Err(AstrariaError::Physics(format!(
+                "Invalid maximum time step {}",
+                max_time_step
+            )));
+        }
+        let mut max_step = self
+            .max_time_step
+            .write()
+            .map_err(|_| AstrariaError::Physics("Failed to acquire write lock".to_string()))?;
+        *max_step = max_time_step;
+        Ok(())
+    }
+
+    /// Run a closure with access to the conservation diagnostics
+    pub fn with_diagnostics<R>(
+        &self,
+        f: impl FnOnce(&mut ConservationDiagnostics) -> R,
+    ) -> AstrariaResult<R> {
+        let mut diagnostics = self.diagnostics.write().map_err(|_| {
+            AstrariaError::Physics("Failed to acquire diagnostics lock".to_string())
+        })?;
+        Ok(f(&mut diagnostics))
+    }
+
+    /// Run a closure with access to the chaos indicator tracker
+    pub fn with_chaos<R>(&self, f: impl FnOnce(&mut ChaosTracker) -> R) -> AstrariaResult<R> {
+        let mut chaos = self
+            .chaos
+            .write()
+            .map_err(|_| AstrariaError::Physics("Failed to acquire chaos lock".to_string()))?;
+        Ok(f(&mut chaos))
+    }
+
+    /// Run a closure with access to the resonance monitor
+    pub fn with_resonances<R>(
+        &self,
+        f: impl FnOnce(&mut ResonanceMonitor) -> R,
+    ) -> AstrariaResult<R> {
+        let mut resonances = self
+            .resonances
+            .write()
+            .map_err(|_| AstrariaError::Physics("Failed to acquire resonance lock".to_string()))?;
+        Ok(f(&mut resonances))
+    }
+
+    /// Run a closure with access to the ephemeris comparison
+    pub fn with_ephemeris<R>(
+        &self,
+        f: impl FnOnce(&mut EphemerisComparison) -> R,
+    ) -> AstrariaResult<R> {
+        let mut ephemeris = self
+            .ephemeris
+            .write()
+            .map_err(|_| AstrariaError::Physics("Failed to acquire ephemeris lock".to_string()))?;
+        Ok(f(&mut ephemeris))
+    }
+
+    /// Start measuring MEGNO and the Lyapunov exponent of a body from its current state
+    pub fn track_chaos(&self, body_index: usize) -> AstrariaResult<()> {
+        let bodies = self.get_bodies()?;
+        let Some(body) = bodies.get(body_index) else {
+            return Err(AstrariaError::Physics(format!(
+                "No body with index {}",
+                body_index
+            )));
+        };
+        let masses: Vec<f64> = bodies.iter().map(|body| body.mass).collect();
+        let positions: Vec<_> = bodies.iter().map(|body| body.position).collect();
+        let velocities: Vec<_> = bodies.iter().map(|body| body.velocity).collect();
+        let name = body.name.clone();
+        self.with_chaos(|chaos| chaos.track(body_index, name, &masses, &positions, &velocities))
+    }
+
+    pub fn get_simulation_time(&self) -> AstrariaResult<f64> {
+        self.simulation_time
+            .read()
+            .map(|time| *time)
+            .map_err(|_| AstrariaError::Physics("Failed to read simulation time".to_string()))
+    }
+
+    /// Replace the maneuver list of a spacecraft with an edited copy.
+    /// Burns keep their live execution state (see [`Maneuver::merge_edits`]).
+    pub fn set_maneuvers(
+        &self,
+        body_index: usize,
+        new_maneuvers: Vec<Maneuver>,
+    ) -> AstrariaResult<()> {
+        let now = self.get_simulation_time()?;
+        let bodies = self
+            .bodies
+            .read()
+            .map_err(|_| AstrariaError::Physics("Failed to acquire read lock".to_string()))?;
+        let body_ref = bodies
+            .bodies()
+            .get(body_index)
+            .ok_or_else(|| AstrariaError::Physics(format!("No body at index {}", body_index)))?;
+        let mut body = body_ref
+            .write()
+            .map_err(|_| AstrariaError::Physics("Failed to acquire body write lock".to_string()))?;
+
+        match &mut body.body_type {
+            BodyType::Spacecraft { maneuvers, .. } => {
+                *maneuvers = Maneuver::merge_edits(maneuvers, new_maneuvers, now);
+                Ok(())
+            }
+            _ => Err(AstrariaError::Physics(format!(
+                "'{}' is not a spacecraft",
+                body.name
+            ))),
+        }
+    }
+
     pub fn get_simulation_speed(&self) -> AstrariaResult<f32> {
         let sim_speed = self
             .simulation_speed
@@ -347,6 +818,43 @@ impl VelocityVerlet {
         Ok(*sim_speed)
     }
 
+    /// Change the viewing frame; existing trails are cleared because they were
+    /// recorded in the previous frame
+    pub fn set_reference_frame(&self, frame: ReferenceFrame) -> AstrariaResult<()> {
+        {
+            let mut current = self
+                .reference_frame
+                .write()
+                .map_err(|_| AstrariaError::Physics("Failed to acquire write lock".to_string()))?;
+            if *current == frame {
+                return Ok(());
+            }
+            *current = frame;
+        }
+
+        let bodies = self
+            .bodies
+            .read()
+            .map_err(|_| AstrariaError::Physics("Failed to acquire read lock".to_string()))?;
+        for body_ref in bodies.bodies() {
+            let mut body = body_ref.write().map_err(|_| {
+                AstrariaError::Physics("Failed to acquire body write lock".to_string())
+            })?;
+            body.orbit_trail.clear();
+        }
+
+        Ok(())
+    }
+
+    pub fn get_reference_frame(&self) -> AstrariaResult<ReferenceFrame> {
+        let frame = self
+            .reference_frame
+            .read()
+            .map_err(|_| AstrariaError::Physics("Failed to acquire read lock".to_string()))?;
+
+        Ok(*frame)
+    }
+
     /// Get current physics simulation statistics
     pub fn get_stats(&self) -> AstrariaResult<PhysicsStats> {
         let stats = self
@@ -389,15 +897,46 @@ impl Drop for VelocityVerlet {
 /// Main physics simulation coordinator
 pub struct PhysicsSimulation {
     algorithm: VelocityVerlet,
+    /// Overrides the scenario's `barycentric` setting when set (from the CLI)
+    barycentric_override: Option<bool>,
+    /// Correction applied when the current scenario was loaded, if any
+    barycentric_correction: Option<BarycentricCorrection>,
+    /// Name, epoch and camera bookmarks of the current scenario
+    metadata: ScenarioMetadata,
+    /// Integrator settings the current scenario asked for
+    integrator: IntegratorSettings,
 }
 
 impl PhysicsSimulation {
     pub fn new() -> Self {
         Self {
             algorithm: VelocityVerlet::new(),
+            barycentric_override: None,
+            barycentric_correction: None,
+            metadata: ScenarioMetadata::default(),
+            integrator: IntegratorSettings::default(),
         }
     }
 
+    /// Force barycentric normalization on or off for every loaded scenario
+    pub fn set_barycentric_override(&mut self, barycentric: Option<bool>) {
+        self.barycentric_override = barycentric;
+    }
+
+    /// Offsets removed by barycentric normalization of the current scenario
+    pub fn barycentric_correction(&self) -> Option<BarycentricCorrection> {
+        self.barycentric_correction
+    }
+
+    /// Metadata of the current scenario (empty for v3 files)
+    pub fn scenario_metadata(&self) -> &ScenarioMetadata {
+        &self.metadata
+    }
+
+    pub fn integrator_settings(&self) -> IntegratorSettings {
+        self.integrator
+    }
+
     pub fn start(&mut self) -> AstrariaResult<()> {
         self.algorithm.start_simulation()
     }
@@ -416,6 +955,20 @@ impl PhysicsSimulation {
         self.algorithm.add_body(body)
     }
 
+    /// Add a body to a running simulation so it is integrated from the next step
+    pub fn spawn_body(&self, body: Body) -> AstrariaResult<()> {
+        let mut bodies = self.algorithm.bodies.write().map_err(|_| {
+            AstrariaError::Physics("Failed to acquire write lock on bodies".to_string())
+        })?;
+        bodies.add_body(body);
+        bodies.update_collection();
+        drop(bodies);
+
+        // New mass changes every conserved total, so measure drift from here on
+        self.with_diagnostics(|diagnostics| diagnostics.reset())?;
+        Ok(())
+    }
+
     pub fn get_bodies(&self) -> AstrariaResult<Vec<Body>> {
         self.algorithm.get_bodies()
     }
@@ -428,19 +981,66 @@ impl PhysicsSimulation {
         self.algorithm.get_simulation_speed()
     }
 
+    /// Simulated seconds since the scenario was loaded
+    pub fn get_simulation_time(&self) -> AstrariaResult<f64> {
+        self.algorithm.get_simulation_time()
+    }
+
+    pub fn set_maneuvers(&self, body_index: usize, maneuvers: Vec<Maneuver>) -> AstrariaResult<()> {
+        self.algorithm.set_maneuvers(body_index, maneuvers)
+    }
+
+    /// Run a closure with access to the conservation diagnostics
+    pub fn with_diagnostics<R>(
+        &self,
+        f: impl FnOnce(&mut ConservationDiagnostics) -> R,
+    ) -> AstrariaResult<R> {
+        self.algorithm.with_diagnostics(f)
+    }
+
+    /// Run a closure with access to the chaos indicator tracker
+    pub fn with_chaos<R>(&self, f: impl FnOnce(&mut ChaosTracker) -> R) -> AstrariaResult<R> {
+        self.algorithm.with_chaos(f)
+    }
+
+    /// Start measuring chaos indicators for a body
+    pub fn track_chaos(&self, body_index: usize) -> AstrariaResult<()> {
+        self.algorithm.track_chaos(body_index)
+    }
+
+    /// Run a closure with access to the resonance monitor
+    pub fn with_resonances<R>(
+        &self,
+        f: impl FnOnce(&mut ResonanceMonitor) -> R,
+    ) -> AstrariaResult<R> {
+        self.algorithm.with_resonances(f)
+    }
+
+    /// Run a closure with access to the ephemeris comparison
+    pub fn with_ephemeris<R>(
+        &self,
+        f: impl FnOnce(&mut EphemerisComparison) -> R,
+    ) -> AstrariaResult<R> {
+        self.algorithm.with_ephemeris(f)
+    }
+
     pub fn load_scenario(&mut self, scenario_data: String) -> AstrariaResult<()> {
-        use crate::math::Body;
         use crate::scenario::ScenarioParser;
 
         // Parse the scenario file
         let scenario = ScenarioParser::parse(&scenario_data)?;
+        self.load_parsed_scenario(scenario)
+    }
 
-        if scenario.bodies.is_empty() {
-            log::warn!("No bodies found in scenario, creating test scenario");
-            return self.create_test_scenario();
-        }
+    /// Replace the simulation with an already parsed (or generated) scenario
+    pub fn load_parsed_scenario(
+        &mut self,
+        scenario: crate::scenario::Scenario,
+    ) -> AstrariaResult<()> {
+        use crate::math::Body;
 
-        log::info!("Loading scenario with {} bodies", scenario.bodies.len());
+        // Stop stepping while the bodies are replaced; the thread restarts once they are loaded
+        self.shutdown();
 
         // Clear existing bodies
         {
@@ -449,10 +1049,41 @@ impl PhysicsSimulation {
             })?;
             *bodies = crate::math::BodyCollection::new();
         }
+        if let Ok(mut time) = self.algorithm.simulation_time.write() {
+            *time = 0.0;
+        }
+        self.with_diagnostics(|diagnostics| diagnostics.reset())?;
+        self.with_chaos(|chaos| chaos.clear())?;
+        self.algorithm
+            .with_resonances(|resonances| resonances.clear())?;
+        self.with_ephemeris(|ephemeris| ephemeris.reset())?;
+
+        self.barycentric_correction = None;
+        self.metadata = scenario.metadata;
+        self.integrator = scenario.integrator;
+
+        if scenario.bodies.is_empty() {
+            log::warn!("No bodies found in scenario, creating test scenario");
+            return self.create_test_scenario();
+        }
+
+        log::info!("Loading scenario with {} bodies", scenario.bodies.len());
+        let barycentric = self
+            .barycentric_override
+            .or(scenario.barycentric)
+            .unwrap_or(false);
+        self.algorithm.set_max_time_step(
+            self.integrator
+                .max_time_step
+                .unwrap_or(DEFAULT_MAX_TIME_STEP),
+        )?;
+        if let Some(time_scale) = self.integrator.time_scale {
+            self.set_simulation_speed(time_scale)?;
+        }
 
         // Add bodies from scenario
         for scenario_body in scenario.bodies {
-            let body = Body::new_with_properties(
+            let mut body = Body::new_with_properties(
                 scenario_body.mass,
                 scenario_body.position,
                 scenario_body.velocity,
@@ -461,6 +1092,7 @@ impl PhysicsSimulation {
                 scenario_body.orbit_color,
                 scenario_body.rotation_params,
             );
+            body.rotation_mode = scenario_body.rotation_mode;
 
             log::info!(
                 "Adding body: {} (mass: {:.2e} kg)",
@@ -476,6 +1108,16 @@ impl PhysicsSimulation {
                 AstrariaError::Physics("Failed to acquire write lock on bodies".to_string())
             })?;
             bodies.update_collection();
+
+            if barycentric {
+                let correction = bodies.shift_to_barycentre();
+                log::info!(
+                    "Moved scenario to barycentric frame: position offset {:.3e} m, velocity offset {:.3e} m/s",
+                    correction.position_offset.length(),
+                    correction.velocity_offset.length()
+                );
+                self.barycentric_correction = Some(correction);
+            }
         }
 
         // Start the simulation
@@ -500,6 +1142,13 @@ impl PhysicsSimulation {
 
         self.add_body(sun)?;
         self.add_body(earth)?;
+        self.algorithm
+            .bodies
+            .write()
+            .map_err(|_| {
+                AstrariaError::Physics("Failed to acquire write lock on bodies".to_string())
+            })?
+            .update_collection();
 
         // Start the simulation
         self.start()?;
@@ -513,6 +1162,37 @@ impl PhysicsSimulation {
         self.algorithm.get_stats()
     }
 
+    pub fn set_reference_frame(&self, frame: ReferenceFrame) -> AstrariaResult<()> {
+        self.algorithm.set_reference_frame(frame)
+    }
+
+    pub fn get_reference_frame(&self) -> AstrariaResult<ReferenceFrame> {
+        self.algorithm.get_reference_frame()
+    }
+
+    /// World -> viewing frame transform for the current body states
+    pub fn get_view_transform(&self) -> AstrariaResult<FrameTransform> {
+        let frame = self.get_reference_frame()?;
+        if frame == ReferenceFrame::Inertial {
+            return Ok(FrameTransform::IDENTITY);
+        }
+        Ok(frame.transform(&self.get_bodies()?))
+    }
+
+    /// Get bodies with positions and velocities expressed in the viewing frame
+    /// This is what the renderer draws; physics itself stays inertial
+    pub fn get_view_bodies(&self) -> AstrariaResult<Vec<Body>> {
+        let mut bodies = self.get_bodies()?;
+        let frame = self.get_reference_frame()?;
+        if frame != ReferenceFrame::Inertial {
+            let transform = frame.transform(&bodies);
+            for body in &mut bodies {
+                transform.apply_to_body(body);
+            }
+        }
+        Ok(bodies)
+    }
+
     /// Update orbital trail GPU buffers for all bodies
     /// This provides the mutable access needed for GPU buffer updates during rendering
     pub fn update_orbital_trail_buffers(&self, device: &wgpu::Device, camera_position: glam::DVec3) -> crate::AstrariaResult<()> {
@@ -525,3 +1205,128 @@ impl Default for PhysicsSimulation {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::DVec3;
+
+    #[test]
+    fn test_maneuver_applied_at_scheduled_time() {
+        // Light bodies far apart so gravity is negligible over the test
+        let reference = Body::new_with_properties(
+            1.0,
+            DVec3::ZERO,
+            DVec3::ZERO,
+            "Beacon".to_string(),
+            BodyType::Planet {
+                radius: 1.0,
+                texture_path: String::new(),
+                rings: None,
+            },
+            [1.0; 4],
+            (0.0, 0.0, 0.0, 0.0),
+        );
+        let craft = Body::new_with_properties(
+            1.0,
+            DVec3::new(1.0e6, 0.0, 0.0),
+            DVec3::new(0.0, 100.0, 0.0),
+            "Probe".to_string(),
+            BodyType::Spacecraft {
+                radius: 1.0,
+                maneuvers: vec![Maneuver::new(0.25, "Beacon".to_string(), 10.0, 0.0, 0.0)],
+            },
+            [1.0; 4],
+            (0.0, 0.0, 0.0, 0.0),
+        );
+
+        let mut collection = BodyCollection::new();
+        collection.add_body(reference);
+        collection.add_body(craft);
+        collection.update_collection();
+        let bodies = Arc::new(RwLock::new(collection));
+        let frame = Arc::new(RwLock::new(ReferenceFrame::Inertial));
+
+        let chaos = Arc::new(RwLock::new(ChaosTracker::default()));
+        let end_time = VelocityVerlet::advance(&bodies, &frame, &chaos, 0.0, 1.0).unwrap();
+        assert_eq!(end_time, 1.0);
+
+        let guard = bodies.read().unwrap();
+        let craft = guard.bodies()[1].read().unwrap();
+        // 0.25 s before the burn at 100 m/s, 0.75 s after at 110 m/s
+        assert!((craft.position.y - (25.0 + 0.75 * 110.0)).abs() < 1e-6);
+        assert!((craft.velocity.y - 110.0).abs() < 1e-9);
+        let BodyType::Spacecraft { maneuvers, .. } = &craft.body_type else {
+            unreachable!();
+        };
+        assert!(maneuvers[0].executed);
+    }
+
+    #[test]
+    fn test_loading_into_running_simulation_replaces_bodies() {
+        let scenario = |name: &str| {
+            format!(
+                "v3
+
+type: planet
+name: {}
+radius: 1000
+mass: 1E20
+velocity: 0 0 0
+position: 0 0 0
+texture: Planet Textures/2k_moon.jpg
+orbit_color: 1.0 1.0 1.0 1.0
+rotation: 0 0 0 0
+",
+                name
+            )
+        };
+
+        let mut physics = PhysicsSimulation::new();
+        physics.load_scenario(scenario("First")).unwrap();
+        physics.load_scenario(scenario("Second")).unwrap();
+        let bodies = physics.get_bodies().unwrap();
+        assert_eq!(bodies.len(), 1);
+        assert_eq!(bodies[0].name, "Second");
+
+        // The restarted thread keeps stepping
+        let start = std::time::Instant::now();
+        while physics.get_simulation_time().unwrap() == 0.0 {
+            assert!(
+                start.elapsed().as_secs() < 5,
+                "physics thread did not restart"
+            );
+            thread::yield_now();
+        }
+        physics.shutdown();
+    }
+
+    #[test]
+    fn test_loading_empty_scenario_clears_running_bodies() {
+        let mut physics = PhysicsSimulation::new();
+        physics
+            .load_scenario(
+                "v3
+
+type: planet
+name: Vesta
+radius: 1000
+mass: 1E20
+velocity: 0 0 0
+position: 0 0 0
+texture: Planet Textures/2k_moon.jpg
+orbit_color: 1.0 1.0 1.0 1.0
+rotation: 0 0 0 0
+"
+                .to_string(),
+            )
+            .unwrap();
+        physics.load_scenario("v3\n".to_string()).unwrap();
+
+        // Only the fallback Sun-Earth pair is left, not appended to Vesta
+        let bodies = physics.get_bodies().unwrap();
+        assert_eq!(bodies.len(), 2);
+        assert!(bodies.iter().all(|body| body.name != "Vesta"));
+        physics.shutdown();
+    }
+}
@@ -322,6 +322,17 @@ impl Camera {
             distance
         );
     }
+
+    /// Place the camera at `position`, turned to face `target`
+    pub fn look_from(&mut self, position: DVec3, target: DVec3) {
+        let direction = (target - position).normalize_or_zero();
+        self.rotation = if direction == DVec3::ZERO {
+            Quat::IDENTITY
+        } else {
+            Quat::from_rotation_arc(Vec3::NEG_Z, direction.as_vec3())
+        };
+        self.position = position;
+    }
 }
 
 impl Default for Camera {
@@ -0,0 +1,152 @@
+/// Comet tails built from instanced particle billboards
+/// The ion tail streams straight away from the star; the dust tail curves back along the orbit
+use glam::{DVec3, Vec3};
+
+use crate::{math::AU_TO_METERS, renderer::shaders::comet_tail_shader::TailParticle};
+
+/// Particles drawn along each tail
+const PARTICLES_PER_TAIL: u32 = 48;
+
+/// Tail length at 1 AU from the star, in meters
+const TAIL_LENGTH_AT_1_AU: f64 = 5.0e10;
+
+/// Closer than this the tail stops growing, in AU
+const MAX_LENGTH_DISTANCE: f64 = 1.0 / 3.0;
+
+/// Outgassing starts to fade at the first distance and stops at the second, in AU
+const ACTIVITY_FADE: (f64, f64) = (3.0, 5.0);
+
+/// Brightness cap so tails close to the star don't saturate to white
+const MAX_ACTIVITY: f64 = 2.0;
+
+/// How far the dust tail lags behind the anti-star line at its end, relative to its length
+const DUST_CURVATURE: f64 = 0.5;
+
+/// Ionised gas glows blue, dust reflects yellowish sunlight
+const ION_COLOR: Vec3 = Vec3::new(0.45, 0.65, 1.0);
+const DUST_COLOR: Vec3 = Vec3::new(1.0, 0.92, 0.75);
+const COMA_COLOR: Vec3 = Vec3::new(0.85, 0.9, 1.0);
+
+/// Both tails of a comet, ready for the tail shader
+#[derive(Debug, Clone)]
+pub struct CometTail {
+    /// Tail length in meters; particle offsets and sizes are in units of it
+    pub length: f64,
+    pub particles: Vec<TailParticle>,
+}
+
+impl CometTail {
+    /// Tails of a comet at `position` moving at `velocity`, both relative to the star lighting it
+    ///
+    /// Returns `None` when the comet is too far out to outgas.
+    pub fn new(position: DVec3, velocity: DVec3) -> Option<Self> {
+        let distance = position.length();
+        let activity = activity(distance);
+        if activity <= 0.0 {
+            return None;
+        }
+
+        let distance_au = distance / AU_TO_METERS;
+        let length =
+            TAIL_LENGTH_AT_1_AU * outgassing(distance_au) / distance_au.max(MAX_LENGTH_DISTANCE);
+        let brightness = activity.min(MAX_ACTIVITY) as f32;
+
+        // The dust falls behind the nucleus, away from the direction of motion
+        let anti_star = position / distance;
+        let lag = -velocity.reject_from(anti_star).normalize_or_zero();
+        let (side_a, side_b) = anti_star.any_orthonormal_pair();
+
+        let mut particles = Vec::with_capacity(2 * PARTICLES_PER_TAIL as usize + 1);
+        particles.push(TailParticle {
+            offset: Vec3::ZERO,
+            size: 0.04,
+            color: COMA_COLOR.extend(brightness),
+        });
+        for index in 0..PARTICLES_PER_TAIL {
+            let s = (index as f64 + 0.5) / PARTICLES_PER_TAIL as f64;
+            let fade = brightness * 0.35 * (1.0 - s as f32).powf(1.5);
+            let spread = |width: f64, salt: u32| {
+                (side_a * jitter(index, salt) + side_b * jitter(index, salt + 1)) * width
+            };
+
+            // Ion tail: a narrow, straight streamer
+            let ion = anti_star * s + spread(0.01 + 0.03 * s, 0);
+            particles.push(TailParticle {
+                offset: ion.as_vec3(),
+                size: (0.02 + 0.04 * s) as f32,
+                color: ION_COLOR.extend(fade),
+            });
+
+            // Dust tail: a broad fan bending back along the orbit
+            let dust = anti_star * s + lag * (DUST_CURVATURE * s * s) + spread(0.02 + 0.1 * s, 2);
+            particles.push(TailParticle {
+                offset: dust.as_vec3(),
+                size: (0.03 + 0.09 * s) as f32,
+                color: DUST_COLOR.extend(fade),
+            });
+        }
+
+        Some(Self { length, particles })
+    }
+}
+
+/// Outgassing strength at `distance` meters from the star, 1 at 1 AU
+///
+/// Follows the received flux, fading out between 3 and 5 AU where the ices stop sublimating.
+pub fn activity(distance: f64) -> f64 {
+    let distance_au = distance / AU_TO_METERS;
+    if distance_au <= 0.0 {
+        return 0.0;
+    }
+    outgassing(distance_au) / (distance_au * distance_au)
+}
+
+/// Fraction of the ices sublimating at `distance_au`, 1 inside the fade and 0 beyond it
+fn outgassing(distance_au: f64) -> f64 {
+    let (fade_start, fade_end) = ACTIVITY_FADE;
+    ((fade_end - distance_au) / (fade_end - fade_start)).clamp(0.0, 1.0)
+}
+
+/// Deterministic pseudo-random value in [-1, 1] so particles hold still between frames
+fn jitter(index: u32, salt: u32) -> f64 {
+    let mut hash = index.wrapping_mul(0x9e37_79b9) ^ salt.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x7feb_352d);
+    hash ^= hash >> 15;
+    hash as f64 / u32::MAX as f64 * 2.0 - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tails_point_away_from_star_and_lag_the_orbit() {
+        // Star at the origin, comet 1 AU out along +x, moving along +y
+        let tail = CometTail::new(
+            DVec3::new(AU_TO_METERS, 0.0, 0.0),
+            DVec3::new(0.0, 40_000.0, 0.0),
+        )
+        .unwrap();
+        assert!((tail.length - TAIL_LENGTH_AT_1_AU).abs() < 1.0);
+
+        // After the coma, ion and dust particles alternate
+        let ion_end = tail.particles[tail.particles.len() - 2].offset;
+        let dust_end = tail.particles[tail.particles.len() - 1].offset;
+        assert!(ion_end.x > 0.9 && ion_end.y.abs() < 0.1);
+        assert!(dust_end.x > 0.9 && dust_end.y < -0.3);
+    }
+
+    #[test]
+    fn test_activity_falls_with_distance() {
+        assert!((activity(AU_TO_METERS) - 1.0).abs() < 1e-12);
+        assert!(activity(4.0 * AU_TO_METERS) > 0.0);
+        assert_eq!(activity(5.0 * AU_TO_METERS), 0.0);
+        assert!(CometTail::new(DVec3::new(6.0 * AU_TO_METERS, 0.0, 0.0), DVec3::ZERO).is_none());
+
+        let near = CometTail::new(DVec3::new(AU_TO_METERS, 0.0, 0.0), DVec3::ZERO).unwrap();
+        let far = CometTail::new(DVec3::new(2.0 * AU_TO_METERS, 0.0, 0.0), DVec3::ZERO).unwrap();
+        assert!(far.length < near.length);
+        assert!(far.particles[0].color.w < near.particles[0].color.w);
+    }
+}
@@ -2,7 +2,10 @@ use glam::Vec3;
 /// Core rendering functionality shared between main app and shader testing
 use wgpu::{Buffer, Texture};
 
-use crate::generated_shaders::common::VertexInput;
+use crate::{
+    generated_shaders::{comet_tail::TailParticle, common::VertexInput},
+    renderer::lighting::StarLight,
+};
 
 /// Shared uniform buffer structures - consolidated from all shaders
 /// This is the master definition used by all shaders to eliminate code duplication
@@ -54,8 +57,10 @@ pub enum RenderCommand {
     /// Regular planet without atmosphere (like Mercury, Moon, etc.)
     Planet {
         texture_path: String,
-        planet_position: glam::DVec3,
-        sun_position: glam::DVec3,
+        lights: Vec<StarLight>,
+        rings: Option<RingGeometry>,
+        /// Turns the sphere model (pole +Y, longitude 0 at +X) to the body's surface orientation
+        orientation: glam::Mat3,
     },
 
     /// Planet with atmospheric scattering
@@ -65,10 +70,20 @@ pub enum RenderCommand {
         use_ambient_texture: bool,
         texture_path: String,
         ambient_texture_path: Option<String>,
-        planet_position: glam::DVec3,
-        sun_position: glam::DVec3,
+        lights: Vec<StarLight>,
+        rings: Option<RingGeometry>,
+        orientation: glam::Mat3,
     },
 
+    /// Planetary rings around a planet (transform places and scales the planet)
+    Rings {
+        rings: RingGeometry,
+        lights: Vec<StarLight>,
+    },
+
+    /// Comet ion and dust tails (transform places the nucleus and scales to the tail length)
+    CometTail { particles: Vec<TailParticle> },
+
     /// Sun/star with stellar surface rendering
     Sun { temperature: f32 },
 
@@ -101,6 +116,23 @@ pub enum RenderCommand {
 
     /// Point rendering for distant objects
     Point,
+
+    /// Satellite layer as points (uses the renderer's satellite vertex buffer)
+    Satellites,
+
+    /// Satellite trails as line segments (uses the renderer's satellite trail buffer)
+    SatelliteTrails,
+}
+
+/// A planet's ring system as the shaders see it, in the planet's model space
+#[derive(Debug, Clone)]
+pub struct RingGeometry {
+    /// In-plane axes (x, y) and plane normal (z) of the rings in the viewing frame
+    pub orientation: glam::Mat3,
+    /// Radii in planet radii
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub texture_path: String,
 }
 
 /// Mesh types available for rendering
@@ -1,10 +1,13 @@
 use bytemuck::{Pod, Zeroable};
-use glam::Vec3;
+use glam::{DVec3, Vec3};
 /// Lighting system management
 /// Ported from the original LightSourceManager.java
 use wgpu::{Device, Queue};
 
-use crate::{AstrariaResult, physics::PhysicsSimulation};
+use crate::{AstrariaResult, physics::PhysicsSimulation, scenario::BodyType};
+
+/// Lights dimmer than this fraction of the brightest one are left out
+const MIN_RELATIVE_INTENSITY: f32 = 1e-3;
 
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Pod, Zeroable)]
@@ -27,9 +30,28 @@ pub struct LightingUniforms {
     pub _padding: [f32; 3],
 }
 
+/// Light from one star as it reaches a body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StarLight {
+    /// Normalized direction from the body to the star (viewing frame)
+    pub direction: Vec3,
+    /// Received flux relative to the brightest star at the body
+    pub intensity: f32,
+}
+
+/// A star as a light source
+#[derive(Debug, Clone, Copy)]
+struct LightSource {
+    position: DVec3,
+    velocity: DVec3,
+    /// Proportional to R² T⁴; only ratios between stars matter
+    luminosity: f64,
+}
+
 pub struct LightManager {
     lights: Vec<DirectionalLight>,
     max_lights: usize,
+    sources: Vec<LightSource>,
 }
 
 impl LightManager {
@@ -37,6 +59,7 @@ impl LightManager {
         Ok(Self {
             lights: Vec::new(),
             max_lights: 8,
+            sources: Vec::new(),
         })
     }
 
@@ -56,8 +79,129 @@ impl LightManager {
         }
     }
 
-    pub fn update(&mut self, _queue: &Queue, _physics: &PhysicsSimulation) -> AstrariaResult<()> {
-        // TODO: Update light positions based on simulation objects
+    /// Collect the stars of the simulation, in the viewing frame, as light sources
+    pub fn update(&mut self, _queue: &Queue, physics: &PhysicsSimulation) -> AstrariaResult<()> {
+        self.sources = physics
+            .get_view_bodies()?
+            .iter()
+            .filter_map(|body| match body.body_type {
+                BodyType::Star {
+                    radius,
+                    temperature,
+                    ..
+                } => Some(LightSource {
+                    position: body.position,
+                    velocity: body.velocity,
+                    luminosity: (radius as f64).powi(2) * (temperature as f64).powi(4),
+                }),
+                _ => None,
+            })
+            .collect();
         Ok(())
     }
+
+    /// Light falling on a body at `position` from every star, brightest first
+    ///
+    /// Each star is weighted by its luminosity and the inverse square of its distance, and
+    /// the intensities are scaled so the brightest star gives 1. Without any stars the
+    /// light comes from the origin.
+    pub fn lights_at(&self, position: DVec3) -> Vec<StarLight> {
+        let mut lights: Vec<(DVec3, f64)> = self
+            .sources
+            .iter()
+            .filter_map(|source| {
+                let offset = source.position - position;
+                let distance_squared = offset.length_squared();
+                (distance_squared > 0.0)
+                    .then(|| (offset.normalize(), source.luminosity / distance_squared))
+            })
+            .collect();
+        if self.sources.is_empty() {
+            lights.push(((-position).normalize_or(DVec3::X), 1.0));
+        }
+
+        lights.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let brightest = lights.first().map_or(1.0, |light| light.1);
+        lights
+            .into_iter()
+            .take(self.max_lights)
+            .map(|(direction, flux)| StarLight {
+                direction: direction.as_vec3(),
+                intensity: (flux / brightest) as f32,
+            })
+            .filter(|light| light.intensity >= MIN_RELATIVE_INTENSITY)
+            .collect()
+    }
+
+    /// Position and velocity of the star giving the most light at `position`
+    pub fn dominant_star(&self, position: DVec3) -> Option<(DVec3, DVec3)> {
+        self.sources
+            .iter()
+            .filter(|source| source.position != position)
+            .max_by(|a, b| {
+                let flux = |source: &LightSource| {
+                    source.luminosity / source.position.distance_squared(position)
+                };
+                flux(a).total_cmp(&flux(b))
+            })
+            .map(|source| (source.position, source.velocity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager(sources: &[(DVec3, f64)]) -> LightManager {
+        LightManager {
+            lights: Vec::new(),
+            max_lights: 8,
+            sources: sources
+                .iter()
+                .map(|&(position, luminosity)| LightSource {
+                    position,
+                    velocity: DVec3::ZERO,
+                    luminosity,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_lights_weighted_by_luminosity_and_distance() {
+        // A star four times as luminous at twice the distance gives the same flux
+        let lights = manager(&[
+            (DVec3::new(-1.0, 0.0, 0.0), 1.0),
+            (DVec3::new(0.0, 2.0, 0.0), 4.0),
+        ])
+        .lights_at(DVec3::ZERO);
+        assert_eq!(lights.len(), 2);
+        assert!(
+            lights
+                .iter()
+                .all(|light| (light.intensity - 1.0).abs() < 1e-6)
+        );
+
+        let lights = manager(&[
+            (DVec3::new(-1.0, 0.0, 0.0), 1.0),
+            (DVec3::new(0.0, 4.0, 0.0), 4.0),
+        ])
+        .lights_at(DVec3::ZERO);
+        assert_eq!(lights[0].direction, Vec3::NEG_X);
+        assert_eq!(lights[0].intensity, 1.0);
+        assert_eq!(lights[1].direction, Vec3::Y);
+        assert!((lights[1].intensity - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_star_does_not_light_itself() {
+        let lights =
+            manager(&[(DVec3::ZERO, 1.0), (DVec3::new(0.0, 0.0, 3.0), 1.0)]).lights_at(DVec3::ZERO);
+        assert_eq!(lights.len(), 1);
+        assert_eq!(lights[0].direction, Vec3::Z);
+
+        // Without stars the light comes from the origin
+        let lights = manager(&[]).lights_at(DVec3::new(5.0, 0.0, 0.0));
+        assert_eq!(lights[0].direction, Vec3::NEG_X);
+    }
 }
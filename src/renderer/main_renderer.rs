@@ -1,4 +1,4 @@
-use glam::{DMat4, DVec3, Mat4};
+use glam::{DMat3, DMat4, DVec3, Mat4};
 use std::sync::Arc;
 use wgpu::util::DeviceExt;
 use wgpu::{Device, Queue, RenderPass};
@@ -10,15 +10,17 @@ use crate::{
     graphics::{Mesh, SkyboxMesh},
     renderer::{
         camera::Camera,
-        core::{MeshType, RenderCommand},
+        core::{MeshType, RenderCommand, RingGeometry},
+        lighting::StarLight,
         cpu_occlusion::{self, CpuOcclusionSystem},
         precision_math::calculate_mvp_matrix_64bit_with_atmosphere,
         shaders::{
-            BillboardShader, BlackHoleShader, DefaultShader, LensGlowShader, LineShader,
-            PlanetAtmoShader, PointShader, SkyboxShader, SunShader,
+            BillboardShader, BlackHoleShader, CometTailShader, DefaultShader, LensGlowShader,
+            LineShader, PlanetAtmoShader, PointShader, RingShader, SkyboxShader, SunShader,
         },
     },
     physics::PhysicsSimulation,
+    satellites::SatelliteLayer,
 };
 
 /// Main rendering coordinator that manages all specialized shaders
@@ -44,6 +46,8 @@ pub struct MainRenderer {
     pub black_hole_shader: BlackHoleShader,
     pub line_shader: LineShader,
     pub point_shader: PointShader,
+    pub ring_shader: RingShader,
+    pub comet_tail_shader: CometTailShader,
 
     // Loaded textures for testing
     pub earth_day_texture: Arc<TextureAsset>,
@@ -53,6 +57,7 @@ pub struct MainRenderer {
     pub atmo_gradient_texture: Arc<TextureAsset>,
     pub star_glow_texture: Arc<TextureAsset>,
     pub star_spectrum_texture: Arc<TextureAsset>,
+    pub particle_texture: Arc<TextureAsset>,
 
     // Generated bind groups for each shader type
     pub default_lighting_bind_group: generated_shaders::default::bind_groups::BindGroup1,
@@ -108,6 +113,10 @@ pub struct MainRenderer {
     
     // Cached orbital trail data for rendering
     cached_trail_data: Vec<Option<TrailRenderData>>,
+
+    // Satellite layer vertices relative to the central body: (buffer, vertex count)
+    satellite_points: Option<(wgpu::Buffer, u32)>,
+    satellite_trails: Option<(wgpu::Buffer, u32)>,
 }
 
 /// Data needed for rendering an orbital trail
@@ -121,29 +130,40 @@ impl MainRenderer {
     /// Create a dynamic lighting bind group for regular planets (default shader)
     fn create_planet_lighting_bind_group(
         &self,
-        planet_world_pos: glam::DVec3,
-        sun_world_pos: glam::DVec3,
+        lights: &[StarLight],
+        rings: Option<&RingGeometry>,
+        orientation: glam::Mat3,
     ) -> AstrariaResult<generated_shaders::default::bind_groups::BindGroup1> {
         log::debug!("MainRenderer: Creating dynamic lighting bind group for regular planet");
 
-        // Calculate light direction from planet to sun in world space
-        let light_direction_world = (sun_world_pos - planet_world_pos).normalize();
+        let (ring_normal, ring_radii) = Self::ring_shadow_uniforms(rings);
+
+        // One directional light per star, FROM object TO star in WORLD SPACE
+        let mut directional_lights = [generated_shaders::default::DirectionalLight {
+            direction: glam::Vec3::Z,
+            _padding1: 0.0,
+            ambient: glam::Vec3::ZERO,
+            _padding2: 0.0,
+            diffuse: glam::Vec3::ZERO,
+            _padding3: 0.0,
+            specular: glam::Vec3::ZERO,
+            _padding4: 0.0,
+        }; 8];
+        for (index, (light, star)) in directional_lights.iter_mut().zip(lights).enumerate() {
+            light.direction = star.direction;
+            light.ambient = glam::Vec3::splat(Self::star_ambient(index));
+            light.diffuse = glam::Vec3::splat(star.intensity);
+            light.specular = glam::Vec3::splat(star.intensity);
+        }
 
-        // Create the lighting uniform with computed light direction
+        // Create the lighting uniform with computed light directions
         let lighting_uniform = generated_shaders::default::LightingUniforms {
-            lights: [generated_shaders::default::DirectionalLight {
-                // Light direction FROM object TO sun in WORLD SPACE
-                direction: light_direction_world.as_vec3(),
-                _padding1: 0.0,
-                ambient: glam::Vec3::new(0.1, 0.1, 0.1),
-                _padding2: 0.0,
-                diffuse: glam::Vec3::new(1.0, 1.0, 1.0),
-                _padding3: 0.0,
-                specular: glam::Vec3::new(1.0, 1.0, 1.0),
-                _padding4: 0.0,
-            }; 8],
-            num_lights: 1,
-            _padding: [glam::Vec4::ZERO; 10], // Match default shader structure
+            lights: directional_lights,
+            num_lights: lights.len().min(8) as i32,
+            ring_normal,
+            ring_radii,
+            body_rotation: glam::Mat4::from_mat3(orientation),
+            _padding: [glam::Vec4::ZERO; 4], // Match default shader structure
         };
 
         // Create lighting buffer using unsafe raw bytes (since structs don't implement Pod)
@@ -244,8 +264,9 @@ impl MainRenderer {
         atmosphere_color: glam::Vec4,
         overglow: f32,
         use_ambient_texture: bool,
-        planet_world_pos: glam::DVec3,
-        sun_world_pos: glam::DVec3,
+        lights: &[StarLight],
+        rings: Option<&RingGeometry>,
+        orientation: glam::Mat3,
     ) -> AstrariaResult<generated_shaders::planet_atmo::bind_groups::BindGroup1> {
         log::debug!(
             "MainRenderer: Creating dynamic lighting bind group with atmo_color: {:?}, overglow: {}, use_ambient: {}",
@@ -254,24 +275,34 @@ impl MainRenderer {
             use_ambient_texture
         );
 
-        // Calculate light direction from planet to sun in world space
-        let light_direction_world = (sun_world_pos - planet_world_pos).normalize();
+        let (ring_normal, ring_radii) = Self::ring_shadow_uniforms(rings);
 
-        // Create the lighting uniform with computed light direction
+        // One directional light per star, FROM planet TO star in WORLD SPACE
+        let mut directional_lights = [generated_shaders::planet_atmo::DirectionalLight {
+            direction: glam::Vec3::Z,
+            _padding1: 0.0,
+            ambient: glam::Vec3::ZERO,
+            _padding2: 0.0,
+            diffuse: glam::Vec3::ZERO,
+            _padding3: 0.0,
+            specular: glam::Vec3::ZERO,
+            _padding4: 0.0,
+        }; 8];
+        for (index, (light, star)) in directional_lights.iter_mut().zip(lights).enumerate() {
+            light.direction = star.direction;
+            light.ambient = glam::Vec3::splat(Self::star_ambient(index));
+            light.diffuse = glam::Vec3::splat(star.intensity);
+            light.specular = glam::Vec3::splat(star.intensity);
+        }
+
+        // Create the lighting uniform with computed light directions
         let lighting_uniform = generated_shaders::planet_atmo::LightingUniform {
-            lights: [generated_shaders::planet_atmo::DirectionalLight {
-                // Light direction FROM planet TO sun in WORLD SPACE
-                direction: light_direction_world.as_vec3(),
-                _padding1: 0.0,
-                ambient: glam::Vec3::new(0.1, 0.1, 0.1),
-                _padding2: 0.0,
-                diffuse: glam::Vec3::new(1.0, 1.0, 1.0),
-                _padding3: 0.0,
-                specular: glam::Vec3::new(1.0, 1.0, 1.0),
-                _padding4: 0.0,
-            }; 8],
-            num_lights: 1,
-            _padding: [glam::Vec4::ZERO; 16],
+            lights: directional_lights,
+            num_lights: lights.len().min(8) as i32,
+            ring_normal,
+            ring_radii,
+            body_rotation: glam::Mat4::from_mat3(orientation),
+            _padding: [glam::Vec4::ZERO; 10],
         };
 
         // Create the atmospheric uniform data
@@ -338,6 +369,7 @@ impl MainRenderer {
         main_texture_path: &str,
         ambient_texture_path: Option<&str>,
         use_ambient: bool,
+        rings: Option<&RingGeometry>,
     ) -> AstrariaResult<generated_shaders::planet_atmo::bind_groups::BindGroup2> {
         log::debug!(
             "MainRenderer: Creating dynamic bind group for textures: main={}, ambient={:?}",
@@ -401,6 +433,11 @@ impl MainRenderer {
                 Arc::clone(&main_texture)
             });
 
+        // Without rings the shadow lookup is never made, so any texture will do
+        let ring_texture = rings
+            .and_then(|rings| self.find_texture(&rings.texture_path))
+            .unwrap_or_else(|| Arc::clone(&main_texture));
+
         // Create the bind group with the dynamic textures
         let bind_group = generated_shaders::planet_atmo::bind_groups::BindGroup2::from_bindings(
             &self.device,
@@ -409,6 +446,7 @@ impl MainRenderer {
                 diffuse_texture: &main_texture.view,
                 atmosphere_gradient_texture: &atmo_gradient.view,
                 texture_sampler: &self.default_sampler,
+                ring_texture: &ring_texture.view,
             },
         );
 
@@ -416,6 +454,103 @@ impl MainRenderer {
         Ok(bind_group)
     }
 
+    /// Ambient level of a star light: the shaders add up every light's ambient term, so only
+    /// the brightest one carries it
+    fn star_ambient(index: usize) -> f32 {
+        if index == 0 { 0.1 } else { 0.0 }
+    }
+
+    /// Ring plane normal and radii for the planet shaders' shadow lookup (w = 0 without rings)
+    fn ring_shadow_uniforms(rings: Option<&RingGeometry>) -> (glam::Vec4, glam::Vec4) {
+        match rings {
+            Some(rings) => (
+                rings.orientation.z_axis.extend(1.0),
+                glam::Vec4::new(rings.inner_radius, rings.outer_radius, 0.0, 0.0),
+            ),
+            None => (glam::Vec4::ZERO, glam::Vec4::ZERO),
+        }
+    }
+
+    /// Look up a cached texture under any of the keys it may have been loaded with
+    fn find_texture(&self, texture_path: &str) -> Option<Arc<TextureAsset>> {
+        self.asset_manager
+            .get_texture_handle(texture_path)
+            .or_else(|| {
+                self.asset_manager
+                    .get_texture_handle(&format!("assets/{}", texture_path))
+            })
+            .or_else(|| {
+                self.asset_manager
+                    .get_texture_handle(&format!("./{}", texture_path))
+            })
+    }
+
+    /// Create the uniform and texture bind groups for a planet's rings
+    ///
+    /// The rings are lit by the brightest star only.
+    fn create_ring_bind_groups(
+        &self,
+        rings: &RingGeometry,
+        lights: &[StarLight],
+    ) -> AstrariaResult<(
+        generated_shaders::rings::bind_groups::BindGroup1,
+        generated_shaders::rings::bind_groups::BindGroup2,
+    )> {
+        let texture = self.find_texture(&rings.texture_path).ok_or_else(|| {
+            AstrariaError::AssetLoading(format!(
+                "Ring texture not found in cache: {}",
+                rings.texture_path
+            ))
+        })?;
+
+        let (light_direction, intensity) = lights.first().map_or((glam::Vec3::Z, 0.0), |light| {
+            (light.direction, light.intensity)
+        });
+        let ring_uniform = generated_shaders::rings::RingUniform {
+            axis_u: rings.orientation.x_axis.extend(0.0),
+            axis_v: rings.orientation.y_axis.extend(0.0),
+            normal: rings.orientation.z_axis.extend(0.0),
+            light_direction: light_direction.extend(0.0),
+            // Same light as the planet shaders: white diffuse with 0.1 ambient
+            light_color: glam::Vec3::splat(intensity).extend(Self::star_ambient(0)),
+            radii: glam::Vec4::new(rings.inner_radius, rings.outer_radius, 0.0, 0.0),
+        };
+
+        // Create uniform buffer using unsafe raw bytes (since structs don't implement Pod)
+        let uniform_buffer = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Dynamic Ring Uniform Buffer"),
+                contents: unsafe {
+                    std::slice::from_raw_parts(
+                        &ring_uniform as *const _ as *const u8,
+                        std::mem::size_of::<generated_shaders::rings::RingUniform>(),
+                    )
+                },
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            });
+
+        let uniform_bind_group = generated_shaders::rings::bind_groups::BindGroup1::from_bindings(
+            &self.device,
+            generated_shaders::rings::bind_groups::BindGroupLayout1 {
+                ring: wgpu::BufferBinding {
+                    buffer: &uniform_buffer,
+                    offset: 0,
+                    size: None,
+                },
+            },
+        );
+        let texture_bind_group = generated_shaders::rings::bind_groups::BindGroup2::from_bindings(
+            &self.device,
+            generated_shaders::rings::bind_groups::BindGroupLayout2 {
+                ring_texture: &texture.view,
+                ring_sampler: &self.default_sampler,
+            },
+        );
+
+        Ok((uniform_bind_group, texture_bind_group))
+    }
+
     /// Load textures for all bodies in a scenario
     pub async fn load_scenario_textures(
         &mut self,
@@ -430,7 +565,8 @@ impl MainRenderer {
         for body in &scenario.bodies {
             match &body.body_type {
                 crate::scenario::BodyType::Planet { texture_path, .. }
-                | crate::scenario::BodyType::Star { texture_path, .. } => {
+                | crate::scenario::BodyType::Star { texture_path, .. }
+                | crate::scenario::BodyType::Comet { texture_path, .. } => {
                     match asset_manager
                         .load_texture(&self.device, &self.queue, texture_path)
                         .await
@@ -503,6 +639,29 @@ impl MainRenderer {
                 }
                 _ => {} // Black holes don't have textures
             }
+
+            if let Some(rings) = body.body_type.rings() {
+                match asset_manager
+                    .load_texture(&self.device, &self.queue, &rings.texture_path)
+                    .await
+                {
+                    Ok(_) => {
+                        // Also load into MainRenderer's asset manager for cache consistency
+                        let _ = self
+                            .asset_manager
+                            .load_texture(&self.device, &self.queue, &rings.texture_path)
+                            .await;
+                    }
+                    Err(e) => {
+                        log::warn!(
+                            "MainRenderer: Failed to load ring texture {} for {}: {}",
+                            rings.texture_path,
+                            body.name,
+                            e
+                        );
+                    }
+                }
+            }
         }
 
         let (textures, models, cubemaps) = self.asset_manager.cache_stats();
@@ -658,6 +817,9 @@ impl MainRenderer {
         let star_spectrum_texture = asset_manager
             .load_texture(&device, &queue, "assets/star_spectrum_1D.png")
             .await?;
+        let particle_texture = asset_manager
+            .load_texture(&device, &queue, "assets/particle.png")
+            .await?;
 
         // Load sphere.obj model
         let sphere_model = asset_manager
@@ -701,6 +863,8 @@ impl MainRenderer {
         let black_hole_shader = BlackHoleShader::new(&device, &queue, surface_format)?;
         let line_shader = LineShader::new(&device, &queue, surface_format)?;
         let point_shader = PointShader::new(&device, &queue, surface_format)?;
+        let ring_shader = RingShader::new(&device, surface_format)?;
+        let comet_tail_shader = CometTailShader::new(&device, surface_format)?;
 
         // Initialize MVP buffers and bind groups storage
         let mvp_buffers = Vec::new();
@@ -832,7 +996,10 @@ impl MainRenderer {
                 _padding4: 0.0,
             }; 8],
             num_lights: 1,
-            _padding: [glam::Vec4::ZERO; 10], // Array of 10 Vec4s for proper alignment
+            ring_normal: glam::Vec4::ZERO,
+            ring_radii: glam::Vec4::ZERO,
+            body_rotation: glam::Mat4::IDENTITY,
+            _padding: [glam::Vec4::ZERO; 4], // Array of 4 Vec4s for proper alignment
         };
         let default_lighting_buffer =
             device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -863,6 +1030,7 @@ impl MainRenderer {
                 generated_shaders::default::bind_groups::BindGroupLayout2 {
                     diffuse_texture: &earth_day_texture.view,
                     diffuse_sampler: &default_sampler,
+                    ring_texture: &earth_day_texture.view,
                 },
             );
 
@@ -881,7 +1049,10 @@ impl MainRenderer {
                 _padding4: 0.0,
             }; 8],
             num_lights: 1,
-            _padding: [glam::Vec4::ZERO; 16],
+            ring_normal: glam::Vec4::ZERO,
+            ring_radii: glam::Vec4::ZERO,
+            body_rotation: glam::Mat4::IDENTITY,
+            _padding: [glam::Vec4::ZERO; 10],
         };
         let planet_atmosphere = generated_shaders::planet_atmo::AtmosphereUniform {
             atmosphere_color_mod: glam::Vec4::new(0.4, 0.6, 1.0, 1.0),
@@ -937,6 +1108,7 @@ impl MainRenderer {
                     diffuse_texture: &earth_day_texture.view,
                     atmosphere_gradient_texture: &atmo_gradient_texture.view,
                     texture_sampler: &default_sampler,
+                    ring_texture: &earth_day_texture.view,
                 },
             );
 
@@ -1059,6 +1231,8 @@ impl MainRenderer {
             black_hole_shader,
             line_shader,
             point_shader,
+            ring_shader,
+            comet_tail_shader,
             earth_day_texture,
             earth_night_texture,
             sun_texture,
@@ -1066,6 +1240,7 @@ impl MainRenderer {
             atmo_gradient_texture,
             star_glow_texture,
             star_spectrum_texture,
+            particle_texture,
             default_lighting_bind_group,
             default_texture_bind_group,
             planet_lighting_bind_group,
@@ -1096,6 +1271,8 @@ impl MainRenderer {
             max_view_distance: 100000000000.0, // Like Java MAXVIEWDISTANCE
             log_depth_constant: 1.0,           // Like Java LOGDEPTHCONSTANT
             cached_trail_data: Vec::new(),
+            satellite_points: None,
+            satellite_trails: None,
         })
     }
 
@@ -1157,7 +1334,7 @@ impl MainRenderer {
         use crate::scenario::BodyType;
         let mut spheres = Vec::new();
 
-        if let Ok(bodies) = physics.get_bodies() {
+        if let Ok(bodies) = physics.get_view_bodies() {
             log::info!(
                 "OCCLUSION DEBUG: Building occluding spheres from {} physics bodies",
                 bodies.len()
@@ -1181,8 +1358,11 @@ impl MainRenderer {
                         );
                         spheres.push(sphere);
                     }
-                    // Skip stars and black holes as occluders for now
-                    BodyType::Star { .. } | BodyType::BlackHole { .. } => {
+                    // Skip stars, black holes, spacecraft and comets as occluders for now
+                    BodyType::Star { .. }
+                    | BodyType::BlackHole { .. }
+                    | BodyType::Spacecraft { .. }
+                    | BodyType::Comet { .. } => {
                         log::info!(
                             "OCCLUSION DEBUG: Skipping '{}' (not an occluder)",
                             body.name
@@ -1445,15 +1625,16 @@ impl MainRenderer {
 
             RenderCommand::Planet {
                 texture_path,
-                planet_position,
-                sun_position,
+                lights,
+                rings,
+                orientation,
             } => {
                 render_pass.set_pipeline(&self.default_shader.pipeline);
                 mvp_bind_group.set(render_pass); // MVP bind group
 
-                // Create dynamic lighting bind group with planet-to-sun direction
+                // Create dynamic lighting bind group with planet-to-star directions
                 let dynamic_lighting_bind_group =
-                    self.create_planet_lighting_bind_group(*planet_position, *sun_position);
+                    self.create_planet_lighting_bind_group(lights, rings.as_ref(), *orientation);
                 match dynamic_lighting_bind_group {
                     Ok(lighting_bind_group) => {
                         lighting_bind_group.set(render_pass);
@@ -1485,6 +1666,12 @@ impl MainRenderer {
 
                 match planet_texture {
                     Some(texture) => {
+                        // Without rings the shadow lookup is never made, so any texture will do
+                        let ring_texture = rings
+                            .as_ref()
+                            .and_then(|rings| self.find_texture(&rings.texture_path))
+                            .unwrap_or_else(|| Arc::clone(&texture));
+
                         // Create dynamic texture bind group with the planet's texture
                         let dynamic_texture_bind_group =
                             generated_shaders::default::bind_groups::BindGroup2::from_bindings(
@@ -1492,6 +1679,7 @@ impl MainRenderer {
                                 generated_shaders::default::bind_groups::BindGroupLayout2 {
                                     diffuse_texture: &texture.view,
                                     diffuse_sampler: &self.default_sampler,
+                                    ring_texture: &ring_texture.view,
                                 },
                             );
                         dynamic_texture_bind_group.set(render_pass);
@@ -1525,8 +1713,9 @@ impl MainRenderer {
                 use_ambient_texture,
                 atmosphere_color,
                 overglow,
-                planet_position,
-                sun_position,
+                lights,
+                rings,
+                orientation,
             } => {
                 render_pass.set_pipeline(&self.planet_atmo_shader.pipeline);
 
@@ -1545,13 +1734,14 @@ impl MainRenderer {
                     );
                 planet_mvp_bind_group.set(render_pass);
 
-                // Create dynamic lighting bind group with the correct atmospheric color and planet-to-sun direction
+                // Create dynamic lighting bind group with the correct atmospheric color and planet-to-star directions
                 let dynamic_lighting_bind_group = self.create_atmospheric_lighting_bind_group(
                     *atmosphere_color,
                     *overglow,
                     *use_ambient_texture,
-                    *planet_position,
-                    *sun_position,
+                    lights,
+                    rings.as_ref(),
+                    *orientation,
                 );
 
                 match dynamic_lighting_bind_group {
@@ -1576,6 +1766,7 @@ impl MainRenderer {
                     texture_path,
                     ambient_texture_path.as_deref(),
                     *use_ambient_texture,
+                    rings.as_ref(),
                 );
 
                 match dynamic_texture_bind_group {
@@ -1602,6 +1793,88 @@ impl MainRenderer {
                 render_pass.draw_indexed(0..self.sphere_model.num_indices, 0, 0..1);
             }
 
+            RenderCommand::Rings { rings, lights } => {
+                let (uniform_bind_group, texture_bind_group) =
+                    match self.create_ring_bind_groups(rings, lights) {
+                        Ok(bind_groups) => bind_groups,
+                        Err(e) => {
+                            log::warn!("MainRenderer: Skipping rings: {}", e);
+                            return;
+                        }
+                    };
+
+                render_pass.set_pipeline(&self.ring_shader.pipeline);
+                let ring_mvp_bind_group =
+                    generated_shaders::rings::bind_groups::BindGroup0::from_bindings(
+                        &self.device,
+                        generated_shaders::rings::bind_groups::BindGroupLayout0 {
+                            mvp: wgpu::BufferBinding {
+                                buffer: &self.mvp_buffers
+                                    [self.mvp_bind_groups[mvp_bind_group_index].1],
+                                offset: 0,
+                                size: None,
+                            },
+                        },
+                    );
+                ring_mvp_bind_group.set(render_pass);
+                uniform_bind_group.set(render_pass);
+                texture_bind_group.set(render_pass);
+
+                // The unit quad is stretched to the outer ring edge in the vertex shader
+                render_pass.set_vertex_buffer(0, self.quad_mesh.vertex_buffer.slice(..));
+                render_pass.set_index_buffer(
+                    self.quad_mesh.index_buffer.slice(..),
+                    wgpu::IndexFormat::Uint32,
+                );
+                render_pass.draw_indexed(0..self.quad_mesh.num_indices, 0, 0..1);
+            }
+
+            RenderCommand::CometTail { particles } => {
+                render_pass.set_pipeline(&self.comet_tail_shader.pipeline);
+                let tail_mvp_bind_group =
+                    generated_shaders::comet_tail::bind_groups::BindGroup0::from_bindings(
+                        &self.device,
+                        generated_shaders::comet_tail::bind_groups::BindGroupLayout0 {
+                            mvp: wgpu::BufferBinding {
+                                buffer: &self.mvp_buffers
+                                    [self.mvp_bind_groups[mvp_bind_group_index].1],
+                                offset: 0,
+                                size: None,
+                            },
+                        },
+                    );
+                let tail_texture_bind_group =
+                    generated_shaders::comet_tail::bind_groups::BindGroup1::from_bindings(
+                        &self.device,
+                        generated_shaders::comet_tail::bind_groups::BindGroupLayout1 {
+                            particle_texture: &self.particle_texture.view,
+                            particle_sampler: &self.default_sampler,
+                        },
+                    );
+                tail_mvp_bind_group.set(render_pass);
+                tail_texture_bind_group.set(render_pass);
+
+                // One quad per particle, stretched into a camera-facing billboard by the shader
+                let instance_buffer = self
+                    .device
+                    .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                        label: Some("Comet Tail Instance Buffer"),
+                        contents: bytemuck::cast_slice(particles),
+                        usage: wgpu::BufferUsages::VERTEX,
+                    });
+                render_pass.set_vertex_buffer(0, self.quad_mesh.vertex_buffer.slice(..));
+                render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
+                render_pass.set_index_buffer(
+                    self.quad_mesh.index_buffer.slice(..),
+                    wgpu::IndexFormat::Uint32,
+                );
+                render_pass.draw_indexed(
+                    0..self.quad_mesh.num_indices,
+                    0,
+                    0..particles.len() as u32,
+                );
+            }
+
             RenderCommand::Sun { temperature: _ } => {
                 // Update sun uniforms if needed
                 let _star_position = glam::Vec3::ZERO; // Placeholder, position is handled by MVP matrix
@@ -1861,9 +2134,94 @@ impl MainRenderer {
                 );
                 render_pass.draw_indexed(0..self.point_mesh.num_indices, 0, 0..1);
             }
+
+            RenderCommand::Satellites | RenderCommand::SatelliteTrails => {
+                let is_trails = matches!(command, RenderCommand::SatelliteTrails);
+                let buffer = if is_trails {
+                    &self.satellite_trails
+                } else {
+                    &self.satellite_points
+                };
+                let Some((vertex_buffer, vertex_count)) = buffer else {
+                    return;
+                };
+                let mvp = wgpu::BufferBinding {
+                    buffer: &self.mvp_buffers[self.mvp_bind_groups[mvp_bind_group_index].1],
+                    offset: 0,
+                    size: None,
+                };
+                if is_trails {
+                    render_pass.set_pipeline(&self.line_shader.pipeline);
+                    generated_shaders::line::bind_groups::BindGroup0::from_bindings(
+                        &self.device,
+                        generated_shaders::line::bind_groups::BindGroupLayout0 { mvp },
+                    )
+                    .set(render_pass);
+                    self.line_uniform_bind_group.set(render_pass);
+                } else {
+                    render_pass.set_pipeline(&self.point_shader.pipeline);
+                    generated_shaders::point::bind_groups::BindGroup0::from_bindings(
+                        &self.device,
+                        generated_shaders::point::bind_groups::BindGroupLayout0 { mvp },
+                    )
+                    .set(render_pass);
+                    self.point_uniform_bind_group.set(render_pass);
+                }
+                render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+                render_pass.draw(0..*vertex_count, 0..1);
+            }
         }
     }
 
+    /// Upload the satellite layer as one point list and one line list, relative to the central body
+    ///
+    /// Both are drawn with a transform that places them at the central body, so the f32
+    /// vertices only span orbital distances. `rotation` turns inertial offsets into the
+    /// axes of the viewing frame.
+    pub fn update_satellite_buffers(&mut self, layer: Option<&SatelliteLayer>, rotation: DMat3) {
+        let vertex = |position: DVec3| generated_shaders::common::VertexInput {
+            position: (rotation * position).as_vec3(),
+            tex_coord: glam::Vec2::ZERO,
+            normal: glam::Vec3::Z,
+        };
+        let mut points = Vec::new();
+        let mut trails = Vec::new();
+        if let Some(layer) = layer {
+            points.reserve(layer.len());
+            for satellite in layer.satellites() {
+                if let Some(position) = satellite.position() {
+                    points.push(vertex(position));
+                }
+                if layer.show_trails {
+                    let trail: Vec<_> = satellite
+                        .trail()
+                        .copied()
+                        .chain(satellite.position())
+                        .collect();
+                    for segment in trail.windows(2) {
+                        trails.push(vertex(segment[0]));
+                        trails.push(vertex(segment[1]));
+                    }
+                }
+            }
+        }
+
+        let upload = |label: &str, vertices: &[generated_shaders::common::VertexInput]| {
+            (!vertices.is_empty()).then(|| {
+                let buffer = self
+                    .device
+                    .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                        label: Some(label),
+                        contents: bytemuck::cast_slice(vertices),
+                        usage: wgpu::BufferUsages::VERTEX,
+                    });
+                (buffer, vertices.len() as u32)
+            })
+        };
+        self.satellite_points = upload("Satellite Points", &points);
+        self.satellite_trails = upload("Satellite Trails", &trails);
+    }
+
     /// Legacy helper method for single command execution using new per-object approach
     /// Use this only for simple cases - prefer the full two-phase approach for multiple objects
     pub fn render<'a>(
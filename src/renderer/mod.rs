@@ -2,6 +2,7 @@ pub mod buffers;
 /// Graphics rendering system using wgpu
 /// Replaces the LibGDX rendering pipeline with modern GPU-driven approach
 pub mod camera;
+pub mod comet;
 pub mod core;
 pub mod cpu_occlusion;
 pub mod lighting;
@@ -16,12 +17,15 @@ pub mod uniforms;
 use wgpu::{Device, Queue, Surface, SurfaceConfiguration};
 use winit::{dpi::PhysicalSize, window::Window};
 
-use crate::{AstrariaError, AstrariaResult, assets::AssetManager, physics::PhysicsSimulation};
+use crate::{
+    AstrariaError, AstrariaResult, assets::AssetManager, math::LagrangePoints,
+    physics::PhysicsSimulation, satellites::SatelliteLayer,
+};
 
 pub use buffers::BufferManager;
 pub use camera::Camera;
 pub use core::*;
-pub use lighting::LightManager;
+pub use lighting::{LightManager, StarLight};
 pub use main_renderer::MainRenderer;
 pub use pipeline::PipelineManager;
 pub use shaders::ShaderManager;
@@ -39,6 +43,14 @@ pub struct Renderer {
 
     // Use MainRenderer for shader management and device access
     main_renderer: MainRenderer,
+
+    // Primary/secondary pair whose Lagrange points are marked, if any
+    lagrange_markers: Option<(usize, usize)>,
+
+    // Earth satellites propagated outside the N-body solver, if loaded
+    satellites: Option<SatelliteLayer>,
+    // Scenario epoch (TDB Julian date) that satellite layers start at, if the scenario has one
+    scenario_epoch: Option<f64>,
 }
 
 /// Calculate lens glow size using exact Java LensGlow.calculateGlowSize() formula
@@ -202,6 +214,9 @@ impl Renderer {
             current_frame: None,
             depth_texture,
             depth_view,
+            lagrange_markers: None,
+            satellites: None,
+            scenario_epoch: None,
         })
     }
 
@@ -306,8 +321,9 @@ impl Renderer {
             render_commands.len()
         );
 
-        // Separate solid objects from lens glow effects for proper rendering order
+        // Separate solid objects from translucent rings, comet tails and lens glow effects for proper rendering order
         let mut solid_commands = Vec::new();
+        let mut ring_commands = Vec::new();
         let mut lens_glow_commands = Vec::new();
 
         for (command, transform) in render_commands {
@@ -315,6 +331,10 @@ impl Renderer {
                 crate::renderer::core::RenderCommand::LensGlow { .. } => {
                     lens_glow_commands.push((command, transform));
                 }
+                crate::renderer::core::RenderCommand::Rings { .. }
+                | crate::renderer::core::RenderCommand::CometTail { .. } => {
+                    ring_commands.push((command, transform));
+                }
                 _ => {
                     solid_commands.push((command, transform));
                 }
@@ -350,6 +370,21 @@ impl Renderer {
             log::debug!("Completed orbital trail buffer updates and command generation");
         }
 
+        // Satellites ride along with their central body, drawn in one call each for points and trails
+        if let Some(transform) = self.update_satellites(physics)? {
+            use crate::renderer::core::RenderCommand;
+            self.main_renderer
+                .prepare_render_command(RenderCommand::Satellites, transform);
+            self.main_renderer
+                .prepare_render_command(RenderCommand::SatelliteTrails, transform);
+        }
+
+        // Rings and comet tails blend over everything solid, so they follow it
+        for (command, transform) in &ring_commands {
+            self.main_renderer
+                .prepare_render_command(command.clone(), *transform);
+        }
+
         // Prepare lens glow commands last (to render on top)
         for (command, transform) in &lens_glow_commands {
             self.main_renderer
@@ -409,14 +444,15 @@ impl Renderer {
         &mut self,
         physics: &PhysicsSimulation,
     ) -> AstrariaResult<Vec<(crate::renderer::core::RenderCommand, glam::Mat4)>> {
-        use crate::renderer::core::{MeshType, RenderCommand};
-        use crate::scenario::BodyType;
-        use glam::{DVec3, Mat4, Vec3, Vec4};
+        use crate::renderer::comet::CometTail;
+        use crate::renderer::core::{MeshType, RenderCommand, RingGeometry};
+        use crate::scenario::{BodyType, RotationMode};
+        use glam::{DMat3, Mat4, Vec3, Vec4};
 
         let mut commands = Vec::new();
 
         // Try to get physics bodies
-        if let Ok(bodies) = physics.get_bodies() {
+        if let Ok(bodies) = physics.get_view_bodies() {
             if !bodies.is_empty() {
                 log::debug!("Generating {} physics body render commands", bodies.len());
                 log::debug!(
@@ -426,12 +462,9 @@ impl Renderer {
                     self.main_renderer.camera.position().z
                 );
 
-                // Find the sun position first (needed for lighting calculations)
-                let sun_position = bodies
-                    .iter()
-                    .find(|body| matches!(body.body_type, BodyType::Star { .. }))
-                    .map(|sun| sun.position)
-                    .unwrap_or(DVec3::ZERO); // Fallback to origin if no sun found
+                // Body orientations are inertial; rotate them into the viewing frame
+                let view_rotation = physics.get_view_transform()?.rotation;
+                let simulation_time = physics.get_simulation_time()?;
 
                 for (body_index, body) in bodies.iter().enumerate() {
                     // Use TRUE ASTRONOMICAL SCALE - no scaling down allowed!
@@ -447,6 +480,8 @@ impl Renderer {
                         BodyType::Star { radius, .. } => *radius as f32,
                         BodyType::PlanetAtmo { radius, .. } => *radius as f32,
                         BodyType::BlackHole { radius } => *radius as f32,
+                        BodyType::Spacecraft { radius, .. } => *radius,
+                        BodyType::Comet { radius, .. } => *radius,
                     };
 
                     log::debug!(
@@ -478,6 +513,34 @@ impl Renderer {
                     let transform = Mat4::from_translation(position)
                         * Mat4::from_scale(Vec3::splat(radius_scale));
 
+                    let rings = body.body_type.rings().map(|rings| RingGeometry {
+                        orientation: (view_rotation
+                            * DMat3::from_quat(body.equatorial_orientation()))
+                        .as_mat3(),
+                        inner_radius: rings.inner_radius / radius_scale,
+                        outer_radius: rings.outer_radius / radius_scale,
+                        texture_path: rings.texture_path.clone(),
+                    });
+
+                    // Tidally locked bodies face where their parent is now, not where a fixed
+                    // period would have them
+                    let parent_offset = match &body.rotation_mode {
+                        RotationMode::TidallyLocked { parent } => bodies
+                            .iter()
+                            .find(|other| other.name == *parent)
+                            .map(|other| {
+                                view_rotation.transpose() * (other.position - body.position)
+                            }),
+                        RotationMode::Fixed => None,
+                    };
+                    // The sphere model has its pole along +Y; the body's spin axis is its +Z
+                    let orientation = (view_rotation
+                        * DMat3::from_quat(
+                            body.surface_orientation(simulation_time, parent_offset),
+                        )
+                        * DMat3::from_rotation_x(std::f64::consts::FRAC_PI_2))
+                    .as_mat3();
+
                     // Choose render command based on body type
                     let command = match &body.body_type {
                         BodyType::Star { temperature, .. } => RenderCommand::Sun {
@@ -499,23 +562,61 @@ impl Renderer {
                             use_ambient_texture: ambient_texture.is_some(),
                             texture_path: texture_path.clone(),
                             ambient_texture_path: ambient_texture.clone(),
-                            planet_position: body.position,
-                            sun_position,
+                            lights: self.lights.lights_at(body.position),
+                            rings: rings.clone(),
+                            orientation,
                         },
                         BodyType::Planet { texture_path, .. } => RenderCommand::Planet {
                             texture_path: texture_path.clone(),
-                            planet_position: body.position,
-                            sun_position,
+                            lights: self.lights.lights_at(body.position),
+                            rings: rings.clone(),
+                            orientation,
                         },
                         BodyType::BlackHole { .. } => RenderCommand::Default {
                             mesh_type: MeshType::Sphere,
                             light_position: Vec3::new(0.0, 0.0, 0.0),
                             light_color: Vec3::new(0.1, 0.1, 0.1), // Dim for black hole
                         },
+                        // Spacecraft are far too small to resolve, draw them as points
+                        BodyType::Spacecraft { .. } => RenderCommand::Point,
+                        BodyType::Comet { texture_path, .. } => RenderCommand::Planet {
+                            texture_path: texture_path.clone(),
+                            lights: self.lights.lights_at(body.position),
+                            rings: None,
+                            orientation,
+                        },
                     };
 
                     commands.push((command.clone(), transform));
 
+                    if let Some(rings) = rings {
+                        commands.push((
+                            RenderCommand::Rings {
+                                rings,
+                                lights: self.lights.lights_at(body.position),
+                            },
+                            transform,
+                        ));
+                    }
+
+                    // Comets near a star grow tails pointing away from it
+                    if matches!(body.body_type, BodyType::Comet { .. })
+                        && let Some((star_position, star_velocity)) =
+                            self.lights.dominant_star(body.position)
+                        && let Some(tail) = CometTail::new(
+                            body.position - star_position,
+                            body.velocity - star_velocity,
+                        )
+                    {
+                        commands.push((
+                            RenderCommand::CometTail {
+                                particles: tail.particles,
+                            },
+                            Mat4::from_translation(position)
+                                * Mat4::from_scale(Vec3::splat(tail.length as f32)),
+                        ));
+                    }
+
                     // Add lens glow effect for stars
                     if let BodyType::Star {
                         temperature,
@@ -554,6 +655,23 @@ impl Renderer {
                         transform
                     );
                 }
+
+                // Mark the Lagrange points of the chosen pair, moving with the viewing frame
+                if let Some((primary, secondary)) = self.lagrange_markers {
+                    let inertial_bodies = physics.get_bodies()?;
+                    let view_transform = physics.get_view_transform()?;
+                    if let Some(points) =
+                        LagrangePoints::for_pair(&inertial_bodies, primary, secondary)
+                    {
+                        for position in points.positions {
+                            let position = view_transform.position_to_frame(position);
+                            commands.push((
+                                RenderCommand::Point,
+                                Mat4::from_translation(position.as_vec3()),
+                            ));
+                        }
+                    }
+                }
                 return Ok(commands);
             }
         }
@@ -569,6 +687,30 @@ impl Renderer {
         Ok(commands)
     }
 
+    /// Propagate the satellite layer to the simulation time and upload its vertices
+    ///
+    /// Returns the transform placing the layer at its central body, or `None` when there is
+    /// nothing to draw.
+    fn update_satellites(&mut self, physics: &PhysicsSimulation) -> AstrariaResult<Option<glam::Mat4>> {
+        let Some(layer) = &mut self.satellites else {
+            self.main_renderer
+                .update_satellite_buffers(None, glam::DMat3::IDENTITY);
+            return Ok(None);
+        };
+        let bodies = physics.get_view_bodies()?;
+        let Some(central) = bodies.iter().find(|body| body.name == layer.central_body) else {
+            self.main_renderer
+                .update_satellite_buffers(None, glam::DMat3::IDENTITY);
+            return Ok(None);
+        };
+
+        layer.update(physics.get_simulation_time()?);
+        let rotation = physics.get_view_transform()?.rotation;
+        self.main_renderer
+            .update_satellite_buffers(Some(layer), rotation);
+        Ok(Some(glam::Mat4::from_translation(central.position.as_vec3())))
+    }
+
     pub fn end_frame(&mut self) -> AstrariaResult<()> {
         if let Some(frame) = self.current_frame.take() {
             frame.present();
@@ -580,7 +722,7 @@ impl Renderer {
     fn position_camera_if_needed(&mut self, physics: &PhysicsSimulation) -> AstrariaResult<()> {
         // Only position camera if it's at origin (initial position)
         if self.main_renderer.camera.position().length() < 1e3 {
-            if let Ok(bodies) = physics.get_bodies() {
+            if let Ok(bodies) = physics.get_view_bodies() {
                 if !bodies.is_empty() {
                     let first_body = &bodies[0];
                     let body_radius = match &first_body.body_type {
@@ -588,6 +730,8 @@ impl Renderer {
                         crate::scenario::BodyType::Star { radius, .. } => *radius,
                         crate::scenario::BodyType::PlanetAtmo { radius, .. } => *radius,
                         crate::scenario::BodyType::BlackHole { radius } => *radius,
+                        crate::scenario::BodyType::Spacecraft { radius, .. } => *radius,
+                        crate::scenario::BodyType::Comet { radius, .. } => *radius,
                     };
 
                     self.main_renderer.camera.position_relative_to_body(
@@ -614,6 +758,37 @@ impl Renderer {
         &mut self.main_renderer.camera
     }
 
+    /// Show Lagrange point markers for a primary/secondary pair (or hide them with `None`)
+    pub fn set_lagrange_markers(&mut self, pair: Option<(usize, usize)>) {
+        self.lagrange_markers = pair;
+    }
+
+    pub fn lagrange_markers(&self) -> Option<(usize, usize)> {
+        self.lagrange_markers
+    }
+
+    /// Replace the satellite layer (or remove it with `None`), starting it at the scenario epoch
+    pub fn set_satellites(&mut self, satellites: Option<SatelliteLayer>) {
+        self.satellites = satellites;
+        self.set_scenario_epoch(self.scenario_epoch);
+    }
+
+    /// Epoch of the loaded scenario; satellite layers follow it when it is set
+    pub fn set_scenario_epoch(&mut self, epoch: Option<f64>) {
+        self.scenario_epoch = epoch;
+        if let (Some(epoch), Some(layer)) = (epoch, &mut self.satellites) {
+            layer.set_scenario_epoch(epoch);
+        }
+    }
+
+    pub fn satellites(&self) -> Option<&SatelliteLayer> {
+        self.satellites.as_ref()
+    }
+
+    pub fn satellites_mut(&mut self) -> Option<&mut SatelliteLayer> {
+        self.satellites.as_mut()
+    }
+
     pub fn set_camera_position(&mut self, position: glam::DVec3) {
         self.main_renderer.camera.set_position(position);
     }
@@ -622,6 +797,10 @@ impl Renderer {
         self.main_renderer.camera.look_at(target, distance);
     }
 
+    pub fn set_camera_look_from(&mut self, position: glam::DVec3, target: glam::DVec3) {
+        self.main_renderer.camera.look_from(position, target);
+    }
+
     pub fn device(&self) -> &Device {
         self.main_renderer.device()
     }
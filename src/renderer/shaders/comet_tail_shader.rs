@@ -0,0 +1,77 @@
+/// Comet tail shader for ion and dust tails
+/// Renders instanced, camera-facing particle billboards with additive blending
+use wgpu::{Device, RenderPipeline};
+
+use crate::{AstrariaResult, generated_shaders};
+
+pub use generated_shaders::comet_tail::TailParticle;
+
+pub struct CometTailShader {
+    pub pipeline: RenderPipeline,
+}
+
+impl CometTailShader {
+    pub fn new(device: &Device, surface_format: wgpu::TextureFormat) -> AstrariaResult<Self> {
+        // Use generated shader module
+        let shader = generated_shaders::comet_tail::create_shader_module(device);
+
+        // Use generated pipeline layout
+        let pipeline_layout = generated_shaders::comet_tail::create_pipeline_layout(device);
+
+        // The quad comes per vertex, the particles per instance
+        let vertex_entry = generated_shaders::comet_tail::vs_main_entry(
+            wgpu::VertexStepMode::Vertex,
+            wgpu::VertexStepMode::Instance,
+        );
+        let fragment_entry =
+            generated_shaders::comet_tail::fs_main_entry([Some(wgpu::ColorTargetState {
+                format: surface_format,
+                blend: Some(wgpu::BlendState {
+                    color: wgpu::BlendComponent {
+                        src_factor: wgpu::BlendFactor::One,
+                        dst_factor: wgpu::BlendFactor::One,
+                        operation: wgpu::BlendOperation::Add,
+                    },
+                    alpha: wgpu::BlendComponent::OVER,
+                }),
+                write_mask: wgpu::ColorWrites::ALL,
+            })]);
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Comet Tail Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: generated_shaders::comet_tail::vertex_state(&shader, &vertex_entry),
+            fragment: Some(generated_shaders::comet_tail::fragment_state(
+                &shader,
+                &fragment_entry,
+            )),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Cw,
+                // Billboards face the camera, whichever way the quad winds
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                // Glowing gas: hidden behind solid bodies, but never hiding anything itself
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            cache: None,
+            multiview: None,
+        });
+
+        Ok(Self { pipeline })
+    }
+}
@@ -1,5 +1,6 @@
 pub mod billboard_shader;
 pub mod black_hole_shader;
+pub mod comet_tail_shader;
 /// Shader system - each shader type has its own struct for specialized rendering
 /// Based on the Java implementation where each shader type manages its own rendering logic
 pub mod default_shader;
@@ -7,16 +8,19 @@ pub mod lens_glow_shader;
 pub mod line_shader;
 pub mod planet_atmo_shader;
 pub mod point_shader;
+pub mod ring_shader;
 pub mod skybox_shader;
 pub mod sun_shader;
 
 pub use billboard_shader::BillboardShader;
 pub use black_hole_shader::BlackHoleShader;
+pub use comet_tail_shader::CometTailShader;
 pub use default_shader::DefaultShader;
 pub use lens_glow_shader::LensGlowShader;
 pub use line_shader::LineShader;
 pub use planet_atmo_shader::PlanetAtmoShader;
 pub use point_shader::PointShader;
+pub use ring_shader::RingShader;
 pub use skybox_shader::SkyboxShader;
 pub use sun_shader::SunShader;
 
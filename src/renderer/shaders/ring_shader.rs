@@ -0,0 +1,67 @@
+/// Ring shader for planetary ring systems
+/// Renders a lit, alpha-blended annulus that is shadowed by its planet
+use wgpu::{Device, RenderPipeline};
+
+use crate::{AstrariaResult, generated_shaders};
+
+pub use generated_shaders::rings::RingUniform;
+
+pub struct RingShader {
+    pub pipeline: RenderPipeline,
+}
+
+impl RingShader {
+    pub fn new(device: &Device, surface_format: wgpu::TextureFormat) -> AstrariaResult<Self> {
+        // Use generated shader module
+        let shader = generated_shaders::rings::create_shader_module(device);
+
+        // Use generated pipeline layout
+        let pipeline_layout = generated_shaders::rings::create_pipeline_layout(device);
+
+        // Use generated vertex and fragment entries
+        let vertex_entry = generated_shaders::rings::vs_main_entry(wgpu::VertexStepMode::Vertex);
+        let fragment_entry =
+            generated_shaders::rings::fs_main_entry([Some(wgpu::ColorTargetState {
+                format: surface_format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::ALL,
+            })]);
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Ring Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: generated_shaders::rings::vertex_state(&shader, &vertex_entry),
+            fragment: Some(generated_shaders::rings::fragment_state(
+                &shader,
+                &fragment_entry,
+            )),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Cw,
+                // Rings are seen from both faces
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                // Translucent: hidden behind the planet, but never hiding what is drawn later
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            cache: None,
+            multiview: None,
+        });
+
+        Ok(Self { pipeline })
+    }
+}
@@ -0,0 +1,645 @@
+/// Mean-motion resonance detection
+/// Flags near-commensurate period ratios between bodies that share a parent and records
+/// the matching resonant angles over time, so libration can be told apart from circulation
+use std::collections::VecDeque;
+use std::f64::consts::{PI, TAU};
+
+use glam::DVec3;
+
+use crate::math::{GRAVITATIONAL_CONSTANT, OrbitalElements, OrbitalHierarchy};
+
+/// Default simulated seconds between samples
+pub const DEFAULT_RESONANCE_SAMPLE_INTERVAL: f64 = 1.0;
+
+/// Default relative distance from an exact commensurability that still counts as near it
+pub const DEFAULT_RESONANCE_TOLERANCE: f64 = 0.01;
+
+/// Default highest resonance order (p - q) to look for
+pub const DEFAULT_MAX_ORDER: u32 = 3;
+
+/// Largest integer considered in a period ratio
+const MAX_COEFFICIENT: u32 = 7;
+
+/// Largest coefficient of a three-body combination; with more freedom almost any
+/// three mean motions can be combined into something close to zero
+const MAX_THREE_BODY_COEFFICIENT: i32 = 4;
+
+/// Three-body combinations must vanish this much more tightly than period ratios
+const THREE_BODY_TOLERANCE_FACTOR: f64 = 0.1;
+
+/// Number of samples kept per resonance for plotting
+const MAX_HISTORY: usize = 5000;
+
+/// Resonances tracked at once; extra candidates are ignored
+const MAX_TRACKED: usize = 32;
+
+fn greatest_common_divisor(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        greatest_common_divisor(b, a % b)
+    }
+}
+
+/// Wrap an angle to (-π, π]
+pub fn wrap_angle(angle: f64) -> f64 {
+    let wrapped = (angle + PI).rem_euclid(TAU) - PI;
+    if wrapped == -PI { PI } else { wrapped }
+}
+
+/// Period ratio p:q between an outer and an inner body (outer period / inner period)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Commensurability {
+    pub p: u32,
+    pub q: u32,
+}
+
+impl Commensurability {
+    /// Order of the resonance, p - q
+    pub fn order(&self) -> u32 {
+        self.p - self.q
+    }
+
+    pub fn ratio(&self) -> f64 {
+        self.p as f64 / self.q as f64
+    }
+
+    /// Closest commensurability of at most `max_order` within `tolerance` (relative) of
+    /// an outer/inner period ratio, with its signed relative deviation
+    pub fn find(period_ratio: f64, max_order: u32, tolerance: f64) -> Option<(Self, f64)> {
+        let mut best: Option<(Self, f64)> = None;
+        for q in 1..MAX_COEFFICIENT {
+            for p in (q + 1)..=(q + max_order).min(MAX_COEFFICIENT) {
+                if greatest_common_divisor(p, q) != 1 {
+                    continue;
+                }
+                let candidate = Self { p, q };
+                let deviation = period_ratio / candidate.ratio() - 1.0;
+                if deviation.abs() <= tolerance
+                    && best.is_none_or(|(_, best_deviation)| deviation.abs() < best_deviation.abs())
+                {
+                    best = Some((candidate, deviation));
+                }
+            }
+        }
+        best
+    }
+
+    /// The order + 1 resonant angles φ_k = p λ' - q λ - k ϖ - (order - k) ϖ',
+    /// from the one with only the outer periapsis (k = 0) to only the inner one
+    pub fn resonant_angles(&self, inner: &OrbitalElements, outer: &OrbitalElements) -> Vec<f64> {
+        let order = self.order();
+        (0..=order)
+            .map(|k| {
+                wrap_angle(
+                    self.p as f64 * outer.mean_longitude
+                        - self.q as f64 * inner.mean_longitude
+                        - k as f64 * inner.longitude_of_periapsis
+                        - (order - k) as f64 * outer.longitude_of_periapsis,
+                )
+            })
+            .collect()
+    }
+
+    /// Formula of each resonant angle, matching `resonant_angles`
+    pub fn angle_labels(&self) -> Vec<String> {
+        let order = self.order();
+        (0..=order)
+            .map(|k| {
+                let mut label = format!("{}λ' - {}λ", self.p, self.q);
+                for (count, symbol) in [(k, "ϖ"), (order - k, "ϖ'")] {
+                    match count {
+                        0 => {}
+                        1 => label.push_str(&format!(" - {}", symbol)),
+                        _ => label.push_str(&format!(" - {}{}", count, symbol)),
+                    }
+                }
+                label
+            })
+            .collect()
+    }
+}
+
+/// Three-body resonance a·n1 + b·n2 + c·n3 ≈ 0 with a + b + c = 0, such as the
+/// Laplace resonance of Io, Europa and Ganymede (1, -3, 2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ThreeBodyResonance {
+    pub coefficients: [i32; 3],
+}
+
+impl ThreeBodyResonance {
+    /// Smallest combination of the mean motions that nearly vanishes, with its value
+    /// relative to the fastest mean motion. Combinations that reduce to a two-body
+    /// resonance (a zero coefficient) are skipped.
+    pub fn find(mean_motions: [f64; 3], tolerance: f64) -> Option<(Self, f64)> {
+        let scale = mean_motions.iter().fold(0.0_f64, |max, n| max.max(n.abs()));
+        if scale <= 0.0 {
+            return None;
+        }
+        let limit = MAX_THREE_BODY_COEFFICIENT;
+
+        let mut best: Option<(Self, f64, i32)> = None;
+        for a in 1..=limit {
+            for b in -limit..=limit {
+                let c = -a - b;
+                if b == 0 || c == 0 || c.abs() > limit {
+                    continue;
+                }
+                let divisor = greatest_common_divisor(
+                    a.unsigned_abs(),
+                    greatest_common_divisor(b.unsigned_abs(), c.unsigned_abs()),
+                );
+                if divisor != 1 {
+                    continue;
+                }
+                let combination = (a as f64 * mean_motions[0]
+                    + b as f64 * mean_motions[1]
+                    + c as f64 * mean_motions[2])
+                    / scale;
+                let size = a.abs() + b.abs() + c.abs();
+                if combination.abs() <= tolerance
+                    && best.is_none_or(|(_, best_value, best_size)| {
+                        size < best_size
+                            || (size == best_size && combination.abs() < best_value.abs())
+                    })
+                {
+                    best = Some((
+                        Self {
+                            coefficients: [a, b, c],
+                        },
+                        combination,
+                        size,
+                    ));
+                }
+            }
+        }
+        best.map(|(resonance, value, _)| (resonance, value))
+    }
+
+    /// Resonant angle a·λ1 + b·λ2 + c·λ3
+    pub fn angle(&self, mean_longitudes: [f64; 3]) -> f64 {
+        wrap_angle(
+            self.coefficients
+                .iter()
+                .zip(mean_longitudes)
+                .map(|(coefficient, longitude)| *coefficient as f64 * longitude)
+                .sum(),
+        )
+    }
+
+    pub fn label(&self) -> String {
+        let [a, b, c] = self.coefficients;
+        format!("{}λ₁ {:+}λ₂ {:+}λ₃", a, b, c)
+    }
+}
+
+/// Centre and half-width of the arc an angle has stayed within, or `None` if it
+/// has covered (nearly) the full circle and is circulating
+pub fn libration(angles: impl IntoIterator<Item = f64>) -> Option<(f64, f64)> {
+    let mut sorted: Vec<f64> = angles
+        .into_iter()
+        .filter(|angle| angle.is_finite())
+        .map(|angle| angle.rem_euclid(TAU))
+        .collect();
+    if sorted.len() < 2 {
+        return None;
+    }
+    sorted.sort_by(f64::total_cmp);
+
+    // The widest gap between neighbouring samples is the part of the circle never visited
+    let (mut gap, mut gap_end) = (sorted[0] + TAU - sorted[sorted.len() - 1], sorted[0]);
+    for pair in sorted.windows(2) {
+        if pair[1] - pair[0] > gap {
+            gap = pair[1] - pair[0];
+            gap_end = pair[1];
+        }
+    }
+    // Anything that left less than 30 degrees unvisited is circulating
+    if gap < PI / 6.0 {
+        return None;
+    }
+    let half_width = (TAU - gap) / 2.0;
+    Some((wrap_angle(gap_end + half_width), half_width))
+}
+
+/// Osculating elements of every body in Jacobi coordinates: each body (with its own
+/// satellites) relative to the barycentre of its parent and the siblings inside its
+/// orbit. Heliocentric elements of outer planets wobble with the Sun's reflex motion
+/// far too much to see a resonance like Neptune - Pluto.
+fn jacobi_elements(
+    hierarchy: &OrbitalHierarchy,
+    masses: &[f64],
+    positions: &[DVec3],
+    velocities: &[DVec3],
+) -> Vec<Option<OrbitalElements>> {
+    // Mass, position and velocity of each body together with everything orbiting it
+    fn subsystem(
+        index: usize,
+        hierarchy: &OrbitalHierarchy,
+        masses: &[f64],
+        positions: &[DVec3],
+        velocities: &[DVec3],
+    ) -> (f64, DVec3, DVec3) {
+        let (mut mass, mut momentum_position, mut momentum) = (
+            masses[index],
+            positions[index] * masses[index],
+            velocities[index] * masses[index],
+        );
+        for &child in hierarchy.children(index) {
+            let (child_mass, child_position, child_velocity) =
+                subsystem(child, hierarchy, masses, positions, velocities);
+            mass += child_mass;
+            momentum_position += child_position * child_mass;
+            momentum += child_velocity * child_mass;
+        }
+        if mass > 0.0 {
+            (mass, momentum_position / mass, momentum / mass)
+        } else {
+            (0.0, positions[index], velocities[index])
+        }
+    }
+
+    let mut elements = vec![None; masses.len()];
+    for parent in 0..masses.len() {
+        let mut children: Vec<(usize, (f64, DVec3, DVec3))> = hierarchy
+            .children(parent)
+            .iter()
+            .map(|&child| {
+                (
+                    child,
+                    subsystem(child, hierarchy, masses, positions, velocities),
+                )
+            })
+            .collect();
+        children.sort_by(|a, b| {
+            let distance = |state: &(f64, DVec3, DVec3)| state.1.distance(positions[parent]);
+            distance(&a.1).total_cmp(&distance(&b.1))
+        });
+
+        let (mut mass, mut position, mut velocity) =
+            (masses[parent], positions[parent], velocities[parent]);
+        for (child, (child_mass, child_position, child_velocity)) in children {
+            elements[child] = OrbitalElements::from_state(
+                child_position - position,
+                child_velocity - velocity,
+                GRAVITATIONAL_CONSTANT * (mass + child_mass),
+            );
+            let total = mass + child_mass;
+            if total > 0.0 {
+                position = (position * mass + child_position * child_mass) / total;
+                velocity = (velocity * mass + child_velocity * child_mass) / total;
+            }
+            mass = total;
+        }
+    }
+    elements
+}
+
+/// Which bodies a resonance involves, indexed into the simulation's bodies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResonanceKind {
+    MeanMotion {
+        inner: usize,
+        outer: usize,
+        commensurability: Commensurability,
+    },
+    ThreeBody {
+        /// Ordered from fastest to slowest
+        bodies: [usize; 3],
+        resonance: ThreeBodyResonance,
+    },
+}
+
+/// Resonant angles at one instant
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResonanceSample {
+    pub time: f64,
+    /// Relative distance from exact commensurability
+    pub deviation: f64,
+    /// Radians in (-π, π], one per entry of `TrackedResonance::angle_labels`
+    pub angles: Vec<f64>,
+}
+
+/// A flagged resonance and the history of its angles
+#[derive(Debug, Clone)]
+pub struct TrackedResonance {
+    pub kind: ResonanceKind,
+    /// e.g. "Io - Europa 2:1"
+    pub label: String,
+    pub angle_labels: Vec<String>,
+    /// Whether the latest sample was within tolerance
+    pub active: bool,
+    history: VecDeque<ResonanceSample>,
+}
+
+impl TrackedResonance {
+    /// Samples, oldest first
+    pub fn history(&self) -> &VecDeque<ResonanceSample> {
+        &self.history
+    }
+
+    /// Libration centre and amplitude of each angle over the recorded history
+    pub fn libration(&self) -> Vec<Option<(f64, f64)>> {
+        (0..self.angle_labels.len())
+            .map(|index| libration(self.history.iter().map(|sample| sample.angles[index])))
+            .collect()
+    }
+}
+
+/// Periodic scan of sibling bodies for resonances
+pub struct ResonanceMonitor {
+    enabled: bool,
+    interval: f64,
+    pub tolerance: f64,
+    pub max_order: u32,
+    last_sample_time: Option<f64>,
+    body_count: usize,
+    tracked: Vec<TrackedResonance>,
+}
+
+impl Default for ResonanceMonitor {
+    fn default() -> Self {
+        Self::new(DEFAULT_RESONANCE_SAMPLE_INTERVAL)
+    }
+}
+
+impl ResonanceMonitor {
+    /// New monitor, disabled until `set_enabled(true)`
+    pub fn new(interval: f64) -> Self {
+        Self {
+            enabled: false,
+            interval,
+            tolerance: DEFAULT_RESONANCE_TOLERANCE,
+            max_order: DEFAULT_MAX_ORDER,
+            last_sample_time: None,
+            body_count: 0,
+            tracked: Vec::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Simulated seconds between samples
+    pub fn interval(&self) -> f64 {
+        self.interval
+    }
+
+    pub fn set_interval(&mut self, interval: f64) {
+        self.interval = interval.max(0.0);
+    }
+
+    /// Check whether a sample should be taken at this simulation time
+    pub fn is_due(&self, time: f64) -> bool {
+        self.enabled
+            && self
+                .last_sample_time
+                .is_none_or(|last| time - last >= self.interval)
+    }
+
+    pub fn tracked(&self) -> &[TrackedResonance] {
+        &self.tracked
+    }
+
+    /// Forget every tracked resonance
+    pub fn clear(&mut self) {
+        self.tracked.clear();
+        self.last_sample_time = None;
+    }
+
+    /// Scan the current state, flag new resonances and record angles of tracked ones
+    pub fn record(
+        &mut self,
+        time: f64,
+        names: &[String],
+        masses: &[f64],
+        positions: &[DVec3],
+        velocities: &[DVec3],
+    ) {
+        self.last_sample_time = Some(time);
+        if masses.len() != self.body_count {
+            self.tracked.clear();
+            self.body_count = masses.len();
+        }
+
+        let hierarchy = OrbitalHierarchy::from_states(masses, positions, velocities);
+        let elements = jacobi_elements(&hierarchy, masses, positions, velocities);
+
+        for parent in 0..masses.len() {
+            // Siblings with bound orbits, fastest first
+            let mut siblings: Vec<(usize, OrbitalElements)> = hierarchy
+                .children(parent)
+                .iter()
+                .filter_map(|&child| elements[child].map(|element| (child, element)))
+                .collect();
+            siblings.sort_by(|a, b| b.1.mean_motion.total_cmp(&a.1.mean_motion));
+
+            for (i, (inner, inner_elements)) in siblings.iter().enumerate() {
+                for (outer, outer_elements) in &siblings[i + 1..] {
+                    let ratio = inner_elements.mean_motion / outer_elements.mean_motion;
+                    if let Some((commensurability, _)) =
+                        Commensurability::find(ratio, self.max_order, self.tolerance)
+                    {
+                        let label = format!(
+                            "{} - {} {}:{}",
+                            names[*inner], names[*outer], commensurability.p, commensurability.q
+                        );
+                        self.flag(
+                            ResonanceKind::MeanMotion {
+                                inner: *inner,
+                                outer: *outer,
+                                commensurability,
+                            },
+                            label,
+                            commensurability.angle_labels(),
+                        );
+                    }
+                }
+            }
+
+            for (i, first) in siblings.iter().enumerate() {
+                for (j, second) in siblings.iter().enumerate().skip(i + 1) {
+                    for third in &siblings[j + 1..] {
+                        let mean_motions = [
+                            first.1.mean_motion,
+                            second.1.mean_motion,
+                            third.1.mean_motion,
+                        ];
+                        if let Some((resonance, _)) = ThreeBodyResonance::find(
+                            mean_motions,
+                            self.tolerance * THREE_BODY_TOLERANCE_FACTOR,
+                        ) {
+                            let label = format!(
+                                "{} - {} - {} ({})",
+                                names[first.0],
+                                names[second.0],
+                                names[third.0],
+                                resonance.coefficients.map(|c| c.to_string()).join(", ")
+                            );
+                            self.flag(
+                                ResonanceKind::ThreeBody {
+                                    bodies: [first.0, second.0, third.0],
+                                    resonance,
+                                },
+                                label,
+                                vec![resonance.label()],
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        // Record every tracked resonance, including ones that drifted out of tolerance
+        let tolerance = self.tolerance;
+        for tracked in &mut self.tracked {
+            let sample = match tracked.kind {
+                ResonanceKind::MeanMotion {
+                    inner,
+                    outer,
+                    commensurability,
+                } => match (elements[inner], elements[outer]) {
+                    (Some(inner), Some(outer)) => Some(ResonanceSample {
+                        time,
+                        deviation: inner.mean_motion / outer.mean_motion / commensurability.ratio()
+                            - 1.0,
+                        angles: commensurability.resonant_angles(&inner, &outer),
+                    }),
+                    _ => None,
+                },
+                ResonanceKind::ThreeBody { bodies, resonance } => {
+                    match bodies.map(|body| elements[body]) {
+                        [Some(a), Some(b), Some(c)] => {
+                            let mean_motions = [a.mean_motion, b.mean_motion, c.mean_motion];
+                            Some(ResonanceSample {
+                                time,
+                                deviation: resonance
+                                    .coefficients
+                                    .iter()
+                                    .zip(mean_motions)
+                                    .map(|(k, n)| *k as f64 * n)
+                                    .sum::<f64>()
+                                    / a.mean_motion,
+                                angles: vec![resonance.angle([
+                                    a.mean_longitude,
+                                    b.mean_longitude,
+                                    c.mean_longitude,
+                                ])],
+                            })
+                        }
+                        _ => None,
+                    }
+                }
+            };
+
+            let limit = match tracked.kind {
+                ResonanceKind::MeanMotion { .. } => tolerance,
+                ResonanceKind::ThreeBody { .. } => tolerance * THREE_BODY_TOLERANCE_FACTOR,
+            };
+            tracked.active = sample
+                .as_ref()
+                .is_some_and(|sample| sample.deviation.abs() <= limit);
+            if let Some(sample) = sample {
+                if tracked.history.len() >= MAX_HISTORY {
+                    tracked.history.pop_front();
+                }
+                tracked.history.push_back(sample);
+            }
+        }
+    }
+
+    fn flag(&mut self, kind: ResonanceKind, label: String, angle_labels: Vec<String>) {
+        if self.tracked.len() >= MAX_TRACKED
+            || self.tracked.iter().any(|tracked| tracked.kind == kind)
+        {
+            return;
+        }
+        log::info!("Near resonance: {}", label);
+        self.tracked.push(TrackedResonance {
+            kind,
+            label,
+            angle_labels,
+            active: true,
+            history: VecDeque::new(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::{AU_TO_METERS, EARTH_MASS, SOLAR_MASS};
+
+    #[test]
+    fn test_find_commensurability() {
+        let find = |ratio| Commensurability::find(ratio, DEFAULT_MAX_ORDER, 0.01);
+        assert_eq!(find(2.007).unwrap().0, Commensurability { p: 2, q: 1 });
+        assert_eq!(find(1.503).unwrap().0, Commensurability { p: 3, q: 2 });
+        assert_eq!(find(2.49).unwrap().0, Commensurability { p: 5, q: 2 });
+        assert!(find(1.23).is_none());
+        assert!(Commensurability::find(2.49, 2, 0.01).is_none());
+
+        // Laplace resonance: n_Io - 3 n_Europa + 2 n_Ganymede = 0
+        let (laplace, _) = ThreeBodyResonance::find([203.489, 101.375, 50.318], 1e-3).unwrap();
+        assert_eq!(laplace.coefficients, [1, -3, 2]);
+
+        assert_eq!(
+            Commensurability { p: 3, q: 2 }.angle_labels(),
+            ["3λ' - 2λ - ϖ'", "3λ' - 2λ - ϖ"]
+        );
+    }
+
+    #[test]
+    fn test_libration_versus_circulation() {
+        let librating = (0..100).map(|i| PI + 0.5 * (i as f64 * 0.3).sin());
+        let (centre, amplitude) = libration(librating).unwrap();
+        assert!((centre.abs() - PI).abs() < 0.05);
+        assert!((amplitude - 0.5).abs() < 0.05);
+
+        let circulating = (0..100).map(|i| i as f64 * 0.37);
+        assert!(libration(circulating).is_none());
+    }
+
+    #[test]
+    fn test_monitor_flags_sibling_pair() {
+        let mu = GRAVITATIONAL_CONSTANT * SOLAR_MASS;
+        let eccentricity = 0.05;
+        // Start at periapsis, so λ = ϖ = angle
+        let at_periapsis = |semi_major_axis: f64, angle: f64| {
+            let direction = DVec3::new(angle.cos(), angle.sin(), 0.0);
+            let radius = semi_major_axis * (1.0 - eccentricity);
+            (
+                direction * radius,
+                DVec3::Z.cross(direction) * (mu * (1.0 + eccentricity) / radius).sqrt(),
+            )
+        };
+        // Outer planet at 2^(2/3) AU has twice the period
+        let (inner_position, inner_velocity) = at_periapsis(AU_TO_METERS, 0.0);
+        let (outer_position, outer_velocity) = at_periapsis(AU_TO_METERS * 4.0_f64.cbrt(), 2.0);
+
+        let names = ["Sun", "Inner", "Outer"].map(String::from);
+        let masses = [SOLAR_MASS, EARTH_MASS, EARTH_MASS];
+        let positions = [DVec3::ZERO, inner_position, outer_position];
+        let velocities = [DVec3::ZERO, inner_velocity, outer_velocity];
+
+        let mut monitor = ResonanceMonitor::default();
+        assert!(!monitor.is_due(0.0));
+        monitor.set_enabled(true);
+        monitor.record(0.0, &names, &masses, &positions, &velocities);
+
+        assert_eq!(monitor.tracked().len(), 1);
+        let tracked = &monitor.tracked()[0];
+        assert_eq!(tracked.label, "Inner - Outer 2:1");
+        assert!(tracked.active);
+        // 2λ' - λ - ϖ' = 2 and 2λ' - λ - ϖ = 4
+        let angles = &tracked.history()[0].angles;
+        assert_eq!(angles.len(), 2);
+        assert!((angles[0] - 2.0).abs() < 1e-4);
+        assert!((angles[1] - wrap_angle(4.0)).abs() < 1e-4);
+    }
+}
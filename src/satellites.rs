@@ -0,0 +1,283 @@
+/// Earth satellites from two-line element sets, propagated with SGP4/SDP4
+/// A separate layer on top of the N-body simulation: satellites follow their central body but never feed back into it
+use std::collections::VecDeque;
+
+use glam::DVec3;
+
+use crate::{
+    AstrariaError, AstrariaResult,
+    math::{Sgp4, Tle},
+};
+
+/// Body the satellites are placed around, looked up by name
+pub const DEFAULT_CENTRAL_BODY: &str = "Earth";
+/// Simulated seconds between trail samples
+pub const DEFAULT_TRAIL_INTERVAL: f64 = 60.0;
+/// Samples kept per trail (an hour and a half, about one low orbit)
+pub const DEFAULT_TRAIL_LENGTH: usize = 90;
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+const J2000: f64 = 2451545.0;
+/// TDB - UTC in seconds (32.184 s plus the 37 leap seconds since 2017)
+const TDB_MINUS_UTC: f64 = 69.184;
+
+/// Rotate a TEME vector into the J2000 ecliptic frame used by the simulation
+///
+/// TEME is treated as the mean equator and equinox of date (the equation of the equinoxes,
+/// about an arcsecond, is ignored), then precessed back to J2000 along the ecliptic.
+pub fn teme_to_ecliptic(vector: DVec3, julian_date: f64) -> DVec3 {
+    let t = (julian_date - J2000) / 36525.0;
+    let obliquity = (23.439291 - 0.0130042 * t).to_radians();
+    let precession = (5028.796195 * t / 3600.0).to_radians();
+
+    let (sin_e, cos_e) = obliquity.sin_cos();
+    let ecliptic_of_date = DVec3::new(
+        vector.x,
+        vector.y * cos_e + vector.z * sin_e,
+        -vector.y * sin_e + vector.z * cos_e,
+    );
+    let (sin_p, cos_p) = precession.sin_cos();
+    DVec3::new(
+        ecliptic_of_date.x * cos_p + ecliptic_of_date.y * sin_p,
+        -ecliptic_of_date.x * sin_p + ecliptic_of_date.y * cos_p,
+        ecliptic_of_date.z,
+    )
+}
+
+/// One propagated satellite
+#[derive(Debug, Clone)]
+pub struct Satellite {
+    pub elements: Tle,
+    propagator: Sgp4,
+    /// Position relative to the central body (m, J2000 ecliptic), `None` once propagation fails
+    position: Option<DVec3>,
+    velocity: DVec3,
+    /// Recent positions relative to the central body, oldest first
+    trail: VecDeque<DVec3>,
+}
+
+impl Satellite {
+    pub fn new(elements: Tle) -> AstrariaResult<Self> {
+        let propagator = Sgp4::new(&elements)?;
+        Ok(Self {
+            elements,
+            propagator,
+            position: None,
+            velocity: DVec3::ZERO,
+            trail: VecDeque::new(),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.elements.name
+    }
+
+    /// Position relative to the central body (m), if the last propagation succeeded
+    pub fn position(&self) -> Option<DVec3> {
+        self.position
+    }
+
+    /// Velocity relative to the central body (m/s)
+    pub fn velocity(&self) -> DVec3 {
+        self.velocity
+    }
+
+    pub fn trail(&self) -> impl Iterator<Item = &DVec3> {
+        self.trail.iter()
+    }
+
+    /// Propagate to a UTC Julian date
+    fn propagate_to(&mut self, julian_date: f64) {
+        let minutes = (julian_date - self.propagator.epoch) * 1440.0;
+        match self.propagator.propagate(minutes) {
+            Ok((position, velocity)) => {
+                self.position = Some(teme_to_ecliptic(position * 1000.0, julian_date));
+                self.velocity = teme_to_ecliptic(velocity * 1000.0, julian_date);
+            }
+            Err(e) => {
+                if self.position.is_some() {
+                    log::debug!("Satellite '{}' dropped: {}", self.elements.name, e);
+                }
+                self.position = None;
+            }
+        }
+    }
+}
+
+/// All satellites loaded from element sets, positioned around one central body
+#[derive(Debug, Clone)]
+pub struct SatelliteLayer {
+    satellites: Vec<Satellite>,
+    /// UTC Julian date at simulation time zero
+    epoch: f64,
+    /// Name of the body the satellites orbit
+    pub central_body: String,
+    pub show_trails: bool,
+    /// Simulated seconds between trail samples
+    pub trail_interval: f64,
+    pub trail_length: usize,
+    last_time: Option<f64>,
+    last_trail_time: f64,
+}
+
+impl SatelliteLayer {
+    /// Build a layer from the contents of a TLE file (two- or three-line format)
+    ///
+    /// The simulation epoch defaults to the newest element set, where SGP4 is most accurate.
+    pub fn from_tle_str(text: &str) -> AstrariaResult<Self> {
+        let mut satellites = Vec::new();
+        for elements in Tle::parse_all(text) {
+            match Satellite::new(elements) {
+                Ok(satellite) => satellites.push(satellite),
+                Err(e) => log::warn!("Skipping satellite: {}", e),
+            }
+        }
+        if satellites.is_empty() {
+            return Err(AstrariaError::ParseError(
+                "No usable two-line element sets found".to_string(),
+            ));
+        }
+        let epoch = satellites
+            .iter()
+            .map(|satellite| satellite.elements.epoch)
+            .fold(f64::MIN, f64::max);
+        Ok(Self {
+            satellites,
+            epoch,
+            central_body: DEFAULT_CENTRAL_BODY.to_string(),
+            show_trails: false,
+            trail_interval: DEFAULT_TRAIL_INTERVAL,
+            trail_length: DEFAULT_TRAIL_LENGTH,
+            last_time: None,
+            last_trail_time: f64::NEG_INFINITY,
+        })
+    }
+
+    /// Load a TLE file from disk
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: impl AsRef<std::path::Path>) -> AstrariaResult<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            AstrariaError::AssetLoading(format!("Failed to read '{}': {}", path.display(), e))
+        })?;
+        let layer = Self::from_tle_str(&text)?;
+        log::info!(
+            "Loaded {} satellites from '{}'",
+            layer.len(),
+            path.display()
+        );
+        Ok(layer)
+    }
+
+    pub fn satellites(&self) -> &[Satellite] {
+        &self.satellites
+    }
+
+    pub fn len(&self) -> usize {
+        self.satellites.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.satellites.is_empty()
+    }
+
+    /// UTC Julian date at simulation time zero
+    pub fn epoch(&self) -> f64 {
+        self.epoch
+    }
+
+    /// Change the date that simulation time zero corresponds to
+    pub fn set_epoch(&mut self, julian_date: f64) {
+        self.epoch = julian_date;
+        self.clear_trails();
+    }
+
+    /// Start at a scenario's epoch, which is a TDB Julian date
+    pub fn set_scenario_epoch(&mut self, julian_date: f64) {
+        self.set_epoch(julian_date - TDB_MINUS_UTC / SECONDS_PER_DAY);
+    }
+
+    /// Number of satellites that currently have a valid position
+    pub fn visible_count(&self) -> usize {
+        self.satellites
+            .iter()
+            .filter(|satellite| satellite.position.is_some())
+            .count()
+    }
+
+    pub fn clear_trails(&mut self) {
+        for satellite in &mut self.satellites {
+            satellite.trail.clear();
+        }
+        self.last_time = None;
+        self.last_trail_time = f64::NEG_INFINITY;
+    }
+
+    /// Propagate every satellite to a simulation time (seconds since the epoch)
+    pub fn update(&mut self, simulation_time: f64) {
+        if self.last_time == Some(simulation_time) {
+            return;
+        }
+        // Running backwards (or reloading) would splice unrelated trail segments together
+        if self.last_time.is_some_and(|last| simulation_time < last) {
+            self.clear_trails();
+        }
+        self.last_time = Some(simulation_time);
+
+        let julian_date = self.epoch + simulation_time / SECONDS_PER_DAY;
+        let sample_trail =
+            self.show_trails && simulation_time - self.last_trail_time >= self.trail_interval;
+        if sample_trail {
+            self.last_trail_time = simulation_time;
+        }
+
+        for satellite in &mut self.satellites {
+            satellite.propagate_to(julian_date);
+            if sample_trail {
+                match satellite.position {
+                    Some(position) => satellite.trail.push_back(position),
+                    None => satellite.trail.clear(),
+                }
+                while satellite.trail.len() > self.trail_length {
+                    satellite.trail.pop_front();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927
+2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537
+";
+
+    #[test]
+    fn test_layer_places_satellites_in_low_orbit() {
+        let mut layer = SatelliteLayer::from_tle_str(ISS).unwrap();
+        assert_eq!(layer.len(), 1);
+        assert_eq!(layer.satellites()[0].name(), "ISS (ZARYA)");
+        assert_eq!(layer.epoch(), layer.satellites()[0].elements.epoch);
+        let epoch = layer.epoch();
+        layer.set_scenario_epoch(epoch + TDB_MINUS_UTC / SECONDS_PER_DAY);
+        assert!((layer.epoch() - epoch).abs() * SECONDS_PER_DAY < 1e-3);
+
+        layer.show_trails = true;
+        for minute in 0..10 {
+            layer.update(minute as f64 * 60.0);
+        }
+        let satellite = &layer.satellites()[0];
+        let altitude = satellite.position().unwrap().length() - 6378.135e3;
+        assert!((300e3..450e3).contains(&altitude), "altitude {}", altitude);
+        assert!((satellite.velocity().length() - 7.7e3).abs() < 100.0);
+        assert_eq!(satellite.trail().count(), 10);
+
+        // Rotating into the ecliptic keeps lengths
+        let vector = DVec3::new(1.0, 2.0, 3.0);
+        let rotated = teme_to_ecliptic(vector, J2000 + 9000.0);
+        assert!((rotated.length() - vector.length()).abs() < 1e-12);
+    }
+}
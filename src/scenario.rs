@@ -1,11 +1,21 @@
-use crate::{AstrariaError, AstrariaResult};
+use crate::{AstrariaError, AstrariaResult, math::Maneuver};
 use glam::DVec3;
+use serde::{Deserialize, Serialize};
+
+pub mod generators;
+pub mod horizons;
+pub mod mpc;
+pub mod v4;
+pub mod validation;
+#[cfg(feature = "native")]
+pub mod watch;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum BodyType {
     Planet {
         radius: f32,
         texture_path: String,
+        rings: Option<Rings>,
     },
     Star {
         radius: f32,
@@ -17,13 +27,53 @@ pub enum BodyType {
         texture_path: String,
         atmo_color: [f32; 4],
         ambient_texture: Option<String>,
+        rings: Option<Rings>,
     },
     BlackHole {
         radius: f32,
     },
+    Spacecraft {
+        radius: f32,
+        maneuvers: Vec<Maneuver>,
+    },
+    /// Icy nucleus that grows ion and dust tails near a star
+    Comet {
+        radius: f32,
+        texture_path: String,
+    },
+}
+
+/// A flat ring system in the body's equatorial plane, tilted with its `rotation_params`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rings {
+    /// Distances from the body's centre (m)
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    /// One-dimensional texture from the inner to the outer edge: colour and opacity
+    pub texture_path: String,
 }
 
-#[derive(Debug, Clone)]
+/// How a body spins about its axis
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum RotationMode {
+    /// At the rate and offset given in `rotation_params`
+    #[default]
+    Fixed,
+    /// Keeps the same face (longitude 0) towards the named body, wherever it is
+    TidallyLocked { parent: String },
+}
+
+impl BodyType {
+    /// Ring system of a planet, if it has one
+    pub fn rings(&self) -> Option<&Rings> {
+        match self {
+            BodyType::Planet { rings, .. } | BodyType::PlanetAtmo { rings, .. } => rings.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct ScenarioBody {
     pub name: String,
     pub mass: f64,
@@ -32,17 +82,155 @@ pub struct ScenarioBody {
     pub body_type: BodyType,
     pub orbit_color: [f32; 4],
     pub rotation_params: (f32, f32, f32, f32), // incTilt, axisRightAsc, rotPeriod, offset (all in radians)
+    pub rotation_mode: RotationMode,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Default)]
 pub struct Scenario {
     pub bodies: Vec<ScenarioBody>,
+    /// Move the system into its barycentric frame on load (`barycentric: true|false`)
+    pub barycentric: Option<bool>,
+    pub metadata: ScenarioMetadata,
+    pub integrator: IntegratorSettings,
+    /// Other scenario files to merge in, not yet loaded (see `AssetManager::load_scenario`)
+    pub includes: Vec<ScenarioInclude>,
+}
+
+/// An `include:` directive: the bodies of another scenario file, renamed and shifted
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioInclude {
+    /// Path relative to the including file
+    pub path: String,
+    /// Put before the name of every included body, separated by a space
+    pub prefix: Option<String>,
+    /// Added to every included position (m)
+    pub position_offset: DVec3,
+    /// Added to every included velocity (m/s)
+    pub velocity_offset: DVec3,
+    /// Number of the including file's own bodies listed before the directive
+    pub index: usize,
+}
+
+impl ScenarioInclude {
+    /// Path of the included file, resolved against the path of the file that includes it
+    pub fn resolve_path(&self, including_path: &str) -> String {
+        let mut parts: Vec<&str> = match including_path.rfind('/') {
+            Some(slash) if !self.path.starts_with('/') => {
+                including_path[..slash].split('/').collect()
+            }
+            _ => Vec::new(),
+        };
+        for part in self.path.split('/') {
+            match part {
+                "." | "" => {}
+                ".." if parts.last().is_some_and(|last| *last != "..") => {
+                    parts.pop();
+                }
+                _ => parts.push(part),
+            }
+        }
+        let resolved = parts.join("/");
+        if self.path.starts_with('/') {
+            format!("/{}", resolved)
+        } else {
+            resolved
+        }
+    }
+}
+
+/// Descriptive parts of a scenario that do not affect the bodies (v4 only)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScenarioMetadata {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// Julian date (TDB) at simulation time zero
+    pub epoch: Option<f64>,
+    pub cameras: Vec<CameraBookmark>,
+}
+
+/// A saved camera placement, resolved against the bodies when applied
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraBookmark {
+    pub name: String,
+    /// Body to look at, by name; the camera looks at the origin when `None`
+    pub focus: Option<String>,
+    /// Camera position relative to the target (m), overriding `distance`
+    pub offset: Option<DVec3>,
+    /// Distance from the target along +Z (m); three radii of the focus body when unset
+    pub distance: Option<f64>,
+}
+
+/// Integration method used by the physics thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum IntegratorMethod {
+    #[default]
+    VelocityVerlet,
+}
+
+/// How the scenario should be integrated
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IntegratorSettings {
+    pub method: IntegratorMethod,
+    /// Longest single step in simulated seconds; the physics default when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_time_step: Option<f64>,
+    /// Simulation speed multiplier at load
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_scale: Option<f32>,
 }
 
 pub struct ScenarioParser;
 
+/// Scenario file formats understood by `ScenarioParser`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScenarioFormat {
+    /// Line-ordered text starting with a `v3` header
+    V3,
+    /// RON document, see `v4::ScenarioFile`
+    V4,
+}
+
 impl ScenarioParser {
+    /// Parse a scenario in either format.
+    /// Bodies that fail to parse are skipped with a warning; use `parse_strict` to refuse them.
     pub fn parse(content: &str) -> AstrariaResult<Scenario> {
+        match Self::detect_format(content)? {
+            ScenarioFormat::V3 => Self::parse_v3(content),
+            ScenarioFormat::V4 => v4::ScenarioFile::parse(content)?.into_scenario(),
+        }
+    }
+
+    /// Tell the formats apart by the first line that is not blank, a comment or a RON attribute
+    pub fn detect_format(content: &str) -> AstrariaResult<ScenarioFormat> {
+        let first = content
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with("//") && !line.starts_with("#!["))
+            .unwrap_or_default();
+        if first == "v3" {
+            Ok(ScenarioFormat::V3)
+        } else if first.starts_with('(') || first.starts_with("Scenario") {
+            Ok(ScenarioFormat::V4)
+        } else {
+            Err(AstrariaError::ParseError(
+                "Invalid scenario file format. Expected a 'v3' header or a RON v4 scenario."
+                    .to_string(),
+            ))
+        }
+    }
+
+    /// Parse only if `ScenarioValidator` finds no errors, reporting all of them otherwise
+    pub fn parse_strict(content: &str) -> AstrariaResult<Scenario> {
+        validation::ScenarioValidator::new().check(content)
+    }
+
+    /// Rewrite a scenario of either format as a v4 document
+    pub fn convert_to_v4(content: &str) -> AstrariaResult<String> {
+        ScenarioWriter::write_v4(&Self::parse(content)?)
+    }
+
+    fn parse_v3(content: &str) -> AstrariaResult<Scenario> {
         let lines: Vec<&str> = content.lines().collect();
 
         if lines.is_empty() || !lines[0].trim().eq("v3") {
@@ -52,6 +240,8 @@ impl ScenarioParser {
         }
 
         let mut bodies = Vec::new();
+        let mut barycentric = None;
+        let mut includes = Vec::new();
         let mut i = 1;
 
         while i < lines.len() {
@@ -67,38 +257,46 @@ impl ScenarioParser {
             if line.starts_with("type:") {
                 let object_type = Self::extract_value(line)?;
 
-                match object_type.as_str() {
-                    "planet" => {
-                        if let Ok(body) = Self::parse_planet(&lines, &mut i) {
-                            bodies.push(body);
-                        }
-                    }
-                    "star" => {
-                        if let Ok(body) = Self::parse_star(&lines, &mut i) {
-                            bodies.push(body);
-                        }
-                    }
-                    "planet_atmo" => {
-                        if let Ok(body) = Self::parse_planet_atmo(&lines, &mut i) {
-                            bodies.push(body);
-                        }
-                    }
-                    "black_hole" => {
-                        if let Ok(body) = Self::parse_black_hole(&lines, &mut i) {
-                            bodies.push(body);
-                        }
-                    }
+                let start = i;
+                let parsed = match object_type.as_str() {
+                    "planet" => Self::parse_planet(&lines, &mut i),
+                    "star" => Self::parse_star(&lines, &mut i),
+                    "planet_atmo" => Self::parse_planet_atmo(&lines, &mut i),
+                    "black_hole" => Self::parse_black_hole(&lines, &mut i),
+                    "spacecraft" => Self::parse_spacecraft(&lines, &mut i),
+                    "comet" => Self::parse_comet(&lines, &mut i),
                     _ => {
                         log::warn!("Unknown object type: {}", object_type);
                         i += 1;
+                        continue;
                     }
+                };
+                match parsed {
+                    Ok(body) => bodies.push(body),
+                    Err(e) => log::warn!("Skipping {} at line {}: {}", object_type, start + 1, e),
                 }
+            } else if line.starts_with("include:") {
+                includes.push(Self::parse_include(&lines, &mut i, bodies.len())?);
+            } else if line.starts_with("barycentric:") {
+                let value = Self::extract_value(line)?;
+                barycentric = Some(value.parse::<bool>().map_err(|_| {
+                    AstrariaError::ParseError(format!(
+                        "Invalid barycentric value '{}', expected true or false",
+                        value
+                    ))
+                })?);
+                i += 1;
             } else {
                 i += 1;
             }
         }
 
-        Ok(Scenario { bodies })
+        Ok(Scenario {
+            bodies,
+            barycentric,
+            includes,
+            ..Default::default()
+        })
     }
 
     fn parse_planet(lines: &[&str], i: &mut usize) -> AstrariaResult<ScenarioBody> {
@@ -110,7 +308,7 @@ impl ScenarioParser {
             ));
         }
 
-        let name = Self::extract_value(lines[*i]).map_err(|e| {
+        let name = Self::extract_value(Self::line(lines, *i)?).map_err(|e| {
             AstrariaError::ParseError(format!(
                 "Error parsing planet name at line {}: {}",
                 *i + 1,
@@ -125,7 +323,7 @@ impl ScenarioParser {
                 name
             )));
         }
-        let radius_str = Self::extract_value(lines[*i]).map_err(|e| {
+        let radius_str = Self::extract_value(Self::line(lines, *i)?).map_err(|e| {
             AstrariaError::ParseError(format!(
                 "Error parsing radius for planet '{}' at line {}: {}",
                 name,
@@ -141,24 +339,29 @@ impl ScenarioParser {
         })? * 1000.0; // Convert km to meters
         *i += 1;
 
-        let mass = Self::extract_value(lines[*i])?.parse::<f64>()?;
+        let mass = Self::extract_value(Self::line(lines, *i)?)?.parse::<f64>()?;
         *i += 1;
 
-        let velocity = Self::parse_vec3(lines[*i])?;
+        let velocity = Self::parse_vec3(Self::line(lines, *i)?)?;
         *i += 1;
 
-        let position = Self::parse_vec3(lines[*i])?;
+        let position = Self::parse_vec3(Self::line(lines, *i)?)?;
         *i += 1;
 
-        let texture_path = Self::normalize_texture_path(&Self::extract_value(lines[*i])?);
+        let texture_path =
+            Self::normalize_texture_path(&Self::extract_value(Self::line(lines, *i)?)?);
         *i += 1;
 
-        let orbit_color = Self::parse_color4(lines[*i])?;
+        let orbit_color = Self::parse_color4(Self::line(lines, *i)?)?;
         *i += 1;
 
-        let rotation_params = Self::parse_rotation(lines[*i])?;
+        let rotation_params = Self::parse_rotation(Self::line(lines, *i)?)?;
         *i += 1;
 
+        let rotation_mode = Self::parse_rotation_mode(lines, i)?;
+
+        let rings = Self::parse_rings(lines, i)?;
+
         Ok(ScenarioBody {
             name,
             mass,
@@ -167,40 +370,67 @@ impl ScenarioParser {
             body_type: BodyType::Planet {
                 radius,
                 texture_path,
+                rings,
             },
             orbit_color,
             rotation_params,
+            rotation_mode,
         })
     }
 
+    /// Comets are laid out like planets, without rings
+    fn parse_comet(lines: &[&str], i: &mut usize) -> AstrariaResult<ScenarioBody> {
+        let mut body = Self::parse_planet(lines, i)?;
+        body.body_type = match body.body_type {
+            BodyType::Planet {
+                radius,
+                texture_path,
+                rings: None,
+            } => BodyType::Comet {
+                radius,
+                texture_path,
+            },
+            _ => {
+                return Err(AstrariaError::ParseError(format!(
+                    "Comet '{}' cannot have rings",
+                    body.name
+                )));
+            }
+        };
+        Ok(body)
+    }
+
     fn parse_star(lines: &[&str], i: &mut usize) -> AstrariaResult<ScenarioBody> {
         *i += 1; // Move past type line
 
-        let name = Self::extract_value(lines[*i])?;
+        let name = Self::extract_value(Self::line(lines, *i)?)?;
         *i += 1;
 
-        let radius = Self::extract_value(lines[*i])?.parse::<f32>()? * 1000.0; // Convert km to meters
+        let radius = Self::extract_value(Self::line(lines, *i)?)?.parse::<f32>()? * 1000.0; // Convert km to meters
         *i += 1;
 
-        let mass = Self::extract_value(lines[*i])?.parse::<f64>()?;
+        let mass = Self::extract_value(Self::line(lines, *i)?)?.parse::<f64>()?;
         *i += 1;
 
-        let velocity = Self::parse_vec3(lines[*i])?;
+        let velocity = Self::parse_vec3(Self::line(lines, *i)?)?;
         *i += 1;
 
-        let position = Self::parse_vec3(lines[*i])?;
+        let position = Self::parse_vec3(Self::line(lines, *i)?)?;
         *i += 1;
 
-        let texture_path = Self::normalize_texture_path(&Self::extract_value(lines[*i])?);
+        let texture_path =
+            Self::normalize_texture_path(&Self::extract_value(Self::line(lines, *i)?)?);
         *i += 1;
 
-        let orbit_color = Self::parse_color4(lines[*i])?;
+        let orbit_color = Self::parse_color4(Self::line(lines, *i)?)?;
         *i += 1;
 
-        let rotation_params = Self::parse_rotation(lines[*i])?;
+        let rotation_params = Self::parse_rotation(Self::line(lines, *i)?)?;
         *i += 1;
 
-        let temperature = Self::extract_value(lines[*i])?.parse::<f32>()?;
+        let rotation_mode = Self::parse_rotation_mode(lines, i)?;
+
+        let temperature = Self::extract_value(Self::line(lines, *i)?)?.parse::<f32>()?;
         *i += 1;
 
         Ok(ScenarioBody {
@@ -215,37 +445,41 @@ impl ScenarioParser {
             },
             orbit_color,
             rotation_params,
+            rotation_mode,
         })
     }
 
     fn parse_planet_atmo(lines: &[&str], i: &mut usize) -> AstrariaResult<ScenarioBody> {
         *i += 1; // Move past type line
 
-        let name = Self::extract_value(lines[*i])?;
+        let name = Self::extract_value(Self::line(lines, *i)?)?;
         *i += 1;
 
-        let radius = Self::extract_value(lines[*i])?.parse::<f32>()? * 1000.0; // Convert km to meters
+        let radius = Self::extract_value(Self::line(lines, *i)?)?.parse::<f32>()? * 1000.0; // Convert km to meters
         *i += 1;
 
-        let mass = Self::extract_value(lines[*i])?.parse::<f64>()?;
+        let mass = Self::extract_value(Self::line(lines, *i)?)?.parse::<f64>()?;
         *i += 1;
 
-        let velocity = Self::parse_vec3(lines[*i])?;
+        let velocity = Self::parse_vec3(Self::line(lines, *i)?)?;
         *i += 1;
 
-        let position = Self::parse_vec3(lines[*i])?;
+        let position = Self::parse_vec3(Self::line(lines, *i)?)?;
         *i += 1;
 
-        let texture_path = Self::normalize_texture_path(&Self::extract_value(lines[*i])?);
+        let texture_path =
+            Self::normalize_texture_path(&Self::extract_value(Self::line(lines, *i)?)?);
         *i += 1;
 
-        let orbit_color = Self::parse_color4(lines[*i])?;
+        let orbit_color = Self::parse_color4(Self::line(lines, *i)?)?;
         *i += 1;
 
-        let rotation_params = Self::parse_rotation(lines[*i])?;
+        let rotation_params = Self::parse_rotation(Self::line(lines, *i)?)?;
         *i += 1;
 
-        let atmo_color = Self::parse_color4(lines[*i])?;
+        let rotation_mode = Self::parse_rotation_mode(lines, i)?;
+
+        let atmo_color = Self::parse_color4(Self::line(lines, *i)?)?;
         *i += 1;
 
         // Check for optional ambient texture
@@ -257,6 +491,8 @@ impl ScenarioParser {
             *i += 1;
         }
 
+        let rings = Self::parse_rings(lines, i)?;
+
         Ok(ScenarioBody {
             name,
             mass,
@@ -267,33 +503,96 @@ impl ScenarioParser {
                 texture_path,
                 atmo_color,
                 ambient_texture,
+                rings,
             },
             orbit_color,
             rotation_params,
+            rotation_mode,
         })
     }
 
+    /// Optional `locked: <body>` line after the rotation, for bodies that always face their parent
+    fn parse_rotation_mode(lines: &[&str], i: &mut usize) -> AstrariaResult<RotationMode> {
+        let Some(line) = lines.get(*i).filter(|line| line.starts_with("locked:")) else {
+            return Ok(RotationMode::Fixed);
+        };
+        let parent = Self::extract_value(line).map_err(|_| {
+            AstrariaError::ParseError(format!(
+                "Missing parent body for locked rotation at line {}",
+                *i + 1
+            ))
+        })?;
+        *i += 1;
+        Ok(RotationMode::TidallyLocked { parent })
+    }
+
+    /// Optional `rings: inner outer texture` line, radii in km from the body's centre
+    fn parse_rings(lines: &[&str], i: &mut usize) -> AstrariaResult<Option<Rings>> {
+        let Some(line) = lines.get(*i).filter(|line| line.starts_with("rings:")) else {
+            return Ok(None);
+        };
+        let value = Self::extract_value(line)?;
+        let mut parts = value.split_whitespace();
+        let (Some(inner), Some(outer)) = (parts.next(), parts.next()) else {
+            return Err(AstrariaError::ParseError(format!(
+                "Expected inner radius, outer radius and texture for rings at line {}",
+                *i + 1
+            )));
+        };
+        let inner_radius = inner.parse::<f32>()? * 1000.0; // Convert km to meters
+        let outer_radius = outer.parse::<f32>()? * 1000.0;
+        let texture = parts.collect::<Vec<_>>().join(" ");
+        if texture.is_empty() {
+            return Err(AstrariaError::ParseError(format!(
+                "Missing ring texture at line {}",
+                *i + 1
+            )));
+        }
+        if !(inner_radius >= 0.0 && outer_radius > inner_radius) {
+            return Err(AstrariaError::ParseError(format!(
+                "Ring radii {} to {} km at line {} are not increasing",
+                inner,
+                outer,
+                *i + 1
+            )));
+        }
+        *i += 1;
+        Ok(Some(Rings {
+            inner_radius,
+            outer_radius,
+            texture_path: Self::normalize_texture_path(&texture),
+        }))
+    }
+
     fn parse_black_hole(lines: &[&str], i: &mut usize) -> AstrariaResult<ScenarioBody> {
         *i += 1; // Move past type line
 
-        let name = Self::extract_value(lines[*i])?;
+        let name = Self::extract_value(Self::line(lines, *i)?)?;
         *i += 1;
 
-        let radius = Self::extract_value(lines[*i])?.parse::<f32>()? * 1000.0; // Convert km to meters
+        let radius = Self::extract_value(Self::line(lines, *i)?)?.parse::<f32>()? * 1000.0; // Convert km to meters
         *i += 1;
 
-        let mass = Self::extract_value(lines[*i])?.parse::<f64>()?;
+        let mass = Self::extract_value(Self::line(lines, *i)?)?.parse::<f64>()?;
         *i += 1;
 
-        let velocity = Self::parse_vec3(lines[*i])?;
+        let velocity = Self::parse_vec3(Self::line(lines, *i)?)?;
         *i += 1;
 
-        let position = Self::parse_vec3(lines[*i])?;
+        let position = Self::parse_vec3(Self::line(lines, *i)?)?;
         *i += 1;
 
-        // Black holes don't have textures or colors in the basic format
-        let orbit_color = [0.0, 0.0, 0.0, 1.0]; // Default black
-        let rotation_params = (0.0, 0.0, 0.0, 0.0); // No rotation
+        // Black holes don't have textures; colour and rotation are optional
+        let mut orbit_color = [0.0, 0.0, 0.0, 1.0]; // Default black
+        if *i < lines.len() && lines[*i].trim().starts_with("orbit_color:") {
+            orbit_color = Self::parse_color4(Self::line(lines, *i)?)?;
+            *i += 1;
+        }
+        let mut rotation_params = (0.0, 0.0, 0.0, 0.0); // No rotation
+        if *i < lines.len() && lines[*i].trim().starts_with("rotation:") {
+            rotation_params = Self::parse_rotation(Self::line(lines, *i)?)?;
+            *i += 1;
+        }
 
         Ok(ScenarioBody {
             name,
@@ -303,9 +602,121 @@ impl ScenarioParser {
             body_type: BodyType::BlackHole { radius },
             orbit_color,
             rotation_params,
+            rotation_mode: RotationMode::Fixed,
+        })
+    }
+
+    fn parse_spacecraft(lines: &[&str], i: &mut usize) -> AstrariaResult<ScenarioBody> {
+        *i += 1; // Move past type line
+
+        let name = Self::extract_value(Self::line(lines, *i)?)?;
+        *i += 1;
+
+        // Spacecraft are small, so their radius is given in meters rather than km
+        let radius = Self::extract_value(Self::line(lines, *i)?)?.parse::<f32>()?;
+        *i += 1;
+
+        let mass = Self::extract_value(Self::line(lines, *i)?)?.parse::<f64>()?;
+        *i += 1;
+
+        let velocity = Self::parse_vec3(Self::line(lines, *i)?)?;
+        *i += 1;
+
+        let position = Self::parse_vec3(Self::line(lines, *i)?)?;
+        *i += 1;
+
+        let orbit_color = Self::parse_color4(Self::line(lines, *i)?)?;
+        *i += 1;
+
+        // Any number of maneuver lines may follow
+        let mut maneuvers = Vec::new();
+        while *i < lines.len() && lines[*i].trim().starts_with("maneuver:") {
+            maneuvers.push(Self::parse_maneuver(Self::line(lines, *i)?)?);
+            *i += 1;
+        }
+
+        Ok(ScenarioBody {
+            name,
+            mass,
+            position,
+            velocity,
+            body_type: BodyType::Spacecraft { radius, maneuvers },
+            orbit_color,
+            rotation_params: (0.0, 0.0, 0.0, 0.0),
+            rotation_mode: RotationMode::Fixed,
         })
     }
 
+    /// Parse `include: <path>` and the optional `prefix:`, `position:` and `velocity:` lines after it
+    fn parse_include(
+        lines: &[&str],
+        i: &mut usize,
+        index: usize,
+    ) -> AstrariaResult<ScenarioInclude> {
+        let mut include = ScenarioInclude {
+            path: Self::extract_value(lines[*i])?,
+            prefix: None,
+            position_offset: DVec3::ZERO,
+            velocity_offset: DVec3::ZERO,
+            index,
+        };
+        *i += 1;
+
+        while let Some(line) = lines.get(*i).map(|line| line.trim()) {
+            if line.starts_with("prefix:") {
+                include.prefix = Some(Self::extract_value(line)?);
+            } else if line.starts_with("position:") {
+                include.position_offset = Self::parse_vec3(line)?;
+            } else if line.starts_with("velocity:") {
+                include.velocity_offset = Self::parse_vec3(line)?;
+            } else {
+                break;
+            }
+            *i += 1;
+        }
+        Ok(include)
+    }
+
+    /// Line `i` of a body, or an error if the file ends first
+    fn line<'a>(lines: &[&'a str], i: usize) -> AstrariaResult<&'a str> {
+        lines.get(i).copied().ok_or_else(|| {
+            AstrariaError::ParseError(format!("Unexpected end of file at line {}", i + 1))
+        })
+    }
+
+    /// Parse `maneuver: <time s> <prograde> <normal> <radial> <reference body name>`
+    fn parse_maneuver(line: &str) -> AstrariaResult<Maneuver> {
+        let values = Self::extract_value(line)?;
+        let Some((parts, reference)) = Self::split_fields(&values, 4) else {
+            return Err(AstrariaError::ParseError(format!(
+                "Expected time, three delta-v components and a reference body in maneuver: {}",
+                line
+            )));
+        };
+
+        Ok(Maneuver::new(
+            parts[0].parse::<f64>()?,
+            reference.to_string(),
+            parts[1].parse::<f64>()?,
+            parts[2].parse::<f64>()?,
+            parts[3].parse::<f64>()?,
+        ))
+    }
+
+    /// Split off `count` whitespace-separated fields and return them with the
+    /// trimmed remainder, which may itself contain spaces (a name or a path)
+    fn split_fields(value: &str, count: usize) -> Option<(Vec<&str>, &str)> {
+        let mut rest = value.trim_start();
+        let mut fields = Vec::with_capacity(count);
+        for _ in 0..count {
+            let end = rest.find(char::is_whitespace)?;
+            fields.push(&rest[..end]);
+            rest = rest[end..].trim_start();
+        }
+        let rest = rest.trim_end();
+        (!rest.is_empty()).then_some((fields, rest))
+    }
+
     fn extract_value(line: &str) -> AstrariaResult<String> {
         if let Some(colon_pos) = line.find(':') {
             let value = line[colon_pos + 1..].trim().to_string();
@@ -393,6 +804,297 @@ impl ScenarioParser {
     }
 }
 
+/// Writes scenarios in the v3 text format read by `ScenarioParser`
+pub struct ScenarioWriter;
+
+impl ScenarioWriter {
+    /// Serialize a scenario so that `ScenarioParser::parse` gives back the same bodies.
+    /// Executed maneuvers are left out, since the format cannot mark them as done.
+    pub fn write(scenario: &Scenario) -> String {
+        let mut out = String::from("v3\n");
+        if let Some(barycentric) = scenario.barycentric {
+            out.push_str(&format!("barycentric: {}\n", barycentric));
+        }
+        for (index, body) in scenario.bodies.iter().enumerate() {
+            Self::write_includes(&mut out, scenario, index);
+            out.push('\n');
+            Self::write_body(&mut out, body);
+        }
+        Self::write_includes(&mut out, scenario, scenario.bodies.len());
+        out
+    }
+
+    /// Write the include directives that come before body `index`
+    fn write_includes(out: &mut String, scenario: &Scenario, index: usize) {
+        for include in scenario
+            .includes
+            .iter()
+            .filter(|include| include.index == index)
+        {
+            out.push_str(&format!("\ninclude: {}\n", include.path));
+            if let Some(prefix) = &include.prefix {
+                out.push_str(&format!("prefix: {}\n", prefix));
+            }
+            if include.position_offset != DVec3::ZERO {
+                out.push_str(&format!(
+                    "position: {}\n",
+                    Self::vec3(include.position_offset)
+                ));
+            }
+            if include.velocity_offset != DVec3::ZERO {
+                out.push_str(&format!(
+                    "velocity: {}\n",
+                    Self::vec3(include.velocity_offset)
+                ));
+            }
+        }
+    }
+
+    /// Serialize a scenario as a v4 RON document, keeping its metadata and integrator settings
+    pub fn write_v4(scenario: &Scenario) -> AstrariaResult<String> {
+        v4::ScenarioFile::from_scenario(scenario).to_ron()
+    }
+
+    /// Write a scenario file to disk, as v4 when the path ends in `.ron` and v3 otherwise
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(scenario: &Scenario, path: &std::path::Path) -> AstrariaResult<()> {
+        let content = if path.extension().is_some_and(|extension| extension == "ron") {
+            Self::write_v4(scenario)?
+        } else {
+            Self::write(scenario)
+        };
+        std::fs::write(path, content)?;
+        log::info!(
+            "Saved scenario with {} bodies to {}",
+            scenario.bodies.len(),
+            path.display()
+        );
+        Ok(())
+    }
+
+    fn write_body(out: &mut String, body: &ScenarioBody) {
+        let kind = match &body.body_type {
+            BodyType::Planet { .. } => "planet",
+            BodyType::Star { .. } => "star",
+            BodyType::PlanetAtmo { .. } => "planet_atmo",
+            BodyType::BlackHole { .. } => "black_hole",
+            BodyType::Spacecraft { .. } => "spacecraft",
+            BodyType::Comet { .. } => "comet",
+        };
+        out.push_str(&format!("type: {}\n", kind));
+        out.push_str(&format!("name: {}\n", body.name));
+        let radius = match &body.body_type {
+            BodyType::Planet { radius, .. }
+            | BodyType::Star { radius, .. }
+            | BodyType::PlanetAtmo { radius, .. }
+            | BodyType::BlackHole { radius }
+            | BodyType::Comet { radius, .. } => Self::meters_to_km(*radius),
+            // Spacecraft radii are already in meters
+            BodyType::Spacecraft { radius, .. } => *radius,
+        };
+        out.push_str(&format!("radius: {}\n", radius));
+        out.push_str(&format!("mass: {:e}\n", body.mass));
+        out.push_str(&format!("velocity: {}\n", Self::vec3(body.velocity)));
+        out.push_str(&format!("position: {}\n", Self::vec3(body.position)));
+
+        match &body.body_type {
+            BodyType::Planet {
+                texture_path,
+                rings,
+                ..
+            } => {
+                Self::write_appearance(out, texture_path, body);
+                Self::write_rings(out, rings.as_ref());
+            }
+            BodyType::Star {
+                texture_path,
+                temperature,
+                ..
+            } => {
+                Self::write_appearance(out, texture_path, body);
+                out.push_str(&format!("temperature: {}\n", temperature));
+            }
+            BodyType::Comet { texture_path, .. } => {
+                Self::write_appearance(out, texture_path, body);
+            }
+            BodyType::PlanetAtmo {
+                texture_path,
+                atmo_color,
+                ambient_texture,
+                rings,
+                ..
+            } => {
+                Self::write_appearance(out, texture_path, body);
+                out.push_str(&format!("atmo_color: {}\n", Self::color(atmo_color)));
+                if let Some(ambient_texture) = ambient_texture {
+                    out.push_str(&format!("ambientTexture: {}\n", ambient_texture));
+                }
+                Self::write_rings(out, rings.as_ref());
+            }
+            BodyType::BlackHole { .. } => {
+                out.push_str(&format!(
+                    "orbit_color: {}\n",
+                    Self::color(&body.orbit_color)
+                ));
+                out.push_str(&format!(
+                    "rotation: {}\n",
+                    Self::rotation(body.rotation_params)
+                ));
+            }
+            BodyType::Spacecraft { maneuvers, .. } => {
+                out.push_str(&format!(
+                    "orbit_color: {}\n",
+                    Self::color(&body.orbit_color)
+                ));
+                for maneuver in maneuvers.iter().filter(|maneuver| !maneuver.executed) {
+                    out.push_str(&format!(
+                        "maneuver: {} {} {} {} {}\n",
+                        maneuver.time,
+                        maneuver.prograde,
+                        maneuver.normal,
+                        maneuver.radial,
+                        maneuver.reference_body
+                    ));
+                }
+            }
+        }
+    }
+
+    fn write_appearance(out: &mut String, texture_path: &str, body: &ScenarioBody) {
+        out.push_str(&format!("texture: {}\n", texture_path));
+        out.push_str(&format!(
+            "orbit_color: {}\n",
+            Self::color(&body.orbit_color)
+        ));
+        out.push_str(&format!(
+            "rotation: {}\n",
+            Self::rotation(body.rotation_params)
+        ));
+        if let RotationMode::TidallyLocked { parent } = &body.rotation_mode {
+            out.push_str(&format!("locked: {}\n", parent));
+        }
+    }
+
+    fn write_rings(out: &mut String, rings: Option<&Rings>) {
+        if let Some(rings) = rings {
+            out.push_str(&format!(
+                "rings: {} {} {}\n",
+                Self::meters_to_km(rings.inner_radius),
+                Self::meters_to_km(rings.outer_radius),
+                rings.texture_path
+            ));
+        }
+    }
+
+    fn vec3(vector: DVec3) -> String {
+        format!("{} {} {}", vector.x, vector.y, vector.z)
+    }
+
+    fn color(color: &[f32; 4]) -> String {
+        format!("{} {} {} {}", color[0], color[1], color[2], color[3])
+    }
+
+    fn rotation(params: (f32, f32, f32, f32)) -> String {
+        let degrees = |radians: f32| Self::invert(radians, radians.to_degrees(), f32::to_radians);
+        format!(
+            "{} {} {} {}",
+            degrees(params.0),
+            degrees(params.1),
+            degrees(params.2),
+            degrees(params.3)
+        )
+    }
+
+    fn meters_to_km(meters: f32) -> f32 {
+        Self::invert(meters, meters / 1000.0, |km| km * 1000.0)
+    }
+
+    /// The f32 near `estimate` that the parser's conversion maps back to exactly `target`.
+    /// Converting with f32 rounding is not exactly reversible, so search a few ulps either side.
+    fn invert(target: f32, estimate: f32, convert: impl Fn(f32) -> f32) -> f32 {
+        (0..=8i32)
+            .flat_map(|step| [step, -step])
+            .map(|step| f32::from_bits(estimate.to_bits().wrapping_add_signed(step)))
+            .find(|candidate| convert(*candidate) == target)
+            .unwrap_or(estimate)
+    }
+}
+
+impl Scenario {
+    /// Merge the bodies of an included scenario in place of its directive.
+    /// Only bodies are taken; the included file's settings, metadata and own includes are ignored,
+    /// so nested includes have to be resolved first.
+    pub fn insert_included(&mut self, include: &ScenarioInclude, included: Scenario) {
+        let rename = |name: &str| match &include.prefix {
+            Some(prefix) => format!("{} {}", prefix, name),
+            None => name.to_string(),
+        };
+        let included_names: Vec<String> = included
+            .bodies
+            .iter()
+            .map(|body| body.name.clone())
+            .collect();
+        let bodies = included.bodies.into_iter().map(|mut body| {
+            body.name = rename(&body.name);
+            body.position += include.position_offset;
+            body.velocity += include.velocity_offset;
+            // Burns and locks referring to a body of the included set follow its new name
+            if let RotationMode::TidallyLocked { parent } = &mut body.rotation_mode
+                && included_names.contains(parent)
+            {
+                *parent = rename(parent);
+            }
+            if let BodyType::Spacecraft { maneuvers, .. } = &mut body.body_type {
+                for maneuver in maneuvers.iter_mut() {
+                    if included_names.contains(&maneuver.reference_body) {
+                        maneuver.reference_body = rename(&maneuver.reference_body);
+                    }
+                }
+            }
+            body
+        });
+        let index = include.index.min(self.bodies.len());
+        self.bodies.splice(index..index, bodies);
+    }
+
+    /// Snapshot of live bodies that restarts at the snapshot time.
+    /// Executed maneuvers are dropped, pending ones are moved to the new time zero and
+    /// rotation offsets are advanced to the current spin angle.
+    pub fn from_bodies(bodies: &[crate::math::Body], simulation_time: f64) -> Self {
+        let bodies = bodies
+            .iter()
+            .map(|body| {
+                let mut body_type = body.body_type.clone();
+                if let BodyType::Spacecraft { maneuvers, .. } = &mut body_type {
+                    maneuvers.retain(|maneuver| !maneuver.executed);
+                    for maneuver in maneuvers.iter_mut() {
+                        maneuver.time -= simulation_time;
+                    }
+                }
+                let (tilt, right_ascension, rate, offset) = body.rotation_params;
+                let spin =
+                    offset as f64 + rate as f64 * simulation_time / crate::math::SECONDS_PER_DAY;
+                let offset = spin.rem_euclid(std::f64::consts::TAU) as f32;
+                ScenarioBody {
+                    name: body.name.clone(),
+                    mass: body.mass,
+                    position: body.position,
+                    velocity: body.velocity,
+                    body_type,
+                    orbit_color: body.orbit_color,
+                    rotation_params: (tilt, right_ascension, rate, offset),
+                    rotation_mode: body.rotation_mode.clone(),
+                }
+            })
+            .collect();
+        Self {
+            bodies,
+            barycentric: None,
+            ..Default::default()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -425,6 +1127,7 @@ rotation: 23.440000000000005 90.0 360.98562350000003 -10
 
         let scenario = ScenarioParser::parse(content).unwrap();
         assert_eq!(scenario.bodies.len(), 2);
+        assert_eq!(scenario.barycentric, None);
 
         // Test star
         let sun = &scenario.bodies[0];
@@ -442,4 +1145,386 @@ rotation: 23.440000000000005 90.0 360.98562350000003 -10
         assert_eq!(earth.name, "Earth");
         assert!(matches!(earth.body_type, BodyType::Planet { .. }));
     }
+
+    #[test]
+    fn test_parse_spacecraft_with_maneuvers() {
+        let content = r#"v3
+
+type: spacecraft
+name: Probe
+radius: 5
+mass: 1000
+velocity: 0 7500 0
+position: 7.0E6 0 0
+orbit_color: 1.0 1.0 1.0 0.8
+maneuver: 3600 120.5 0 -3 Earth
+maneuver: 7200  0 15   0   Planet Nine
+"#;
+
+        let scenario = ScenarioParser::parse(content).unwrap();
+        assert_eq!(scenario.bodies.len(), 1);
+
+        let BodyType::Spacecraft { radius, maneuvers } = &scenario.bodies[0].body_type else {
+            panic!("expected a spacecraft");
+        };
+        assert_eq!(*radius, 5.0);
+        assert_eq!(maneuvers.len(), 2);
+        assert_eq!(maneuvers[0].prograde, 120.5);
+        assert_eq!(maneuvers[0].radial, -3.0);
+        assert_eq!(maneuvers[1].reference_body, "Planet Nine");
+        assert_eq!(maneuvers[1].normal, 15.0);
+        assert!(!maneuvers[1].executed);
+    }
+
+    #[test]
+    fn test_parse_barycentric_option() {
+        let scenario = ScenarioParser::parse("v3\nbarycentric: true\n").unwrap();
+        assert_eq!(scenario.barycentric, Some(true));
+
+        assert!(ScenarioParser::parse("v3\nbarycentric: maybe\n").is_err());
+    }
+
+    #[test]
+    fn test_includes_merge_renamed_and_shifted_bodies() {
+        let include = |path: &str| ScenarioInclude {
+            path: path.to_string(),
+            prefix: None,
+            position_offset: DVec3::ZERO,
+            velocity_offset: DVec3::ZERO,
+            index: 0,
+        };
+        assert_eq!(
+            include("systems/A.txt").resolve_path("B.txt"),
+            "systems/A.txt"
+        );
+        assert_eq!(
+            include("A.txt").resolve_path("systems/B.txt"),
+            "systems/A.txt"
+        );
+        assert_eq!(
+            include("../A.txt").resolve_path("systems/x/B.txt"),
+            "systems/A.txt"
+        );
+        assert_eq!(include("./A.txt").resolve_path("../B.txt"), "../A.txt");
+
+        let mut scenario = ScenarioParser::parse(
+            "v3\ninclude: probe.txt\nprefix: Jovian \nposition: 1 2 3\nvelocity: 0 10 0\n",
+        )
+        .unwrap();
+        let directive = scenario.includes.remove(0);
+        assert_eq!(directive.prefix.as_deref(), Some("Jovian"));
+        assert_eq!(directive.position_offset, DVec3::new(1.0, 2.0, 3.0));
+        assert!(
+            ScenarioWriter::write(&Scenario {
+                includes: vec![directive.clone()],
+                ..Default::default()
+            })
+            .contains("include: probe.txt\nprefix: Jovian\nposition: 1 2 3\nvelocity: 0 10 0\n")
+        );
+
+        let included = ScenarioParser::parse(
+            "v3\ntype: spacecraft\nname: Probe\nradius: 5\nmass: 1000\nvelocity: 0 7500 0\n\
+             position: 7.0E6 0 0\norbit_color: 1 1 1 1\nmaneuver: 60 1 0 0 Probe\nmaneuver: 90 1 0 0 Sun\n",
+        )
+        .unwrap();
+        scenario.insert_included(&directive, included);
+        let probe = &scenario.bodies[0];
+        assert_eq!(probe.name, "Jovian Probe");
+        assert_eq!(probe.position, DVec3::new(7.0e6 + 1.0, 2.0, 3.0));
+        assert_eq!(probe.velocity, DVec3::new(0.0, 7510.0, 0.0));
+        let BodyType::Spacecraft { maneuvers, .. } = &probe.body_type else {
+            panic!("expected a spacecraft");
+        };
+        assert_eq!(maneuvers[0].reference_body, "Jovian Probe");
+        assert_eq!(maneuvers[1].reference_body, "Sun");
+
+        // The bundled example resolves include paths relative to itself
+        let composed = pollster::block_on(async {
+            crate::assets::AssetManager::new()
+                .await?
+                .load_scenario("Composed_Systems.txt")
+                .await
+        })
+        .unwrap();
+        let names: Vec<&str> = composed
+            .bodies
+            .iter()
+            .map(|body| body.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "Sun", "Earth", "Moon", "Jupiter", "Io", "Europa", "Ganymede", "Callisto"
+            ]
+        );
+        assert_eq!(composed.barycentric, Some(true));
+        assert_eq!(composed.bodies[1].velocity.y, 29780.0);
+    }
+
+    #[test]
+    fn test_validation_reports_included_files_against_their_own_lines() {
+        let dir = std::env::temp_dir().join(format!("astraria_include_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let main = dir.join("main.txt");
+        std::fs::write(&main, "v3\n\ninclude: moon.txt\n").unwrap();
+        std::fs::write(
+            dir.join("moon.txt"),
+            "v3
+
+type: planet
+name: Moon
+radius: 1737.4
+mass: heavy
+velocity: 0 1022 0
+position: 384400000 0 0
+texture: Planet Textures/2k_moon.jpg
+orbit_color: 0.5 0.5 0.5 1.0
+rotation: 6.68 0 13.18 0
+",
+        )
+        .unwrap();
+
+        let path = main.to_str().unwrap();
+        let (scenario, reports) = pollster::block_on(async {
+            let assets = crate::assets::AssetManager::new().await?;
+            let reports = assets
+                .validate_scenario(path, &validation::ScenarioValidator::new())
+                .await?;
+            Ok::<_, AstrariaError>((assets.load_scenario(path).await?, reports))
+        })
+        .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // Lenient loading drops the bad body, validation points at it in the included file
+        assert!(scenario.bodies.is_empty());
+        assert_eq!(reports.len(), 2);
+        assert!(reports[0].1.is_empty());
+        assert!(reports[1].0.ends_with("moon.txt"));
+        let error = &reports[1].1.diagnostics[0];
+        assert_eq!(
+            (error.line, error.field.as_deref()),
+            (Some(6), Some("mass"))
+        );
+    }
+
+    #[test]
+    fn test_writer_round_trips_every_body_type() {
+        let content = r#"v3
+barycentric: false
+
+type: star
+name: Sun
+radius: 695700.0
+mass: 1.9890984042E30
+velocity: -8.095051963673479 10.079420705729289 0.1813033769140992
+position: 3.857986024050543E8 7.962288116906488E8 -2.0698254349011008E7
+texture: ./Planet Textures/2k_sun.jpg
+orbit_color: 0.8901961 0.6509804 0.0 0.8
+rotation: 7.25 331.15 14.18 0
+temperature: 5778
+
+type: planet_atmo
+name: Earth
+radius: 6378.1
+mass: 5.9723E24
+velocity: 21105.314172210237 20398.1878091857 2.087430514166044E-4
+position: 1.0482728064552127E11 -1.0927061102891383E11 -1.6817723556683976E7
+texture: ./Planet Textures/earth.jpg
+orbit_color: 0.18039216 0.43137255 0.6392157 0.8
+rotation: 23.440000000000005 90.0 360.98562350000003 -10
+atmo_color: 1 1 1 1
+ambientTexture: ./Planet Textures/earth_night.jpg
+
+type: planet
+name: Moon
+radius: 1737.4
+mass: 7.349135348701E22
+velocity: 20105.3 21398.1 10.2
+position: 1.0452728064552127E11 -1.0937061102891383E11 -1.6817723556683976E7
+texture: ./Planet Textures/2k_moon.jpg
+orbit_color: 1.0 1.0 1.0 1.0
+rotation: 6.68 266.86 13.1763581 0
+
+type: black_hole
+name: Gargantua
+radius: 29.5
+mass: 1.989e31
+velocity: 0 0 0
+position: 1e13 0 0
+
+type: spacecraft
+name: Probe
+radius: 5
+mass: 1000
+velocity: 0 7500 0
+position: 7.0E6 0 0
+orbit_color: 1.0 1.0 1.0 0.8
+maneuver: 3600 120.5 0 -3 Earth
+"#;
+        let original = ScenarioParser::parse(content).unwrap();
+        assert_eq!(original.bodies.len(), 5);
+
+        let written = ScenarioWriter::write(&original);
+        let reparsed = ScenarioParser::parse(&written).unwrap();
+        assert_eq!(reparsed.barycentric, Some(false));
+        assert_eq!(reparsed.bodies, original.bodies);
+        // Writing again gives the same text
+        assert_eq!(ScenarioWriter::write(&reparsed), written);
+    }
+
+    #[test]
+    fn test_rings_parse_and_round_trip() {
+        let saturn = |rings: &str| {
+            format!(
+                "v3
+
+type: planet_atmo
+name: Saturn
+radius: 60268.0
+mass: 5.68324068E26
+velocity: 9104.4 -733.2 -350.1
+position: -1.0946826681985458E11 -1.4999085496094253E12 3.0435903409404217E10
+texture: Planet Textures/2k_saturn.jpg
+orbit_color: 0.76 0.65 0.45 0.8
+rotation: 27.93 79.79 810.79 0
+atmo_color: 0.76 0.65 0.45 1.0
+{rings}
+"
+            )
+        };
+
+        let scenario = ScenarioParser::parse(&saturn(
+            "rings: 74500 140220 Planet Textures/saturn rings.png",
+        ))
+        .unwrap();
+        let rings = scenario.bodies[0].body_type.rings().unwrap();
+        assert_eq!(rings.inner_radius, 74_500_000.0);
+        assert_eq!(rings.outer_radius, 140_220_000.0);
+        assert_eq!(rings.texture_path, "Planet Textures/saturn rings.png");
+
+        let written = ScenarioWriter::write(&scenario);
+        assert!(written.contains("rings: 74500 140220 Planet Textures/saturn rings.png\n"));
+        assert_eq!(
+            ScenarioParser::parse(&written).unwrap().bodies,
+            scenario.bodies
+        );
+
+        // A body with malformed rings is skipped like any other malformed body
+        for rings in ["rings: 140220 74500 rings.png", "rings: 74500 140220"] {
+            assert!(
+                ScenarioParser::parse(&saturn(rings))
+                    .unwrap()
+                    .bodies
+                    .is_empty()
+            );
+        }
+    }
+
+    #[test]
+    fn test_comet_parse_and_round_trip() {
+        let content = "v3
+
+type: comet
+name: Halley
+radius: 5.5
+mass: 2.2E14
+velocity: 0 54500 0
+position: 8.77E10 0 0
+texture: Planet Textures/moon.jpg
+orbit_color: 0.5 0.9 1.0 0.8
+rotation: 0 0 2.2 0
+";
+        let scenario = ScenarioParser::parse(content).unwrap();
+        assert_eq!(
+            scenario.bodies[0].body_type,
+            BodyType::Comet {
+                radius: 5500.0,
+                texture_path: "Planet Textures/moon.jpg".to_string(),
+            }
+        );
+
+        let written = ScenarioWriter::write(&scenario);
+        assert!(written.contains("type: comet\n"));
+        assert_eq!(
+            ScenarioParser::parse(&written).unwrap().bodies,
+            scenario.bodies
+        );
+    }
+
+    #[test]
+    fn test_tidally_locked_moon_round_trips() {
+        let content = "v3
+
+type: planet
+name: Moon
+radius: 1737.4
+mass: 7.349135348701E22
+velocity: 0 1024.5 0
+position: 3.844E8 0 0
+texture: Planet Textures/2k_moon.jpg
+orbit_color: 1.0 1.0 1.0 1.0
+rotation: 1.54 55.0 13.17635815 0
+locked: Earth
+";
+        let scenario = ScenarioParser::parse(content).unwrap();
+        assert_eq!(
+            scenario.bodies[0].rotation_mode,
+            RotationMode::TidallyLocked {
+                parent: "Earth".to_string()
+            }
+        );
+
+        let written = ScenarioWriter::write(&scenario);
+        assert!(written.contains("locked: Earth\n"));
+        assert_eq!(
+            ScenarioParser::parse(&written).unwrap().bodies,
+            scenario.bodies
+        );
+        let converted = ScenarioParser::convert_to_v4(content).unwrap();
+        assert!(converted.contains("locked_to: \"Earth\""));
+        assert_eq!(
+            ScenarioParser::parse(&converted).unwrap().bodies,
+            scenario.bodies
+        );
+
+        let error = ScenarioParser::parse_rotation_mode(&["locked: "], &mut 0).unwrap_err();
+        assert!(error.to_string().contains("Missing parent body"));
+    }
+
+    #[test]
+    fn test_snapshot_keeps_the_spin_angle() {
+        let rotation = (0.4, 1.1, 13.2f32.to_radians(), 0.3);
+        let body = crate::math::Body::new_with_properties(
+            7.3e22,
+            DVec3::new(3.8e8, 0.0, 0.0),
+            DVec3::new(0.0, 1000.0, 0.0),
+            "Moon".to_string(),
+            BodyType::Planet {
+                radius: 1.7e6,
+                texture_path: "Planet Textures/2k_moon.jpg".to_string(),
+                rings: None,
+            },
+            [1.0; 4],
+            rotation,
+        );
+        let time = 3.7 * crate::math::SECONDS_PER_DAY;
+
+        let snapshot = Scenario::from_bodies(std::slice::from_ref(&body), time);
+        let written = ScenarioWriter::write(&snapshot);
+        let restored = &ScenarioParser::parse(&written).unwrap().bodies[0];
+        let restarted = crate::math::Body::new_with_properties(
+            restored.mass,
+            restored.position,
+            restored.velocity,
+            restored.name.clone(),
+            restored.body_type.clone(),
+            restored.orbit_color,
+            restored.rotation_params,
+        );
+
+        let before = body.surface_orientation(time, None);
+        let after = restarted.surface_orientation(0.0, None);
+        assert!(before.angle_between(after) < 1e-5);
+        assert_eq!(restored.rotation_params.2, rotation.2);
+    }
 }
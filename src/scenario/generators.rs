@@ -0,0 +1,845 @@
+/// Procedural scenario generators
+/// Build planetary systems, star clusters, disk galaxies and three-body choreographies from a
+/// handful of parameters; the same parameters and seed always give the same scenario
+use glam::DVec3;
+
+use super::{
+    BodyType, CameraBookmark, IntegratorSettings, RotationMode, Scenario, ScenarioBody,
+    ScenarioMetadata,
+};
+use crate::math::{
+    AU_TO_METERS, EARTH_MASS, EARTH_RADIUS, GRAVITATIONAL_CONSTANT, SECONDS_PER_YEAR, SOLAR_MASS,
+    SOLAR_RADIUS, SPEED_OF_LIGHT, conic_state,
+};
+
+/// Effective temperature of the Sun (K), the reference point of the main-sequence relations
+const SOLAR_TEMPERATURE: f64 = 5772.0;
+
+/// Mutual Hill radii between neighbouring planets; systems spaced wider than ~10 stay stable
+/// for billions of orbits
+const MIN_HILL_SPACING: f64 = 12.0;
+const MAX_HILL_SPACING: f64 = 24.0;
+
+/// Integration steps per orbit of the tightest pair, and wall-clock seconds per displayed orbit
+const STEPS_PER_ORBIT: f64 = 2000.0;
+const SECONDS_PER_DISPLAYED_ORBIT: f64 = 60.0;
+
+/// Period of the figure-eight choreography in units where G = m = 1
+const FIGURE_EIGHT_PERIOD: f64 = 6.325_913_98;
+
+const ROCKY_TEXTURES: [&str; 4] = [
+    "Planet Textures/2k_mercury.jpg",
+    "Planet Textures/2k_mars.jpg",
+    "Planet Textures/2k_moon.jpg",
+    "Planet Textures/2k_venus_atmosphere.jpg",
+];
+const ICE_GIANT_TEXTURES: [&str; 2] = [
+    "Planet Textures/2k_neptune.jpg",
+    "Planet Textures/2k_uranus.jpg",
+];
+const GAS_GIANT_TEXTURES: [&str; 2] = [
+    "Planet Textures/2k_jupiter.jpg",
+    "Planet Textures/2k_saturn.jpg",
+];
+
+/// Small deterministic random number generator (SplitMix64), so generated scenarios do not
+/// depend on the platform
+#[derive(Debug, Clone)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in [min, max)
+    pub fn range(&mut self, min: f64, max: f64) -> f64 {
+        min + (max - min) * self.next_f64()
+    }
+
+    /// Uniform in log space between two positive bounds
+    pub fn log_range(&mut self, min: f64, max: f64) -> f64 {
+        self.range(min.ln(), max.ln()).exp()
+    }
+
+    /// Standard normal deviate (Box-Muller)
+    pub fn normal(&mut self) -> f64 {
+        let u = 1.0 - self.next_f64();
+        let v = self.next_f64();
+        (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos()
+    }
+
+    /// Direction uniformly distributed over the sphere
+    pub fn unit_vector(&mut self) -> DVec3 {
+        let z = self.range(-1.0, 1.0);
+        let (sin_phi, cos_phi) = self.range(0.0, std::f64::consts::TAU).sin_cos();
+        let r = (1.0 - z * z).sqrt();
+        DVec3::new(r * cos_phi, r * sin_phi, z)
+    }
+}
+
+/// A random, dynamically stable planetary system around a main-sequence star
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlanetarySystem {
+    /// Effective temperature of the star (K); mass and radius follow from it
+    pub star_temperature: f64,
+    pub planet_count: usize,
+}
+
+impl Default for PlanetarySystem {
+    fn default() -> Self {
+        Self {
+            star_temperature: SOLAR_TEMPERATURE,
+            planet_count: 6,
+        }
+    }
+}
+
+/// Equal-mass-ish stars drawn from a Plummer sphere in virial equilibrium
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StarCluster {
+    pub star_count: usize,
+    /// Mean stellar mass (solar masses)
+    pub star_mass: f64,
+    /// Plummer scale radius (AU)
+    pub scale_radius: f64,
+}
+
+impl Default for StarCluster {
+    fn default() -> Self {
+        Self {
+            star_count: 64,
+            star_mass: 1.0,
+            scale_radius: 500.0,
+        }
+    }
+}
+
+/// Stars on circular orbits along two logarithmic spiral arms around a central black hole.
+/// The arms wind up as the disk rotates differentially; there is no dark matter halo.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiskGalaxy {
+    pub star_count: usize,
+    /// Mass of the central black hole (solar masses)
+    pub core_mass: f64,
+    /// Outer radius of the disk (AU)
+    pub radius: f64,
+    /// Angle between the arms and a circle (degrees)
+    pub pitch_angle: f64,
+}
+
+impl Default for DiskGalaxy {
+    fn default() -> Self {
+        Self {
+            star_count: 200,
+            core_mass: 1.0e5,
+            radius: 5000.0,
+            pitch_angle: 15.0,
+        }
+    }
+}
+
+/// Planets orbiting both stars of a close binary, outside its unstable zone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circumbinary {
+    /// Stellar masses (solar masses)
+    pub primary_mass: f64,
+    pub secondary_mass: f64,
+    /// Separation of the circular binary (AU)
+    pub separation: f64,
+    pub planet_count: usize,
+}
+
+impl Default for Circumbinary {
+    fn default() -> Self {
+        Self {
+            primary_mass: 1.0,
+            secondary_mass: 0.5,
+            separation: 0.25,
+            planet_count: 3,
+        }
+    }
+}
+
+/// The Chenciner-Montgomery figure-eight: three equal masses chasing each other on one curve
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FigureEight {
+    /// Mass of each star (solar masses)
+    pub mass: f64,
+    /// Length unit of the choreography, about half its width (AU)
+    pub scale: f64,
+    /// Random velocity kick relative to each star's speed, to study how the orbit breaks up
+    pub perturbation: f64,
+}
+
+impl Default for FigureEight {
+    fn default() -> Self {
+        Self {
+            mass: 1.0,
+            scale: 1.0,
+            perturbation: 0.0,
+        }
+    }
+}
+
+/// Any of the generators, with its parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Generator {
+    PlanetarySystem(PlanetarySystem),
+    StarCluster(StarCluster),
+    DiskGalaxy(DiskGalaxy),
+    Circumbinary(Circumbinary),
+    FigureEight(FigureEight),
+}
+
+impl Generator {
+    /// Names accepted by `from_name`, as used on the command line
+    pub const NAMES: [&'static str; 5] = [
+        "planetary",
+        "cluster",
+        "galaxy",
+        "circumbinary",
+        "figure-eight",
+    ];
+
+    /// The named generator with default parameters
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "planetary" => Some(Self::PlanetarySystem(PlanetarySystem::default())),
+            "cluster" => Some(Self::StarCluster(StarCluster::default())),
+            "galaxy" => Some(Self::DiskGalaxy(DiskGalaxy::default())),
+            "circumbinary" => Some(Self::Circumbinary(Circumbinary::default())),
+            "figure-eight" => Some(Self::FigureEight(FigureEight::default())),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PlanetarySystem(_) => "planetary",
+            Self::StarCluster(_) => "cluster",
+            Self::DiskGalaxy(_) => "galaxy",
+            Self::Circumbinary(_) => "circumbinary",
+            Self::FigureEight(_) => "figure-eight",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::PlanetarySystem(_) => "Planetary system",
+            Self::StarCluster(_) => "Star cluster (Plummer)",
+            Self::DiskGalaxy(_) => "Disk galaxy",
+            Self::Circumbinary(_) => "Circumbinary system",
+            Self::FigureEight(_) => "Figure-eight three-body",
+        }
+    }
+
+    pub fn generate(&self, seed: u64) -> Scenario {
+        match self {
+            Self::PlanetarySystem(generator) => generator.generate(seed),
+            Self::StarCluster(generator) => generator.generate(seed),
+            Self::DiskGalaxy(generator) => generator.generate(seed),
+            Self::Circumbinary(generator) => generator.generate(seed),
+            Self::FigureEight(generator) => generator.generate(seed),
+        }
+    }
+}
+
+impl PlanetarySystem {
+    pub fn generate(&self, seed: u64) -> Scenario {
+        let mut rng = SeededRng::new(seed);
+        let temperature = self.star_temperature.clamp(2500.0, 40000.0);
+        let star_mass = main_sequence_mass(temperature) * SOLAR_MASS;
+        let star = star_body("Star", star_mass, temperature, DVec3::ZERO, DVec3::ZERO);
+
+        // Rocky planets start near where Mercury would be for this luminosity
+        let luminosity = (main_sequence_radius(star_mass) / SOLAR_RADIUS).powi(2)
+            * (temperature / SOLAR_TEMPERATURE).powi(4);
+        let snow_line = 2.7 * AU_TO_METERS * luminosity.sqrt();
+        let first_orbit = rng.range(0.2, 0.5) * AU_TO_METERS * luminosity.sqrt();
+
+        let masses = planet_masses(
+            &mut rng,
+            self.planet_count,
+            star_mass,
+            first_orbit,
+            snow_line,
+        );
+        let orbits = stable_orbits(&mut rng, first_orbit, star_mass, &masses);
+        let mut bodies = vec![star];
+        for (index, (&mass, &semi_major_axis)) in masses.iter().zip(&orbits).enumerate() {
+            let name = format!("Star {}", planet_letter(index));
+            bodies.push(planet_on_orbit(
+                &mut rng,
+                name,
+                mass,
+                semi_major_axis,
+                star_mass,
+            ));
+        }
+
+        let inner_period = orbits
+            .first()
+            .map_or(SECONDS_PER_YEAR, |&a| orbital_period(a, star_mass));
+        let extent = orbits.last().copied().unwrap_or(AU_TO_METERS);
+        generated(
+            format!("Planetary system (seed {})", seed),
+            format!(
+                "{} planets around a {:.0} K main-sequence star of {:.2} solar masses",
+                self.planet_count,
+                temperature,
+                star_mass / SOLAR_MASS
+            ),
+            bodies,
+            integrator(inner_period, inner_period),
+            vec![
+                bookmark("Overview", Some("Star"), 2.5 * extent),
+                bookmark("Inner planets", Some("Star"), 4.0 * first_orbit),
+            ],
+        )
+    }
+}
+
+impl StarCluster {
+    pub fn generate(&self, seed: u64) -> Scenario {
+        let mut rng = SeededRng::new(seed);
+        let count = self.star_count.max(2);
+        let scale = self.scale_radius * AU_TO_METERS;
+        let masses: Vec<f64> = (0..count)
+            .map(|_| self.star_mass * rng.range(0.5, 1.5) * SOLAR_MASS)
+            .collect();
+        let total_mass: f64 = masses.iter().sum();
+
+        // Aarseth, Henon & Wielen (1974): radius from the cumulative mass profile, speed by
+        // rejection sampling of the isotropic distribution function
+        let mut bodies = Vec::with_capacity(count);
+        for (index, &mass) in masses.iter().enumerate() {
+            let radius = loop {
+                let u = rng.next_f64().max(f64::MIN_POSITIVE);
+                let radius = scale / (u.powf(-2.0 / 3.0) - 1.0).sqrt();
+                if radius < 10.0 * scale {
+                    break radius;
+                }
+            };
+            let q = loop {
+                let q = rng.next_f64();
+                if rng.range(0.0, 0.1) < q * q * (1.0 - q * q).powf(3.5) {
+                    break q;
+                }
+            };
+            let escape_speed = (2.0 * GRAVITATIONAL_CONSTANT * total_mass / scale).sqrt()
+                * (1.0 + (radius / scale).powi(2)).powf(-0.25);
+            let temperature = main_sequence_temperature(mass);
+            bodies.push(star_body(
+                &format!("Star {}", index + 1),
+                mass,
+                temperature,
+                rng.unit_vector() * radius,
+                rng.unit_vector() * q * escape_speed,
+            ));
+        }
+
+        let dynamical_time = (scale.powi(3) / (GRAVITATIONAL_CONSTANT * total_mass)).sqrt();
+        generated(
+            format!("Star cluster (seed {})", seed),
+            format!(
+                "Plummer sphere of {} stars, {:.0} solar masses within a {:.0} AU scale radius",
+                count,
+                total_mass / SOLAR_MASS,
+                self.scale_radius
+            ),
+            bodies,
+            integrator(dynamical_time, std::f64::consts::TAU * dynamical_time),
+            vec![bookmark("Overview", None, 6.0 * scale)],
+        )
+    }
+}
+
+impl DiskGalaxy {
+    pub fn generate(&self, seed: u64) -> Scenario {
+        let mut rng = SeededRng::new(seed);
+        let core_mass = self.core_mass.max(1.0) * SOLAR_MASS;
+        let outer = self.radius * AU_TO_METERS;
+        let inner = 0.1 * outer;
+        let winding = 1.0 / self.pitch_angle.clamp(1.0, 89.0).to_radians().tan();
+
+        let masses: Vec<f64> = (0..self.star_count)
+            .map(|_| rng.log_range(0.3, 3.0) * SOLAR_MASS)
+            .collect();
+        let disk_mass: f64 = masses.iter().sum();
+
+        let mut bodies = vec![ScenarioBody {
+            name: "Core".to_string(),
+            mass: core_mass,
+            position: DVec3::ZERO,
+            velocity: DVec3::ZERO,
+            body_type: BodyType::BlackHole {
+                radius: (2.0 * GRAVITATIONAL_CONSTANT * core_mass / SPEED_OF_LIGHT.powi(2)) as f32,
+            },
+            orbit_color: [1.0, 1.0, 1.0, 1.0],
+            rotation_params: (0.0, 0.0, 0.0, 0.0),
+            rotation_mode: RotationMode::Fixed,
+        }];
+        for (index, &mass) in masses.iter().enumerate() {
+            let fraction = rng.next_f64();
+            let radius = inner + (outer - inner) * fraction;
+            let arm = (index % 2) as f64 * std::f64::consts::PI;
+            let angle = arm + winding * (radius / inner).ln() + 0.15 * rng.normal();
+            let (sin, cos) = angle.sin_cos();
+            let height = 0.01 * outer * rng.normal();
+
+            // Radii are uniform, so the disk mass inside grows linearly
+            let enclosed = core_mass + disk_mass * fraction;
+            let speed = (GRAVITATIONAL_CONSTANT * enclosed / radius).sqrt();
+            let velocity = DVec3::new(-sin, cos, 0.0) * speed
+                + DVec3::new(rng.normal(), rng.normal(), 0.0) * 0.02 * speed;
+            bodies.push(star_body(
+                &format!("Star {}", index + 1),
+                mass,
+                main_sequence_temperature(mass),
+                DVec3::new(radius * cos, radius * sin, height),
+                velocity,
+            ));
+        }
+
+        let middle = 0.5 * (inner + outer);
+        generated(
+            format!("Disk galaxy (seed {})", seed),
+            format!(
+                "{} stars in two spiral arms around a {:.0e} solar-mass black hole",
+                self.star_count, self.core_mass
+            ),
+            bodies,
+            integrator(
+                orbital_period(inner, core_mass),
+                orbital_period(middle, core_mass + 0.5 * disk_mass),
+            ),
+            vec![
+                bookmark("Face on", Some("Core"), 2.5 * outer),
+                CameraBookmark {
+                    name: "Edge on".to_string(),
+                    focus: Some("Core".to_string()),
+                    offset: Some(DVec3::new(0.0, -2.5 * outer, 0.0)),
+                    distance: None,
+                },
+            ],
+        )
+    }
+}
+
+impl Circumbinary {
+    pub fn generate(&self, seed: u64) -> Scenario {
+        let mut rng = SeededRng::new(seed);
+        let primary_mass = self.primary_mass.max(0.08) * SOLAR_MASS;
+        let secondary_mass =
+            self.secondary_mass.clamp(0.08, self.primary_mass.max(0.08)) * SOLAR_MASS;
+        let binary_mass = primary_mass + secondary_mass;
+        let separation = self.separation * AU_TO_METERS;
+
+        // Circular binary about the origin, at a random phase
+        let (sin, cos) = rng.range(0.0, std::f64::consts::TAU).sin_cos();
+        let axis = DVec3::new(cos, sin, 0.0);
+        let along = DVec3::new(-sin, cos, 0.0);
+        let relative_speed = (GRAVITATIONAL_CONSTANT * binary_mass / separation).sqrt();
+        let primary_share = secondary_mass / binary_mass;
+        let secondary_share = primary_mass / binary_mass;
+        let mut bodies = vec![
+            star_body(
+                "Star A",
+                primary_mass,
+                main_sequence_temperature(primary_mass),
+                -axis * separation * primary_share,
+                -along * relative_speed * primary_share,
+            ),
+            star_body(
+                "Star B",
+                secondary_mass,
+                main_sequence_temperature(secondary_mass),
+                axis * separation * secondary_share,
+                along * relative_speed * secondary_share,
+            ),
+        ];
+
+        // Holman & Wiegert (1999): circular binaries clear out everything within this radius
+        let mu = secondary_mass / binary_mass;
+        let critical = (1.60 + 4.12 * mu - 5.09 * mu * mu) * separation;
+        let first_orbit = critical * rng.range(1.3, 1.6);
+        let masses: Vec<f64> = (0..self.planet_count)
+            .map(|_| rng.log_range(0.1, 300.0) * EARTH_MASS)
+            .collect();
+        let orbits = stable_orbits(&mut rng, first_orbit, binary_mass, &masses);
+        for (index, (&mass, &semi_major_axis)) in masses.iter().zip(&orbits).enumerate() {
+            let name = format!("AB {}", planet_letter(index));
+            bodies.push(planet_on_orbit(
+                &mut rng,
+                name,
+                mass,
+                semi_major_axis,
+                binary_mass,
+            ));
+        }
+
+        let binary_period = orbital_period(separation, binary_mass);
+        let extent = orbits.last().copied().unwrap_or(separation);
+        generated(
+            format!("Circumbinary system (seed {})", seed),
+            format!(
+                "{} planets around a {:.2} + {:.2} solar-mass binary {:.2} AU apart",
+                self.planet_count,
+                primary_mass / SOLAR_MASS,
+                secondary_mass / SOLAR_MASS,
+                self.separation
+            ),
+            bodies,
+            integrator(binary_period, orbital_period(first_orbit, binary_mass)),
+            vec![
+                bookmark("Overview", None, 2.5 * extent),
+                bookmark("Binary", None, 6.0 * separation),
+            ],
+        )
+    }
+}
+
+impl FigureEight {
+    pub fn generate(&self, seed: u64) -> Scenario {
+        let mut rng = SeededRng::new(seed);
+        let mass = self.mass.max(0.01) * SOLAR_MASS;
+        let length = self.scale * AU_TO_METERS;
+        let speed_unit = (GRAVITATIONAL_CONSTANT * mass / length).sqrt();
+
+        // Chenciner & Montgomery (2000), in units where G = m = 1
+        let outer = DVec3::new(0.970_004_36, -0.243_087_53, 0.0);
+        let middle_velocity = DVec3::new(-0.932_407_37, -0.864_731_46, 0.0);
+        let states = [
+            (outer, -0.5 * middle_velocity),
+            (-outer, -0.5 * middle_velocity),
+            (DVec3::ZERO, middle_velocity),
+        ];
+
+        let temperature = main_sequence_temperature(mass);
+        let bodies = states
+            .iter()
+            .zip(["Star A", "Star B", "Star C"])
+            .map(|(&(position, velocity), name)| {
+                let velocity = velocity * speed_unit;
+                let kick = rng.unit_vector() * self.perturbation * velocity.length();
+                star_body(name, mass, temperature, position * length, velocity + kick)
+            })
+            .collect();
+
+        let period =
+            FIGURE_EIGHT_PERIOD * (length.powi(3) / (GRAVITATIONAL_CONSTANT * mass)).sqrt();
+        generated(
+            format!("Figure-eight (seed {})", seed),
+            format!(
+                "Three {:.2} solar-mass stars on the figure-eight choreography",
+                self.mass
+            ),
+            bodies,
+            integrator(period, period),
+            vec![bookmark("Overview", None, 4.0 * length)],
+        )
+    }
+}
+
+/// Main-sequence mass (solar masses) for an effective temperature, from L ∝ M^3.5 and R ∝ M^0.8
+pub fn main_sequence_mass(temperature: f64) -> f64 {
+    (temperature / SOLAR_TEMPERATURE).powf(1.0 / 0.475)
+}
+
+/// Inverse of `main_sequence_mass`, taking kilograms
+pub fn main_sequence_temperature(mass: f64) -> f64 {
+    SOLAR_TEMPERATURE * (mass / SOLAR_MASS).powf(0.475)
+}
+
+/// Main-sequence radius (m) of a star of the given mass (kg)
+pub fn main_sequence_radius(mass: f64) -> f64 {
+    SOLAR_RADIUS * (mass / SOLAR_MASS).powf(0.8)
+}
+
+/// Star texture matching the spectral class of a temperature
+pub fn star_texture(temperature: f64) -> &'static str {
+    match temperature {
+        t if t >= 30000.0 => "Planet Textures/ostar.png",
+        t if t >= 10000.0 => "Planet Textures/bstar.png",
+        t if t >= 7500.0 => "Planet Textures/astar.jpg",
+        t if t >= 6000.0 => "Planet Textures/fstar.png",
+        t if t >= 5200.0 => "Planet Textures/gstar.png",
+        t if t >= 3700.0 => "Planet Textures/kstar.png",
+        _ => "Planet Textures/mstar.png",
+    }
+}
+
+fn star_body(
+    name: &str,
+    mass: f64,
+    temperature: f64,
+    position: DVec3,
+    velocity: DVec3,
+) -> ScenarioBody {
+    ScenarioBody {
+        name: name.to_string(),
+        mass,
+        position,
+        velocity,
+        body_type: BodyType::Star {
+            radius: main_sequence_radius(mass) as f32,
+            texture_path: star_texture(temperature).to_string(),
+            temperature: temperature as f32,
+        },
+        orbit_color: [1.0, 0.9, 0.6, 1.0],
+        rotation_params: (0.0, 0.0, 0.0, 0.0),
+        rotation_mode: RotationMode::Fixed,
+    }
+}
+
+/// Planet masses (kg), giants only beyond the snow line and never above a few Jupiters per
+/// solar mass
+fn planet_masses(
+    rng: &mut SeededRng,
+    count: usize,
+    star_mass: f64,
+    first_orbit: f64,
+    snow_line: f64,
+) -> Vec<f64> {
+    // Orbits roughly double outwards, which is enough to decide which side of the line a
+    // planet ends up on
+    let mut estimate = first_orbit;
+    (0..count)
+        .map(|_| {
+            let earth_masses = if estimate < snow_line {
+                rng.log_range(0.05, 10.0)
+            } else {
+                rng.log_range(5.0, 1000.0)
+            };
+            estimate *= 1.8;
+            (earth_masses * EARTH_MASS).min(3.0e-3 * star_mass)
+        })
+        .collect()
+}
+
+/// Semi-major axes starting at `first_orbit`, with neighbours a random 12-24 mutual Hill radii
+/// apart
+fn stable_orbits(
+    rng: &mut SeededRng,
+    first_orbit: f64,
+    central_mass: f64,
+    masses: &[f64],
+) -> Vec<f64> {
+    let mut orbits = Vec::with_capacity(masses.len());
+    let mut semi_major_axis = first_orbit;
+    for (index, &mass) in masses.iter().enumerate() {
+        if index > 0 {
+            // (a2 - a1) = K (a1 + a2) / 2 * h, solved for a2
+            let hill = ((masses[index - 1] + mass) / (3.0 * central_mass)).cbrt();
+            let half_gap = (0.5 * rng.range(MIN_HILL_SPACING, MAX_HILL_SPACING) * hill).min(0.8);
+            semi_major_axis *= (1.0 + half_gap) / (1.0 - half_gap);
+        }
+        orbits.push(semi_major_axis);
+    }
+    orbits
+}
+
+/// A planet at a random phase of a nearly circular, nearly coplanar orbit
+fn planet_on_orbit(
+    rng: &mut SeededRng,
+    name: String,
+    mass: f64,
+    semi_major_axis: f64,
+    central_mass: f64,
+) -> ScenarioBody {
+    let eccentricity = rng.range(0.0, 0.05);
+    let inclination = rng.range(0.0, 2.0).to_radians();
+    let node = rng.range(0.0, std::f64::consts::TAU);
+    let argument = rng.range(0.0, std::f64::consts::TAU);
+    let phase = rng.next_f64() * orbital_period(semi_major_axis, central_mass + mass);
+    let (position, velocity) = conic_state(
+        semi_major_axis * (1.0 - eccentricity),
+        eccentricity,
+        inclination,
+        node,
+        argument,
+        phase,
+        GRAVITATIONAL_CONSTANT * (central_mass + mass),
+    );
+
+    // Mass-radius relations of Chen & Kipping (2017), flattening out at Jupiter's size
+    let earth_masses = mass / EARTH_MASS;
+    let (radius, textures): (f64, &[&str]) = if earth_masses < 2.0 {
+        (EARTH_RADIUS * earth_masses.powf(0.28), &ROCKY_TEXTURES)
+    } else if earth_masses < 50.0 {
+        (EARTH_RADIUS * earth_masses.powf(0.59), &ICE_GIANT_TEXTURES)
+    } else {
+        (
+            (EARTH_RADIUS * earth_masses.powf(0.59)).min(11.0 * EARTH_RADIUS),
+            &GAS_GIANT_TEXTURES,
+        )
+    };
+    let texture = textures[(rng.next_u64() % textures.len() as u64) as usize];
+
+    ScenarioBody {
+        name,
+        mass,
+        position,
+        velocity,
+        body_type: BodyType::Planet {
+            radius: radius as f32,
+            texture_path: texture.to_string(),
+            rings: None,
+        },
+        orbit_color: [
+            rng.range(0.4, 1.0) as f32,
+            rng.range(0.4, 1.0) as f32,
+            rng.range(0.4, 1.0) as f32,
+            1.0,
+        ],
+        rotation_params: (0.0, 0.0, 0.0, 0.0),
+        rotation_mode: RotationMode::Fixed,
+    }
+}
+
+/// Exoplanet-style letter for the n-th planet: b, c, d, ...
+fn planet_letter(index: usize) -> String {
+    match u8::try_from(index).ok().filter(|&index| index < 25) {
+        Some(index) => char::from(b'b' + index).to_string(),
+        None => (index + 1).to_string(),
+    }
+}
+
+fn orbital_period(semi_major_axis: f64, central_mass: f64) -> f64 {
+    std::f64::consts::TAU
+        * (semi_major_axis.powi(3) / (GRAVITATIONAL_CONSTANT * central_mass)).sqrt()
+}
+
+/// Steps small enough for the tightest orbit, at a speed showing one display orbit a minute
+fn integrator(shortest_period: f64, display_period: f64) -> IntegratorSettings {
+    IntegratorSettings {
+        max_time_step: Some(shortest_period / STEPS_PER_ORBIT),
+        time_scale: Some((display_period / SECONDS_PER_DISPLAYED_ORBIT) as f32),
+        ..Default::default()
+    }
+}
+
+fn bookmark(name: &str, focus: Option<&str>, distance: f64) -> CameraBookmark {
+    CameraBookmark {
+        name: name.to_string(),
+        focus: focus.map(str::to_string),
+        offset: None,
+        distance: Some(distance),
+    }
+}
+
+/// Generated systems are always moved into their barycentric frame
+fn generated(
+    name: String,
+    description: String,
+    bodies: Vec<ScenarioBody>,
+    integrator: IntegratorSettings,
+    cameras: Vec<CameraBookmark>,
+) -> Scenario {
+    Scenario {
+        bodies,
+        barycentric: Some(true),
+        metadata: ScenarioMetadata {
+            name: Some(name),
+            description: Some(description),
+            epoch: None,
+            cameras,
+        },
+        integrator,
+        includes: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn states(scenario: &Scenario) -> Vec<(String, DVec3, DVec3)> {
+        scenario
+            .bodies
+            .iter()
+            .map(|body| (body.name.clone(), body.position, body.velocity))
+            .collect()
+    }
+
+    #[test]
+    fn test_generators_are_deterministic_per_seed() {
+        for name in Generator::NAMES {
+            let generator = Generator::from_name(name).unwrap();
+            assert_eq!(generator.name(), name);
+            let first = generator.generate(42);
+            assert!(first.bodies.len() >= 3, "{} generated too few bodies", name);
+            assert_eq!(states(&first), states(&generator.generate(42)));
+
+            // A different seed only changes anything for generators that use randomness
+            if name != "figure-eight" {
+                assert_ne!(states(&first), states(&generator.generate(43)));
+            }
+
+            let mut names: Vec<_> = first.bodies.iter().map(|body| &body.name).collect();
+            names.sort();
+            names.dedup();
+            assert_eq!(names.len(), first.bodies.len(), "{} repeats names", name);
+        }
+    }
+
+    #[test]
+    fn test_planetary_system_is_widely_spaced_and_bound() {
+        let generator = PlanetarySystem {
+            star_temperature: 4500.0,
+            planet_count: 8,
+        };
+        let scenario = generator.generate(7);
+        let star = &scenario.bodies[0];
+        assert!(matches!(
+            &star.body_type,
+            BodyType::Star { texture_path, .. } if texture_path.ends_with("kstar.png")
+        ));
+
+        let planets = &scenario.bodies[1..];
+        assert_eq!(planets.len(), 8);
+        let semi_major_axes: Vec<f64> = planets
+            .iter()
+            .map(|planet| {
+                let mu = GRAVITATIONAL_CONSTANT * (star.mass + planet.mass);
+                let energy = 0.5 * planet.velocity.length_squared() - mu / planet.position.length();
+                assert!(energy < 0.0, "{} is unbound", planet.name);
+                -mu / (2.0 * energy)
+            })
+            .collect();
+        for (pair, axes) in planets.windows(2).zip(semi_major_axes.windows(2)) {
+            let hill = ((pair[0].mass + pair[1].mass) / (3.0 * star.mass)).cbrt();
+            let spacing = (axes[1] - axes[0]) / (hill * 0.5 * (axes[0] + axes[1]));
+            assert!(spacing > MIN_HILL_SPACING - 1e-6 || hill > 0.1);
+        }
+    }
+
+    #[test]
+    fn test_figure_eight_has_zero_momentum() {
+        let scenario = FigureEight::default().generate(1);
+        let momentum: DVec3 = scenario
+            .bodies
+            .iter()
+            .map(|body| body.velocity * body.mass)
+            .sum();
+        let scale = scenario.bodies[0].mass * scenario.bodies[2].velocity.length();
+        assert!(momentum.length() < 1e-8 * scale);
+        assert!(scenario.integrator.time_scale.is_some());
+    }
+}
@@ -0,0 +1,919 @@
+/// JPL Horizons vector-table importer
+/// Reads saved Horizons "VECTORS" exports ($$SOE / $$EOE blocks, text or CSV layout)
+/// and turns them into a scenario, with physical properties from a body catalog
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+use glam::DVec3;
+
+use super::{BodyType, RotationMode, Scenario, ScenarioBody, ScenarioParser};
+use crate::{AstrariaError, AstrariaResult, math::AU_TO_METERS};
+
+/// Obliquity of the ecliptic at J2000 (IAU 2006), used to rotate equatorial exports
+const J2000_OBLIQUITY_DEGREES: f64 = 23.439_279_444;
+
+/// Two records closer than this (days) are treated as the same epoch
+const EPOCH_TOLERANCE_DAYS: f64 = 1e-6;
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// Texture used for catalog bodies that do not name one
+pub(super) const DEFAULT_TEXTURE: &str = "Planet Textures/2k_moon.jpg";
+
+const DEFAULT_ORBIT_COLOR: [f32; 4] = [0.6, 0.6, 0.6, 0.8];
+
+/// Plane the export's coordinates are referred to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferencePlane {
+    /// Ecliptic and equinox of J2000, the plane scenarios use
+    Ecliptic,
+    /// Earth mean equator (ICRF "FRAME" output), rotated to the ecliptic on import
+    Equator,
+}
+
+/// One state vector of an export, converted to meters and m/s
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StateRecord {
+    /// Julian date (TDB)
+    pub julian_date: f64,
+    pub position: DVec3,
+    pub velocity: DVec3,
+}
+
+/// Target, centre and state vectors of one Horizons export
+#[derive(Debug, Clone, PartialEq)]
+pub struct HorizonsEphemeris {
+    pub target_name: String,
+    /// Horizons designation in parentheses after the name, e.g. "399" or "A801 AA"
+    pub target_id: Option<String>,
+    pub center_name: String,
+    pub center_id: Option<String>,
+    pub reference_plane: ReferencePlane,
+    /// Records in the order they appear, already rotated to the ecliptic
+    pub records: Vec<StateRecord>,
+}
+
+impl HorizonsEphemeris {
+    /// Parse a saved Horizons VECTORS export
+    pub fn parse(content: &str) -> AstrariaResult<Self> {
+        let mut target = None;
+        let mut center = None;
+        let mut reference_plane = ReferencePlane::Ecliptic;
+        // Output units: length scale (m) and time scale (s)
+        let mut units = (1000.0, 1.0);
+
+        let mut lines = content.lines().enumerate();
+        let mut found_start = false;
+        for (_, line) in lines.by_ref() {
+            let trimmed = line.trim();
+            if trimmed == "$$SOE" {
+                found_start = true;
+                break;
+            }
+            let Some((key, value)) = trimmed.split_once(':') else {
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "Target body name" => target = Some(Self::parse_body_name(value)),
+                "Center body name" => center = Some(Self::parse_body_name(value)),
+                "Output units" => {
+                    units = match value.split_whitespace().next().unwrap_or_default() {
+                        "KM-S" => (1000.0, 1.0),
+                        "KM-D" => (1000.0, SECONDS_PER_DAY),
+                        "AU-D" => (AU_TO_METERS, SECONDS_PER_DAY),
+                        other => {
+                            return Err(AstrariaError::ParseError(format!(
+                                "Unsupported Horizons output units '{}'",
+                                other
+                            )));
+                        }
+                    }
+                }
+                "Reference frame" | "Reference plane" | "Coordinate systm" => {
+                    let value = value.to_ascii_lowercase();
+                    if value.contains("ecliptic") {
+                        reference_plane = ReferencePlane::Ecliptic;
+                    } else if value.contains("equator") || value.starts_with("frame") {
+                        reference_plane = ReferencePlane::Equator;
+                    }
+                }
+                _ => {}
+            }
+        }
+        if !found_start {
+            return Err(AstrariaError::ParseError(
+                "No $$SOE marker found; is this a Horizons VECTORS export?".to_string(),
+            ));
+        }
+        let (target_name, target_id) = target.ok_or_else(|| {
+            AstrariaError::ParseError("Horizons export has no 'Target body name'".to_string())
+        })?;
+        let (center_name, center_id) = center.ok_or_else(|| {
+            AstrariaError::ParseError("Horizons export has no 'Center body name'".to_string())
+        })?;
+
+        // Records until $$EOE: a Julian date line followed by X/Y/Z and VX/VY/VZ lines,
+        // or one comma-separated line per record
+        let mut records = Vec::new();
+        let mut julian_date = None;
+        let mut values: Vec<(String, f64)> = Vec::new();
+        let mut found_end = false;
+        for (index, line) in lines {
+            let trimmed = line.trim();
+            if trimmed == "$$EOE" {
+                found_end = true;
+                break;
+            }
+            if trimmed.is_empty() {
+                continue;
+            }
+            let number_error = |text: &str| {
+                AstrariaError::ParseError(format!(
+                    "Invalid number '{}' at line {}",
+                    text.trim(),
+                    index + 1
+                ))
+            };
+
+            if trimmed.contains(',') {
+                let fields: Vec<&str> = trimmed.split(',').map(str::trim).collect();
+                if fields.len() < 8 {
+                    return Err(AstrariaError::ParseError(format!(
+                        "Expected JD, date, X, Y, Z, VX, VY, VZ at line {}",
+                        index + 1
+                    )));
+                }
+                let mut numbers = [0.0; 7];
+                for (number, field) in numbers
+                    .iter_mut()
+                    .zip([fields[0]].iter().chain(&fields[2..8]))
+                {
+                    *number = field.parse().map_err(|_| number_error(field))?;
+                }
+                records.push(StateRecord {
+                    julian_date: numbers[0],
+                    position: DVec3::new(numbers[1], numbers[2], numbers[3]),
+                    velocity: DVec3::new(numbers[4], numbers[5], numbers[6]),
+                });
+                continue;
+            }
+
+            if trimmed.contains("= A.D.") || trimmed.contains("= B.C.") {
+                let date = trimmed.split_whitespace().next().unwrap_or_default();
+                julian_date = Some(date.parse::<f64>().map_err(|_| number_error(date))?);
+                values.clear();
+                continue;
+            }
+
+            // "X =-1.77E+07 Y = 1.44E+08 Z = 6.28E+02": key, '=', value triples
+            let spaced = trimmed.replace('=', " = ");
+            let tokens: Vec<&str> = spaced.split_whitespace().collect();
+            for window in tokens.windows(3) {
+                if window[1] == "=" {
+                    let value = window[2].parse().map_err(|_| number_error(window[2]))?;
+                    values.push((window[0].to_string(), value));
+                }
+            }
+            let get = |key: &str| values.iter().find(|(name, _)| name == key).map(|v| v.1);
+            if let (Some(date), Some(x), Some(y), Some(z), Some(vx), Some(vy), Some(vz)) = (
+                julian_date,
+                get("X"),
+                get("Y"),
+                get("Z"),
+                get("VX"),
+                get("VY"),
+                get("VZ"),
+            ) {
+                records.push(StateRecord {
+                    julian_date: date,
+                    position: DVec3::new(x, y, z),
+                    velocity: DVec3::new(vx, vy, vz),
+                });
+                julian_date = None;
+                values.clear();
+            }
+        }
+        if !found_end {
+            return Err(AstrariaError::ParseError(
+                "Horizons export is missing its $$EOE marker".to_string(),
+            ));
+        }
+        if records.is_empty() {
+            return Err(AstrariaError::ParseError(format!(
+                "Horizons export for '{}' contains no state vectors",
+                target_name
+            )));
+        }
+
+        let (length, time) = units;
+        for record in &mut records {
+            record.position *= length;
+            record.velocity *= length / time;
+            if reference_plane == ReferencePlane::Equator {
+                record.position = equatorial_to_ecliptic(record.position);
+                record.velocity = equatorial_to_ecliptic(record.velocity);
+            }
+        }
+
+        Ok(Self {
+            target_name,
+            target_id,
+            center_name,
+            center_id,
+            reference_plane,
+            records,
+        })
+    }
+
+    /// Split "Earth (399)   {source: DE441}" into its name and designation
+    fn parse_body_name(value: &str) -> (String, Option<String>) {
+        let value = value.split('{').next().unwrap_or_default().trim();
+        match (value.rfind('('), value.ends_with(')')) {
+            (Some(open), true) => (
+                value[..open].trim().to_string(),
+                Some(value[open + 1..value.len() - 1].trim().to_string()),
+            ),
+            _ => (value.to_string(), None),
+        }
+    }
+
+    /// Key the target is matched on: its designation, or its name if it has none
+    fn target_key(&self) -> &str {
+        self.target_id.as_deref().unwrap_or(&self.target_name)
+    }
+
+    fn center_key(&self) -> &str {
+        self.center_id.as_deref().unwrap_or(&self.center_name)
+    }
+
+    /// Record at a Julian date, or the first one if no date is given
+    fn record_at(&self, julian_date: Option<f64>) -> Option<&StateRecord> {
+        match julian_date {
+            Some(date) => self
+                .records
+                .iter()
+                .find(|record| (record.julian_date - date).abs() < EPOCH_TOLERANCE_DAYS),
+            None => self.records.first(),
+        }
+    }
+}
+
+/// Rotate a vector about the x axis from the J2000 mean equator to the J2000 ecliptic
+pub(crate) fn equatorial_to_ecliptic(vector: DVec3) -> DVec3 {
+    let (sin, cos) = J2000_OBLIQUITY_DEGREES.to_radians().sin_cos();
+    DVec3::new(
+        vector.x,
+        vector.y * cos + vector.z * sin,
+        -vector.y * sin + vector.z * cos,
+    )
+}
+
+/// Physical properties and appearance of a body, matched by Horizons designation or name
+#[derive(Debug, Clone)]
+pub struct CatalogEntry {
+    pub id: Option<String>,
+    /// Also the name the body gets in the scenario
+    pub name: String,
+    pub mass: f64,
+    /// Type, radius and texture
+    pub body_type: BodyType,
+    pub orbit_color: [f32; 4],
+    pub rotation_params: (f32, f32, f32, f32),
+}
+
+/// Major bodies in sidecar format, with the same values as the bundled Solar System scenario
+const BUILTIN_CATALOG: &str = "
+id: 10
+name: Sun
+type: star
+mass: 1.9890984042E30
+radius: 695700.0
+texture: Planet Textures/2k_sun.jpg
+orbit_color: 0.8901961 0.6509804 0.0 0.8
+rotation: 7.25 331.15 14.18 0
+temperature: 5778
+
+id: 199
+name: Mercury
+mass: 3.3026818999999996E23
+radius: 2439.7
+texture: Planet Textures/2k_mercury.jpg
+orbit_color: 0.63529414 0.03529412 0.0 0.8
+rotation: 0.01 326.01 6.13851 0
+
+id: 299
+name: Venus
+type: planet_atmo
+mass: 4.8674245E24
+radius: 6051.8
+texture: Planet Textures/2k_venus_atmosphere.jpg
+orbit_color: 0.6 0.6392157 0.18039216 0.8
+rotation: 2.64 317.76 -1.48137 0
+atmo_color: 0.984313725 0.8431372549 0.61568627451 1
+
+id: 399
+name: Earth
+type: planet_atmo
+mass: 5.9723E24
+radius: 6378.1
+texture: Planet Textures/earth.jpg
+orbit_color: 0.18039216 0.43137255 0.6392157 0.8
+rotation: 23.44 90.0 360.9856235 -10
+atmo_color: 1 1 1 1
+
+id: 301
+name: Moon
+mass: 7.349135348701E22
+radius: 1737.4
+texture: Planet Textures/2k_moon.jpg
+orbit_color: 1.0 1.0 1.0 1.0
+rotation: 1.54 55.0 13.17635815 0
+
+id: 499
+name: Mars
+type: planet_atmo
+mass: 6.390361E23
+radius: 3396.2
+texture: Planet Textures/2k_mars.jpg
+orbit_color: 0.9372549 0.3137255 0.0 0.8
+rotation: 25.19 354.67 350.891981 0
+atmo_color: 0.82745098039 0.34117647058 0.19215686274 1
+
+id: 401
+name: Phobos
+mass: 1.0659E16
+radius: 11.27
+
+id: 402
+name: Deimos
+mass: 1.4762E15
+radius: 6.2
+
+id: 599
+name: Jupiter
+type: planet_atmo
+mass: 1.8981761089999996E27
+radius: 66854.0
+texture: Planet Textures/2k_jupiter.jpg
+orbit_color: 0.4 0.3019608 0.10980392 0.8
+rotation: 3.12 263.06 870.53 0
+atmo_color: 0.4 0.3019608 0.10980392 1.0
+
+id: 501
+name: Io
+mass: 8.931938E22
+radius: 1821.6
+
+id: 502
+name: Europa
+mass: 4.799844E22
+radius: 1560.8
+
+id: 503
+name: Ganymede
+mass: 1.4819E23
+radius: 2634.1
+
+id: 504
+name: Callisto
+mass: 1.075938E23
+radius: 2410.3
+
+id: 699
+name: Saturn
+type: planet_atmo
+mass: 5.683240679999999E26
+radius: 60268.0
+texture: Planet Textures/2k_saturn.jpg
+orbit_color: 0.7607843 0.64705884 0.44705883 0.8
+rotation: 27.93 79.79 810.79 0
+atmo_color: 0.7607843 0.64705884 0.44705883 1.0
+
+id: 606
+name: Titan
+mass: 1.3452E23
+radius: 2574.7
+
+id: 799
+name: Uranus
+type: planet_atmo
+mass: 8.6837242E25
+radius: 25559.0
+texture: Planet Textures/2k_uranus.jpg
+orbit_color: 0.7137255 0.8901961 0.88235295 0.8
+rotation: 82.23 257.31 -501.16 0
+atmo_color: 0.6862745098 0.89411764705 0.91764705882 1.0
+
+id: 899
+name: Neptune
+type: planet_atmo
+mass: 1.0242494499999998E26
+radius: 24341.0
+texture: Planet Textures/2k_neptune.jpg
+orbit_color: 0.07058824 0.7411765 0.7882353 0.8
+rotation: 28.33 318.9 536.31 0
+atmo_color: 0.25882352941 0.49803921568 0.82352941176 1
+
+id: 801
+name: Triton
+mass: 2.139E22
+radius: 1353.4
+
+id: 999
+name: Pluto
+mass: 1.309E22
+radius: 1187.0
+texture: Planet Textures/plutomap2k.jpg
+orbit_color: 0.6392157 0.654902 0.65882355 0.8
+rotation: 66.91 316.99 -56.363 0
+
+id: 901
+name: Charon
+mass: 1.586E21
+radius: 606.0
+";
+
+/// Body type of a given kind with default type-specific properties
+fn body_type_for(kind: &str, radius_km: f32, texture_path: String) -> AstrariaResult<BodyType> {
+    let radius = radius_km * 1000.0;
+    Ok(match kind {
+        "planet" => BodyType::Planet {
+            radius,
+            texture_path,
+            rings: None,
+        },
+        "star" => BodyType::Star {
+            radius,
+            texture_path,
+            temperature: 5778.0,
+        },
+        "planet_atmo" => BodyType::PlanetAtmo {
+            radius,
+            texture_path,
+            atmo_color: [1.0, 1.0, 1.0, 1.0],
+            ambient_texture: None,
+            rings: None,
+        },
+        "black_hole" => BodyType::BlackHole { radius },
+        "comet" => BodyType::Comet {
+            radius,
+            texture_path,
+        },
+        "spacecraft" => BodyType::Spacecraft {
+            radius,
+            maneuvers: Vec::new(),
+        },
+        other => {
+            return Err(AstrariaError::ParseError(format!(
+                "Unknown body type '{}'",
+                other
+            )));
+        }
+    })
+}
+
+/// Lookup table of physical properties for imported bodies
+#[derive(Debug, Clone, Default)]
+pub struct BodyCatalog {
+    entries: Vec<CatalogEntry>,
+}
+
+impl BodyCatalog {
+    /// The Sun, planets, Pluto and their larger moons
+    pub fn builtin() -> Self {
+        Self {
+            entries: Self::parse_sidecar(BUILTIN_CATALOG).expect("built-in catalog is valid"),
+        }
+    }
+
+    pub fn entries(&self) -> &[CatalogEntry] {
+        &self.entries
+    }
+
+    /// Add an entry, replacing any existing one with the same designation or name
+    pub fn insert(&mut self, entry: CatalogEntry) {
+        self.entries.retain(|existing| {
+            let same_id = entry.id.is_some() && existing.id == entry.id;
+            !same_id && !existing.name.eq_ignore_ascii_case(&entry.name)
+        });
+        self.entries.push(entry);
+    }
+
+    /// Entry for a designation, falling back to a case-insensitive name match
+    pub fn lookup(&self, id: Option<&str>, name: &str) -> Option<&CatalogEntry> {
+        id.and_then(|id| {
+            self.entries
+                .iter()
+                .find(|entry| entry.id.as_deref() == Some(id))
+        })
+        .or_else(|| {
+            self.entries
+                .iter()
+                .find(|entry| entry.name.eq_ignore_ascii_case(name))
+        })
+    }
+
+    /// Parse a sidecar file: blocks of `key: value` lines separated by blank lines,
+    /// with `#` comments. Each block needs `name` and `mass` (kg); `id`, `radius` (km),
+    /// `type`, `texture`, `temperature` (K), `atmo_color`, `orbit_color` and
+    /// `rotation` (same as scenario files) are optional.
+    pub fn parse_sidecar(content: &str) -> AstrariaResult<Vec<CatalogEntry>> {
+        let mut entries = Vec::new();
+        let mut block: Vec<(usize, &str)> = Vec::new();
+        for (index, line) in content.lines().enumerate().chain([(usize::MAX, "")]) {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if !line.is_empty() {
+                block.push((index, line));
+                continue;
+            }
+            if !block.is_empty() {
+                entries.push(Self::parse_sidecar_entry(&block)?);
+                block.clear();
+            }
+        }
+        Ok(entries)
+    }
+
+    fn parse_sidecar_entry(block: &[(usize, &str)]) -> AstrariaResult<CatalogEntry> {
+        let first_line = block[0].0 + 1;
+        let mut fields = Vec::new();
+        for &(index, line) in block {
+            let Some((key, _)) = line.split_once(':') else {
+                return Err(AstrariaError::ParseError(format!(
+                    "Invalid sidecar line (missing ':') at line {}: {}",
+                    index + 1,
+                    line
+                )));
+            };
+            fields.push((key.trim(), line));
+        }
+        let field = |key: &str| fields.iter().find(|(name, _)| *name == key).map(|f| f.1);
+        let value = |key: &str| field(key).map(ScenarioParser::extract_value).transpose();
+
+        let name = value("name")?.ok_or_else(|| {
+            AstrariaError::ParseError(format!("Sidecar entry at line {} has no name", first_line))
+        })?;
+        let mass = value("mass")?
+            .ok_or_else(|| {
+                AstrariaError::ParseError(format!("Sidecar entry '{}' has no mass", name))
+            })?
+            .parse::<f64>()?;
+        let radius = value("radius")?
+            .map(|radius| radius.parse::<f32>())
+            .transpose()
+            .map_err(|e| {
+                AstrariaError::ParseError(format!("Invalid radius for '{}': {}", name, e))
+            })?
+            .unwrap_or(0.0);
+        let texture = value("texture")?
+            .map(|texture| ScenarioParser::normalize_texture_path(&texture))
+            .unwrap_or_else(|| DEFAULT_TEXTURE.to_string());
+        let kind = value("type")?.unwrap_or_else(|| "planet".to_string());
+
+        let mut body_type = body_type_for(&kind, radius, texture)?;
+        match &mut body_type {
+            BodyType::Star { temperature, .. } => {
+                if let Some(value) = value("temperature")? {
+                    *temperature = value.parse()?;
+                }
+            }
+            BodyType::PlanetAtmo { atmo_color, .. } => {
+                if let Some(line) = field("atmo_color") {
+                    *atmo_color = ScenarioParser::parse_color4(line)?;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(CatalogEntry {
+            id: value("id")?,
+            name,
+            mass,
+            body_type,
+            orbit_color: field("orbit_color")
+                .map(ScenarioParser::parse_color4)
+                .transpose()?
+                .unwrap_or(DEFAULT_ORBIT_COLOR),
+            rotation_params: field("rotation")
+                .map(ScenarioParser::parse_rotation)
+                .transpose()?
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// Builds a scenario from Horizons exports
+#[derive(Debug, Clone)]
+pub struct HorizonsImporter {
+    catalog: BodyCatalog,
+    epoch: Option<f64>,
+}
+
+impl Default for HorizonsImporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HorizonsImporter {
+    /// Importer using the built-in catalog, at the first epoch of the first export
+    pub fn new() -> Self {
+        Self {
+            catalog: BodyCatalog::builtin(),
+            epoch: None,
+        }
+    }
+
+    /// Add or override catalog entries from a sidecar file's contents
+    pub fn with_sidecar(mut self, content: &str) -> AstrariaResult<Self> {
+        for entry in BodyCatalog::parse_sidecar(content)? {
+            self.catalog.insert(entry);
+        }
+        Ok(self)
+    }
+
+    /// Use the records at this Julian date (TDB) instead of the first ones
+    pub fn with_epoch(mut self, julian_date: f64) -> Self {
+        self.epoch = Some(julian_date);
+        self
+    }
+
+    pub fn catalog(&self) -> &BodyCatalog {
+        &self.catalog
+    }
+
+    /// Combine the contents of several exports into one scenario.
+    ///
+    /// Exports may use different centres as long as each centre is either a shared
+    /// origin or another imported target (e.g. the Moon relative to Earth with Earth
+    /// relative to the Sun); every body ends up relative to the shared origin, which
+    /// is added at rest at zero if the catalog knows it.
+    pub fn import(&self, exports: &[&str]) -> AstrariaResult<Scenario> {
+        let ephemerides = exports
+            .iter()
+            .map(|content| HorizonsEphemeris::parse(content))
+            .collect::<AstrariaResult<Vec<_>>>()?;
+        self.import_ephemerides(&ephemerides)
+    }
+
+    /// Read and combine saved export files
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn import_files<P: AsRef<Path>>(&self, paths: &[P]) -> AstrariaResult<Scenario> {
+        let contents = paths
+            .iter()
+            .map(|path| {
+                std::fs::read_to_string(path).map_err(|e| {
+                    AstrariaError::AssetLoading(format!(
+                        "Failed to read Horizons export '{}': {}",
+                        path.as_ref().display(),
+                        e
+                    ))
+                })
+            })
+            .collect::<AstrariaResult<Vec<_>>>()?;
+        let exports: Vec<&str> = contents.iter().map(String::as_str).collect();
+        self.import(&exports)
+    }
+
+    pub fn import_ephemerides(
+        &self,
+        ephemerides: &[HorizonsEphemeris],
+    ) -> AstrariaResult<Scenario> {
+        let Some(first) = ephemerides.first() else {
+            return Err(AstrariaError::ParseError(
+                "No Horizons exports to import".to_string(),
+            ));
+        };
+        let epoch = self.epoch.unwrap_or_else(|| first.records[0].julian_date);
+
+        let mut states = Vec::with_capacity(ephemerides.len());
+        for ephemeris in ephemerides {
+            let record = ephemeris.record_at(Some(epoch)).ok_or_else(|| {
+                AstrariaError::ParseError(format!(
+                    "Horizons export for '{}' has no record at JD {}",
+                    ephemeris.target_name, epoch
+                ))
+            })?;
+            states.push(*record);
+        }
+
+        // Walk each body's chain of centres up to an origin that is not itself imported
+        let mut bodies = Vec::with_capacity(ephemerides.len());
+        let mut origin: Option<&HorizonsEphemeris> = None;
+        for (index, ephemeris) in ephemerides.iter().enumerate() {
+            let mut position = states[index].position;
+            let mut velocity = states[index].velocity;
+            let mut root = ephemeris;
+            let mut depth = 0;
+            while let Some(parent) = ephemerides
+                .iter()
+                .position(|other| other.target_key() == root.center_key())
+            {
+                depth += 1;
+                if depth > ephemerides.len() {
+                    return Err(AstrariaError::ParseError(format!(
+                        "Circular centres in Horizons exports involving '{}'",
+                        ephemeris.target_name
+                    )));
+                }
+                position += states[parent].position;
+                velocity += states[parent].velocity;
+                root = &ephemerides[parent];
+            }
+            match origin {
+                None => origin = Some(root),
+                Some(existing) if existing.center_key() != root.center_key() => {
+                    return Err(AstrariaError::ParseError(format!(
+                        "Horizons exports use different centres ('{}' and '{}'); export them relative to the same body",
+                        existing.center_key(),
+                        root.center_key()
+                    )));
+                }
+                Some(_) => {}
+            }
+
+            let entry = self
+                .catalog
+                .lookup(ephemeris.target_id.as_deref(), &ephemeris.target_name)
+                .ok_or_else(|| {
+                    AstrariaError::ParseError(format!(
+                        "No mass or radius known for '{}' ({}); add it to a sidecar file",
+                        ephemeris.target_name,
+                        ephemeris.target_id.as_deref().unwrap_or("no id")
+                    ))
+                })?;
+            bodies.push(ScenarioBody {
+                name: entry.name.clone(),
+                mass: entry.mass,
+                position,
+                velocity,
+                body_type: entry.body_type.clone(),
+                orbit_color: entry.orbit_color,
+                rotation_params: entry.rotation_params,
+                rotation_mode: RotationMode::Fixed,
+            });
+        }
+
+        let origin = origin.expect("at least one export");
+        let entry = self
+            .catalog
+            .lookup(origin.center_id.as_deref(), &origin.center_name)
+            .ok_or_else(|| {
+                AstrariaError::ParseError(format!(
+                    "No mass or radius known for the shared centre '{}' ({}); add it to a sidecar file",
+                    origin.center_name,
+                    origin.center_id.as_deref().unwrap_or("no id")
+                ))
+            })?;
+        bodies.insert(
+            0,
+            ScenarioBody {
+                name: entry.name.clone(),
+                mass: entry.mass,
+                position: DVec3::ZERO,
+                velocity: DVec3::ZERO,
+                body_type: entry.body_type.clone(),
+                orbit_color: entry.orbit_color,
+                rotation_params: entry.rotation_params,
+                rotation_mode: RotationMode::Fixed,
+            },
+        );
+
+        log::info!(
+            "Imported {} bodies from Horizons at JD {} relative to {}",
+            bodies.len(),
+            epoch,
+            entry.name
+        );
+        Ok(Scenario {
+            bodies,
+            barycentric: None,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EARTH_EXPORT: &str = r#"
+*******************************************************************************
+Ephemeris / WWW_USER Fri Jan  3 10:00:00 2025 Pasadena, USA      / Horizons
+*******************************************************************************
+Target body name: Earth (399)                     {source: DE441}
+Center body name: Sun (10)                        {source: DE441}
+Center-site name: BODY CENTER
+*******************************************************************************
+Start time      : A.D. 2000-Jan-01 12:00:00.0000 TDB
+Stop  time      : A.D. 2000-Jan-02 12:00:00.0000 TDB
+Step-size       : 1440 minutes
+*******************************************************************************
+Reference frame : ICRF
+Coordinate systm: Ecliptic of J2000.0
+Output units    : KM-S
+*******************************************************************************
+$$SOE
+2451545.000000000 = A.D. 2000-Jan-01 12:00:00.0000 TDB
+ X =-2.649903375682292E+07 Y = 1.446972967792532E+08 Z =-6.112214304122329E+02
+ VX=-2.979426006719171E+01 VY=-5.018052308143506E+00 VZ= 1.824517247440606E-04
+ LT= 4.907012574289680E+02 RG= 1.471038132232785E+08 RR=-3.318567046463627E-01
+2451546.000000000 = A.D. 2000-Jan-02 12:00:00.0000 TDB
+ X =-2.906586882304186E+07 Y = 1.442335159961963E+08 Z =-5.962624106334895E+02
+ VX=-2.962397893211087E+01 VY=-5.715718617779624E+00 VZ= 1.624419476226151E-04
+ LT= 4.907935853124236E+02 RG= 1.471329811412766E+08 RR=-3.297829163245154E-01
+$$EOE
+*******************************************************************************
+"#;
+
+    #[test]
+    fn test_parse_text_and_csv_exports() {
+        let earth = HorizonsEphemeris::parse(EARTH_EXPORT).unwrap();
+        assert_eq!(earth.target_name, "Earth");
+        assert_eq!(earth.target_id.as_deref(), Some("399"));
+        assert_eq!(earth.center_id.as_deref(), Some("10"));
+        assert_eq!(earth.reference_plane, ReferencePlane::Ecliptic);
+        assert_eq!(earth.records.len(), 2);
+        assert_eq!(earth.records[0].julian_date, 2451545.0);
+        assert!((earth.records[0].position.x - -2.649903375682292e10).abs() < 1.0);
+        assert!((earth.records[0].velocity.y - -5018.052308143506).abs() < 1e-6);
+
+        // Equatorial CSV export in AU and AU/day
+        let moon = r#"
+Target body name: Moon (301)                      {source: DE441}
+Center body name: Earth (399)                     {source: DE441}
+Reference frame : ICRF
+Coordinate systm: Earth Mean Equator and Equinox of Reference Epoch
+Output units    : AU-D
+$$SOE
+2451545.000000000, A.D. 2000-Jan-01 12:00:00.0000, -1.949E-03, -1.787E-04, -1.0E-04, 6.0E-06, -3.9E-04, -1.9E-04,
+$$EOE
+"#;
+        let moon = HorizonsEphemeris::parse(moon).unwrap();
+        assert_eq!(moon.reference_plane, ReferencePlane::Equator);
+        let record = moon.records[0];
+        let expected_x = -1.949e-3 * AU_TO_METERS;
+        assert!((record.position.x - expected_x).abs() < 1.0);
+        // Rotation about x keeps lengths
+        let equatorial = DVec3::new(-1.949e-3, -1.787e-4, -1.0e-4) * AU_TO_METERS;
+        assert!((record.position.length() - equatorial.length()).abs() < 1e-3);
+        assert!(
+            (record.velocity.length() * SECONDS_PER_DAY / AU_TO_METERS
+                - DVec3::new(6.0e-6, -3.9e-4, -1.9e-4).length())
+            .abs()
+                < 1e-12
+        );
+
+        assert!(HorizonsEphemeris::parse("no markers here").is_err());
+    }
+
+    #[test]
+    fn test_import_with_chained_centres_and_sidecar() {
+        let moon = r#"
+Target body name: Moon (301)
+Center body name: Earth (399)
+Output units    : KM-S
+$$SOE
+2451545.000000000 = A.D. 2000-Jan-01 12:00:00.0000 TDB
+ X = 3.0E+05 Y = 1.0E+05 Z = 0.0E+00
+ VX= 0.0E+00 VY= 1.0E+00 VZ= 0.0E+00
+$$EOE
+"#;
+        let ceres = r#"
+Target body name: 1 Ceres (A801 AA)
+Center body name: Sun (10)
+$$SOE
+2451545.000000000 = A.D. 2000-Jan-01 12:00:00.0000 TDB
+ X = 4.0E+08 Y = 0.0E+00 Z = 0.0E+00
+ VX= 0.0E+00 VY= 1.8E+01 VZ= 0.0E+00
+$$EOE
+"#;
+        let importer = HorizonsImporter::new();
+        assert!(importer.import(&[EARTH_EXPORT, moon, ceres]).is_err());
+
+        let sidecar = "# Dwarf planets\nname: Ceres\nid: A801 AA\nmass: 9.38e20\nradius: 469.7\n";
+        let scenario = importer
+            .with_sidecar(sidecar)
+            .unwrap()
+            .import(&[EARTH_EXPORT, moon, ceres])
+            .unwrap();
+
+        let names: Vec<&str> = scenario.bodies.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, ["Sun", "Earth", "Moon", "Ceres"]);
+        let (sun, earth, moon, ceres) = (
+            &scenario.bodies[0],
+            &scenario.bodies[1],
+            &scenario.bodies[2],
+            &scenario.bodies[3],
+        );
+        assert_eq!(sun.position, DVec3::ZERO);
+        assert_eq!(sun.velocity, DVec3::ZERO);
+        assert!(matches!(sun.body_type, BodyType::Star { .. }));
+        assert_eq!(earth.mass, 5.9723e24);
+        assert!((moon.position - earth.position - DVec3::new(3.0e8, 1.0e8, 0.0)).length() < 1e-3);
+        assert!((moon.velocity.y - earth.velocity.y - 1000.0).abs() < 1e-9);
+        assert_eq!(ceres.mass, 9.38e20);
+        assert!(matches!(ceres.body_type, BodyType::Planet { radius, .. } if radius == 469700.0));
+    }
+}
@@ -0,0 +1,682 @@
+/// Minor Planet Center orbit-file importer
+/// Reads locally saved MPCORB-format asteroid elements and the MPC comet element format,
+/// and turns them into low-mass heliocentric bodies at a chosen epoch
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+use glam::DVec3;
+
+use super::{BodyType, RotationMode, Scenario, ScenarioBody, horizons::DEFAULT_TEXTURE};
+use crate::{
+    AstrariaError, AstrariaResult,
+    math::{AU_TO_METERS, GRAVITATIONAL_CONSTANT, SOLAR_MASS, conic_state},
+};
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+const J2000: f64 = 2451545.0;
+
+/// Gaussian gravitational constant (deg/day), for mean motions missing from the file
+const GAUSSIAN_MEAN_MOTION: f64 = 0.985_607_668_6;
+
+/// Geometric albedo and bulk density assumed when sizing asteroids from their magnitude
+const ASTEROID_ALBEDO: f64 = 0.14;
+const ASTEROID_DENSITY: f64 = 2000.0;
+/// Asteroids without a magnitude get this radius (m)
+const DEFAULT_ASTEROID_RADIUS: f64 = 1000.0;
+
+/// Comet magnitudes include the coma, so nuclei get a fixed size instead
+const COMET_NUCLEUS_RADIUS: f64 = 2500.0;
+const COMET_DENSITY: f64 = 600.0;
+
+/// Dynamical class of a small body, from its heliocentric orbit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OrbitClass {
+    /// Orbit entirely inside Earth's (Q < 0.983 AU)
+    Atira,
+    /// Earth-crossing with a < 1 AU
+    Aten,
+    /// Earth-crossing with a > 1 AU
+    Apollo,
+    /// Approaching Earth from outside (1.017 < q < 1.3 AU)
+    Amor,
+    MarsCrosser,
+    Hungaria,
+    MainBelt,
+    /// 3:2 resonance with Jupiter
+    Hilda,
+    JupiterTrojan,
+    /// Between Jupiter and Neptune
+    Centaur,
+    /// Beyond Neptune, or unbound
+    TransNeptunian,
+    /// Anything read from the comet element format
+    Comet,
+}
+
+impl OrbitClass {
+    pub const ALL: [OrbitClass; 12] = [
+        OrbitClass::Atira,
+        OrbitClass::Aten,
+        OrbitClass::Apollo,
+        OrbitClass::Amor,
+        OrbitClass::MarsCrosser,
+        OrbitClass::Hungaria,
+        OrbitClass::MainBelt,
+        OrbitClass::Hilda,
+        OrbitClass::JupiterTrojan,
+        OrbitClass::Centaur,
+        OrbitClass::TransNeptunian,
+        OrbitClass::Comet,
+    ];
+
+    /// Classify an asteroid orbit (semi-major axis in AU, inclination in degrees)
+    pub fn classify(semi_major_axis: f64, eccentricity: f64, inclination: f64) -> Self {
+        if eccentricity >= 1.0 || semi_major_axis <= 0.0 {
+            return OrbitClass::TransNeptunian;
+        }
+        let a = semi_major_axis;
+        let perihelion = a * (1.0 - eccentricity);
+        let aphelion = a * (1.0 + eccentricity);
+        if a < 1.0 {
+            if aphelion < 0.983 {
+                OrbitClass::Atira
+            } else {
+                OrbitClass::Aten
+            }
+        } else if perihelion < 1.017 {
+            OrbitClass::Apollo
+        } else if perihelion < 1.3 {
+            OrbitClass::Amor
+        } else if (1.78..2.0).contains(&a)
+            && eccentricity < 0.18
+            && (16.0..34.0).contains(&inclination)
+        {
+            OrbitClass::Hungaria
+        } else if perihelion < 1.665 {
+            OrbitClass::MarsCrosser
+        } else if (3.7..4.2).contains(&a) {
+            OrbitClass::Hilda
+        } else if (5.05..5.35).contains(&a) {
+            OrbitClass::JupiterTrojan
+        } else if a < 5.05 {
+            OrbitClass::MainBelt
+        } else if a < 30.1 {
+            OrbitClass::Centaur
+        } else {
+            OrbitClass::TransNeptunian
+        }
+    }
+
+    /// Class for a name such as "apollo", "main-belt" or "Jupiter trojan"
+    pub fn from_name(name: &str) -> Option<Self> {
+        let key = |text: &str| {
+            text.chars()
+                .filter(char::is_ascii_alphanumeric)
+                .collect::<String>()
+                .to_ascii_lowercase()
+        };
+        Self::ALL
+            .into_iter()
+            .find(|class| key(class.label()) == key(name))
+    }
+
+    pub fn is_near_earth(self) -> bool {
+        matches!(
+            self,
+            OrbitClass::Atira | OrbitClass::Aten | OrbitClass::Apollo | OrbitClass::Amor
+        )
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OrbitClass::Atira => "Atira",
+            OrbitClass::Aten => "Aten",
+            OrbitClass::Apollo => "Apollo",
+            OrbitClass::Amor => "Amor",
+            OrbitClass::MarsCrosser => "Mars-crosser",
+            OrbitClass::Hungaria => "Hungaria",
+            OrbitClass::MainBelt => "Main belt",
+            OrbitClass::Hilda => "Hilda",
+            OrbitClass::JupiterTrojan => "Jupiter trojan",
+            OrbitClass::Centaur => "Centaur",
+            OrbitClass::TransNeptunian => "Trans-Neptunian",
+            OrbitClass::Comet => "Comet",
+        }
+    }
+
+    fn orbit_color(self) -> [f32; 4] {
+        match self {
+            OrbitClass::Atira | OrbitClass::Aten | OrbitClass::Apollo | OrbitClass::Amor => {
+                [1.0, 0.55, 0.2, 0.8]
+            }
+            OrbitClass::MarsCrosser => [0.9, 0.4, 0.3, 0.6],
+            OrbitClass::Hungaria | OrbitClass::MainBelt => [0.6, 0.6, 0.6, 0.5],
+            OrbitClass::Hilda | OrbitClass::JupiterTrojan => [0.7, 0.6, 0.3, 0.6],
+            OrbitClass::Centaur | OrbitClass::TransNeptunian => [0.4, 0.6, 0.9, 0.6],
+            OrbitClass::Comet => [0.5, 0.9, 1.0, 0.8],
+        }
+    }
+}
+
+/// Heliocentric elements of one asteroid or comet (J2000 ecliptic)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MpcOrbit {
+    /// Number in parentheses, provisional designation or comet designation
+    pub designation: String,
+    pub name: Option<String>,
+    /// Absolute magnitude (H for asteroids, total magnitude for comets)
+    pub magnitude: Option<f64>,
+    /// Perihelion distance (AU)
+    pub perihelion_distance: f64,
+    pub eccentricity: f64,
+    /// Degrees
+    pub inclination: f64,
+    pub longitude_of_node: f64,
+    pub argument_of_perihelion: f64,
+    /// Julian date (TT) of perihelion passage
+    pub perihelion_time: f64,
+    pub class: OrbitClass,
+}
+
+impl MpcOrbit {
+    /// Parse one line of an MPCORB or comet element file
+    pub fn parse(line: &str) -> AstrariaResult<Self> {
+        let century = column(line, 21, 21);
+        if matches!(century, "I" | "J" | "K") && !column(line, 93, 103).is_empty() {
+            Self::parse_asteroid(line)
+        } else if column(line, 15, 18).len() == 4
+            && column(line, 15, 18).bytes().all(|b| b.is_ascii_digit())
+        {
+            Self::parse_comet(line)
+        } else {
+            Err(AstrariaError::ParseError(format!(
+                "Not an MPC orbit line: '{}'",
+                line.trim()
+            )))
+        }
+    }
+
+    /// Parse every orbit in a file, skipping headers and lines that do not parse
+    pub fn parse_all(content: &str) -> Vec<Self> {
+        let mut orbits = Vec::new();
+        let mut skipped = 0;
+        for line in content.lines() {
+            // MPCORB.DAT starts with a free-form header ending in a dashed rule
+            if line.trim().is_empty() || line.starts_with("----") {
+                continue;
+            }
+            match Self::parse(line) {
+                Ok(orbit) => orbits.push(orbit),
+                Err(_) => skipped += 1,
+            }
+        }
+        if skipped > 0 {
+            log::debug!("Skipped {} lines that are not MPC orbits", skipped);
+        }
+        orbits
+    }
+
+    /// Name if the object has one, otherwise its designation
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.designation)
+    }
+
+    /// Semi-major axis (AU), `None` for parabolic and hyperbolic orbits
+    pub fn semi_major_axis(&self) -> Option<f64> {
+        (self.eccentricity < 1.0).then(|| self.perihelion_distance / (1.0 - self.eccentricity))
+    }
+
+    fn parse_asteroid(line: &str) -> AstrariaResult<Self> {
+        let packed = column(line, 1, 7);
+        let epoch = unpack_epoch(column(line, 21, 25))?;
+        let mean_anomaly = number(line, 27, 35, "mean anomaly")?;
+        let argument_of_perihelion = number(line, 38, 46, "argument of perihelion")?;
+        let longitude_of_node = number(line, 49, 57, "longitude of node")?;
+        let inclination = number(line, 60, 68, "inclination")?;
+        let eccentricity = number(line, 71, 79, "eccentricity")?;
+        let semi_major_axis = number(line, 93, 103, "semi-major axis")?;
+        let mean_motion = match column(line, 81, 91) {
+            "" => GAUSSIAN_MEAN_MOTION / semi_major_axis.powf(1.5),
+            _ => number(line, 81, 91, "mean motion")?,
+        };
+        if semi_major_axis <= 0.0 || !(0.0..1.0).contains(&eccentricity) || mean_motion <= 0.0 {
+            return Err(AstrariaError::ParseError(format!(
+                "Unbound or degenerate orbit for '{}'",
+                packed
+            )));
+        }
+
+        let (designation, name) = match column(line, 167, 194) {
+            "" => (unpack_designation(packed), None),
+            readable => split_readable(readable),
+        };
+        Ok(Self {
+            designation,
+            name,
+            magnitude: optional_number(line, 9, 13),
+            perihelion_distance: semi_major_axis * (1.0 - eccentricity),
+            eccentricity,
+            inclination,
+            longitude_of_node,
+            argument_of_perihelion,
+            perihelion_time: epoch - mean_anomaly / mean_motion,
+            class: OrbitClass::classify(semi_major_axis, eccentricity, inclination),
+        })
+    }
+
+    fn parse_comet(line: &str) -> AstrariaResult<Self> {
+        let year = number(line, 15, 18, "perihelion year")? as i32;
+        let month = number(line, 20, 21, "perihelion month")? as u32;
+        let day = number(line, 23, 29, "perihelion day")?;
+        if !(1..=12).contains(&month) {
+            return Err(AstrariaError::ParseError(format!(
+                "Invalid perihelion month {} in comet line",
+                month
+            )));
+        }
+
+        let designation = match column(line, 103, 158) {
+            "" => format!("{}{}", column(line, 1, 5), column(line, 6, 12)),
+            name => name.to_string(),
+        };
+        Ok(Self {
+            designation,
+            name: None,
+            magnitude: optional_number(line, 92, 95),
+            perihelion_distance: number(line, 31, 39, "perihelion distance")?,
+            eccentricity: number(line, 42, 49, "eccentricity")?,
+            inclination: number(line, 72, 79, "inclination")?,
+            longitude_of_node: number(line, 62, 69, "longitude of node")?,
+            argument_of_perihelion: number(line, 52, 59, "argument of perihelion")?,
+            perihelion_time: julian_date(year, month, day),
+            class: OrbitClass::Comet,
+        })
+    }
+
+    /// Estimated radius (m) and mass (kg)
+    fn physical_properties(&self) -> (f64, f64) {
+        let (radius, density) = match (self.class, self.magnitude) {
+            (OrbitClass::Comet, _) => (COMET_NUCLEUS_RADIUS, COMET_DENSITY),
+            (_, Some(magnitude)) => {
+                let diameter_km = 1329.0 / ASTEROID_ALBEDO.sqrt() * 10f64.powf(-magnitude / 5.0);
+                (diameter_km * 500.0, ASTEROID_DENSITY)
+            }
+            (_, None) => (DEFAULT_ASTEROID_RADIUS, ASTEROID_DENSITY),
+        };
+        let mass = density * 4.0 / 3.0 * std::f64::consts::PI * radius.powi(3);
+        (radius, mass)
+    }
+}
+
+/// Which orbits of a file to import
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MpcFilter {
+    /// Faintest absolute magnitude to keep; objects without one are dropped when set
+    pub max_magnitude: Option<f64>,
+    /// Classes to keep, all of them when empty
+    pub classes: Vec<OrbitClass>,
+    /// Keep at most this many orbits, in file order (MPCORB is sorted by number)
+    pub limit: Option<usize>,
+}
+
+impl MpcFilter {
+    pub fn accepts(&self, orbit: &MpcOrbit) -> bool {
+        let bright_enough = match self.max_magnitude {
+            Some(limit) => orbit.magnitude.is_some_and(|magnitude| magnitude <= limit),
+            None => true,
+        };
+        bright_enough && (self.classes.is_empty() || self.classes.contains(&orbit.class))
+    }
+}
+
+/// Builds small bodies around the Sun from MPC element files
+#[derive(Debug, Clone)]
+pub struct MpcImporter {
+    /// Julian date (TT) the bodies are placed at
+    epoch: f64,
+    filter: MpcFilter,
+}
+
+impl Default for MpcImporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MpcImporter {
+    /// Importer placing every orbit at J2000
+    pub fn new() -> Self {
+        Self {
+            epoch: J2000,
+            filter: MpcFilter::default(),
+        }
+    }
+
+    /// Place the bodies at this Julian date (TT), normally the scenario's epoch
+    pub fn with_epoch(mut self, julian_date: f64) -> Self {
+        self.epoch = julian_date;
+        self
+    }
+
+    pub fn with_filter(mut self, filter: MpcFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn epoch(&self) -> f64 {
+        self.epoch
+    }
+
+    pub fn filter(&self) -> &MpcFilter {
+        &self.filter
+    }
+
+    /// Orbits in a file that pass the filter
+    pub fn orbits(&self, content: &str) -> Vec<MpcOrbit> {
+        MpcOrbit::parse_all(content)
+            .into_iter()
+            .filter(|orbit| self.filter.accepts(orbit))
+            .take(self.filter.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+
+    /// Bodies relative to the Sun, which is assumed to have the standard solar mass
+    pub fn import(&self, content: &str) -> AstrariaResult<Vec<ScenarioBody>> {
+        self.import_around(content, SOLAR_MASS)
+    }
+
+    /// Read and import a saved element file
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn import_file(&self, path: impl AsRef<Path>) -> AstrariaResult<Vec<ScenarioBody>> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            AstrariaError::AssetLoading(format!(
+                "Failed to read MPC orbit file '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+        self.import(&content)
+    }
+
+    /// Add the orbits to a scenario around its Sun (the body named "Sun", else the first star).
+    /// Returns how many bodies were added.
+    pub fn add_to(&self, scenario: &mut Scenario, content: &str) -> AstrariaResult<usize> {
+        let sun = scenario
+            .bodies
+            .iter()
+            .find(|body| body.name.eq_ignore_ascii_case("Sun"))
+            .or_else(|| {
+                scenario
+                    .bodies
+                    .iter()
+                    .find(|body| matches!(body.body_type, BodyType::Star { .. }))
+            })
+            .ok_or_else(|| {
+                AstrariaError::ParseError(
+                    "Scenario has no Sun to place MPC orbits around".to_string(),
+                )
+            })?;
+        let (sun_mass, sun_position, sun_velocity) = (sun.mass, sun.position, sun.velocity);
+
+        let mut bodies = self.import_around(content, sun_mass)?;
+        for body in &mut bodies {
+            body.position += sun_position;
+            body.velocity += sun_velocity;
+        }
+        let count = bodies.len();
+        scenario.bodies.extend(bodies);
+        Ok(count)
+    }
+
+    fn import_around(&self, content: &str, sun_mass: f64) -> AstrariaResult<Vec<ScenarioBody>> {
+        let orbits = self.orbits(content);
+        if orbits.is_empty() {
+            return Err(AstrariaError::ParseError(
+                "No MPC orbits found that match the filter".to_string(),
+            ));
+        }
+
+        let mu = GRAVITATIONAL_CONSTANT * sun_mass;
+        let bodies: Vec<ScenarioBody> = orbits
+            .iter()
+            .map(|orbit| {
+                let (position, velocity) = self.state(orbit, mu);
+                let (radius, mass) = orbit.physical_properties();
+                ScenarioBody {
+                    name: orbit.display_name().to_string(),
+                    mass,
+                    position,
+                    velocity,
+                    body_type: match orbit.class {
+                        OrbitClass::Comet => BodyType::Comet {
+                            radius: radius as f32,
+                            texture_path: DEFAULT_TEXTURE.to_string(),
+                        },
+                        _ => BodyType::Planet {
+                            radius: radius as f32,
+                            texture_path: DEFAULT_TEXTURE.to_string(),
+                            rings: None,
+                        },
+                    },
+                    orbit_color: orbit.class.orbit_color(),
+                    rotation_params: (0.0, 0.0, 0.0, 0.0),
+                    rotation_mode: RotationMode::Fixed,
+                }
+            })
+            .collect();
+        log::info!("Imported {} MPC orbits at JD {}", bodies.len(), self.epoch);
+        Ok(bodies)
+    }
+
+    /// Heliocentric position (m) and velocity (m/s) at the importer's epoch
+    fn state(&self, orbit: &MpcOrbit, mu: f64) -> (DVec3, DVec3) {
+        conic_state(
+            orbit.perihelion_distance * AU_TO_METERS,
+            orbit.eccentricity,
+            orbit.inclination.to_radians(),
+            orbit.longitude_of_node.to_radians(),
+            orbit.argument_of_perihelion.to_radians(),
+            (self.epoch - orbit.perihelion_time) * SECONDS_PER_DAY,
+            mu,
+        )
+    }
+}
+
+/// Trimmed text of 1-indexed, inclusive columns, empty past the end of the line
+fn column(line: &str, first: usize, last: usize) -> &str {
+    let end = last.min(line.len());
+    line.get(first - 1..end).map(str::trim).unwrap_or("")
+}
+
+fn number(line: &str, first: usize, last: usize, what: &str) -> AstrariaResult<f64> {
+    let text = column(line, first, last);
+    text.parse().map_err(|_| {
+        AstrariaError::ParseError(format!(
+            "Invalid {} '{}' in columns {}-{}",
+            what, text, first, last
+        ))
+    })
+}
+
+fn optional_number(line: &str, first: usize, last: usize) -> Option<f64> {
+    column(line, first, last).parse().ok()
+}
+
+/// Value of one packed digit: 0-9, A-Z for 10-35, a-z for 36-61
+fn packed_digit(c: char) -> Option<u32> {
+    match c {
+        '0'..='9' => Some(c as u32 - '0' as u32),
+        'A'..='Z' => Some(c as u32 - 'A' as u32 + 10),
+        'a'..='z' => Some(c as u32 - 'a' as u32 + 36),
+        _ => None,
+    }
+}
+
+/// Packed epoch such as "K2555" (2025 May 5) to a Julian date at 0h TT
+fn unpack_epoch(packed: &str) -> AstrariaResult<f64> {
+    let invalid = || AstrariaError::ParseError(format!("Invalid packed epoch '{}'", packed));
+    let chars: Vec<char> = packed.chars().collect();
+    if chars.len() != 5 {
+        return Err(invalid());
+    }
+    let century = packed_digit(chars[0]).filter(|c| (18..=20).contains(c));
+    let year: Option<u32> = packed[1..3].parse().ok();
+    let month = packed_digit(chars[3]).filter(|m| (1..=12).contains(m));
+    let day = packed_digit(chars[4]).filter(|d| (1..=31).contains(d));
+    match (century, year, month, day) {
+        (Some(century), Some(year), Some(month), Some(day)) => Ok(julian_date(
+            (century * 100 + year) as i32,
+            month,
+            day as f64,
+        )),
+        _ => Err(invalid()),
+    }
+}
+
+/// Readable form of a packed designation, e.g. "00433" to "(433)" and "K24A00B" to "2024 AB"
+fn unpack_designation(packed: &str) -> String {
+    let chars: Vec<char> = packed.chars().collect();
+    let number = match chars.as_slice() {
+        ['~', rest @ ..] if rest.len() == 4 => rest
+            .iter()
+            .try_fold(0, |value, &c| packed_digit(c).map(|d| value * 62 + d))
+            .map(|value| value + 620_000),
+        [first, rest @ ..] if rest.len() == 4 && rest.iter().all(char::is_ascii_digit) => {
+            packed_digit(*first)
+                .zip(packed[1..].parse::<u32>().ok())
+                .map(|(high, low)| high * 10_000 + low)
+        }
+        _ => None,
+    };
+    if let Some(number) = number {
+        return format!("({})", number);
+    }
+
+    if let [century, y1, y2, half_month, cycle_high, cycle_low, letter] = chars.as_slice()
+        && let (Some(century @ 18..=20), Some(cycle_high), Some(cycle_low)) = (
+            packed_digit(*century),
+            packed_digit(*cycle_high),
+            cycle_low.to_digit(10),
+        )
+        && y1.is_ascii_digit()
+        && y2.is_ascii_digit()
+    {
+        let cycle = cycle_high * 10 + cycle_low;
+        let cycle = if cycle > 0 {
+            cycle.to_string()
+        } else {
+            String::new()
+        };
+        return format!("{}{}{} {}{}{}", century, y1, y2, half_month, letter, cycle);
+    }
+    packed.to_string()
+}
+
+/// Split MPCORB's readable designation, e.g. "(1) Ceres", into designation and name
+fn split_readable(readable: &str) -> (String, Option<String>) {
+    if readable.starts_with('(')
+        && let Some((number, name)) = readable.split_once(") ")
+    {
+        return (format!("{})", number), Some(name.trim().to_string()));
+    }
+    (readable.to_string(), None)
+}
+
+/// Julian date of a calendar date (Meeus, Gregorian calendar)
+fn julian_date(year: i32, month: u32, day: f64) -> f64 {
+    let (year, month) = if month <= 2 {
+        (year - 1, month + 12)
+    } else {
+        (year, month)
+    };
+    let century = (year as f64 / 100.0).floor();
+    let correction = 2.0 - century + (century / 4.0).floor();
+    (365.25 * (year as f64 + 4716.0)).floor()
+        + (30.6001 * (month as f64 + 1.0)).floor()
+        + day
+        + correction
+        - 1524.5
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MPCORB: &str = "\
+Des'n     H     G   Epoch     M        Peri.      Node       Incl.       e            n           a        Reference #Obs #Opp    Arc    rms  Perts   Computer
+----------------------------------------------------------------------------------------------------------------------------------------------------------------
+00001    3.34  0.15 K2555 188.70269   73.27343   80.25221   10.58780  0.0794013  0.21424651   2.7660512  0 E2024-V47  7330 125 1801-2024 0.80 M-v 30k MPCLINUX   0000 (1) Ceres                   20241101
+00433   10.38  0.46 K2555 310.55432  178.92706  304.27656   10.82773  0.2228359  0.55988651   1.4580404  0 E2024-V47  7330 125 1801-2024 0.80 M-v 30k MPCLINUX   2804 (433) Eros                  20241101
+";
+
+    const COMETS: &str = "\
+0001P         1986 02  9.4589  0.585978  0.967143  111.8657   58.8601  162.2422  19860219   5.5  8.0  1P/Halley                                                98, 1083
+";
+
+    #[test]
+    fn test_parses_asteroid_and_comet_lines() {
+        let orbits = MpcOrbit::parse_all(MPCORB);
+        assert_eq!(orbits.len(), 2);
+        let ceres = &orbits[0];
+        assert_eq!(ceres.designation, "(1)");
+        assert_eq!(ceres.display_name(), "Ceres");
+        assert_eq!(ceres.magnitude, Some(3.34));
+        assert_eq!(ceres.class, OrbitClass::MainBelt);
+        assert!((ceres.semi_major_axis().unwrap() - 2.7660512).abs() < 1e-9);
+        // Epoch 2025 May 5 is JD 2460800.5, and M/n days before it
+        let expected = 2460800.5 - 188.70269 / 0.21424651;
+        assert!((ceres.perihelion_time - expected).abs() < 1e-9);
+        assert_eq!(orbits[1].class, OrbitClass::Amor);
+
+        let halley = MpcOrbit::parse(COMETS.lines().next().unwrap()).unwrap();
+        assert_eq!(halley.designation, "1P/Halley");
+        assert_eq!(halley.class, OrbitClass::Comet);
+        assert!((halley.perihelion_time - 2446470.9589).abs() < 1e-6);
+
+        assert_eq!(
+            OrbitClass::from_name("main-belt"),
+            Some(OrbitClass::MainBelt)
+        );
+        assert_eq!(
+            OrbitClass::from_name("jupiter_trojan"),
+            Some(OrbitClass::JupiterTrojan)
+        );
+        assert_eq!(OrbitClass::from_name("NEO"), None);
+
+        assert_eq!(unpack_designation("K24A00B"), "2024 AB");
+        assert_eq!(unpack_designation("J95X12A"), "1995 XA12");
+        assert_eq!(unpack_designation("A0345"), "(100345)");
+    }
+
+    #[test]
+    fn test_import_places_bodies_on_their_orbits() {
+        let filter = MpcFilter {
+            classes: vec![OrbitClass::Amor, OrbitClass::Comet],
+            max_magnitude: Some(12.0),
+            limit: None,
+        };
+        let importer = MpcImporter::new().with_epoch(2460800.5).with_filter(filter);
+        let content = format!("{}{}", MPCORB, COMETS);
+        let bodies = importer.import(&content).unwrap();
+        let names: Vec<&str> = bodies.iter().map(|body| body.name.as_str()).collect();
+        assert_eq!(names, ["Eros", "1P/Halley"]);
+
+        // Eros sits between perihelion and aphelion with the vis-viva speed
+        let eros = &bodies[0];
+        let a = 1.4580404 * AU_TO_METERS;
+        let r = eros.position.length();
+        assert!(r > a * (1.0 - 0.2228359) && r < a * (1.0 + 0.2228359));
+        let mu = GRAVITATIONAL_CONSTANT * SOLAR_MASS;
+        let speed = (mu * (2.0 / r - 1.0 / a)).sqrt();
+        assert!((eros.velocity.length() - speed).abs() / speed < 1e-9);
+        assert!(eros.mass > 1e15 && eros.mass < 1e17);
+
+        let mut scenario = Scenario {
+            bodies: Vec::new(),
+            barycentric: None,
+            ..Default::default()
+        };
+        assert!(importer.add_to(&mut scenario, &content).is_err());
+    }
+}
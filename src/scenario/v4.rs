@@ -0,0 +1,659 @@
+/// Version 4 scenario files: a RON document read and written with serde
+/// Fields can come in any order and most of them have defaults, unlike the line-ordered v3 format
+use glam::DVec3;
+use ron::{extensions::Extensions, ser::PrettyConfig};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    BodyType, CameraBookmark, IntegratorSettings, Rings, RotationMode, Scenario, ScenarioBody,
+    ScenarioInclude, ScenarioMetadata, ScenarioParser, ScenarioWriter, horizons::DEFAULT_TEXTURE,
+};
+use crate::{
+    AstrariaError, AstrariaResult,
+    math::{AU_TO_METERS, EARTH_MASS, JUPITER_MASS, Maneuver, SECONDS_PER_DAY, SOLAR_MASS},
+};
+
+pub const VERSION: u32 = 4;
+
+const DEFAULT_STAR_TEXTURE: &str = "Planet Textures/2k_sun.jpg";
+const DEFAULT_ORBIT_COLOR: [f32; 4] = [0.6, 0.6, 0.6, 0.8];
+const DEFAULT_BLACK_HOLE_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+const DEFAULT_TEMPERATURE: f32 = 5778.0;
+const DEFAULT_ATMOSPHERE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LengthUnit {
+    #[default]
+    Meters,
+    Kilometers,
+    AstronomicalUnits,
+}
+
+impl LengthUnit {
+    fn meters(self) -> f64 {
+        match self {
+            Self::Meters => 1.0,
+            Self::Kilometers => 1000.0,
+            Self::AstronomicalUnits => AU_TO_METERS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum VelocityUnit {
+    #[default]
+    MetersPerSecond,
+    KilometersPerSecond,
+    AstronomicalUnitsPerDay,
+}
+
+impl VelocityUnit {
+    fn meters_per_second(self) -> f64 {
+        match self {
+            Self::MetersPerSecond => 1.0,
+            Self::KilometersPerSecond => 1000.0,
+            Self::AstronomicalUnitsPerDay => AU_TO_METERS / SECONDS_PER_DAY,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MassUnit {
+    #[default]
+    Kilograms,
+    EarthMasses,
+    JupiterMasses,
+    SolarMasses,
+}
+
+impl MassUnit {
+    fn kilograms(self) -> f64 {
+        match self {
+            Self::Kilograms => 1.0,
+            Self::EarthMasses => EARTH_MASS,
+            Self::JupiterMasses => JUPITER_MASS,
+            Self::SolarMasses => SOLAR_MASS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AngleUnit {
+    #[default]
+    Degrees,
+    Radians,
+}
+
+impl AngleUnit {
+    /// Converted in f32 like the v3 parser, so angles written by `from_scenario` read back exactly
+    fn to_radians(self, value: f64) -> f32 {
+        match self {
+            Self::Degrees => (value as f32).to_radians(),
+            Self::Radians => value as f32,
+        }
+    }
+}
+
+/// Units used by every quantity in the file; times are always seconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Units {
+    /// Positions, camera offsets and distances
+    pub length: LengthUnit,
+    pub velocity: VelocityUnit,
+    pub mass: MassUnit,
+    /// Body radii, kilometers unless set
+    pub radius: LengthUnit,
+    /// Rotation tilt, right ascension, rate (per day) and offset
+    pub angle: AngleUnit,
+}
+
+impl Default for Units {
+    fn default() -> Self {
+        Self {
+            length: LengthUnit::Meters,
+            velocity: VelocityUnit::MetersPerSecond,
+            mass: MassUnit::Kilograms,
+            radius: LengthUnit::Kilometers,
+            angle: AngleUnit::Degrees,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BodyKind {
+    #[default]
+    Planet,
+    Star,
+    PlanetAtmo,
+    BlackHole,
+    Spacecraft,
+    Comet,
+}
+
+/// Axis orientation and spin, in the file's angle unit
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RotationEntry {
+    pub tilt: f64,
+    pub right_ascension: f64,
+    /// Rotation per day
+    pub rate: f64,
+    pub offset: f64,
+}
+
+/// Ring system of a planet; radii from the body's centre in the file's radius unit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RingEntry {
+    pub inner_radius: f64,
+    pub outer_radius: f64,
+    pub texture: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManeuverEntry {
+    /// Simulation time of the burn in seconds
+    pub time: f64,
+    /// Name of the body the burn directions are measured against
+    pub reference: String,
+    /// Delta-v components (m/s)
+    #[serde(default)]
+    pub prograde: f64,
+    #[serde(default)]
+    pub normal: f64,
+    #[serde(default)]
+    pub radial: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BodyEntry {
+    pub name: String,
+    #[serde(default)]
+    pub kind: BodyKind,
+    pub mass: f64,
+    #[serde(default)]
+    pub radius: f64,
+    #[serde(default)]
+    pub position: [f64; 3],
+    #[serde(default)]
+    pub velocity: [f64; 3],
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub texture: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub orbit_color: Option<[f32; 4]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<RotationEntry>,
+    /// Body this one is tidally locked to, keeping the same face towards it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locked_to: Option<String>,
+    /// Surface temperature of stars (K)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub atmosphere_color: Option<[f32; 4]>,
+    /// Night-side texture of planets with an atmosphere
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ambient_texture: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rings: Option<RingEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub maneuvers: Vec<ManeuverEntry>,
+}
+
+/// A named camera placement; `offset` and `distance` are in the file's length unit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CameraEntry {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub focus: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<[f64; 3]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance: Option<f64>,
+}
+
+/// Another scenario file whose bodies are listed before this file's own;
+/// `position` and `velocity` are offsets in the including file's units
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IncludeEntry {
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<[f64; 3]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub velocity: Option<[f64; 3]>,
+}
+
+/// The whole v4 document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename = "Scenario")]
+pub struct ScenarioFile {
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Julian date (TDB) at simulation time zero
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<f64>,
+    #[serde(default)]
+    pub units: Units,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub barycentric: Option<bool>,
+    #[serde(default)]
+    pub integrator: IntegratorSettings,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cameras: Vec<CameraEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub includes: Vec<IncludeEntry>,
+    #[serde(default)]
+    pub bodies: Vec<BodyEntry>,
+}
+
+impl ScenarioFile {
+    fn options() -> ron::Options {
+        ron::Options::default().with_default_extension(Extensions::IMPLICIT_SOME)
+    }
+
+    /// Read a v4 document; optional fields may be written without `Some(...)`
+    pub fn parse(content: &str) -> AstrariaResult<Self> {
+        let file = Self::from_ron(content)
+            .map_err(|e| AstrariaError::ParseError(format!("Invalid v4 scenario at {}", e)))?;
+        if file.version != VERSION {
+            return Err(AstrariaError::ParseError(format!(
+                "Unsupported scenario version {}, expected {}",
+                file.version, VERSION
+            )));
+        }
+        Ok(file)
+    }
+
+    /// Deserialize without checking the version, keeping the error's position
+    pub(super) fn from_ron(content: &str) -> Result<Self, ron::error::SpannedError> {
+        Self::options().from_str(content)
+    }
+
+    pub fn to_ron(&self) -> AstrariaResult<String> {
+        Self::options()
+            .to_string_pretty(self, PrettyConfig::new())
+            .map_err(|e| AstrariaError::ParseError(format!("Failed to write scenario: {}", e)))
+    }
+
+    /// Convert to SI units and fill in the defaults
+    pub fn into_scenario(self) -> AstrariaResult<Scenario> {
+        let units = self.units;
+        let length = units.length.meters();
+        let vector = |v: [f64; 3], scale: f64| DVec3::from_array(v) * scale;
+
+        let bodies = self
+            .bodies
+            .into_iter()
+            .map(|body| Self::body_from_entry(body, &units))
+            .collect::<AstrariaResult<Vec<_>>>()?;
+
+        let cameras = self
+            .cameras
+            .into_iter()
+            .map(|camera| CameraBookmark {
+                name: camera.name,
+                focus: camera.focus,
+                offset: camera.offset.map(|offset| vector(offset, length)),
+                distance: camera.distance.map(|distance| distance * length),
+            })
+            .collect();
+
+        let includes = self
+            .includes
+            .into_iter()
+            .map(|include| ScenarioInclude {
+                path: include.path,
+                prefix: include.prefix,
+                position_offset: include.position.map_or(DVec3::ZERO, |p| vector(p, length)),
+                velocity_offset: include.velocity.map_or(DVec3::ZERO, |v| {
+                    vector(v, units.velocity.meters_per_second())
+                }),
+                index: 0,
+            })
+            .collect();
+
+        Ok(Scenario {
+            bodies,
+            barycentric: self.barycentric,
+            includes,
+            metadata: ScenarioMetadata {
+                name: self.name,
+                description: self.description,
+                epoch: self.epoch,
+                cameras,
+            },
+            integrator: self.integrator,
+        })
+    }
+
+    fn body_from_entry(entry: BodyEntry, units: &Units) -> AstrariaResult<ScenarioBody> {
+        if !(entry.mass.is_finite() && entry.mass >= 0.0) {
+            return Err(AstrariaError::ParseError(format!(
+                "Body '{}' has invalid mass {}",
+                entry.name, entry.mass
+            )));
+        }
+        let radius = entry.radius as f32 * units.radius.meters() as f32;
+        let texture = |default: &str| {
+            ScenarioParser::normalize_texture_path(entry.texture.as_deref().unwrap_or(default))
+        };
+        let rings = match &entry.rings {
+            Some(rings)
+                if !(rings.inner_radius >= 0.0 && rings.outer_radius > rings.inner_radius) =>
+            {
+                return Err(AstrariaError::ParseError(format!(
+                    "Body '{}' has ring radii {} to {} that are not increasing",
+                    entry.name, rings.inner_radius, rings.outer_radius
+                )));
+            }
+            Some(rings) => Some(Rings {
+                inner_radius: (rings.inner_radius * units.radius.meters()) as f32,
+                outer_radius: (rings.outer_radius * units.radius.meters()) as f32,
+                texture_path: ScenarioParser::normalize_texture_path(&rings.texture),
+            }),
+            None => None,
+        };
+        let body_type = match entry.kind {
+            BodyKind::Planet => BodyType::Planet {
+                radius,
+                texture_path: texture(DEFAULT_TEXTURE),
+                rings,
+            },
+            BodyKind::Star => BodyType::Star {
+                radius,
+                texture_path: texture(DEFAULT_STAR_TEXTURE),
+                temperature: entry.temperature.unwrap_or(DEFAULT_TEMPERATURE),
+            },
+            BodyKind::PlanetAtmo => BodyType::PlanetAtmo {
+                radius,
+                texture_path: texture(DEFAULT_TEXTURE),
+                atmo_color: entry.atmosphere_color.unwrap_or(DEFAULT_ATMOSPHERE_COLOR),
+                ambient_texture: entry
+                    .ambient_texture
+                    .as_deref()
+                    .map(ScenarioParser::normalize_texture_path),
+                rings,
+            },
+            BodyKind::BlackHole => BodyType::BlackHole { radius },
+            BodyKind::Comet => BodyType::Comet {
+                radius,
+                texture_path: texture(DEFAULT_TEXTURE),
+            },
+            BodyKind::Spacecraft => BodyType::Spacecraft {
+                radius,
+                maneuvers: entry
+                    .maneuvers
+                    .iter()
+                    .map(|maneuver| {
+                        Maneuver::new(
+                            maneuver.time,
+                            maneuver.reference.clone(),
+                            maneuver.prograde,
+                            maneuver.normal,
+                            maneuver.radial,
+                        )
+                    })
+                    .collect(),
+            },
+        };
+        let default_color = match entry.kind {
+            BodyKind::BlackHole => DEFAULT_BLACK_HOLE_COLOR,
+            _ => DEFAULT_ORBIT_COLOR,
+        };
+        let rotation = entry.rotation.unwrap_or_default();
+        let angle = |value: f64| units.angle.to_radians(value);
+
+        Ok(ScenarioBody {
+            name: entry.name,
+            mass: entry.mass * units.mass.kilograms(),
+            position: DVec3::from_array(entry.position) * units.length.meters(),
+            velocity: DVec3::from_array(entry.velocity) * units.velocity.meters_per_second(),
+            body_type,
+            orbit_color: entry.orbit_color.unwrap_or(default_color),
+            rotation_params: (
+                angle(rotation.tilt),
+                angle(rotation.right_ascension),
+                angle(rotation.rate),
+                angle(rotation.offset),
+            ),
+            rotation_mode: match entry.locked_to {
+                Some(parent) => RotationMode::TidallyLocked { parent },
+                None => RotationMode::Fixed,
+            },
+        })
+    }
+
+    /// Describe a scenario in default units (SI, radii in km, angles in degrees).
+    /// Every field is written out, so reading the result gives back the same scenario.
+    pub fn from_scenario(scenario: &Scenario) -> Self {
+        let units = Units::default();
+        let metadata = &scenario.metadata;
+        let length = units.length.meters();
+
+        let cameras = metadata
+            .cameras
+            .iter()
+            .map(|camera| CameraEntry {
+                name: camera.name.clone(),
+                focus: camera.focus.clone(),
+                offset: camera.offset.map(|offset| (offset / length).to_array()),
+                distance: camera.distance.map(|distance| distance / length),
+            })
+            .collect();
+
+        let offset = |vector: DVec3, scale: f64| {
+            (vector != DVec3::ZERO).then(|| (vector / scale).to_array())
+        };
+        let includes = scenario
+            .includes
+            .iter()
+            .map(|include| IncludeEntry {
+                path: include.path.clone(),
+                prefix: include.prefix.clone(),
+                position: offset(include.position_offset, length),
+                velocity: offset(include.velocity_offset, units.velocity.meters_per_second()),
+            })
+            .collect();
+
+        Self {
+            version: VERSION,
+            name: metadata.name.clone(),
+            description: metadata.description.clone(),
+            epoch: metadata.epoch,
+            units,
+            barycentric: scenario.barycentric,
+            integrator: scenario.integrator,
+            cameras,
+            includes,
+            bodies: scenario.bodies.iter().map(Self::entry_from_body).collect(),
+        }
+    }
+
+    /// Body in the default units, with radii and angles rounded the way the v3 writer does
+    fn entry_from_body(body: &ScenarioBody) -> BodyEntry {
+        let (tilt, right_ascension, rate, offset) = body.rotation_params;
+        let degrees = |radians: f32| {
+            decimal(ScenarioWriter::invert(
+                radians,
+                radians.to_degrees(),
+                f32::to_radians,
+            ))
+        };
+        let mut entry = BodyEntry {
+            name: body.name.clone(),
+            kind: BodyKind::Planet,
+            mass: body.mass,
+            radius: 0.0,
+            position: body.position.to_array(),
+            velocity: body.velocity.to_array(),
+            texture: None,
+            orbit_color: Some(body.orbit_color),
+            rotation: Some(RotationEntry {
+                tilt: degrees(tilt),
+                right_ascension: degrees(right_ascension),
+                rate: degrees(rate),
+                offset: degrees(offset),
+            }),
+            locked_to: match &body.rotation_mode {
+                RotationMode::TidallyLocked { parent } => Some(parent.clone()),
+                RotationMode::Fixed => None,
+            },
+            temperature: None,
+            atmosphere_color: None,
+            ambient_texture: None,
+            rings: None,
+            maneuvers: Vec::new(),
+        };
+        let radius = match &body.body_type {
+            BodyType::Planet {
+                radius,
+                texture_path,
+                rings,
+            } => {
+                entry.texture = Some(texture_path.clone());
+                entry.rings = rings.as_ref().map(Self::ring_entry);
+                radius
+            }
+            BodyType::Star {
+                radius,
+                texture_path,
+                temperature,
+            } => {
+                entry.kind = BodyKind::Star;
+                entry.texture = Some(texture_path.clone());
+                entry.temperature = Some(*temperature);
+                radius
+            }
+            BodyType::PlanetAtmo {
+                radius,
+                texture_path,
+                atmo_color,
+                ambient_texture,
+                rings,
+            } => {
+                entry.kind = BodyKind::PlanetAtmo;
+                entry.texture = Some(texture_path.clone());
+                entry.atmosphere_color = Some(*atmo_color);
+                entry.ambient_texture = ambient_texture.clone();
+                entry.rings = rings.as_ref().map(Self::ring_entry);
+                radius
+            }
+            BodyType::BlackHole { radius } => {
+                entry.kind = BodyKind::BlackHole;
+                radius
+            }
+            BodyType::Spacecraft { radius, maneuvers } => {
+                entry.kind = BodyKind::Spacecraft;
+                entry.maneuvers = maneuvers
+                    .iter()
+                    .filter(|maneuver| !maneuver.executed)
+                    .map(|maneuver| ManeuverEntry {
+                        time: maneuver.time,
+                        reference: maneuver.reference_body.clone(),
+                        prograde: maneuver.prograde,
+                        normal: maneuver.normal,
+                        radial: maneuver.radial,
+                    })
+                    .collect();
+                radius
+            }
+            BodyType::Comet {
+                radius,
+                texture_path,
+            } => {
+                entry.kind = BodyKind::Comet;
+                entry.texture = Some(texture_path.clone());
+                radius
+            }
+        };
+        entry.radius = decimal(ScenarioWriter::meters_to_km(*radius));
+        entry
+    }
+
+    fn ring_entry(rings: &Rings) -> RingEntry {
+        RingEntry {
+            inner_radius: decimal(ScenarioWriter::meters_to_km(rings.inner_radius)),
+            outer_radius: decimal(ScenarioWriter::meters_to_km(rings.outer_radius)),
+            texture: rings.texture_path.clone(),
+        }
+    }
+}
+
+/// Widen through the shortest decimal, so the file shows 6378.1 rather than 6378.10009765625
+fn decimal(value: f32) -> f64 {
+    value.to_string().parse().unwrap_or(value as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_v4_defaults_and_units() {
+        let content = r#"
+#![enable(implicit_some)]
+Scenario(
+    version: 4,
+    name: "Binary",
+    epoch: 2451545.0,
+    units: (length: AstronomicalUnits, velocity: KilometersPerSecond, mass: SolarMasses),
+    integrator: (max_time_step: 0.05, time_scale: 10.0),
+    cameras: [(name: "Overview", distance: 5.0), (name: "Star", focus: "A", offset: (0.0, 0.1, 0.2))],
+    bodies: [
+        (name: "A", kind: Star, mass: 1.0, radius: 696000.0),
+        (name: "B", mass: 0.001, position: (1.0, 0.0, 0.0), velocity: (0.0, 30.0, 0.0),
+         rotation: (tilt: 90.0)),
+    ],
+)
+"#;
+        let scenario = ScenarioParser::parse(content).unwrap();
+        assert_eq!(scenario.metadata.name.as_deref(), Some("Binary"));
+        assert_eq!(scenario.metadata.epoch, Some(2451545.0));
+        assert_eq!(scenario.integrator.max_time_step, Some(0.05));
+        assert_eq!(scenario.integrator.time_scale, Some(10.0));
+
+        let cameras = &scenario.metadata.cameras;
+        assert_eq!(cameras[0].distance, Some(5.0 * AU_TO_METERS));
+        assert_eq!(cameras[1].focus.as_deref(), Some("A"));
+
+        let (star, planet) = (&scenario.bodies[0], &scenario.bodies[1]);
+        assert_eq!(star.mass, SOLAR_MASS);
+        assert!(matches!(
+            star.body_type,
+            BodyType::Star { radius, temperature, .. } if radius == 6.96e8 && temperature == DEFAULT_TEMPERATURE
+        ));
+        assert_eq!(planet.position.x, AU_TO_METERS);
+        assert_eq!(planet.velocity.y, 30000.0);
+        assert_eq!(planet.orbit_color, DEFAULT_ORBIT_COLOR);
+        assert_eq!(planet.rotation_params.0, std::f32::consts::FRAC_PI_2);
+        assert!(matches!(
+            &planet.body_type,
+            BodyType::Planet { texture_path, .. } if texture_path == DEFAULT_TEXTURE
+        ));
+
+        assert!(ScenarioParser::parse("Scenario(version: 3)").is_err());
+        assert!(ScenarioParser::parse("Scenario(version: 4, bodies: [(name: \"X\")])").is_err());
+    }
+
+    #[test]
+    fn test_converted_v3_scenario_matches() {
+        let content = std::fs::read_to_string("assets/examples/Solar_System_2K.txt").unwrap();
+        let original = ScenarioParser::parse(&content).unwrap();
+
+        let converted = ScenarioParser::convert_to_v4(&content).unwrap();
+        let reparsed = ScenarioParser::parse(&converted).unwrap();
+        assert_eq!(reparsed.bodies, original.bodies);
+        assert_eq!(reparsed.barycentric, original.barycentric);
+    }
+}
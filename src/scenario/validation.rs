@@ -0,0 +1,933 @@
+/// Validation of scenario files before they are loaded
+/// Collects every problem with its line and field instead of stopping at (or skipping past) the first one
+use std::fmt;
+
+use super::{ScenarioFormat, ScenarioParser, v4};
+use crate::{AstrariaError, AstrariaResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Loads, but probably not as intended
+    Warning,
+    /// The body or file cannot be loaded as written
+    Error,
+}
+
+/// One problem found in a scenario file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// 1-based line number, when the problem can be tied to one
+    pub line: Option<usize>,
+    /// Key the problem was found in
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "warning")?,
+            Severity::Error => write!(f, "error")?,
+        }
+        if let Some(line) = self.line {
+            write!(f, " (line {})", line)?;
+        }
+        if let Some(field) = &self.field {
+            write!(f, " [{}]", field)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Everything a validation pass found, in file order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    pub diagnostics: Vec<Diagnostic>,
+    /// Body blocks of a known type, whether or not they are valid
+    pub body_count: usize,
+}
+
+impl ValidationReport {
+    pub fn error_count(&self) -> usize {
+        self.count(Severity::Error)
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.count(Severity::Warning)
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.error_count() > 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    fn count(&self, severity: Severity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == severity)
+            .count()
+    }
+
+    /// Record a problem; `line` is a 0-based line index
+    fn push(
+        &mut self,
+        severity: Severity,
+        line: Option<usize>,
+        field: Option<&str>,
+        message: String,
+    ) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            line: line.map(|index| index + 1),
+            field: field.map(str::to_string),
+            message,
+        });
+    }
+
+    fn error(&mut self, line: Option<usize>, field: Option<&str>, message: String) {
+        self.push(Severity::Error, line, field, message);
+    }
+
+    fn warning(&mut self, line: Option<usize>, field: Option<&str>, message: String) {
+        self.push(Severity::Warning, line, field, message);
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "{}", diagnostic)?;
+        }
+        write!(
+            f,
+            "{} error(s), {} warning(s)",
+            self.error_count(),
+            self.warning_count()
+        )
+    }
+}
+
+/// What a v3 field holds, and so how its value is checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Text,
+    /// Name of another body in the scenario
+    BodyName,
+    Texture,
+    Radius,
+    Mass,
+    Temperature,
+    Vector,
+    Color,
+    Rotation,
+    Rings,
+    Maneuver,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FieldSpec {
+    key: &'static str,
+    kind: FieldKind,
+    required: bool,
+    /// May appear any number of times in a row
+    repeated: bool,
+}
+
+const fn required(key: &'static str, kind: FieldKind) -> FieldSpec {
+    FieldSpec {
+        key,
+        kind,
+        required: true,
+        repeated: false,
+    }
+}
+
+const fn optional(key: &'static str, kind: FieldKind) -> FieldSpec {
+    FieldSpec {
+        key,
+        kind,
+        required: false,
+        repeated: false,
+    }
+}
+
+const PLANET: &[FieldSpec] = &[
+    required("name", FieldKind::Text),
+    required("radius", FieldKind::Radius),
+    required("mass", FieldKind::Mass),
+    required("velocity", FieldKind::Vector),
+    required("position", FieldKind::Vector),
+    required("texture", FieldKind::Texture),
+    required("orbit_color", FieldKind::Color),
+    required("rotation", FieldKind::Rotation),
+    optional("locked", FieldKind::BodyName),
+    optional("rings", FieldKind::Rings),
+];
+
+const STAR: &[FieldSpec] = &[
+    required("name", FieldKind::Text),
+    required("radius", FieldKind::Radius),
+    required("mass", FieldKind::Mass),
+    required("velocity", FieldKind::Vector),
+    required("position", FieldKind::Vector),
+    required("texture", FieldKind::Texture),
+    required("orbit_color", FieldKind::Color),
+    required("rotation", FieldKind::Rotation),
+    optional("locked", FieldKind::BodyName),
+    required("temperature", FieldKind::Temperature),
+];
+
+const PLANET_ATMO: &[FieldSpec] = &[
+    required("name", FieldKind::Text),
+    required("radius", FieldKind::Radius),
+    required("mass", FieldKind::Mass),
+    required("velocity", FieldKind::Vector),
+    required("position", FieldKind::Vector),
+    required("texture", FieldKind::Texture),
+    required("orbit_color", FieldKind::Color),
+    required("rotation", FieldKind::Rotation),
+    optional("locked", FieldKind::BodyName),
+    required("atmo_color", FieldKind::Color),
+    optional("ambientTexture", FieldKind::Texture),
+    optional("rings", FieldKind::Rings),
+];
+
+const BLACK_HOLE: &[FieldSpec] = &[
+    required("name", FieldKind::Text),
+    required("radius", FieldKind::Radius),
+    required("mass", FieldKind::Mass),
+    required("velocity", FieldKind::Vector),
+    required("position", FieldKind::Vector),
+    optional("orbit_color", FieldKind::Color),
+    optional("rotation", FieldKind::Rotation),
+];
+
+const SPACECRAFT: &[FieldSpec] = &[
+    required("name", FieldKind::Text),
+    required("radius", FieldKind::Radius),
+    required("mass", FieldKind::Mass),
+    required("velocity", FieldKind::Vector),
+    required("position", FieldKind::Vector),
+    required("orbit_color", FieldKind::Color),
+    FieldSpec {
+        key: "maneuver",
+        kind: FieldKind::Maneuver,
+        required: false,
+        repeated: true,
+    },
+];
+
+const COMET: &[FieldSpec] = &[
+    required("name", FieldKind::Text),
+    required("radius", FieldKind::Radius),
+    required("mass", FieldKind::Mass),
+    required("velocity", FieldKind::Vector),
+    required("position", FieldKind::Vector),
+    required("texture", FieldKind::Texture),
+    required("orbit_color", FieldKind::Color),
+    required("rotation", FieldKind::Rotation),
+    optional("locked", FieldKind::BodyName),
+];
+
+/// Body fields, in the order the v3 parser reads them
+fn schema(body_type: &str) -> Option<&'static [FieldSpec]> {
+    match body_type {
+        "planet" => Some(PLANET),
+        "star" => Some(STAR),
+        "planet_atmo" => Some(PLANET_ATMO),
+        "black_hole" => Some(BLACK_HOLE),
+        "spacecraft" => Some(SPACECRAFT),
+        "comet" => Some(COMET),
+        _ => None,
+    }
+}
+
+/// Body names and maneuver references seen so far, checked once the whole file is read
+#[derive(Default)]
+struct NameTable {
+    bodies: Vec<(String, Option<usize>)>,
+    references: Vec<(String, Option<usize>)>,
+    /// Included files may define the referenced bodies, so unknown references cannot be reported
+    has_includes: bool,
+}
+
+/// Answers whether a (normalized) texture path can be loaded
+type TextureCheck = Box<dyn Fn(&str) -> bool>;
+
+/// Checks scenario files of either format and reports every problem at once
+#[derive(Default)]
+pub struct ScenarioValidator {
+    texture_exists: Option<TextureCheck>,
+}
+
+impl ScenarioValidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also report textures for which `exists` returns false
+    pub fn with_texture_check(mut self, exists: impl Fn(&str) -> bool + 'static) -> Self {
+        self.texture_exists = Some(Box::new(exists));
+        self
+    }
+
+    pub fn validate(&self, content: &str) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        match ScenarioParser::detect_format(content) {
+            Ok(ScenarioFormat::V3) => self.validate_v3(content, &mut report),
+            Ok(ScenarioFormat::V4) => self.validate_v4(content, &mut report),
+            Err(e) => report.error(Some(0), None, e.to_string()),
+        }
+        report
+    }
+
+    /// Strict loading: parse only if validation found no errors
+    pub fn check(&self, content: &str) -> AstrariaResult<super::Scenario> {
+        let report = self.validate(content);
+        for diagnostic in &report.diagnostics {
+            log::warn!("Scenario {}", diagnostic);
+        }
+        if report.has_errors() {
+            return Err(AstrariaError::ParseError(format!(
+                "Scenario failed validation:\n{}",
+                report
+            )));
+        }
+        ScenarioParser::parse(content)
+    }
+
+    fn validate_v3(&self, content: &str, report: &mut ValidationReport) {
+        let lines: Vec<&str> = content.lines().collect();
+        if lines.first().map(|line| line.trim()) != Some("v3") {
+            report.error(Some(0), None, "the first line must be 'v3'".to_string());
+        }
+
+        let mut names = NameTable::default();
+        let mut i = 1;
+        while i < lines.len() {
+            let line = lines[i].trim();
+            if line.is_empty() {
+                i += 1;
+                continue;
+            }
+            let Some((key, value)) = split(line) else {
+                report.error(
+                    Some(i),
+                    None,
+                    format!("expected 'key: value', found '{}'", line),
+                );
+                i += 1;
+                continue;
+            };
+            match key {
+                "type" => i = self.validate_v3_body(&lines, i, value, report, &mut names),
+                "include" => {
+                    names.has_includes = true;
+                    i = Self::validate_v3_include(&lines, i, value, report);
+                }
+                "barycentric" => {
+                    if value.parse::<bool>().is_err() {
+                        report.error(
+                            Some(i),
+                            Some(key),
+                            format!("expected true or false, found '{}'", value),
+                        );
+                    }
+                    i += 1;
+                }
+                _ => {
+                    report.error(Some(i), Some(key), format!("unknown key '{}'", key));
+                    i += 1;
+                }
+            }
+        }
+        Self::check_names(&names, report);
+    }
+
+    /// Check an `include:` line and the offsets after it; returns the index after them
+    fn validate_v3_include(
+        lines: &[&str],
+        start: usize,
+        path: &str,
+        report: &mut ValidationReport,
+    ) -> usize {
+        if path.is_empty() {
+            report.error(
+                Some(start),
+                Some("include"),
+                "empty include path".to_string(),
+            );
+        }
+        let mut i = start + 1;
+        while let Some((key, value)) = lines.get(i).and_then(|line| split(line.trim())) {
+            let problem = match key {
+                "prefix" if value.is_empty() => Some("empty prefix".to_string()),
+                "prefix" => None,
+                "position" | "velocity" => numbers(value, 3).err(),
+                _ => break,
+            };
+            if let Some(message) = problem {
+                report.error(
+                    Some(i),
+                    Some(key),
+                    format!("{} for include '{}'", message, path),
+                );
+            }
+            i += 1;
+        }
+        i
+    }
+
+    /// Check one body block starting at its `type:` line; returns the index after the block
+    fn validate_v3_body(
+        &self,
+        lines: &[&str],
+        start: usize,
+        body_type: &str,
+        report: &mut ValidationReport,
+        names: &mut NameTable,
+    ) -> usize {
+        let Some(fields) = schema(body_type) else {
+            report.error(
+                Some(start),
+                Some("type"),
+                format!("unknown body type '{}'", body_type),
+            );
+            return start + 1;
+        };
+        report.body_count += 1;
+
+        let mut label = body_type.to_string();
+        let mut i = start + 1;
+        for (index, spec) in fields.iter().enumerate() {
+            loop {
+                // A blank line or the end of the file ends the block
+                let Some(line) = lines
+                    .get(i)
+                    .map(|line| line.trim())
+                    .filter(|line| !line.is_empty())
+                else {
+                    if spec.required {
+                        report.error(
+                            Some(i.min(lines.len()).saturating_sub(1)),
+                            Some(spec.key),
+                            format!("{} is missing '{}'", label, spec.key),
+                        );
+                    }
+                    break;
+                };
+                let (key, value) = split(line).unwrap_or((line, ""));
+
+                if key == spec.key {
+                    if spec.key == "name" {
+                        label = format!("{} '{}'", body_type, value);
+                        names.bodies.push((value.to_string(), Some(i)));
+                    }
+                    self.check_v3_value(i, spec, value, &label, report, names);
+                    i += 1;
+                    if spec.repeated {
+                        continue;
+                    }
+                    break;
+                }
+                if !spec.required {
+                    break;
+                }
+                if key == "type" || fields[index + 1..].iter().any(|later| later.key == key) {
+                    report.error(
+                        Some(i),
+                        Some(spec.key),
+                        format!("{} is missing '{}' before '{}'", label, spec.key, key),
+                    );
+                    break;
+                }
+                report.error(
+                    Some(i),
+                    Some(key),
+                    format!(
+                        "unknown key '{}' in {}, expected '{}'",
+                        key, label, spec.key
+                    ),
+                );
+                i += 1;
+            }
+        }
+        i
+    }
+
+    fn check_v3_value(
+        &self,
+        line: usize,
+        spec: &FieldSpec,
+        value: &str,
+        label: &str,
+        report: &mut ValidationReport,
+        names: &mut NameTable,
+    ) {
+        let at = Some(line);
+        let field = Some(spec.key);
+        if value.is_empty() {
+            report.error(at, field, format!("empty value for {}", label));
+            return;
+        }
+        let values = match spec.kind {
+            FieldKind::Text => return,
+            FieldKind::BodyName => {
+                names.references.push((value.to_string(), at));
+                return;
+            }
+            FieldKind::Texture => {
+                self.check_texture(at, field, value, label, report);
+                return;
+            }
+            FieldKind::Maneuver => {
+                let Some((parts, reference)) = ScenarioParser::split_fields(value, 4) else {
+                    report.error(
+                        at,
+                        field,
+                        "expected time, three delta-v components and a reference body".to_string(),
+                    );
+                    return;
+                };
+                names.references.push((reference.to_string(), at));
+                parts.join(" ")
+            }
+            FieldKind::Rings => {
+                let Some((parts, texture)) = ScenarioParser::split_fields(value, 2) else {
+                    report.error(
+                        at,
+                        field,
+                        "expected inner radius, outer radius and a texture".to_string(),
+                    );
+                    return;
+                };
+                self.check_texture(at, field, texture, label, report);
+                parts.join(" ")
+            }
+            _ => value.to_string(),
+        };
+
+        let count = match spec.kind {
+            FieldKind::Radius | FieldKind::Mass | FieldKind::Temperature => 1,
+            FieldKind::Rings => 2,
+            FieldKind::Vector => 3,
+            _ => 4,
+        };
+        let numbers = match numbers(&values, count) {
+            Ok(numbers) => numbers,
+            Err(message) => {
+                report.error(at, field, format!("{} for {}", message, label));
+                return;
+            }
+        };
+        match spec.kind {
+            FieldKind::Radius => check_radius(numbers[0], at, label, report),
+            FieldKind::Mass => check_mass(numbers[0], at, label, report),
+            FieldKind::Temperature => check_temperature(numbers[0], at, label, report),
+            FieldKind::Color => check_color(&numbers, at, field, label, report),
+            FieldKind::Rings => check_rings(numbers[0], numbers[1], at, label, report),
+            _ => {}
+        }
+    }
+
+    fn validate_v4(&self, content: &str, report: &mut ValidationReport) {
+        let file = match v4::ScenarioFile::from_ron(content) {
+            Ok(file) => file,
+            Err(e) => {
+                report.error(
+                    Some(e.span.start.line.saturating_sub(1)),
+                    None,
+                    e.code.to_string(),
+                );
+                return;
+            }
+        };
+        if file.version != v4::VERSION {
+            report.error(
+                line_of(content, "version"),
+                Some("version"),
+                format!(
+                    "unsupported version {}, expected {}",
+                    file.version,
+                    v4::VERSION
+                ),
+            );
+        }
+
+        let mut names = NameTable {
+            has_includes: !file.includes.is_empty(),
+            ..Default::default()
+        };
+        report.body_count = file.bodies.len();
+        for body in &file.bodies {
+            let at = line_of(content, &format!("\"{}\"", body.name));
+            let label = format!("'{}'", body.name);
+            names.bodies.push((body.name.clone(), at));
+
+            check_mass(body.mass, at, &label, report);
+            check_radius(body.radius, at, &label, report);
+            if let Some(temperature) = body.temperature {
+                check_temperature(temperature as f64, at, &label, report);
+            }
+            for (field, color) in [
+                ("orbit_color", body.orbit_color),
+                ("atmosphere_color", body.atmosphere_color),
+            ] {
+                if let Some(color) = color {
+                    let color = color.map(f64::from);
+                    check_color(&color, at, Some(field), &label, report);
+                }
+            }
+            for texture in [&body.texture, &body.ambient_texture].into_iter().flatten() {
+                self.check_texture(at, Some("texture"), texture, &label, report);
+            }
+            if let Some(rings) = &body.rings {
+                if !matches!(body.kind, v4::BodyKind::Planet | v4::BodyKind::PlanetAtmo) {
+                    report.warning(
+                        at,
+                        Some("rings"),
+                        format!("rings of {} are ignored on this body kind", label),
+                    );
+                }
+                check_rings(rings.inner_radius, rings.outer_radius, at, &label, report);
+                self.check_texture(at, Some("rings"), &rings.texture, &label, report);
+            }
+            for maneuver in &body.maneuvers {
+                names.references.push((maneuver.reference.clone(), at));
+            }
+            if let Some(parent) = &body.locked_to {
+                names.references.push((parent.clone(), at));
+            }
+        }
+        for camera in &file.cameras {
+            if let Some(focus) = &camera.focus {
+                names
+                    .references
+                    .push((focus.clone(), line_of(content, &camera.name)));
+            }
+        }
+        Self::check_names(&names, report);
+    }
+
+    fn check_texture(
+        &self,
+        at: Option<usize>,
+        field: Option<&str>,
+        path: &str,
+        label: &str,
+        report: &mut ValidationReport,
+    ) {
+        if let Some(exists) = &self.texture_exists
+            && !exists(&ScenarioParser::normalize_texture_path(path))
+        {
+            report.error(
+                at,
+                field,
+                format!("texture '{}' of {} not found", path, label),
+            );
+        }
+    }
+
+    fn check_names(names: &NameTable, report: &mut ValidationReport) {
+        for (index, (name, at)) in names.bodies.iter().enumerate() {
+            if names.bodies[..index].iter().any(|(other, _)| other == name) {
+                report.warning(*at, Some("name"), format!("duplicate body name '{}'", name));
+            }
+        }
+        if names.has_includes {
+            return;
+        }
+        for (reference, at) in &names.references {
+            if !names.bodies.iter().any(|(name, _)| name == reference) {
+                report.error(*at, None, format!("no body named '{}'", reference));
+            }
+        }
+    }
+}
+
+fn split(line: &str) -> Option<(&str, &str)> {
+    line.split_once(':')
+        .map(|(key, value)| (key.trim(), value.trim()))
+}
+
+/// Exactly `count` finite numbers separated by whitespace
+fn numbers(value: &str, count: usize) -> Result<Vec<f64>, String> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    if parts.len() != count {
+        return Err(format!(
+            "expected {} value(s), found {}",
+            count,
+            parts.len()
+        ));
+    }
+    parts
+        .iter()
+        .map(|part| match part.parse::<f64>() {
+            Ok(number) if number.is_finite() => Ok(number),
+            _ => Err(format!("'{}' is not a finite number", part)),
+        })
+        .collect()
+}
+
+fn check_mass(mass: f64, at: Option<usize>, label: &str, report: &mut ValidationReport) {
+    if !(mass.is_finite() && mass >= 0.0) {
+        report.error(
+            at,
+            Some("mass"),
+            format!("{} has non-physical mass {}", label, mass),
+        );
+    }
+}
+
+fn check_radius(radius: f64, at: Option<usize>, label: &str, report: &mut ValidationReport) {
+    if !(radius.is_finite() && radius >= 0.0) {
+        report.error(
+            at,
+            Some("radius"),
+            format!("{} has non-physical radius {}", label, radius),
+        );
+    }
+}
+
+fn check_rings(
+    inner: f64,
+    outer: f64,
+    at: Option<usize>,
+    label: &str,
+    report: &mut ValidationReport,
+) {
+    if !(inner >= 0.0 && outer > inner) {
+        report.error(
+            at,
+            Some("rings"),
+            format!(
+                "rings of {} run from {} to {}, not outwards",
+                label, inner, outer
+            ),
+        );
+    }
+}
+
+fn check_temperature(kelvin: f64, at: Option<usize>, label: &str, report: &mut ValidationReport) {
+    if !(kelvin.is_finite() && kelvin > 0.0) {
+        report.error(
+            at,
+            Some("temperature"),
+            format!("{} has non-physical temperature {} K", label, kelvin),
+        );
+    }
+}
+
+fn check_color(
+    color: &[f64],
+    at: Option<usize>,
+    field: Option<&str>,
+    label: &str,
+    report: &mut ValidationReport,
+) {
+    if color
+        .iter()
+        .any(|component| !(0.0..=1.0).contains(component))
+    {
+        report.warning(
+            at,
+            field,
+            format!("colour of {} has components outside 0-1", label),
+        );
+    }
+}
+
+/// First line containing `needle`, as a 0-based index
+fn line_of(content: &str, needle: &str) -> Option<usize> {
+    content.lines().position(|line| line.contains(needle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reports_every_problem_with_its_line() {
+        let content = "v3
+barycentirc: true
+
+type: planet
+name: Broken
+radius: -5
+mass: 1e20
+velocity: 0 0
+position: 0 0 0
+texture: ./Planet Textures/missing.jpg
+orbit_color: 1 1 2 1
+rotation: 0 0 0 0
+
+type: star
+name: Sun
+radius: 695700
+mass: 2e30
+velocity: 0 0 0
+position: 0 0 0
+texture: ./Planet Textures/2k_sun.jpg
+colour: 1 1 1 1
+orbit_color: 1 1 1 1
+rotation: 0 0 0 0
+
+type: spacecraft
+name: Probe
+radius: 5
+mass: 1000
+velocity: 0 7500 0
+position: 7e6 0 0
+orbit_color: 1 1 1 1
+maneuver: 60  10 0\t0  Earth
+";
+        let report = ScenarioValidator::new()
+            .with_texture_check(|path| !path.contains("missing"))
+            .validate(content);
+        let located: Vec<(Option<usize>, Option<&str>, Severity)> = report
+            .diagnostics
+            .iter()
+            .map(|d| (d.line, d.field.as_deref(), d.severity))
+            .collect();
+        assert_eq!(
+            located,
+            vec![
+                (Some(2), Some("barycentirc"), Severity::Error),
+                (Some(6), Some("radius"), Severity::Error),
+                (Some(8), Some("velocity"), Severity::Error),
+                (Some(10), Some("texture"), Severity::Error),
+                (Some(11), Some("orbit_color"), Severity::Warning),
+                (Some(21), Some("colour"), Severity::Error),
+                (Some(23), Some("temperature"), Severity::Error),
+                (Some(32), None, Severity::Error),
+            ]
+        );
+        assert_eq!(report.error_count(), 7);
+        assert!(ScenarioValidator::new().check(content).is_err());
+
+        // Lenient parsing still loads what it can
+        let scenario = ScenarioParser::parse(content).unwrap();
+        assert_eq!(scenario.bodies.len(), 1);
+    }
+
+    #[test]
+    fn test_clean_files_pass_in_both_formats() {
+        let content = std::fs::read_to_string("assets/examples/Solar_System_2K.txt").unwrap();
+        let validator = ScenarioValidator::new();
+        let report = validator.validate(&content);
+        assert!(report.is_empty(), "{}", report);
+
+        let converted = ScenarioParser::convert_to_v4(&content).unwrap();
+        assert!(validator.validate(&converted).is_empty());
+        assert_eq!(validator.check(&converted).unwrap().bodies.len(), 11);
+
+        let report = validator
+            .validate("Scenario(version: 4,\n bodies: [(name: \"A\", mass: -1.0, colour: 3)])");
+        assert_eq!(report.diagnostics[0].line, Some(2));
+    }
+
+    #[test]
+    fn test_examples_parse_and_validate_alike() {
+        let mut pending = vec![std::path::PathBuf::from("assets/examples")];
+        let mut checked = 0;
+        while let Some(path) = pending.pop() {
+            if path.is_dir() {
+                let entries = std::fs::read_dir(&path).unwrap();
+                pending.extend(entries.map(|entry| entry.unwrap().path()));
+                continue;
+            }
+            let content = std::fs::read_to_string(&path).unwrap();
+            let report = ScenarioValidator::new().validate(&content);
+            assert!(!report.has_errors(), "{}: {}", path.display(), report);
+            // The parser skips bodies it cannot read, so the counts only agree if it read them all
+            let scenario = ScenarioParser::parse(&content).unwrap();
+            assert_eq!(
+                report.body_count,
+                scenario.bodies.len(),
+                "{}",
+                path.display()
+            );
+            checked += 1;
+        }
+        assert!(checked >= 3);
+    }
+
+    /// A value of each kind that both the parser and the validator accept
+    fn sample(kind: FieldKind) -> &'static str {
+        match kind {
+            FieldKind::Text | FieldKind::BodyName => "Sample",
+            FieldKind::Texture => "Planet Textures/2k_moon.jpg",
+            FieldKind::Radius => "1000",
+            FieldKind::Mass => "1E20",
+            FieldKind::Temperature => "5000",
+            FieldKind::Vector => "1 2 3",
+            FieldKind::Color => "0.5 0.5 0.5 1",
+            FieldKind::Rotation => "10 20 30 40",
+            FieldKind::Rings => "2000 3000 Planet Textures/saturn_rings.png",
+            FieldKind::Maneuver => "10 1 0 0 Sample",
+        }
+    }
+
+    #[test]
+    fn test_schemas_match_the_parser() {
+        let block = |body_type: &str, fields: &[&FieldSpec]| {
+            let mut content = format!("v3\ntype: {}\n", body_type);
+            for spec in fields {
+                content.push_str(&format!("{}: {}\n", spec.key, sample(spec.kind)));
+            }
+            content
+        };
+        let validator = ScenarioValidator::new();
+        for body_type in [
+            "planet",
+            "star",
+            "planet_atmo",
+            "black_hole",
+            "spacecraft",
+            "comet",
+        ] {
+            let fields = schema(body_type).unwrap();
+            let required: Vec<&FieldSpec> = fields.iter().filter(|spec| spec.required).collect();
+            let content = block(body_type, &required);
+            let report = validator.validate(&content);
+            assert!(report.is_empty(), "{}: {}", body_type, report);
+            let base = ScenarioParser::parse(&content).unwrap().bodies;
+            assert_eq!(base.len(), 1, "{}", body_type);
+
+            // Every optional line the validator accepts changes what the parser loads
+            for spec in fields.iter().filter(|spec| !spec.required) {
+                let with: Vec<&FieldSpec> = fields
+                    .iter()
+                    .filter(|other| other.required || other.key == spec.key)
+                    .collect();
+                let content = block(body_type, &with);
+                let report = validator.validate(&content);
+                assert!(report.is_empty(), "{}: {}", body_type, report);
+                let bodies = ScenarioParser::parse(&content).unwrap().bodies;
+                assert_eq!(bodies.len(), 1, "{} with '{}'", body_type, spec.key);
+                assert_ne!(bodies[0], base[0], "{} ignores '{}'", body_type, spec.key);
+            }
+
+            // Every line the validator requires, the parser cannot do without
+            for spec in &required {
+                let without: Vec<&FieldSpec> = required
+                    .iter()
+                    .copied()
+                    .filter(|other| other.key != spec.key)
+                    .collect();
+                let content = block(body_type, &without);
+                assert!(validator.validate(&content).has_errors());
+                assert!(
+                    ScenarioParser::parse(&content).unwrap().bodies.is_empty(),
+                    "{} loads without '{}'",
+                    body_type,
+                    spec.key
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,226 @@
+/// Change detection for a loaded scenario file, the files it includes and its textures
+/// Polls modification times, so edits show up in the running app without a restart
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime},
+};
+
+use super::{BodyType, ScenarioParser};
+use crate::assets::AssetManager;
+
+/// How often the watched files are checked
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// What changed on disk since the last poll
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WatchChanges {
+    /// The scenario file or one of its includes
+    pub scenario: bool,
+    /// Texture paths as written in the scenario
+    pub textures: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct WatchedFile {
+    /// Path as written in the scenario (or given for the scenario itself)
+    name: String,
+    /// File on disk
+    path: PathBuf,
+    is_texture: bool,
+    modified: Option<SystemTime>,
+}
+
+/// Watches the files behind a scenario loaded with [`AssetManager::load_scenario`]
+#[derive(Debug, Clone)]
+pub struct ScenarioWatcher {
+    scenario_path: String,
+    files: Vec<WatchedFile>,
+    last_poll: Instant,
+}
+
+impl ScenarioWatcher {
+    pub fn new(scenario_path: &str) -> Self {
+        let mut watcher = Self {
+            scenario_path: scenario_path.to_string(),
+            files: Vec::new(),
+            last_poll: Instant::now(),
+        };
+        watcher.refresh();
+        watcher
+    }
+
+    /// The scenario file and the files it includes, as they were named
+    pub fn scenario_files(&self) -> impl Iterator<Item = &str> {
+        self.files
+            .iter()
+            .filter(|file| !file.is_texture)
+            .map(|file| file.name.as_str())
+    }
+
+    /// Rebuild the list of watched files, as includes and textures may have changed
+    pub fn refresh(&mut self) {
+        let mut scenario_files = Vec::new();
+        let mut textures = Vec::new();
+        Self::collect(&self.scenario_path, &mut scenario_files, &mut textures);
+
+        self.files = scenario_files
+            .into_iter()
+            .map(|name| (AssetManager::scenario_file_path(&name), name, false))
+            .chain(textures.into_iter().map(|name| {
+                let path = PathBuf::from(AssetManager::resolve_texture_path(&name));
+                (path, name, true)
+            }))
+            .map(|(path, name, is_texture)| WatchedFile {
+                name,
+                modified: modified(&path),
+                path,
+                is_texture,
+            })
+            .collect();
+    }
+
+    /// Files that changed since the last poll, checked at most every [`POLL_INTERVAL`]
+    pub fn poll(&mut self) -> Option<WatchChanges> {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return None;
+        }
+        self.last_poll = Instant::now();
+        self.changes()
+    }
+
+    fn changes(&mut self) -> Option<WatchChanges> {
+        let mut changes = WatchChanges::default();
+        for file in &mut self.files {
+            let modified = modified(&file.path);
+            if modified == file.modified {
+                continue;
+            }
+            file.modified = modified;
+            if file.is_texture {
+                changes.textures.push(file.name.clone());
+            } else {
+                changes.scenario = true;
+            }
+        }
+        (changes != WatchChanges::default()).then_some(changes)
+    }
+
+    /// Gather `path`, the files it includes and every texture they use.
+    /// Files that cannot be read or parsed are still watched, so fixing them triggers a reload.
+    fn collect(path: &str, scenario_files: &mut Vec<String>, textures: &mut Vec<String>) {
+        if scenario_files.iter().any(|file| file == path) {
+            return;
+        }
+        scenario_files.push(path.to_string());
+
+        let Ok(content) = std::fs::read_to_string(AssetManager::scenario_file_path(path)) else {
+            return;
+        };
+        let Ok(scenario) = ScenarioParser::parse(&content) else {
+            return;
+        };
+        for body in &scenario.bodies {
+            for texture in texture_paths(&body.body_type) {
+                if !textures.iter().any(|known| known == texture) {
+                    textures.push(texture.to_string());
+                }
+            }
+        }
+        for include in &scenario.includes {
+            Self::collect(&include.resolve_path(path), scenario_files, textures);
+        }
+    }
+}
+
+/// Every texture a body is drawn with
+fn texture_paths(body_type: &BodyType) -> Vec<&str> {
+    let mut paths = match body_type {
+        BodyType::Planet { texture_path, .. }
+        | BodyType::Star { texture_path, .. }
+        | BodyType::Comet { texture_path, .. } => vec![texture_path.as_str()],
+        BodyType::PlanetAtmo {
+            texture_path,
+            ambient_texture,
+            ..
+        } => std::iter::once(texture_path.as_str())
+            .chain(ambient_texture.as_deref())
+            .collect(),
+        BodyType::BlackHole { .. } | BodyType::Spacecraft { .. } => Vec::new(),
+    };
+    paths.extend(body_type.rings().map(|rings| rings.texture_path.as_str()));
+    paths
+}
+
+fn modified(path: &std::path::Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watcher_reports_scenario_include_and_texture_changes() {
+        let dir = std::env::temp_dir().join(format!("astraria_watch_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let main = dir.join("main.txt");
+        let moon = dir.join("moon.txt");
+        let texture = dir.join("moon.png");
+        std::fs::write(&main, "v3\n\ninclude: moon.txt\n").unwrap();
+        std::fs::write(
+            &moon,
+            format!(
+                "v3
+
+type: planet
+name: Moon
+radius: 1737.4
+mass: 7.342E22
+velocity: 0 1022 0
+position: 384400000 0 0
+texture: {}
+orbit_color: 0.5 0.5 0.5 1.0
+rotation: 6.68 0 13.18 0
+",
+                texture.display()
+            ),
+        )
+        .unwrap();
+        std::fs::write(&texture, b"png").unwrap();
+
+        let mut watcher = ScenarioWatcher::new(main.to_str().unwrap());
+        assert_eq!(watcher.files.len(), 3);
+        assert_eq!(
+            watcher.scenario_files().collect::<Vec<_>>(),
+            vec![main.to_str().unwrap(), moon.to_str().unwrap()]
+        );
+        assert_eq!(watcher.changes(), None);
+
+        let touch = |path: &PathBuf| {
+            std::fs::File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(SystemTime::now() + Duration::from_secs(10))
+                .unwrap();
+        };
+        touch(&moon);
+        assert_eq!(
+            watcher.changes(),
+            Some(WatchChanges {
+                scenario: true,
+                textures: Vec::new(),
+            })
+        );
+        touch(&texture);
+        assert_eq!(
+            watcher.changes().unwrap().textures,
+            vec![texture.display().to_string()]
+        );
+        assert_eq!(watcher.changes(), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
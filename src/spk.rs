@@ -0,0 +1,621 @@
+/// SPICE SPK ephemeris kernels
+/// Pure-Rust reader for binary DAF/SPK files with Chebyshev segments of type 2
+/// (position only) and type 3 (position and velocity)
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+use glam::DVec3;
+
+use crate::{
+    AstrariaError, AstrariaResult,
+    ephemeris::{ReferenceBody, ReferenceState, ReferenceTrajectory},
+    scenario::{
+        RotationMode, Scenario, ScenarioBody,
+        horizons::{BodyCatalog, equatorial_to_ecliptic},
+    },
+};
+
+/// Size of a DAF record in bytes
+const RECORD_BYTES: usize = 1024;
+
+/// NAIF id of the solar system barycentre, the root of every SPK chain
+pub const SOLAR_SYSTEM_BARYCENTER: i32 = 0;
+
+/// NAIF frame ids
+const FRAME_J2000: i32 = 1;
+const FRAME_ECLIPJ2000: i32 = 17;
+
+/// Julian date of the J2000 epoch (TDB)
+const J2000_JULIAN_DATE: f64 = 2451545.0;
+
+/// Seconds past J2000 (TDB), as used by SPK kernels, for a Julian date
+pub fn julian_date_to_et(julian_date: f64) -> f64 {
+    (julian_date - J2000_JULIAN_DATE) * 86400.0
+}
+
+pub fn et_to_julian_date(et: f64) -> f64 {
+    et / 86400.0 + J2000_JULIAN_DATE
+}
+
+/// Descriptor of one segment of a kernel
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpkSegment {
+    pub name: String,
+    pub target: i32,
+    pub center: i32,
+    pub frame: i32,
+    pub data_type: i32,
+    /// Coverage in seconds past J2000 (TDB)
+    pub start_et: f64,
+    pub end_et: f64,
+    /// 1-based double-precision word addresses of the segment's data
+    start_address: usize,
+    end_address: usize,
+}
+
+/// Directory stored at the end of every type 2 / 3 segment
+struct ChebyshevDirectory {
+    init: f64,
+    interval_length: f64,
+    record_size: usize,
+    record_count: usize,
+}
+
+/// A loaded SPK kernel
+pub struct SpkKernel {
+    data: Vec<u8>,
+    little_endian: bool,
+    segments: Vec<SpkSegment>,
+}
+
+impl SpkKernel {
+    /// Read a kernel file into memory
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open(path: impl AsRef<Path>) -> AstrariaResult<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).map_err(|e| {
+            AstrariaError::AssetLoading(format!(
+                "Failed to read SPK kernel '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Self::from_bytes(data)
+    }
+
+    /// Parse a kernel held in memory
+    pub fn from_bytes(data: Vec<u8>) -> AstrariaResult<Self> {
+        if data.len() < RECORD_BYTES {
+            return Err(AstrariaError::ParseError(
+                "SPK kernel is shorter than its file record".to_string(),
+            ));
+        }
+        let id_word = String::from_utf8_lossy(&data[0..8]).to_string();
+        if !id_word.starts_with("DAF/SPK") && !id_word.starts_with("NAIF/DAF") {
+            return Err(AstrariaError::ParseError(format!(
+                "Not an SPK kernel (file id '{}')",
+                id_word.trim()
+            )));
+        }
+        let little_endian = match &data[88..96] {
+            b"BIG-IEEE" => false,
+            b"LTL-IEEE" => true,
+            // Files written before the format string existed use the writer's byte order
+            _ => cfg!(target_endian = "little"),
+        };
+
+        let mut kernel = Self {
+            data,
+            little_endian,
+            segments: Vec::new(),
+        };
+        let double_count = kernel.read_i32(8)?;
+        let integer_count = kernel.read_i32(12)?;
+        if double_count != 2 || integer_count != 6 {
+            return Err(AstrariaError::ParseError(format!(
+                "Unexpected SPK summary format (ND = {}, NI = {})",
+                double_count, integer_count
+            )));
+        }
+        kernel.segments = kernel.read_summaries()?;
+        Ok(kernel)
+    }
+
+    pub fn segments(&self) -> &[SpkSegment] {
+        &self.segments
+    }
+
+    /// Every body that has a segment of its own
+    pub fn bodies(&self) -> Vec<i32> {
+        let mut bodies: Vec<i32> = self.segments.iter().map(|segment| segment.target).collect();
+        bodies.sort_unstable();
+        bodies.dedup();
+        bodies
+    }
+
+    fn read_bytes<const N: usize>(&self, offset: usize) -> AstrariaResult<[u8; N]> {
+        self.data
+            .get(offset..offset + N)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| {
+                AstrariaError::ParseError(format!("SPK kernel truncated at byte {}", offset))
+            })
+    }
+
+    fn read_i32(&self, offset: usize) -> AstrariaResult<i32> {
+        let bytes = self.read_bytes::<4>(offset)?;
+        Ok(if self.little_endian {
+            i32::from_le_bytes(bytes)
+        } else {
+            i32::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_f64(&self, offset: usize) -> AstrariaResult<f64> {
+        let bytes = self.read_bytes::<8>(offset)?;
+        Ok(if self.little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+
+    /// Double at a 1-based DAF word address
+    fn word(&self, address: usize) -> AstrariaResult<f64> {
+        let offset = address
+            .checked_sub(1)
+            .and_then(|index| index.checked_mul(8))
+            .ok_or_else(|| {
+                AstrariaError::ParseError(format!("Invalid SPK word address {}", address))
+            })?;
+        self.read_f64(offset)
+    }
+
+    /// Walk the doubly linked list of summary records and their name records
+    fn read_summaries(&self) -> AstrariaResult<Vec<SpkSegment>> {
+        // Summary size in doubles: ND + (NI + 1) / 2
+        const SUMMARY_DOUBLES: usize = 5;
+        const NAME_BYTES: usize = SUMMARY_DOUBLES * 8;
+
+        let mut segments = Vec::new();
+        let mut record = self.read_i32(76)? as usize;
+        let mut visited = 0;
+        while record != 0 {
+            visited += 1;
+            if visited > self.data.len() / RECORD_BYTES {
+                return Err(AstrariaError::ParseError(
+                    "SPK summary records form a loop".to_string(),
+                ));
+            }
+            if record > self.data.len() / RECORD_BYTES {
+                return Err(AstrariaError::ParseError(format!(
+                    "SPK summary record {} is past the end of the kernel",
+                    record
+                )));
+            }
+            let offset = (record - 1) * RECORD_BYTES;
+            let next = self.read_f64(offset)? as usize;
+            let count = self.read_f64(offset + 16)? as usize;
+            for index in 0..count {
+                let summary = offset + 24 + index * SUMMARY_DOUBLES * 8;
+                let integers = summary + 16;
+                let name_offset = offset + RECORD_BYTES + index * NAME_BYTES;
+                let name = self
+                    .data
+                    .get(name_offset..name_offset + NAME_BYTES)
+                    .map(|bytes| {
+                        String::from_utf8_lossy(bytes)
+                            .trim_matches(|c: char| c.is_whitespace() || c == '\0')
+                            .to_string()
+                    })
+                    .unwrap_or_default();
+                segments.push(SpkSegment {
+                    name,
+                    start_et: self.read_f64(summary)?,
+                    end_et: self.read_f64(summary + 8)?,
+                    target: self.read_i32(integers)?,
+                    center: self.read_i32(integers + 4)?,
+                    frame: self.read_i32(integers + 8)?,
+                    data_type: self.read_i32(integers + 12)?,
+                    start_address: self.read_i32(integers + 16)? as usize,
+                    end_address: self.read_i32(integers + 20)? as usize,
+                });
+            }
+            record = next;
+        }
+        Ok(segments)
+    }
+
+    /// Segment for a body at an epoch; later segments take priority, as in SPICE
+    fn segment_for(&self, target: i32, et: f64) -> Option<&SpkSegment> {
+        self.segments.iter().rev().find(|segment| {
+            segment.target == target && segment.start_et <= et && et <= segment.end_et
+        })
+    }
+
+    /// Position (km) and velocity (km/s) of a segment's target relative to its centre,
+    /// in the segment's frame
+    fn evaluate(&self, segment: &SpkSegment, et: f64) -> AstrariaResult<(DVec3, DVec3)> {
+        let components = match segment.data_type {
+            2 => 3,
+            3 => 6,
+            other => {
+                return Err(AstrariaError::ParseError(format!(
+                    "SPK segment '{}' has unsupported type {}",
+                    segment.name, other
+                )));
+            }
+        };
+
+        let invalid = || {
+            AstrariaError::ParseError(format!(
+                "SPK segment '{}' has an invalid directory",
+                segment.name
+            ))
+        };
+        let directory_start = segment.end_address.checked_sub(3).ok_or_else(invalid)?;
+        let directory = ChebyshevDirectory {
+            init: self.word(directory_start)?,
+            interval_length: self.word(directory_start + 1)?,
+            record_size: self.word(directory_start + 2)? as usize,
+            record_count: self.word(directory_start + 3)? as usize,
+        };
+        if directory.record_count == 0
+            || directory.interval_length <= 0.0
+            || directory.record_size < 2 + components
+        {
+            return Err(invalid());
+        }
+
+        let index = (((et - directory.init) / directory.interval_length)
+            .floor()
+            .max(0.0) as usize)
+            .min(directory.record_count - 1);
+        let record = index
+            .checked_mul(directory.record_size)
+            .and_then(|offset| offset.checked_add(segment.start_address))
+            .filter(|record| {
+                record
+                    .checked_add(directory.record_size)
+                    .is_some_and(|end| end <= directory_start)
+            })
+            .ok_or_else(invalid)?;
+        let midpoint = self.word(record)?;
+        let radius = self.word(record + 1)?;
+        let coefficient_count = (directory.record_size - 2) / components;
+        let x = (et - midpoint) / radius;
+
+        let mut values = [0.0; 6];
+        let mut derivatives = [0.0; 6];
+        for (component, (value, derivative)) in values
+            .iter_mut()
+            .zip(derivatives.iter_mut())
+            .take(components)
+            .enumerate()
+        {
+            let start = record + 2 + component * coefficient_count;
+            let coefficients = (0..coefficient_count)
+                .map(|offset| self.word(start + offset))
+                .collect::<AstrariaResult<Vec<f64>>>()?;
+            (*value, *derivative) = chebyshev(&coefficients, x);
+        }
+
+        let position = DVec3::new(values[0], values[1], values[2]);
+        let velocity = if components == 6 {
+            DVec3::new(values[3], values[4], values[5])
+        } else {
+            DVec3::new(derivatives[0], derivatives[1], derivatives[2]) / radius
+        };
+        Ok((position, velocity))
+    }
+
+    /// State of a body relative to the root of its chain of centres (usually the solar
+    /// system barycentre), in meters, m/s and the ecliptic of J2000
+    fn state_from_root(&self, body: i32, et: f64) -> AstrariaResult<(DVec3, DVec3, i32)> {
+        let mut position = DVec3::ZERO;
+        let mut velocity = DVec3::ZERO;
+        let mut current = body;
+        for _ in 0..=self.segments.len() {
+            let Some(segment) = self.segment_for(current, et) else {
+                return Ok((position, velocity, current));
+            };
+            let (segment_position, segment_velocity) = self.evaluate(segment, et)?;
+            let (segment_position, segment_velocity) = match segment.frame {
+                FRAME_J2000 => (
+                    equatorial_to_ecliptic(segment_position),
+                    equatorial_to_ecliptic(segment_velocity),
+                ),
+                FRAME_ECLIPJ2000 => (segment_position, segment_velocity),
+                other => {
+                    return Err(AstrariaError::ParseError(format!(
+                        "SPK segment '{}' uses unsupported frame {}",
+                        segment.name, other
+                    )));
+                }
+            };
+            position += segment_position * 1000.0;
+            velocity += segment_velocity * 1000.0;
+            current = segment.center;
+        }
+        Err(AstrariaError::ParseError(format!(
+            "SPK centres of body {} form a loop",
+            body
+        )))
+    }
+
+    /// Position (m) and velocity (m/s) of `target` relative to `center` at `et` seconds
+    /// past J2000 (TDB), in the ecliptic of J2000
+    pub fn state(&self, target: i32, center: i32, et: f64) -> AstrariaResult<(DVec3, DVec3)> {
+        let (target_position, target_velocity, target_root) = self.state_from_root(target, et)?;
+        let (center_position, center_velocity, center_root) = self.state_from_root(center, et)?;
+        if target_root != center_root {
+            return Err(AstrariaError::ParseError(format!(
+                "No SPK data connects body {} to body {} at ET {} (missing or outside coverage)",
+                target, center, et
+            )));
+        }
+        Ok((
+            target_position - center_position,
+            target_velocity - center_velocity,
+        ))
+    }
+
+    /// Scenario of the given bodies at an epoch, relative to the solar system barycentre,
+    /// with masses, radii and appearance from a catalog keyed by NAIF id
+    pub fn scenario(
+        &self,
+        bodies: &[i32],
+        et: f64,
+        catalog: &BodyCatalog,
+    ) -> AstrariaResult<Scenario> {
+        let mut scenario_bodies = Vec::with_capacity(bodies.len());
+        for &body in bodies {
+            let id = body.to_string();
+            let entry = catalog.lookup(Some(&id), &id).ok_or_else(|| {
+                AstrariaError::ParseError(format!(
+                    "No mass or radius known for NAIF body {}; add it to a sidecar file",
+                    body
+                ))
+            })?;
+            let (position, velocity) = self.state(body, SOLAR_SYSTEM_BARYCENTER, et)?;
+            scenario_bodies.push(ScenarioBody {
+                name: entry.name.clone(),
+                mass: entry.mass,
+                position,
+                velocity,
+                body_type: entry.body_type.clone(),
+                orbit_color: entry.orbit_color,
+                rotation_params: entry.rotation_params,
+                rotation_mode: RotationMode::Fixed,
+            });
+        }
+        Ok(Scenario {
+            bodies: scenario_bodies,
+            barycentric: None,
+            ..Default::default()
+        })
+    }
+
+    /// Reference trajectory of every kernel body the catalog can name, relative to the
+    /// solar system barycentre, sampled every `step` seconds for `duration` seconds.
+    /// Time zero is `start_et`, the epoch the simulation starts at; samples outside a
+    /// body's own coverage are left out.
+    pub fn reference_trajectory(
+        &self,
+        catalog: &BodyCatalog,
+        start_et: f64,
+        duration: f64,
+        step: f64,
+    ) -> AstrariaResult<ReferenceTrajectory> {
+        if step <= 0.0 || duration < 0.0 {
+            return Err(AstrariaError::ParseError(format!(
+                "Invalid SPK sampling: {} s every {} s",
+                duration, step
+            )));
+        }
+        let count = (duration / step).floor() as usize + 1;
+
+        let mut bodies = Vec::new();
+        for body in self.bodies() {
+            let id = body.to_string();
+            let Some(entry) = catalog.lookup(Some(&id), &id) else {
+                continue;
+            };
+            let mut states = Vec::new();
+            for index in 0..count {
+                let time = index as f64 * step;
+                if self.segment_for(body, start_et + time).is_none() {
+                    continue;
+                }
+                let (position, velocity) =
+                    self.state(body, SOLAR_SYSTEM_BARYCENTER, start_et + time)?;
+                states.push(ReferenceState {
+                    time,
+                    position,
+                    velocity,
+                });
+            }
+            if !states.is_empty() {
+                bodies.push(ReferenceBody {
+                    name: entry.name.clone(),
+                    states,
+                });
+            }
+        }
+
+        if bodies.is_empty() {
+            return Err(AstrariaError::ParseError(
+                "SPK kernel has no catalogued body with data in the requested span".to_string(),
+            ));
+        }
+        Ok(ReferenceTrajectory { bodies })
+    }
+}
+
+/// Value and derivative (with respect to x) of a Chebyshev series at x in [-1, 1]
+fn chebyshev(coefficients: &[f64], x: f64) -> (f64, f64) {
+    let (mut value, mut derivative) = (0.0, 0.0);
+    // T_n and T'_n for the previous two orders
+    let (mut t_previous, mut t_current) = (1.0, x);
+    let (mut d_previous, mut d_current) = (0.0, 1.0);
+    for (order, coefficient) in coefficients.iter().enumerate() {
+        match order {
+            0 => value += coefficient,
+            1 => {
+                value += coefficient * x;
+                derivative += coefficient;
+            }
+            _ => {
+                let t_next = 2.0 * x * t_current - t_previous;
+                let d_next = 2.0 * t_current + 2.0 * x * d_current - d_previous;
+                value += coefficient * t_next;
+                derivative += coefficient * d_next;
+                (t_previous, t_current) = (t_current, t_next);
+                (d_previous, d_current) = (d_current, d_next);
+            }
+        }
+    }
+    (value, derivative)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Segment of a test kernel: (target, center, frame, type, start, end, data words)
+    type TestSegment = (i32, i32, i32, i32, f64, f64, Vec<f64>);
+
+    /// Little-endian kernel with one summary record holding the given segments
+    fn build_kernel(segments: &[TestSegment]) -> Vec<u8> {
+        let mut data = vec![0u8; RECORD_BYTES * 3];
+        data[0..8].copy_from_slice(b"DAF/SPK ");
+        data[8..12].copy_from_slice(&2i32.to_le_bytes());
+        data[12..16].copy_from_slice(&6i32.to_le_bytes());
+        data[76..80].copy_from_slice(&2i32.to_le_bytes());
+        data[88..96].copy_from_slice(b"LTL-IEEE");
+
+        let summary = RECORD_BYTES;
+        data[summary + 16..summary + 24].copy_from_slice(&(segments.len() as f64).to_le_bytes());
+        for (index, (target, center, frame, data_type, start, end, words)) in
+            segments.iter().enumerate()
+        {
+            let first_address = data.len() / 8 + 1;
+            for word in words {
+                data.extend_from_slice(&word.to_le_bytes());
+            }
+            let offset = summary + 24 + index * 40;
+            data[offset..offset + 8].copy_from_slice(&start.to_le_bytes());
+            data[offset + 8..offset + 16].copy_from_slice(&end.to_le_bytes());
+            let integers = [
+                *target,
+                *center,
+                *frame,
+                *data_type,
+                first_address as i32,
+                (first_address + words.len() - 1) as i32,
+            ];
+            for (slot, value) in integers.iter().enumerate() {
+                let at = offset + 16 + slot * 4;
+                data[at..at + 4].copy_from_slice(&value.to_le_bytes());
+            }
+            let name = format!("SEGMENT {}", index);
+            let name_offset = summary + RECORD_BYTES + index * 40;
+            data[name_offset..name_offset + name.len()].copy_from_slice(name.as_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_chebyshev_segments_and_chains() {
+        // Type 2, two 200 s records, ecliptic frame: x = 1000 + 50 T1 + 2 T2 km
+        let mut type2 = Vec::new();
+        for midpoint in [100.0, 300.0] {
+            type2.extend([midpoint, 100.0]);
+            type2.extend([1000.0, 50.0, 2.0, 0.0, 0.0, 0.0, -5.0, 0.0, 0.0]);
+        }
+        type2.extend([0.0, 200.0, 11.0, 2.0]);
+
+        // Type 3 relative to body 3, one record: constant offset with its own velocity
+        let mut type3 = vec![200.0, 200.0];
+        type3.extend([
+            10.0, 0.0, 20.0, 0.0, 30.0, 0.0, 0.1, 0.0, 0.2, 0.0, 0.3, 0.0,
+        ]);
+        type3.extend([0.0, 400.0, 14.0, 1.0]);
+
+        let kernel = SpkKernel::from_bytes(build_kernel(&[
+            (3, 0, FRAME_ECLIPJ2000, 2, 0.0, 400.0, type2),
+            (399, 3, FRAME_ECLIPJ2000, 3, 0.0, 400.0, type3),
+        ]))
+        .unwrap();
+        assert_eq!(kernel.segments().len(), 2);
+        assert_eq!(kernel.segments()[1].name, "SEGMENT 1");
+        assert_eq!(kernel.bodies(), [3, 399]);
+
+        // Second record at et = 350: T1 = 0.5, T2 = -0.5
+        let (position, velocity) = kernel.state(3, 0, 350.0).unwrap();
+        assert!((position.x - (1000.0 + 25.0 - 1.0) * 1000.0).abs() < 1e-6);
+        assert!((position.z - -5000.0).abs() < 1e-6);
+        // d/dt = (50 + 2 · 4x) / 100 km/s
+        assert!((velocity.x - (50.0 + 4.0) / 100.0 * 1000.0).abs() < 1e-9);
+
+        // Chains add up through body 3, and relative states subtract
+        let (earth, earth_velocity) = kernel.state(399, 0, 350.0).unwrap();
+        assert!((earth - position - DVec3::new(10.0, 20.0, 30.0) * 1000.0).length() < 1e-6);
+        assert!((earth_velocity - velocity - DVec3::new(100.0, 200.0, 300.0)).length() < 1e-9);
+        let (relative, _) = kernel.state(399, 3, 350.0).unwrap();
+        assert!((relative - DVec3::new(10.0, 20.0, 30.0) * 1000.0).length() < 1e-6);
+
+        assert!(kernel.state(499, 0, 350.0).is_err());
+        assert!(SpkKernel::from_bytes(vec![0; 2048]).is_err());
+    }
+
+    #[test]
+    fn test_reference_trajectory_and_bad_addresses() {
+        // Body 399 moving along x at 0.5 km/s, 1000 km from the barycentre at et = 200
+        let mut words = vec![200.0, 200.0];
+        words.extend([
+            1000.0, 100.0, 0.0, 0.0, 0.0, 0.0, 0.5, 0.0, 0.0, 0.0, 0.0, 0.0,
+        ]);
+        words.extend([0.0, 400.0, 14.0, 1.0]);
+        let segments = [(399, 0, FRAME_ECLIPJ2000, 3, 0.0, 400.0, words)];
+
+        let kernel = SpkKernel::from_bytes(build_kernel(&segments)).unwrap();
+        let reference = kernel
+            .reference_trajectory(&BodyCatalog::builtin(), 100.0, 1000.0, 100.0)
+            .unwrap();
+        assert_eq!(reference.bodies.len(), 1);
+        let earth = reference.body("Earth").unwrap();
+        // Coverage ends 300 s after the start
+        assert_eq!(earth.span(), Some((0.0, 300.0)));
+        let (position, velocity) = earth.state_at(150.0).unwrap();
+        assert!((position - DVec3::new(1.025e6, 0.0, 0.0)).length() < 1e-6);
+        assert!((velocity - DVec3::new(500.0, 0.0, 0.0)).length() < 1e-9);
+        assert!(
+            kernel
+                .reference_trajectory(&BodyCatalog::default(), 0.0, 400.0, 100.0)
+                .is_err()
+        );
+
+        // Addresses below the first word are errors, not overflows
+        let integers = RECORD_BYTES + 24 + 16;
+        let bad_addresses: [(usize, i32); 2] = [(5, 2), (4, 0)];
+        for (slot, address) in bad_addresses {
+            let mut data = build_kernel(&segments);
+            let at = integers + slot * 4;
+            data[at..at + 4].copy_from_slice(&address.to_le_bytes());
+            let kernel = SpkKernel::from_bytes(data).unwrap();
+            assert!(kernel.state(399, 0, 100.0).is_err());
+        }
+    }
+
+    #[test]
+    fn test_chebyshev_derivative() {
+        let coefficients = [0.3, -1.2, 0.7, 0.25, -0.1];
+        let x = 0.37;
+        let (_, derivative) = chebyshev(&coefficients, x);
+        let h = 1e-6;
+        let numeric =
+            (chebyshev(&coefficients, x + h).0 - chebyshev(&coefficients, x - h).0) / (2.0 * h);
+        assert!((derivative - numeric).abs() < 1e-8);
+    }
+}
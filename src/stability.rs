@@ -0,0 +1,574 @@
+/// Stability analysis with chaos indicators
+/// Live MEGNO / Lyapunov tracking for the running simulation, headless indicator runs
+/// of a scenario, and stability maps over a grid of test-particle orbits
+use std::collections::VecDeque;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use glam::DVec3;
+
+use crate::{
+    AstrariaError, AstrariaResult,
+    math::{
+        GRAVITATIONAL_CONSTANT, TangentVector, VariationalIntegrator, characteristic_time_scale,
+    },
+    scenario::Scenario,
+};
+
+/// Default simulated seconds between indicator samples
+pub const DEFAULT_CHAOS_SAMPLE_INTERVAL: f64 = 1.0;
+
+/// Mean MEGNO above which an orbit is treated as fully chaotic and integration stops
+pub const MEGNO_SATURATION: f64 = 16.0;
+
+/// Number of samples kept per tracked body for plotting
+const MAX_HISTORY: usize = 5000;
+
+/// Indicator values at one instant
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChaosSample {
+    pub time: f64,
+    /// Instantaneous MEGNO, Y
+    pub megno: f64,
+    /// Time-averaged MEGNO, <Y>
+    pub mean_megno: f64,
+    /// Maximum Lyapunov exponent estimate (1/s)
+    pub lyapunov_exponent: f64,
+}
+
+impl ChaosSample {
+    fn from_tangent(tangent: &TangentVector, time: f64) -> Self {
+        Self {
+            time,
+            megno: tangent.indicator.megno(),
+            mean_megno: tangent.indicator.mean_megno(),
+            lyapunov_exponent: tangent.indicator.lyapunov_exponent(),
+        }
+    }
+}
+
+/// A body of the live simulation whose chaos indicators are being measured
+#[derive(Debug, Clone)]
+pub struct TrackedBody {
+    pub name: String,
+    pub tangent: TangentVector,
+    history: VecDeque<ChaosSample>,
+}
+
+impl TrackedBody {
+    /// Samples, oldest first
+    pub fn history(&self) -> &VecDeque<ChaosSample> {
+        &self.history
+    }
+}
+
+/// Variational equations integrated alongside the live simulation
+pub struct ChaosTracker {
+    interval: f64,
+    last_sample_time: Option<f64>,
+    tracked: Vec<TrackedBody>,
+}
+
+impl Default for ChaosTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_CHAOS_SAMPLE_INTERVAL)
+    }
+}
+
+impl ChaosTracker {
+    pub fn new(interval: f64) -> Self {
+        Self {
+            interval,
+            last_sample_time: None,
+            tracked: Vec::new(),
+        }
+    }
+
+    /// Check whether any body is being tracked
+    pub fn is_empty(&self) -> bool {
+        self.tracked.is_empty()
+    }
+
+    pub fn tracked(&self) -> &[TrackedBody] {
+        &self.tracked
+    }
+
+    pub fn is_tracked(&self, body: usize) -> bool {
+        self.tracked
+            .iter()
+            .any(|tracked| tracked.tangent.body == body)
+    }
+
+    /// Simulated seconds between samples
+    pub fn interval(&self) -> f64 {
+        self.interval
+    }
+
+    pub fn set_interval(&mut self, interval: f64) {
+        self.interval = interval.max(0.0);
+    }
+
+    /// Start integrating a tangent vector seeded at `body`
+    pub fn track(
+        &mut self,
+        body: usize,
+        name: String,
+        masses: &[f64],
+        positions: &[DVec3],
+        velocities: &[DVec3],
+    ) {
+        if body >= masses.len() || self.is_tracked(body) {
+            return;
+        }
+        let time_scale = characteristic_time_scale(masses, positions, velocities, body);
+        self.tracked.push(TrackedBody {
+            name,
+            tangent: TangentVector::new(body, masses.len(), time_scale),
+            history: VecDeque::new(),
+        });
+    }
+
+    pub fn untrack(&mut self, body: usize) {
+        self.tracked.retain(|tracked| tracked.tangent.body != body);
+    }
+
+    /// Stop tracking everything (e.g. when a new scenario is loaded)
+    pub fn clear(&mut self) {
+        self.tracked.clear();
+        self.last_sample_time = None;
+    }
+
+    /// Advance every tangent vector by one integration step of the simulation
+    pub fn step(
+        &mut self,
+        masses: &[f64],
+        old_positions: &[DVec3],
+        new_positions: &[DVec3],
+        delta_time: f64,
+        time: f64,
+    ) {
+        if self
+            .tracked
+            .iter()
+            .any(|tracked| tracked.tangent.body_count() != masses.len())
+        {
+            log::warn!("Body count changed; chaos indicator tracking stopped");
+            self.clear();
+            return;
+        }
+
+        for tracked in &mut self.tracked {
+            tracked
+                .tangent
+                .step(masses, old_positions, new_positions, delta_time);
+        }
+
+        if self
+            .last_sample_time
+            .is_none_or(|last| time - last >= self.interval)
+        {
+            self.last_sample_time = Some(time);
+            for tracked in &mut self.tracked {
+                if tracked.history.len() >= MAX_HISTORY {
+                    tracked.history.pop_front();
+                }
+                tracked
+                    .history
+                    .push_back(ChaosSample::from_tangent(&tracked.tangent, time));
+            }
+        }
+    }
+}
+
+/// Masses and initial states of a scenario's bodies
+fn scenario_states(scenario: &Scenario) -> (Vec<f64>, Vec<DVec3>, Vec<DVec3>) {
+    let masses = scenario.bodies.iter().map(|body| body.mass).collect();
+    let positions = scenario.bodies.iter().map(|body| body.position).collect();
+    let velocities = scenario.bodies.iter().map(|body| body.velocity).collect();
+    (masses, positions, velocities)
+}
+
+fn find_body(scenario: &Scenario, name: &str) -> AstrariaResult<usize> {
+    scenario
+        .bodies
+        .iter()
+        .position(|body| body.name == name)
+        .ok_or_else(|| AstrariaError::Physics(format!("No body named '{}' in scenario", name)))
+}
+
+/// Indicator history of selected bodies from a headless integration
+#[derive(Debug, Clone, Default)]
+pub struct IndicatorRun {
+    pub names: Vec<String>,
+    /// One row per sample time, one entry per body in `names`
+    pub samples: Vec<Vec<ChaosSample>>,
+}
+
+impl IndicatorRun {
+    /// Integrate a scenario for `duration` seconds and sample the indicators of the named bodies
+    pub fn compute(
+        scenario: &Scenario,
+        body_names: &[String],
+        duration: f64,
+        time_step: f64,
+        sample_interval: f64,
+    ) -> AstrariaResult<Self> {
+        if time_step <= 0.0 {
+            return Err(AstrariaError::Physics(
+                "Time step must be positive".to_string(),
+            ));
+        }
+
+        let (masses, positions, velocities) = scenario_states(scenario);
+        let mut integrator = VariationalIntegrator::new(masses, positions, velocities);
+        for name in body_names {
+            integrator.track(find_body(scenario, name)?);
+        }
+
+        let mut run = Self {
+            names: body_names.to_vec(),
+            samples: Vec::new(),
+        };
+        let mut next_sample = sample_interval;
+        while integrator.time() < duration {
+            integrator.step(time_step.min(duration - integrator.time()));
+            if integrator.time() >= next_sample || integrator.time() >= duration {
+                let time = integrator.time();
+                run.samples.push(
+                    integrator
+                        .tangents()
+                        .iter()
+                        .map(|tangent| ChaosSample::from_tangent(tangent, time))
+                        .collect(),
+                );
+                next_sample += sample_interval.max(time_step);
+            }
+        }
+        Ok(run)
+    }
+
+    /// Write the samples as CSV: time, then MEGNO, mean MEGNO and Lyapunov exponent per body
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_csv(&self, path: &Path) -> AstrariaResult<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        write!(writer, "time_s")?;
+        for name in &self.names {
+            write!(
+                writer,
+                ",{name}_megno,{name}_mean_megno,{name}_lyapunov_per_s"
+            )?;
+        }
+        writeln!(writer)?;
+
+        for row in &self.samples {
+            write!(writer, "{}", row.first().map_or(0.0, |sample| sample.time))?;
+            for sample in row {
+                write!(
+                    writer,
+                    ",{:e},{:e},{:e}",
+                    sample.megno, sample.mean_megno, sample.lyapunov_exponent
+                )?;
+            }
+            writeln!(writer)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Grid of test-particle orbits to evaluate
+#[derive(Debug, Clone, PartialEq)]
+pub struct StabilityMapSettings {
+    /// Body the test particle orbits; `None` orbits the barycentre of the whole system
+    pub host: Option<String>,
+    /// Semi-major axis range (m)
+    pub semi_major_axis: (f64, f64),
+    /// Eccentricity range
+    pub eccentricity: (f64, f64),
+    /// Grid cells along semi-major axis and eccentricity
+    pub resolution: (usize, usize),
+    /// Integration time per cell (s)
+    pub duration: f64,
+    pub time_step: f64,
+}
+
+/// Mean MEGNO over a grid of semi-major axis and eccentricity
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StabilityMap {
+    pub semi_major_axes: Vec<f64>,
+    pub eccentricities: Vec<f64>,
+    /// Row-major by eccentricity
+    pub mean_megno: Vec<f64>,
+}
+
+impl StabilityMap {
+    /// Integrate a massless test particle from periapsis for every grid cell
+    pub fn compute(scenario: &Scenario, settings: &StabilityMapSettings) -> AstrariaResult<Self> {
+        if settings.time_step <= 0.0 || settings.duration <= 0.0 {
+            return Err(AstrariaError::Physics(
+                "Duration and time step must be positive".to_string(),
+            ));
+        }
+
+        let (masses, positions, velocities) = scenario_states(scenario);
+        let total_mass: f64 = masses.iter().sum();
+        let (central_mass, origin, origin_velocity) = match &settings.host {
+            Some(name) => {
+                let host = find_body(scenario, name)?;
+                (masses[host], positions[host], velocities[host])
+            }
+            None if total_mass > 0.0 => (
+                total_mass,
+                positions
+                    .iter()
+                    .zip(&masses)
+                    .map(|(p, m)| *p * *m)
+                    .sum::<DVec3>()
+                    / total_mass,
+                velocities
+                    .iter()
+                    .zip(&masses)
+                    .map(|(v, m)| *v * *m)
+                    .sum::<DVec3>()
+                    / total_mass,
+            ),
+            None => {
+                return Err(AstrariaError::Physics(
+                    "Scenario has no mass to orbit".to_string(),
+                ));
+            }
+        };
+
+        // Orbit in the plane of the system's angular momentum
+        let normal = positions
+            .iter()
+            .zip(&velocities)
+            .zip(&masses)
+            .map(|((p, v), m)| (*p - origin).cross(*v - origin_velocity) * *m)
+            .sum::<DVec3>()
+            .try_normalize()
+            .unwrap_or(DVec3::Z);
+        let periapsis_direction = normal.any_orthonormal_vector();
+        let prograde = normal.cross(periapsis_direction);
+        let mu = GRAVITATIONAL_CONSTANT * central_mass;
+
+        let sweep = |(start, end): (f64, f64), steps: usize| -> Vec<f64> {
+            (0..steps)
+                .map(|i| start + (end - start) * i as f64 / (steps.max(2) - 1) as f64)
+                .collect()
+        };
+        let semi_major_axes = sweep(settings.semi_major_axis, settings.resolution.0);
+        let eccentricities = sweep(settings.eccentricity, settings.resolution.1);
+
+        let cell = |index: usize| {
+            let semi_major_axis = semi_major_axes[index % semi_major_axes.len()];
+            let eccentricity = eccentricities[index / semi_major_axes.len()];
+            let periapsis = semi_major_axis * (1.0 - eccentricity);
+            let speed = (mu * (1.0 + eccentricity) / periapsis).sqrt();
+
+            let mut cell_masses = masses.clone();
+            let mut cell_positions = positions.clone();
+            let mut cell_velocities = velocities.clone();
+            cell_masses.push(0.0);
+            cell_positions.push(origin + periapsis_direction * periapsis);
+            cell_velocities.push(origin_velocity + prograde * speed);
+            Self::test_particle_megno(
+                cell_masses,
+                cell_positions,
+                cell_velocities,
+                settings.duration,
+                settings.time_step,
+            )
+        };
+
+        let cells = semi_major_axes.len() * eccentricities.len();
+        let mut mean_megno = vec![0.0; cells];
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+            let chunk = cells.div_ceil(threads).max(1);
+            std::thread::scope(|scope| {
+                for (chunk_index, values) in mean_megno.chunks_mut(chunk).enumerate() {
+                    let cell = &cell;
+                    scope.spawn(move || {
+                        for (offset, value) in values.iter_mut().enumerate() {
+                            *value = cell(chunk_index * chunk + offset);
+                        }
+                    });
+                }
+            });
+        }
+        #[cfg(target_arch = "wasm32")]
+        for (index, value) in mean_megno.iter_mut().enumerate() {
+            *value = cell(index);
+        }
+
+        Ok(Self {
+            semi_major_axes,
+            eccentricities,
+            mean_megno,
+        })
+    }
+
+    /// Mean MEGNO of the last body (the test particle), capped at `MEGNO_SATURATION`
+    fn test_particle_megno(
+        masses: Vec<f64>,
+        positions: Vec<DVec3>,
+        velocities: Vec<DVec3>,
+        duration: f64,
+        time_step: f64,
+    ) -> f64 {
+        let particle = masses.len() - 1;
+        let mut integrator = VariationalIntegrator::new(masses, positions, velocities);
+        integrator.track(particle);
+
+        while integrator.time() < duration {
+            integrator.step(time_step);
+            let megno = integrator.tangents()[0].indicator.mean_megno();
+            if !megno.is_finite() || !integrator.positions[particle].is_finite() {
+                return MEGNO_SATURATION;
+            }
+            if megno >= MEGNO_SATURATION {
+                break;
+            }
+        }
+        integrator.tangents()[0]
+            .indicator
+            .mean_megno()
+            .min(MEGNO_SATURATION)
+    }
+
+    /// Mean MEGNO at a grid cell
+    pub fn get(&self, semi_major_axis_index: usize, eccentricity_index: usize) -> Option<f64> {
+        self.mean_megno
+            .get(eccentricity_index * self.semi_major_axes.len() + semi_major_axis_index)
+            .copied()
+    }
+
+    /// Colour-coded map: dark blue where <Y> is about 2 (regular), through red to
+    /// yellow at saturation (chaotic). Eccentricity increases upwards.
+    pub fn to_image(&self) -> image::RgbImage {
+        let width = self.semi_major_axes.len() as u32;
+        let height = self.eccentricities.len() as u32;
+        image::RgbImage::from_fn(width, height, |x, y| {
+            let value = self
+                .get(x as usize, (height - 1 - y) as usize)
+                .unwrap_or(MEGNO_SATURATION);
+            image::Rgb(megno_colour(value))
+        })
+    }
+
+    /// Save the map as a PNG image
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save_png(&self, path: &Path) -> AstrariaResult<()> {
+        self.to_image().save(path).map_err(|e| {
+            AstrariaError::AssetLoading(format!("Failed to write {}: {}", path.display(), e))
+        })
+    }
+}
+
+/// Colour for a mean MEGNO value
+pub fn megno_colour(value: f64) -> [u8; 3] {
+    const STOPS: [[f64; 3]; 4] = [
+        [20.0, 30.0, 110.0],
+        [40.0, 160.0, 200.0],
+        [210.0, 50.0, 40.0],
+        [255.0, 240.0, 90.0],
+    ];
+    let t = ((value - 2.0) / (MEGNO_SATURATION - 2.0))
+        .clamp(0.0, 1.0)
+        .sqrt();
+    let scaled = t * (STOPS.len() - 1) as f64;
+    let index = (scaled as usize).min(STOPS.len() - 2);
+    let fraction = scaled - index as f64;
+    let lerp = |channel: usize| {
+        (STOPS[index][channel] + (STOPS[index + 1][channel] - STOPS[index][channel]) * fraction)
+            as u8
+    };
+    [lerp(0), lerp(1), lerp(2)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        math::{AU_TO_METERS, SOLAR_MASS},
+        scenario::{BodyType, RotationMode, ScenarioBody},
+    };
+
+    fn star(name: &str, mass: f64, position: DVec3, velocity: DVec3) -> ScenarioBody {
+        ScenarioBody {
+            name: name.to_string(),
+            mass,
+            position,
+            velocity,
+            body_type: BodyType::Star {
+                radius: 7.0e8,
+                texture_path: String::new(),
+                temperature: 5778.0,
+            },
+            orbit_color: [1.0; 4],
+            rotation_params: (0.0, 0.0, 0.0, 0.0),
+            rotation_mode: RotationMode::Fixed,
+        }
+    }
+
+    #[test]
+    fn test_single_star_map_is_regular() {
+        let scenario = Scenario {
+            bodies: vec![star("Sun", SOLAR_MASS, DVec3::ZERO, DVec3::ZERO)],
+            barycentric: None,
+            ..Default::default()
+        };
+        let year = 365.25 * 86400.0;
+        let settings = StabilityMapSettings {
+            host: Some("Sun".to_string()),
+            semi_major_axis: (AU_TO_METERS, 2.0 * AU_TO_METERS),
+            eccentricity: (0.0, 0.5),
+            resolution: (2, 2),
+            duration: 20.0 * year,
+            time_step: year / 2000.0,
+        };
+
+        let map = StabilityMap::compute(&scenario, &settings).unwrap();
+        assert_eq!(map.mean_megno.len(), 4);
+        for value in &map.mean_megno {
+            assert!((value - 2.0).abs() < 0.5, "mean MEGNO {value}");
+        }
+
+        let image = map.to_image();
+        assert_eq!(image.dimensions(), (2, 2));
+    }
+
+    #[test]
+    fn test_tracker_samples_tracked_bodies() {
+        let masses = [SOLAR_MASS, 0.0];
+        let positions = [DVec3::ZERO, DVec3::new(AU_TO_METERS, 0.0, 0.0)];
+        let velocities = [DVec3::ZERO, DVec3::new(0.0, 29_780.0, 0.0)];
+
+        let mut tracker = ChaosTracker::new(10.0);
+        tracker.track(1, "Earth".to_string(), &masses, &positions, &velocities);
+        tracker.track(1, "Earth".to_string(), &masses, &positions, &velocities);
+        assert_eq!(tracker.tracked().len(), 1);
+
+        let moved = [DVec3::ZERO, positions[1] + velocities[1]];
+        tracker.step(&masses, &positions, &moved, 1.0, 1.0);
+        assert_eq!(tracker.tracked()[0].history().len(), 1);
+        tracker.step(&masses, &moved, &moved, 1.0, 2.0);
+        assert_eq!(tracker.tracked()[0].history().len(), 1);
+
+        // A spawned body invalidates the tangent vectors
+        tracker.step(
+            &[SOLAR_MASS, 0.0, 0.0],
+            &[DVec3::ZERO; 3],
+            &[DVec3::ZERO; 3],
+            1.0,
+            3.0,
+        );
+        assert!(tracker.is_empty());
+    }
+}
@@ -3,8 +3,20 @@
 use egui_winit::winit;
 use winit::dpi::PhysicalSize;
 
-use crate::{AstrariaResult, physics::PhysicsSimulation, renderer::Renderer};
-use glam::DVec3;
+use crate::{
+    AstrariaResult,
+    ephemeris::{EphemerisComparison, EphemerisError, EphemerisSummary},
+    math::{
+        Body, GRAVITATIONAL_CONSTANT, LAGRANGE_POINT_NAMES, LagrangePoints, Maneuver,
+        OrbitalHierarchy, PorkchopPlot, ReferenceFrame, Transfer,
+    },
+    photometry::{EventKind, LightCurve, LimbDarkening, Observer, PhotometricBody},
+    physics::PhysicsSimulation,
+    renderer::Renderer,
+    satellites::DEFAULT_TRAIL_LENGTH,
+    scenario::{Scenario, generators::Generator},
+};
+use glam::{DMat4, DVec3, DVec4};
 
 /// Actions that the UI can request from the application
 #[derive(Debug, Clone)]
@@ -15,27 +27,444 @@ pub enum UiAction {
         radius: f64,
     },
     ClearCameraFocus,
+    SetReferenceFrame(ReferenceFrame),
+    SetManeuvers {
+        body_index: usize,
+        maneuvers: Vec<Maneuver>,
+    },
+    SpawnAtLagrangePoint {
+        primary: usize,
+        secondary: usize,
+        point: usize,
+    },
+    SpawnBody(Box<Body>),
+    /// Move the camera to one of the scenario's bookmarks, by index
+    ApplyCameraBookmark(usize),
+    /// Replace the simulation with a freshly generated scenario
+    LoadGeneratedScenario(Box<Scenario>),
+}
+
+/// State of the Lagrange Points window
+#[derive(Debug, Clone, Copy, Default)]
+struct LagrangeSettings {
+    show_window: bool,
+    show_markers: bool,
+    /// Primary/secondary pair, chosen from the selection when first opened
+    pair: Option<(usize, usize)>,
+}
+
+/// Work too slow for a frame, run on its own thread and collected once it finishes.
+/// The web build has no threads, so there it runs in place.
+struct BackgroundTask<T> {
+    #[cfg(not(target_arch = "wasm32"))]
+    handle: Option<std::thread::JoinHandle<T>>,
+    #[cfg(target_arch = "wasm32")]
+    result: Option<T>,
+}
+
+impl<T> Default for BackgroundTask<T> {
+    fn default() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            handle: None,
+            #[cfg(target_arch = "wasm32")]
+            result: None,
+        }
+    }
+}
+
+impl<T: Send + 'static> BackgroundTask<T> {
+    /// Start `work`; the result of an earlier run that was not collected is dropped
+    #[cfg(not(target_arch = "wasm32"))]
+    fn start(&mut self, work: impl FnOnce() -> T + Send + 'static) {
+        self.handle = Some(std::thread::spawn(work));
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn start(&mut self, work: impl FnOnce() -> T + Send + 'static) {
+        self.result = Some(work());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn is_running(&self) -> bool {
+        self.handle
+            .as_ref()
+            .is_some_and(|handle| !handle.is_finished())
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn is_running(&self) -> bool {
+        false
+    }
+
+    /// The result once the work has finished, or None while it runs
+    #[cfg(not(target_arch = "wasm32"))]
+    fn take_finished(&mut self) -> Option<T> {
+        if self.is_running() {
+            return None;
+        }
+        match self.handle.take()?.join() {
+            Ok(result) => Some(result),
+            Err(_) => {
+                log::error!("Background computation panicked");
+                None
+            }
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn take_finished(&mut self) -> Option<T> {
+        self.result.take()
+    }
+}
+
+/// Inputs a porkchop plot was computed from, needed to turn a picked cell back into a transfer
+#[derive(Debug, Clone, Copy)]
+struct PorkchopContext {
+    departure_body: usize,
+    arrival_body: usize,
+    central_body: usize,
+    mu: f64,
+    /// Departure and arrival body states relative to the central body at `epoch`
+    departure_state: (DVec3, DVec3),
+    arrival_state: (DVec3, DVec3),
+    /// Simulation time the states were sampled at
+    epoch: f64,
+}
+
+/// State of the Transfer Planner (porkchop plot) window
+struct PorkchopSettings {
+    show_window: bool,
+    departure_body: usize,
+    arrival_body: usize,
+    /// Sweep ranges in days from the moment the plot is computed
+    departure_days: (f64, f64),
+    arrival_days: (f64, f64),
+    plot: Option<(PorkchopPlot, PorkchopContext)>,
+    /// Grid being swept, with the inputs and the central body's name
+    task: BackgroundTask<(PorkchopPlot, PorkchopContext, String)>,
+    texture: Option<egui::TextureHandle>,
+    selected: Option<(usize, usize)>,
+    status: String,
+}
+
+impl Default for PorkchopSettings {
+    fn default() -> Self {
+        Self {
+            show_window: false,
+            departure_body: 0,
+            arrival_body: 0,
+            departure_days: (0.0, 365.0),
+            arrival_days: (100.0, 700.0),
+            plot: None,
+            task: BackgroundTask::default(),
+            texture: None,
+            selected: None,
+            status: String::new(),
+        }
+    }
+}
+
+/// State of the Conservation Diagnostics window
+struct DiagnosticsSettings {
+    show_window: bool,
+    /// File the CSV log is written to (native only)
+    csv_path: String,
+    csv_error: Option<String>,
+}
+
+impl Default for DiagnosticsSettings {
+    fn default() -> Self {
+        Self {
+            show_window: false,
+            csv_path: "conservation_diagnostics.csv".to_string(),
+            csv_error: None,
+        }
+    }
+}
+
+/// State of the Chaos Indicators window
+#[derive(Default, Clone, Copy)]
+struct ChaosSettings {
+    show_window: bool,
+    /// Body to start tracking
+    body: usize,
+}
+
+/// State of the Resonances window
+#[derive(Default, Clone, Copy)]
+struct ResonanceSettings {
+    show_window: bool,
+    /// Tracked resonance whose angles are plotted
+    selected: usize,
+}
+
+/// State of the Photometry window
+struct PhotometrySettings {
+    show_window: bool,
+    /// Observe from this body, or from far away along `direction`
+    observer_body: usize,
+    distant: bool,
+    direction: [f64; 3],
+    limb_darkening: LimbDarkening,
+    duration_days: f64,
+    time_step: f64,
+    sample_interval: f64,
+    curve: Option<LightCurve>,
+    task: BackgroundTask<AstrariaResult<LightCurve>>,
+    csv_path: String,
+    status: String,
+}
+
+impl Default for PhotometrySettings {
+    fn default() -> Self {
+        Self {
+            show_window: false,
+            observer_body: 0,
+            distant: false,
+            direction: [0.0, 0.0, 1.0],
+            limb_darkening: LimbDarkening::default(),
+            duration_days: 30.0,
+            time_step: 60.0,
+            sample_interval: 600.0,
+            curve: None,
+            task: BackgroundTask::default(),
+            csv_path: "light_curve.csv".to_string(),
+            status: String::new(),
+        }
+    }
+}
+
+/// Loading or removing the satellite layer, applied to the renderer after the frame is built
+#[derive(Clone, Copy, PartialEq)]
+enum SatelliteRequest {
+    Load,
+    Unload,
+}
+
+/// Summary of the loaded satellite layer shown in the Satellites window
+struct SatelliteSummary {
+    count: usize,
+    visible: usize,
+    central_body: String,
+}
+
+/// State of the Satellites window
+struct SatelliteSettings {
+    show_window: bool,
+    tle_path: String,
+    request: Option<SatelliteRequest>,
+    /// UTC Julian date at simulation time zero
+    epoch: f64,
+    show_trails: bool,
+    trail_length: usize,
+    loaded: Option<SatelliteSummary>,
+    status: String,
+}
+
+impl Default for SatelliteSettings {
+    fn default() -> Self {
+        Self {
+            show_window: false,
+            tle_path: "satellites.tle".to_string(),
+            request: None,
+            epoch: 0.0,
+            show_trails: false,
+            trail_length: DEFAULT_TRAIL_LENGTH,
+            loaded: None,
+            status: String::new(),
+        }
+    }
+}
+
+/// State of the Ephemeris Comparison window
+struct EphemerisSettings {
+    show_window: bool,
+    reference_path: String,
+    /// SPK kernel sampled into a reference, from a start date (JD, TDB) for some days
+    spk_path: String,
+    spk_start: f64,
+    spk_days: f64,
+    summary_path: String,
+    /// Body both trajectories are measured from, empty for the raw simulation frame
+    relative_to: String,
+    /// Compared body whose error curves are plotted
+    selected: usize,
+    status: String,
+}
+
+impl Default for EphemerisSettings {
+    fn default() -> Self {
+        Self {
+            show_window: false,
+            reference_path: "reference.csv".to_string(),
+            spk_path: "de440s.bsp".to_string(),
+            spk_start: 2451545.0,
+            spk_days: 365.0,
+            summary_path: "ephemeris_summary.csv".to_string(),
+            relative_to: String::new(),
+            selected: 0,
+            status: String::new(),
+        }
+    }
+}
+
+/// What the Ephemeris Comparison window draws, copied out of the live comparison
+struct EphemerisSnapshot {
+    /// Reference body count and the time span it covers (s)
+    reference: Option<(usize, (f64, f64))>,
+    interval: f64,
+    /// Name and latest error of each compared body
+    latest: Vec<(String, Option<EphemerisError>)>,
+    /// Summary and error history of the selected body
+    selected: Option<(EphemerisSummary, Vec<EphemerisError>)>,
+}
+
+impl EphemerisSnapshot {
+    fn capture(comparison: &EphemerisComparison, selected: usize) -> Self {
+        let reference = comparison.reference().map(|reference| {
+            let span = reference
+                .bodies
+                .iter()
+                .filter_map(|body| body.span())
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(start, end), span| {
+                    (start.min(span.0), end.max(span.1))
+                });
+            (reference.bodies.len(), span)
+        });
+        Self {
+            reference,
+            interval: comparison.interval(),
+            latest: comparison
+                .bodies()
+                .iter()
+                .map(|body| (body.name.clone(), body.latest().copied()))
+                .collect(),
+            selected: comparison
+                .bodies()
+                .get(selected)
+                .map(|body| (body.summary(), body.history().iter().copied().collect())),
+        }
+    }
+}
+
+/// State of the Save Scenario window
+struct SaveScenarioSettings {
+    show_window: bool,
+    path: String,
+    status: String,
+}
+
+impl Default for SaveScenarioSettings {
+    fn default() -> Self {
+        Self {
+            show_window: false,
+            path: "assets/examples/Saved_Scenario.txt".to_string(),
+            status: String::new(),
+        }
+    }
+}
+
+/// Outcome of the last reload of the scenario from disk
+#[derive(Default)]
+struct ScenarioReloadSettings {
+    show_window: bool,
+    status: String,
+    /// Validation problems, each prefixed with the file it was found in
+    diagnostics: Vec<String>,
+}
+
+/// State of the New Scenario window
+struct NewScenarioSettings {
+    show_window: bool,
+    generator: Generator,
+    seed: u64,
+}
+
+impl Default for NewScenarioSettings {
+    fn default() -> Self {
+        Self {
+            show_window: false,
+            generator: Generator::PlanetarySystem(Default::default()),
+            seed: 1,
+        }
+    }
+}
+
+/// Window toggles and per-window settings, handed to each frame's UI pass together
+#[derive(Default)]
+struct UiPanels {
+    show_controls: bool,
+    show_info: bool,
+    show_stats: bool,
+    show_object_list: bool,
+    show_orbital_paths: bool,
+    lagrange: LagrangeSettings,
+    porkchop: PorkchopSettings,
+    diagnostics: DiagnosticsSettings,
+    chaos: ChaosSettings,
+    resonance: ResonanceSettings,
+    photometry: PhotometrySettings,
+    satellites: SatelliteSettings,
+    ephemeris: EphemerisSettings,
+    save_scenario: SaveScenarioSettings,
+    new_scenario: NewScenarioSettings,
+    scenario_reload: ScenarioReloadSettings,
 }
 
+/// Orbital hierarchy shared by the windows that list or plan against it
+#[derive(Default)]
+struct HierarchyCache {
+    hierarchy: OrbitalHierarchy,
+    frames_since_build: u32,
+}
+
+impl HierarchyCache {
+    /// Frames a hierarchy is reused for before it is rebuilt
+    const REFRESH_FRAMES: u32 = 30;
+
+    /// Rebuild from the inertial state when stale or when bodies were added or removed.
+    /// View-frame velocities would skew the bound-orbit test in rotating frames.
+    fn refresh(&mut self, physics: &PhysicsSimulation, body_count: usize) {
+        if self.hierarchy.len() == body_count && self.frames_since_build < Self::REFRESH_FRAMES {
+            self.frames_since_build += 1;
+            return;
+        }
+        if let Ok(bodies) = physics.get_bodies() {
+            self.hierarchy = OrbitalHierarchy::from_bodies(&bodies);
+            self.frames_since_build = 0;
+        }
+    }
+}
+
+/// Grid resolution of the porkchop plot
+const PORKCHOP_STEPS: usize = 80;
+
+/// Seconds between the states sampled from an SPK kernel for the ephemeris comparison
+const SPK_SAMPLE_STEP: f64 = 6.0 * 3600.0;
+
 pub struct UserInterface {
     egui_ctx: egui::Context,
     egui_winit: egui_winit::State,
     egui_renderer: egui_wgpu::Renderer,
 
     // UI state
-    show_controls: bool,
-    show_info: bool,
-    show_stats: bool,
-    show_object_list: bool,
     simulation_speed: f32,
+    /// Speed last exchanged with the physics, to tell slider changes from scenario ones
+    synced_speed: f32,
     selected_object_index: Option<usize>,
     pending_actions: Vec<UiAction>,
     ui_visible: bool,
     
     // Orbital path controls (like Java Options.drawOrbits)
-    show_orbital_paths: bool,
     orbital_path_history_length: i32,
     orbital_path_segment_distance: f32,  // In km
+
+    // Viewing frame for positions and trails
+    reference_frame: ReferenceFrame,
+
+    panels: UiPanels,
+    hierarchy: HierarchyCache,
 }
 
 impl UserInterface {
@@ -66,19 +495,26 @@ impl UserInterface {
             egui_ctx,
             egui_winit,
             egui_renderer,
-            show_controls: true,
-            show_info: true,
-            show_stats: false,
-            show_object_list: true,
             simulation_speed: 1.0,
+            synced_speed: 1.0,
             selected_object_index: None,
             pending_actions: Vec::new(),
             ui_visible: true,
             
             // Default orbital path settings (same as Java)
-            show_orbital_paths: true,  // Like Options.drawOrbits = true
             orbital_path_history_length: 500,  // Like Java MAX_POINTS
             orbital_path_segment_distance: 5000.0,  // Like Java segmentLength (5000 km)
+
+            reference_frame: ReferenceFrame::Inertial,
+
+            panels: UiPanels {
+                show_controls: true,
+                show_info: true,
+                show_object_list: true,
+                show_orbital_paths: true, // Like Options.drawOrbits = true
+                ..UiPanels::default()
+            },
+            hierarchy: HierarchyCache::default(),
         })
     }
 
@@ -113,12 +549,16 @@ impl UserInterface {
         physics: Option<&PhysicsSimulation>,
         _renderer: &mut Option<Renderer>,
     ) -> AstrariaResult<()> {
-        // Update simulation speed if changed
+        // Push slider changes to the physics, and adopt speeds set by a loaded scenario
         if let Some(physics) = physics {
-            if let Ok(current_speed) = physics.get_simulation_speed() {
-                if (current_speed - self.simulation_speed).abs() > 1e-6 {
-                    let _ = physics.set_simulation_speed(self.simulation_speed);
-                }
+            if (self.simulation_speed - self.synced_speed).abs() > 1e-6 {
+                let _ = physics.set_simulation_speed(self.simulation_speed);
+                self.synced_speed = self.simulation_speed;
+            } else if let Ok(current_speed) = physics.get_simulation_speed()
+                && (current_speed - self.synced_speed).abs() > 1e-6
+            {
+                self.simulation_speed = current_speed;
+                self.synced_speed = current_speed;
             }
         }
 
@@ -172,54 +612,103 @@ impl UserInterface {
             }
         }
 
-        let mut show_controls = self.show_controls;
-        let mut show_info = self.show_info;
-        let mut show_stats = self.show_stats;
-        let mut show_object_list = self.show_object_list;
         let mut simulation_speed = self.simulation_speed;
         let mut selected_object_index = self.selected_object_index;
         let mut pending_actions = Vec::new();
-        let mut show_orbital_paths = self.show_orbital_paths;
         let mut orbital_path_history_length = self.orbital_path_history_length;
         let mut orbital_path_segment_distance = self.orbital_path_segment_distance;
+        let mut reference_frame = self.reference_frame;
+        let mut panels = std::mem::take(&mut self.panels);
+        let satellites = &mut panels.satellites;
+        satellites.loaded = renderer.satellites().map(|layer| {
+            satellites.epoch = layer.epoch();
+            satellites.show_trails = layer.show_trails;
+            satellites.trail_length = layer.trail_length;
+            SatelliteSummary {
+                count: layer.len(),
+                visible: layer.visible_count(),
+                central_body: layer.central_body.clone(),
+            }
+        });
         let ui_visible = self.ui_visible;
 
         // Get physics data for object list
         let bodies = if let Some(physics) = physics {
-            physics.get_bodies().unwrap_or_default()
+            physics.get_view_bodies().unwrap_or_default()
         } else {
             Vec::new()
         };
 
+        // Work out which body orbits which, but only while a window lists or plans with it
+        let spacecraft_selected = selected_object_index
+            .and_then(|index| bodies.get(index))
+            .is_some_and(|body| {
+                matches!(body.body_type, crate::scenario::BodyType::Spacecraft { .. })
+            });
+        let hierarchy_needed = panels.show_object_list
+            || panels.porkchop.show_window
+            || spacecraft_selected
+            || (panels.lagrange.show_window && panels.lagrange.pair.is_none());
+        if hierarchy_needed && let Some(physics) = physics {
+            self.hierarchy.refresh(physics, bodies.len());
+        }
+        let hierarchy = &self.hierarchy.hierarchy;
+
+        // Lagrange points are solved in the inertial frame, then shown in the viewing frame
+        let lagrange_points = match (panels.lagrange.pair, physics) {
+            (Some((primary, secondary)), Some(physics)) => physics
+                .get_bodies()
+                .ok()
+                .and_then(|inertial| LagrangePoints::for_pair(&inertial, primary, secondary)),
+            _ => None,
+        };
+        let marker_positions: Vec<DVec3> = match (&lagrange_points, physics) {
+            (Some(points), Some(physics)) if panels.lagrange.show_markers => {
+                let view_transform = physics.get_view_transform().unwrap_or_default();
+                points
+                    .positions
+                    .iter()
+                    .map(|position| view_transform.position_to_frame(*position))
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        let view_projection = renderer.camera().view_projection_matrix();
+
         let full_output = self.egui_ctx.run(raw_input, |ctx| {
             Self::render_ui_static(
                 ctx,
-                &mut show_controls,
-                &mut show_info,
-                &mut show_stats,
-                &mut show_object_list,
                 &mut simulation_speed,
                 &mut selected_object_index,
                 &bodies,
                 &mut pending_actions,
                 ui_visible,
                 physics,
-                &mut show_orbital_paths,
                 &mut orbital_path_history_length,
                 &mut orbital_path_segment_distance,
+                hierarchy,
+                &mut reference_frame,
+                lagrange_points.as_ref(),
+                &mut panels,
             );
+            if ui_visible {
+                Self::paint_lagrange_labels(ctx, &marker_positions, view_projection);
+            }
         });
 
         // Update state after rendering
-        self.show_controls = show_controls;
-        self.show_info = show_info;
-        self.show_stats = show_stats;
-        self.show_object_list = show_object_list;
         self.simulation_speed = simulation_speed;
         self.selected_object_index = selected_object_index;
-        self.show_orbital_paths = show_orbital_paths;
         self.orbital_path_history_length = orbital_path_history_length;
         self.orbital_path_segment_distance = orbital_path_segment_distance;
+        Self::apply_satellite_settings(&mut panels.satellites, renderer);
+        let lagrange = panels.lagrange;
+        renderer.set_lagrange_markers(lagrange.pair.filter(|_| lagrange.show_markers));
+        self.panels = panels;
+        if reference_frame != self.reference_frame {
+            self.reference_frame = reference_frame;
+            pending_actions.push(UiAction::SetReferenceFrame(reference_frame));
+        }
 
         // Store pending actions
         self.pending_actions.extend(pending_actions);
@@ -306,24 +795,41 @@ impl UserInterface {
 
     fn render_ui_static(
         ctx: &egui::Context,
-        show_controls: &mut bool,
-        show_info: &mut bool,
-        show_stats: &mut bool,
-        show_object_list: &mut bool,
         simulation_speed: &mut f32,
         selected_object_index: &mut Option<usize>,
         bodies: &[crate::math::Body],
         pending_actions: &mut Vec<UiAction>,
         ui_visible: bool,
         physics: Option<&crate::physics::PhysicsSimulation>,
-        show_orbital_paths: &mut bool,
         orbital_path_history_length: &mut i32,
         orbital_path_segment_distance: &mut f32,
+        hierarchy: &OrbitalHierarchy,
+        reference_frame: &mut ReferenceFrame,
+        lagrange_points: Option<&LagrangePoints>,
+        panels: &mut UiPanels,
     ) {
         // If UI is hidden, don't render any windows
         if !ui_visible {
             return;
         }
+        let UiPanels {
+            show_controls,
+            show_info,
+            show_stats,
+            show_object_list,
+            show_orbital_paths,
+            lagrange,
+            porkchop,
+            diagnostics,
+            chaos,
+            resonance,
+            photometry,
+            satellites,
+            ephemeris,
+            save_scenario,
+            new_scenario,
+            scenario_reload,
+        } = panels;
         // Control panel
         if *show_controls {
             egui::Window::new("Simulation Controls")
@@ -337,6 +843,7 @@ impl UserInterface {
                         ui.add(
                             egui::Slider::new(simulation_speed, 0.1..=10.0)
                                 .logarithmic(true)
+                                .clamping(egui::SliderClamping::Never)
                                 .text("x"),
                         );
                     });
@@ -373,12 +880,99 @@ impl UserInterface {
 
                     ui.separator();
 
+                    ui.heading("Reference Frame");
+                    Self::render_reference_frame_controls(ui, reference_frame, bodies, hierarchy);
+
+                    ui.separator();
+
                     ui.heading("View");
                     ui.checkbox(show_info, "Show Info");
                     ui.checkbox(show_stats, "Show Statistics");
                     if ui.checkbox(show_object_list, "Show Object List").changed() {
                         log::info!("Object List toggled to: {}", *show_object_list);
                     }
+                    ui.checkbox(&mut lagrange.show_window, "Show Lagrange Points");
+                    ui.checkbox(&mut porkchop.show_window, "Show Transfer Planner");
+                    ui.checkbox(
+                        &mut diagnostics.show_window,
+                        "Show Conservation Diagnostics",
+                    );
+                    if ui
+                        .checkbox(&mut chaos.show_window, "Show Chaos Indicators")
+                        .changed()
+                        && let Some(index) = *selected_object_index
+                    {
+                        chaos.body = index;
+                    }
+                    ui.checkbox(&mut resonance.show_window, "Show Resonances");
+                    if ui
+                        .checkbox(&mut photometry.show_window, "Show Photometry")
+                        .changed()
+                        && let Some(index) = *selected_object_index
+                    {
+                        photometry.observer_body = index;
+                    }
+                    ui.checkbox(&mut satellites.show_window, "Show Satellites");
+                    ui.checkbox(&mut ephemeris.show_window, "Show Ephemeris Comparison");
+                });
+        }
+
+        // Lagrange point window
+        if lagrange.show_window {
+            // Default to the selected body and whatever it orbits
+            if lagrange.pair.is_none() {
+                let secondary = selected_object_index
+                    .filter(|&index| hierarchy.parent(index).is_some())
+                    .or_else(|| (0..bodies.len()).find(|&index| hierarchy.parent(index).is_some()));
+                lagrange.pair =
+                    secondary.and_then(|secondary| Some((hierarchy.parent(secondary)?, secondary)));
+            }
+
+            egui::Window::new("Lagrange Points")
+                .open(&mut lagrange.show_window)
+                .default_pos([580.0, 10.0])
+                .default_size([260.0, 220.0])
+                .show(ctx, |ui| {
+                    let Some((primary, secondary)) = lagrange.pair.as_mut() else {
+                        ui.label("No orbiting pair in the simulation");
+                        return;
+                    };
+
+                    Self::body_picker(ui, "lagrange_primary", "Primary:", primary, bodies);
+                    Self::body_picker(ui, "lagrange_secondary", "Secondary:", secondary, bodies);
+                    ui.checkbox(&mut lagrange.show_markers, "Show markers");
+                    ui.separator();
+
+                    let Some(points) = lagrange_points else {
+                        ui.label("Pair has no relative orbital motion");
+                        return;
+                    };
+
+                    egui::Grid::new("lagrange_points_grid")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("Point");
+                            ui.label("From secondary");
+                            ui.label("");
+                            ui.end_row();
+
+                            for (index, name) in LAGRANGE_POINT_NAMES.iter().enumerate() {
+                                ui.label(*name);
+                                ui.label(format!(
+                                    "{:.3e} m",
+                                    points.distance_from_secondary(index)
+                                ));
+                                if ui.small_button("Spawn").clicked() {
+                                    pending_actions.push(UiAction::SpawnAtLagrangePoint {
+                                        primary: *primary,
+                                        secondary: *secondary,
+                                        point: index,
+                                    });
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    ui.small("Spawns a spacecraft co-rotating with the pair.");
                 });
         }
 
@@ -456,39 +1050,19 @@ impl UserInterface {
                         ui.small("Objects will appear here when");
                         ui.small("a scenario is loaded.");
                     } else {
-                        // Scrollable list of objects
+                        // Scrollable tree of objects grouped by what they orbit
                         egui::ScrollArea::vertical()
                             .max_height(200.0)
                             .show(ui, |ui| {
-                                for (index, body) in bodies.iter().enumerate() {
-                                    let is_selected = selected_object_index.map_or(false, |sel| sel == index);
-
-                                    // Create selectable object entry
-                                    let response = ui.selectable_label(is_selected, &body.name);
-
-                                    if response.clicked() {
-                                        // Handle object selection
-                                        *selected_object_index = Some(index);
-                                        log::info!("Selected object: {} (index: {})", body.name, index);
-
-                                        // Get radius for camera positioning
-                                        let radius = match &body.body_type {
-                                            crate::scenario::BodyType::Planet { radius, .. } => *radius as f64,
-                                            crate::scenario::BodyType::Star { radius, .. } => *radius as f64,
-                                            crate::scenario::BodyType::PlanetAtmo { radius, .. } => *radius as f64,
-                                            crate::scenario::BodyType::BlackHole { radius } => *radius as f64,
-                                        };
-
-                                        // Queue camera focus action
-                                        pending_actions.push(UiAction::FocusCameraOnObject {
-                                            object_index: index,
-                                            position: body.position,
-                                            radius,
-                                        });
-
-                                        log::info!("Queued camera focus on '{}' at position ({:.2e}, {:.2e}, {:.2e})",
-                                            body.name, body.position.x, body.position.y, body.position.z);
-                                    }
+                                for &root in hierarchy.roots() {
+                                    Self::render_body_tree_node(
+                                        ui,
+                                        root,
+                                        bodies,
+                                        hierarchy,
+                                        selected_object_index,
+                                        pending_actions,
+                                    );
                                 }
                             });
 
@@ -508,8 +1082,29 @@ impl UserInterface {
                                     crate::scenario::BodyType::Star { radius, .. } => *radius,
                                     crate::scenario::BodyType::PlanetAtmo { radius, .. } => *radius,
                                     crate::scenario::BodyType::BlackHole { radius } => *radius,
+                                    crate::scenario::BodyType::Spacecraft { radius, .. } => *radius,
+                                    crate::scenario::BodyType::Comet { radius, .. } => *radius,
                                 };
                                 ui.small(format!("Radius: {:.2e} m", radius));
+
+                                // Show where the body sits in the orbital hierarchy
+                                match hierarchy.parent(selected_idx).and_then(|p| bodies.get(p)) {
+                                    Some(parent) => {
+                                        let distance =
+                                            (selected_body.position - parent.position).length();
+                                        let speed =
+                                            (selected_body.velocity - parent.velocity).length();
+                                        ui.small(format!("Orbits: {}", parent.name));
+                                        ui.small(format!("Distance to parent: {:.3e} m", distance));
+                                        ui.small(format!("Relative speed: {:.3e} m/s", speed));
+                                        if let Some(hill) = hierarchy.hill_radius_of(selected_idx) {
+                                            ui.small(format!("Hill radius: {:.3e} m", hill));
+                                        }
+                                    }
+                                    None => {
+                                        ui.small("Orbits: nothing (root body)");
+                                    }
+                                }
                             }
                         } else {
                             ui.label("No object selected");
@@ -530,15 +1125,105 @@ impl UserInterface {
             }
         }
 
+        // Energy / momentum drift
+        if diagnostics.show_window
+            && let Some(physics) = physics
+        {
+            Self::render_diagnostics_window(ctx, diagnostics, physics);
+        }
+
+        // MEGNO / Lyapunov exponents
+        if chaos.show_window
+            && let Some(physics) = physics
+        {
+            Self::render_chaos_window(ctx, chaos, bodies, physics);
+        }
+
+        // Mean-motion resonances
+        if resonance.show_window
+            && let Some(physics) = physics
+        {
+            Self::render_resonance_window(ctx, resonance, physics);
+        }
+
+        // Transits, eclipses and light curves
+        if photometry.show_window
+            && let Some(physics) = physics
+        {
+            Self::render_photometry_window(ctx, photometry, bodies, physics);
+        }
+
+        // Earth satellites from two-line element sets
+        if satellites.show_window {
+            Self::render_satellite_window(ctx, satellites);
+        }
+
+        // Error against reference trajectories
+        if ephemeris.show_window
+            && let Some(physics) = physics
+        {
+            Self::render_ephemeris_window(ctx, ephemeris, physics);
+        }
+
+        // Snapshot of the live simulation
+        if save_scenario.show_window {
+            Self::render_save_scenario_window(ctx, save_scenario, physics);
+        }
+
+        // Procedurally generated systems
+        if new_scenario.show_window {
+            Self::render_new_scenario_window(ctx, new_scenario, pending_actions);
+        }
+
+        // Problems found when the scenario file was reloaded
+        if scenario_reload.show_window {
+            Self::render_scenario_reload_window(ctx, scenario_reload);
+        }
+
+        // Transfer planner
+        if porkchop.show_window {
+            Self::render_porkchop_window(
+                ctx,
+                porkchop,
+                bodies,
+                hierarchy,
+                physics,
+                pending_actions,
+            );
+        }
+
+        // Maneuver planner for the selected spacecraft
+        if let Some(index) = *selected_object_index
+            && let Some(crate::scenario::BodyType::Spacecraft { maneuvers, .. }) =
+                bodies.get(index).map(|body| &body.body_type)
+        {
+            let simulation_time = physics
+                .and_then(|physics| physics.get_simulation_time().ok())
+                .unwrap_or(0.0);
+            Self::render_maneuver_planner(
+                ctx,
+                index,
+                maneuvers,
+                bodies,
+                hierarchy,
+                simulation_time,
+                pending_actions,
+            );
+        }
+
         // Top menu bar
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
             egui::MenuBar::new().ui(ui, |ui| {
                 ui.menu_button("File", |ui| {
+                    if ui.button("New Scenario...").clicked() {
+                        new_scenario.show_window = true;
+                    }
                     if ui.button("Load Scenario...").clicked() {
                         // TODO: Open file dialog
                     }
                     if ui.button("Save Scenario...").clicked() {
-                        // TODO: Save current state
+                        save_scenario.show_window = true;
+                        save_scenario.status.clear();
                     }
                     ui.separator();
                     if ui.button("Exit").clicked() {
@@ -557,6 +1242,19 @@ impl UserInterface {
                     if ui.checkbox(show_orbital_paths, "Orbital Paths").changed() {
                         log::info!("Orbital paths toggled via menu to: {}", *show_orbital_paths);
                     }
+                    if let Some(physics) = physics {
+                        let cameras = &physics.scenario_metadata().cameras;
+                        if !cameras.is_empty() {
+                            ui.separator();
+                            ui.menu_button("Camera Bookmarks", |ui| {
+                                for (index, camera) in cameras.iter().enumerate() {
+                                    if ui.button(&camera.name).clicked() {
+                                        pending_actions.push(UiAction::ApplyCameraBookmark(index));
+                                    }
+                                }
+                            });
+                        }
+                    }
                 });
 
                 ui.menu_button("Help", |ui| {
@@ -568,6 +1266,1863 @@ impl UserInterface {
         });
     }
 
+    /// Render one body of the Object List tree, recursing into the bodies that orbit it
+    fn render_body_tree_node(
+        ui: &mut egui::Ui,
+        index: usize,
+        bodies: &[crate::math::Body],
+        hierarchy: &OrbitalHierarchy,
+        selected_object_index: &mut Option<usize>,
+        pending_actions: &mut Vec<UiAction>,
+    ) {
+        let Some(body) = bodies.get(index) else {
+            return;
+        };
+
+        let is_selected = *selected_object_index == Some(index);
+        let children = hierarchy.children(index);
+
+        let clicked = if children.is_empty() {
+            ui.selectable_label(is_selected, &body.name).clicked()
+        } else {
+            let id = ui.make_persistent_id(("object_tree", index));
+            let mut clicked = false;
+            egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, true)
+                .show_header(ui, |ui| {
+                    clicked = ui.selectable_label(is_selected, &body.name).clicked();
+                })
+                .body(|ui| {
+                    for &child in children {
+                        Self::render_body_tree_node(
+                            ui,
+                            child,
+                            bodies,
+                            hierarchy,
+                            selected_object_index,
+                            pending_actions,
+                        );
+                    }
+                });
+            clicked
+        };
+
+        if clicked {
+            // Handle object selection
+            *selected_object_index = Some(index);
+            log::info!("Selected object: {} (index: {})", body.name, index);
+
+            // Get radius for camera positioning
+            let radius = match &body.body_type {
+                crate::scenario::BodyType::Planet { radius, .. } => *radius as f64,
+                crate::scenario::BodyType::Star { radius, .. } => *radius as f64,
+                crate::scenario::BodyType::PlanetAtmo { radius, .. } => *radius as f64,
+                crate::scenario::BodyType::BlackHole { radius } => *radius as f64,
+                crate::scenario::BodyType::Spacecraft { radius, .. } => *radius as f64,
+                crate::scenario::BodyType::Comet { radius, .. } => *radius as f64,
+            };
+
+            // Queue camera focus action
+            pending_actions.push(UiAction::FocusCameraOnObject {
+                object_index: index,
+                position: body.position,
+                radius,
+            });
+
+            log::info!(
+                "Queued camera focus on '{}' at position ({:.2e}, {:.2e}, {:.2e})",
+                body.name,
+                body.position.x,
+                body.position.y,
+                body.position.z
+            );
+        }
+    }
+
+    /// Drift of energy, momentum and angular momentum since load, with optional CSV log
+    fn render_diagnostics_window(
+        ctx: &egui::Context,
+        settings: &mut DiagnosticsSettings,
+        physics: &crate::physics::PhysicsSimulation,
+    ) {
+        let mut open = settings.show_window;
+        egui::Window::new("Conservation Diagnostics")
+            .open(&mut open)
+            .default_pos([320.0, 300.0])
+            .default_size([340.0, 420.0])
+            .show(ctx, |ui| {
+                if let Some(correction) = physics.barycentric_correction() {
+                    ui.label(format!(
+                        "Barycentric correction at load: {:.3e} m, {:.3e} m/s",
+                        correction.position_offset.length(),
+                        correction.velocity_offset.length()
+                    ));
+                    ui.separator();
+                }
+
+                let _ = physics.with_diagnostics(|diagnostics| {
+                    let mut interval = diagnostics.interval();
+                    ui.horizontal(|ui| {
+                        ui.label("Sample every:");
+                        if ui
+                            .add(
+                                egui::DragValue::new(&mut interval)
+                                    .speed(0.1)
+                                    .range(0.0..=3600.0)
+                                    .suffix(" s"),
+                            )
+                            .changed()
+                        {
+                            diagnostics.set_interval(interval);
+                        }
+                        if ui.button("Reset Baseline").clicked() {
+                            diagnostics.reset();
+                        }
+                    });
+
+                    let Some(latest) = diagnostics.latest_drift() else {
+                        ui.label("Waiting for samples...");
+                        return;
+                    };
+                    ui.label(format!(
+                        "Samples: {} (t = {:.1} s)",
+                        diagnostics.history().len(),
+                        latest.time
+                    ));
+                    ui.separator();
+
+                    let series = |value: fn(&crate::diagnostics::ConservationDrift) -> f64| {
+                        diagnostics
+                            .history()
+                            .iter()
+                            .map(|drift| [drift.time, value(drift)])
+                            .collect::<Vec<_>>()
+                    };
+                    ui.label(format!("Energy drift ΔE/|E₀|: {:+.3e}", latest.energy));
+                    Self::draw_time_series(
+                        ui,
+                        &series(|drift| drift.energy),
+                        egui::Color32::LIGHT_RED,
+                    );
+                    ui.label(format!("Momentum drift |ΔP|/Σ|p|: {:.3e}", latest.momentum));
+                    Self::draw_time_series(
+                        ui,
+                        &series(|drift| drift.momentum),
+                        egui::Color32::LIGHT_GREEN,
+                    );
+                    ui.label(format!(
+                        "Angular momentum drift |ΔL|/Σ|l|: {:.3e}",
+                        latest.angular_momentum
+                    ));
+                    Self::draw_time_series(
+                        ui,
+                        &series(|drift| drift.angular_momentum),
+                        egui::Color32::LIGHT_BLUE,
+                    );
+
+                    #[cfg(not(target_arch = "wasm32"))]
+                    {
+                        ui.separator();
+                        match diagnostics
+                            .csv_path()
+                            .map(|path| path.display().to_string())
+                        {
+                            Some(path) => {
+                                ui.label(format!("Logging to {}", path));
+                                if ui.button("Stop CSV Log").clicked() {
+                                    diagnostics.stop_csv();
+                                }
+                            }
+                            None => {
+                                ui.horizontal(|ui| {
+                                    ui.label("CSV:");
+                                    ui.text_edit_singleline(&mut settings.csv_path);
+                                    if ui.button("Start Log").clicked() {
+                                        settings.csv_error = diagnostics
+                                            .start_csv(std::path::Path::new(&settings.csv_path))
+                                            .err()
+                                            .map(|e| e.to_string());
+                                    }
+                                });
+                                if let Some(error) = &settings.csv_error {
+                                    ui.colored_label(egui::Color32::LIGHT_RED, error);
+                                }
+                            }
+                        }
+                    }
+                });
+            });
+        settings.show_window = open;
+    }
+
+    /// Live MEGNO and Lyapunov exponent of the tracked bodies
+    fn render_chaos_window(
+        ctx: &egui::Context,
+        settings: &mut ChaosSettings,
+        bodies: &[crate::math::Body],
+        physics: &crate::physics::PhysicsSimulation,
+    ) {
+        let mut open = settings.show_window;
+        egui::Window::new("Chaos Indicators")
+            .open(&mut open)
+            .default_pos([320.0, 120.0])
+            .default_size([340.0, 420.0])
+            .show(ctx, |ui| {
+                ui.label("Integrates the variational equations alongside the simulation.");
+                Self::body_picker(ui, "chaos_body", "Body:", &mut settings.body, bodies);
+                ui.horizontal(|ui| {
+                    if ui.button("Track").clicked()
+                        && let Err(e) = physics.track_chaos(settings.body)
+                    {
+                        log::warn!("Failed to track chaos indicators: {}", e);
+                    }
+                    if ui.button("Stop All").clicked() {
+                        let _ = physics.with_chaos(|chaos| chaos.clear());
+                    }
+                });
+
+                let _ = physics.with_chaos(|chaos| {
+                    let mut interval = chaos.interval();
+                    ui.horizontal(|ui| {
+                        ui.label("Sample every:");
+                        if ui
+                            .add(
+                                egui::DragValue::new(&mut interval)
+                                    .speed(0.1)
+                                    .range(0.0..=3600.0)
+                                    .suffix(" s"),
+                            )
+                            .changed()
+                        {
+                            chaos.set_interval(interval);
+                        }
+                    });
+
+                    if chaos.is_empty() {
+                        ui.label("No bodies tracked.");
+                        return;
+                    }
+
+                    let mut stop = None;
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        for tracked in chaos.tracked() {
+                            ui.separator();
+                            let indicator = tracked.tangent.indicator;
+                            ui.horizontal(|ui| {
+                                ui.strong(&tracked.name);
+                                if ui.small_button("Stop").clicked() {
+                                    stop = Some(tracked.tangent.body);
+                                }
+                            });
+                            ui.label(format!(
+                                "<Y> = {:.3}   Y = {:.3}   (t = {:.1} s)",
+                                indicator.mean_megno(),
+                                indicator.megno(),
+                                indicator.time()
+                            ));
+                            ui.label(format!(
+                                "λ = {:.3e} /s   Lyapunov time = {:.3e} s",
+                                indicator.lyapunov_exponent(),
+                                indicator.lyapunov_time()
+                            ));
+                            let series: Vec<[f64; 2]> = tracked
+                                .history()
+                                .iter()
+                                .map(|sample| [sample.time, sample.mean_megno])
+                                .collect();
+                            Self::draw_time_series(ui, &series, egui::Color32::GOLD);
+                        }
+                    });
+                    if let Some(body) = stop {
+                        chaos.untrack(body);
+                    }
+                });
+                ui.small("<Y> → 2: regular orbit; growing without bound: chaotic");
+            });
+        settings.show_window = open;
+    }
+
+    /// Near-commensurate sibling pairs / triples and their resonant angles
+    fn render_resonance_window(
+        ctx: &egui::Context,
+        settings: &mut ResonanceSettings,
+        physics: &crate::physics::PhysicsSimulation,
+    ) {
+        let mut open = settings.show_window;
+        egui::Window::new("Resonances")
+            .open(&mut open)
+            .default_pos([340.0, 140.0])
+            .default_size([360.0, 460.0])
+            .show(ctx, |ui| {
+                let _ = physics.with_resonances(|monitor| {
+                    let mut enabled = monitor.is_enabled();
+                    if ui.checkbox(&mut enabled, "Detect resonances").changed() {
+                        monitor.set_enabled(enabled);
+                    }
+                    let mut interval = monitor.interval();
+                    ui.horizontal(|ui| {
+                        ui.label("Sample every:");
+                        if ui
+                            .add(
+                                egui::DragValue::new(&mut interval)
+                                    .speed(1.0)
+                                    .range(0.0..=86400.0)
+                                    .suffix(" s"),
+                            )
+                            .changed()
+                        {
+                            monitor.set_interval(interval);
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Tolerance:");
+                        ui.add(
+                            egui::DragValue::new(&mut monitor.tolerance)
+                                .speed(0.001)
+                                .range(0.0001..=0.1)
+                                .fixed_decimals(4),
+                        );
+                        ui.label("Max order:");
+                        ui.add(egui::DragValue::new(&mut monitor.max_order).range(1..=5));
+                    });
+                    if ui.button("Clear").clicked() {
+                        monitor.clear();
+                    }
+
+                    ui.separator();
+                    if monitor.tracked().is_empty() {
+                        ui.label(if monitor.is_enabled() {
+                            "No near-commensurabilities found yet."
+                        } else {
+                            "Detection is off."
+                        });
+                        return;
+                    }
+
+                    egui::ScrollArea::vertical()
+                        .max_height(140.0)
+                        .show(ui, |ui| {
+                            for (index, tracked) in monitor.tracked().iter().enumerate() {
+                                let deviation = tracked
+                                    .history()
+                                    .back()
+                                    .map_or(f64::NAN, |sample| sample.deviation);
+                                let status = if tracked.active {
+                                    ""
+                                } else {
+                                    " (out of range)"
+                                };
+                                ui.selectable_value(
+                                    &mut settings.selected,
+                                    index,
+                                    format!(
+                                        "{}   Δ = {:+.2}%{}",
+                                        tracked.label,
+                                        deviation * 100.0,
+                                        status
+                                    ),
+                                );
+                            }
+                        });
+
+                    let Some(tracked) = monitor.tracked().get(settings.selected) else {
+                        return;
+                    };
+                    ui.separator();
+                    ui.strong(&tracked.label);
+                    for (index, (label, libration)) in tracked
+                        .angle_labels
+                        .iter()
+                        .zip(tracked.libration())
+                        .enumerate()
+                    {
+                        ui.label(match libration {
+                            Some((centre, amplitude)) => format!(
+                                "φ = {}: librates about {:.0}° ± {:.0}°",
+                                label,
+                                centre.to_degrees(),
+                                amplitude.to_degrees()
+                            ),
+                            None => format!("φ = {}: circulating", label),
+                        });
+                        let series: Vec<[f64; 2]> = tracked
+                            .history()
+                            .iter()
+                            .map(|sample| [sample.time, sample.angles[index]])
+                            .collect();
+                        Self::draw_angle_series(ui, &series, egui::Color32::LIGHT_BLUE);
+                    }
+                });
+                ui.small("Libration of an angle means the bodies are locked in resonance");
+            });
+        settings.show_window = open;
+    }
+
+    /// Predicted transits / eclipses and the synthetic light curve seen by an observer
+    fn render_photometry_window(
+        ctx: &egui::Context,
+        settings: &mut PhotometrySettings,
+        bodies: &[crate::math::Body],
+        physics: &crate::physics::PhysicsSimulation,
+    ) {
+        if let Some(result) = settings.task.take_finished() {
+            Self::finish_light_curve(settings, result);
+        }
+        let mut open = settings.show_window;
+        egui::Window::new("Photometry")
+            .open(&mut open)
+            .default_pos([360.0, 160.0])
+            .default_size([380.0, 520.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut settings.distant, false, "From body");
+                    ui.radio_value(&mut settings.distant, true, "Distant observer");
+                });
+                if settings.distant {
+                    ui.horizontal(|ui| {
+                        ui.label("Direction:");
+                        for component in &mut settings.direction {
+                            ui.add(
+                                egui::DragValue::new(component)
+                                    .speed(0.01)
+                                    .range(-1.0..=1.0),
+                            );
+                        }
+                    });
+                } else {
+                    Self::body_picker(
+                        ui,
+                        "photometry_observer",
+                        "Observer:",
+                        &mut settings.observer_body,
+                        bodies,
+                    );
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Limb darkening u1:");
+                    ui.add(
+                        egui::DragValue::new(&mut settings.limb_darkening.u1)
+                            .speed(0.01)
+                            .range(0.0..=1.0),
+                    );
+                    ui.label("u2:");
+                    ui.add(
+                        egui::DragValue::new(&mut settings.limb_darkening.u2)
+                            .speed(0.01)
+                            .range(-1.0..=1.0),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Predict:");
+                    ui.add(
+                        egui::DragValue::new(&mut settings.duration_days)
+                            .speed(1.0)
+                            .range(0.01..=36500.0)
+                            .suffix(" days"),
+                    );
+                    ui.label("step:");
+                    ui.add(
+                        egui::DragValue::new(&mut settings.time_step)
+                            .speed(1.0)
+                            .range(0.1..=86400.0)
+                            .suffix(" s"),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Sample every:");
+                    ui.add(
+                        egui::DragValue::new(&mut settings.sample_interval)
+                            .speed(10.0)
+                            .range(0.0..=864000.0)
+                            .suffix(" s"),
+                    );
+                    let idle = !settings.task.is_running();
+                    if ui.add_enabled(idle, egui::Button::new("Compute")).clicked() {
+                        Self::compute_light_curve(settings, physics);
+                    }
+                });
+                if settings.task.is_running() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.small("Integrating...");
+                    });
+                    ctx.request_repaint();
+                } else if !settings.status.is_empty() {
+                    ui.small(&settings.status);
+                }
+
+                let Some(curve) = &settings.curve else {
+                    return;
+                };
+                let series: Vec<[f64; 2]> = curve
+                    .samples
+                    .iter()
+                    .map(|sample| [sample.time, sample.relative_flux])
+                    .collect();
+                Self::draw_time_series(ui, &series, egui::Color32::GOLD);
+
+                ui.separator();
+                if curve.events.is_empty() {
+                    ui.label("No transits or eclipses in this interval.");
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(180.0)
+                    .show(ui, |ui| {
+                        for event in &curve.events {
+                            let kind = match event.kind {
+                                EventKind::Transit => "Transit",
+                                EventKind::Eclipse => "Eclipse",
+                            };
+                            ui.label(format!(
+                                "{} of {} by {}: day {:.3}, {:.2} h, depth {:.3e}",
+                                kind,
+                                event.star,
+                                event.occulter,
+                                event.mid / 86400.0,
+                                (event.end - event.start) / 3600.0,
+                                event.depth
+                            ));
+                        }
+                    });
+
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.label("CSV:");
+                        ui.text_edit_singleline(&mut settings.csv_path);
+                        if ui.button("Export").clicked() {
+                            let path = std::path::Path::new(&settings.csv_path);
+                            let events_path = path.with_file_name(format!(
+                                "{}_events.csv",
+                                path.file_stem()
+                                    .map_or("light_curve".into(), |stem| stem.to_string_lossy())
+                            ));
+                            settings.status = match curve
+                                .write_csv(path)
+                                .and_then(|_| curve.write_events_csv(&events_path))
+                            {
+                                Ok(()) => format!(
+                                    "Wrote {} and {}",
+                                    path.display(),
+                                    events_path.display()
+                                ),
+                                Err(e) => format!("Export failed: {}", e),
+                            };
+                        }
+                    });
+                }
+            });
+        settings.show_window = open;
+    }
+
+    fn render_satellite_window(ctx: &egui::Context, settings: &mut SatelliteSettings) {
+        let mut open = settings.show_window;
+        egui::Window::new("Satellites")
+            .open(&mut open)
+            .default_pos([360.0, 200.0])
+            .default_size([340.0, 200.0])
+            .show(ctx, |ui| {
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.horizontal(|ui| {
+                    ui.label("TLE file:");
+                    ui.text_edit_singleline(&mut settings.tle_path);
+                    if ui.button("Load").clicked() {
+                        settings.request = Some(SatelliteRequest::Load);
+                    }
+                });
+                #[cfg(target_arch = "wasm32")]
+                ui.label("Start the native build with --tle <file> to load satellites.");
+
+                if let Some(loaded) = &settings.loaded {
+                    ui.separator();
+                    ui.label(format!(
+                        "{} satellites around {} ({} propagating)",
+                        loaded.count, loaded.central_body, loaded.visible
+                    ));
+                    ui.horizontal(|ui| {
+                        ui.label("Epoch at t = 0:");
+                        ui.add(
+                            egui::DragValue::new(&mut settings.epoch)
+                                .speed(0.01)
+                                .fixed_decimals(5)
+                                .prefix("JD "),
+                        );
+                    });
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut settings.show_trails, "Trails");
+                        ui.add(
+                            egui::DragValue::new(&mut settings.trail_length)
+                                .speed(1.0)
+                                .range(2..=2000)
+                                .suffix(" samples"),
+                        );
+                    });
+                    if ui.button("Unload").clicked() {
+                        settings.request = Some(SatelliteRequest::Unload);
+                    }
+                }
+                if !settings.status.is_empty() {
+                    ui.small(&settings.status);
+                }
+            });
+        settings.show_window = open;
+    }
+
+    /// Per-body position and velocity error against a reference trajectory
+    fn render_ephemeris_window(
+        ctx: &egui::Context,
+        settings: &mut EphemerisSettings,
+        physics: &crate::physics::PhysicsSimulation,
+    ) {
+        // The physics thread records into the comparison, so copy out what is drawn and
+        // only lock again briefly for edits; file I/O runs with no lock held
+        let Ok(snapshot) = physics
+            .with_ephemeris(|comparison| EphemerisSnapshot::capture(comparison, settings.selected))
+        else {
+            return;
+        };
+
+        let mut open = settings.show_window;
+        egui::Window::new("Ephemeris Comparison")
+            .open(&mut open)
+            .default_pos([380.0, 180.0])
+            .default_size([360.0, 440.0])
+            .show(ctx, |ui| {
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.horizontal(|ui| {
+                    ui.label("Reference CSV:");
+                    ui.text_edit_singleline(&mut settings.reference_path);
+                    if ui.button("Load").clicked() {
+                        let path = std::path::Path::new(&settings.reference_path);
+                        match crate::ephemeris::ReferenceTrajectory::load(path) {
+                            Ok(reference) => {
+                                settings.status =
+                                    format!("Loaded {} reference bodies", reference.bodies.len());
+                                let _ = physics.with_ephemeris(|comparison| {
+                                    comparison.set_reference(Some(reference))
+                                });
+                                settings.selected = 0;
+                            }
+                            Err(e) => settings.status = format!("Load failed: {}", e),
+                        }
+                    }
+                });
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.horizontal(|ui| {
+                    ui.label("SPK kernel:");
+                    ui.text_edit_singleline(&mut settings.spk_path);
+                });
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.horizontal(|ui| {
+                    ui.label("From JD");
+                    ui.add(egui::DragValue::new(&mut settings.spk_start).speed(1.0));
+                    ui.label("for");
+                    ui.add(
+                        egui::DragValue::new(&mut settings.spk_days)
+                            .speed(1.0)
+                            .range(0.0..=36500.0)
+                            .suffix(" d"),
+                    );
+                    if ui.button("Load").clicked() {
+                        let reference =
+                            crate::spk::SpkKernel::open(&settings.spk_path).and_then(|kernel| {
+                                kernel.reference_trajectory(
+                                    &crate::scenario::horizons::BodyCatalog::builtin(),
+                                    crate::spk::julian_date_to_et(settings.spk_start),
+                                    settings.spk_days * 86400.0,
+                                    SPK_SAMPLE_STEP,
+                                )
+                            });
+                        match reference {
+                            Ok(reference) => {
+                                settings.status =
+                                    format!("Sampled {} bodies from SPK", reference.bodies.len());
+                                let _ = physics.with_ephemeris(|comparison| {
+                                    comparison.set_reference(Some(reference))
+                                });
+                                settings.selected = 0;
+                            }
+                            Err(e) => settings.status = format!("SPK load failed: {}", e),
+                        }
+                    }
+                });
+                #[cfg(target_arch = "wasm32")]
+                ui.label("Reference trajectories can only be loaded in the native build.");
+
+                let Some((reference_bodies, span)) = snapshot.reference else {
+                    if !settings.status.is_empty() {
+                        ui.small(&settings.status);
+                    }
+                    return;
+                };
+                ui.label(format!(
+                    "{} bodies, t = {:.2} to {:.2} days",
+                    reference_bodies,
+                    span.0 / 86400.0,
+                    span.1 / 86400.0
+                ));
+
+                let mut interval = snapshot.interval;
+                ui.horizontal(|ui| {
+                    ui.label("Compare every:");
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut interval)
+                                .speed(10.0)
+                                .range(0.0..=864000.0)
+                                .suffix(" s"),
+                        )
+                        .changed()
+                    {
+                        let _ =
+                            physics.with_ephemeris(|comparison| comparison.set_interval(interval));
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Relative to:");
+                    if ui.text_edit_singleline(&mut settings.relative_to).changed() {
+                        let origin = settings.relative_to.trim();
+                        let origin = (!origin.is_empty()).then(|| origin.to_string());
+                        let _ = physics.with_ephemeris(|comparison| {
+                            comparison.relative_to = origin;
+                            comparison.reset();
+                        });
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("Reset").clicked() {
+                        let _ = physics.with_ephemeris(|comparison| comparison.reset());
+                    }
+                    if ui.button("Unload").clicked() {
+                        let _ = physics.with_ephemeris(|comparison| comparison.set_reference(None));
+                        settings.status.clear();
+                    }
+                });
+
+                ui.separator();
+                if snapshot.latest.is_empty() {
+                    ui.label("No simulated body matches the reference at this time yet.");
+                    return;
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(120.0)
+                    .show(ui, |ui| {
+                        for (index, (name, latest)) in snapshot.latest.iter().enumerate() {
+                            let Some(latest) = latest else {
+                                continue;
+                            };
+                            ui.selectable_value(
+                                &mut settings.selected,
+                                index,
+                                format!(
+                                    "{}   Δr = {:.3e} m   Δv = {:.3e} m/s",
+                                    name, latest.position, latest.velocity
+                                ),
+                            );
+                        }
+                    });
+
+                if let Some((summary, history)) = &snapshot.selected {
+                    ui.separator();
+                    ui.strong(&summary.name);
+                    let series = |value: fn(&EphemerisError) -> f64| {
+                        history
+                            .iter()
+                            .map(|error| [error.time, value(error)])
+                            .collect::<Vec<_>>()
+                    };
+                    ui.label(format!(
+                        "Position error: max {:.3e} m, RMS {:.3e} m",
+                        summary.max_position, summary.rms_position
+                    ));
+                    Self::draw_time_series(
+                        ui,
+                        &series(|error| error.position),
+                        egui::Color32::LIGHT_RED,
+                    );
+                    ui.label(format!(
+                        "Velocity error: max {:.3e} m/s, RMS {:.3e} m/s",
+                        summary.max_velocity, summary.rms_velocity
+                    ));
+                    Self::draw_time_series(
+                        ui,
+                        &series(|error| error.velocity),
+                        egui::Color32::LIGHT_BLUE,
+                    );
+                }
+
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.label("Summary CSV:");
+                        ui.text_edit_singleline(&mut settings.summary_path);
+                        if ui.button("Export").clicked() {
+                            let path = std::path::Path::new(&settings.summary_path);
+                            settings.status = match physics
+                                .with_ephemeris(|comparison| comparison.summaries())
+                                .and_then(|summaries| EphemerisSummary::write_csv(&summaries, path))
+                            {
+                                Ok(()) => format!("Wrote {}", path.display()),
+                                Err(e) => format!("Export failed: {}", e),
+                            };
+                        }
+                    });
+                }
+                if !settings.status.is_empty() {
+                    ui.small(&settings.status);
+                }
+            });
+        settings.show_window = open;
+    }
+
+    /// Write the current bodies to a scenario file (v4 for `.ron` paths, v3 otherwise)
+    fn render_save_scenario_window(
+        ctx: &egui::Context,
+        settings: &mut SaveScenarioSettings,
+        physics: Option<&PhysicsSimulation>,
+    ) {
+        let mut open = settings.show_window;
+        egui::Window::new("Save Scenario")
+            .open(&mut open)
+            .default_pos([400.0, 120.0])
+            .default_size([360.0, 100.0])
+            .show(ctx, |ui| {
+                #[cfg(not(target_arch = "wasm32"))]
+                ui.horizontal(|ui| {
+                    ui.label("File:");
+                    ui.text_edit_singleline(&mut settings.path);
+                    if ui.button("Save").clicked() {
+                        settings.status = match Self::save_scenario(&settings.path, physics) {
+                            Ok(count) => format!("Saved {} bodies to {}", count, settings.path),
+                            Err(e) => format!("Save failed: {}", e),
+                        };
+                    }
+                });
+                #[cfg(target_arch = "wasm32")]
+                {
+                    let _ = physics;
+                    ui.label("Saving scenarios is only available in the native build.");
+                }
+                ui.small("Positions, velocities and pending maneuvers are saved as of now.");
+                ui.small(
+                    "A .ron file also keeps the name, epoch, cameras and integrator settings.",
+                );
+                if !settings.status.is_empty() {
+                    ui.small(&settings.status);
+                }
+            });
+        settings.show_window = open;
+    }
+
+    /// Status and validation problems of the last scenario reload
+    fn render_scenario_reload_window(ctx: &egui::Context, settings: &mut ScenarioReloadSettings) {
+        let mut open = settings.show_window;
+        egui::Window::new("Scenario Reload")
+            .open(&mut open)
+            .default_pos([400.0, 240.0])
+            .default_size([420.0, 160.0])
+            .show(ctx, |ui| {
+                ui.label(&settings.status);
+                egui::ScrollArea::vertical()
+                    .max_height(240.0)
+                    .show(ui, |ui| {
+                        for diagnostic in &settings.diagnostics {
+                            ui.small(diagnostic);
+                        }
+                    });
+            });
+        settings.show_window = open;
+    }
+
+    /// Pick a generator, its parameters and a seed, and replace the simulation with the result
+    fn render_new_scenario_window(
+        ctx: &egui::Context,
+        settings: &mut NewScenarioSettings,
+        pending_actions: &mut Vec<UiAction>,
+    ) {
+        let mut open = settings.show_window;
+        egui::Window::new("New Scenario")
+            .open(&mut open)
+            .default_pos([400.0, 120.0])
+            .default_size([320.0, 200.0])
+            .show(ctx, |ui| {
+                egui::ComboBox::from_id_salt("new_scenario_generator")
+                    .selected_text(settings.generator.label())
+                    .show_ui(ui, |ui| {
+                        for name in Generator::NAMES {
+                            let Some(generator) = Generator::from_name(name) else {
+                                continue;
+                            };
+                            if ui
+                                .selectable_label(
+                                    generator.name() == settings.generator.name(),
+                                    generator.label(),
+                                )
+                                .clicked()
+                                && generator.name() != settings.generator.name()
+                            {
+                                settings.generator = generator;
+                            }
+                        }
+                    });
+
+                egui::Grid::new("new_scenario_parameters")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        Self::generator_parameters(ui, &mut settings.generator);
+                        ui.label("Seed:");
+                        ui.add(egui::DragValue::new(&mut settings.seed));
+                        ui.end_row();
+                    });
+
+                ui.horizontal(|ui| {
+                    if ui.button("Generate").clicked() {
+                        let scenario = settings.generator.generate(settings.seed);
+                        pending_actions.push(UiAction::LoadGeneratedScenario(Box::new(scenario)));
+                    }
+                    if ui.button("Random Seed").clicked() {
+                        settings.seed = crate::scenario::generators::SeededRng::new(settings.seed)
+                            .next_u64()
+                            % 1_000_000;
+                    }
+                });
+                ui.small("The same parameters and seed always give the same system.");
+            });
+        settings.show_window = open;
+    }
+
+    /// Rows of the New Scenario grid for the chosen generator's parameters
+    fn generator_parameters(ui: &mut egui::Ui, generator: &mut Generator) {
+        let mut row = |label: &str, value: egui::DragValue| {
+            ui.label(label);
+            ui.add(value);
+            ui.end_row();
+        };
+        match generator {
+            Generator::PlanetarySystem(system) => {
+                row(
+                    "Star temperature:",
+                    egui::DragValue::new(&mut system.star_temperature)
+                        .range(2500.0..=40000.0)
+                        .speed(50.0)
+                        .suffix(" K"),
+                );
+                row(
+                    "Planets:",
+                    egui::DragValue::new(&mut system.planet_count).range(0..=12),
+                );
+            }
+            Generator::StarCluster(cluster) => {
+                row(
+                    "Stars:",
+                    egui::DragValue::new(&mut cluster.star_count).range(2..=500),
+                );
+                row(
+                    "Mean mass:",
+                    egui::DragValue::new(&mut cluster.star_mass)
+                        .range(0.1..=50.0)
+                        .speed(0.05)
+                        .suffix(" M☉"),
+                );
+                row(
+                    "Scale radius:",
+                    egui::DragValue::new(&mut cluster.scale_radius)
+                        .range(1.0..=1.0e6)
+                        .speed(10.0)
+                        .suffix(" AU"),
+                );
+            }
+            Generator::DiskGalaxy(galaxy) => {
+                row(
+                    "Stars:",
+                    egui::DragValue::new(&mut galaxy.star_count).range(2..=1000),
+                );
+                row(
+                    "Core mass:",
+                    egui::DragValue::new(&mut galaxy.core_mass)
+                        .range(1.0..=1.0e9)
+                        .speed(1000.0)
+                        .suffix(" M☉"),
+                );
+                row(
+                    "Radius:",
+                    egui::DragValue::new(&mut galaxy.radius)
+                        .range(10.0..=1.0e7)
+                        .speed(50.0)
+                        .suffix(" AU"),
+                );
+                row(
+                    "Pitch angle:",
+                    egui::DragValue::new(&mut galaxy.pitch_angle)
+                        .range(1.0..=89.0)
+                        .suffix("°"),
+                );
+            }
+            Generator::Circumbinary(system) => {
+                row(
+                    "Primary mass:",
+                    egui::DragValue::new(&mut system.primary_mass)
+                        .range(0.08..=50.0)
+                        .speed(0.01)
+                        .suffix(" M☉"),
+                );
+                row(
+                    "Secondary mass:",
+                    egui::DragValue::new(&mut system.secondary_mass)
+                        .range(0.08..=50.0)
+                        .speed(0.01)
+                        .suffix(" M☉"),
+                );
+                row(
+                    "Separation:",
+                    egui::DragValue::new(&mut system.separation)
+                        .range(0.01..=100.0)
+                        .speed(0.01)
+                        .suffix(" AU"),
+                );
+                row(
+                    "Planets:",
+                    egui::DragValue::new(&mut system.planet_count).range(0..=12),
+                );
+            }
+            Generator::FigureEight(figure) => {
+                row(
+                    "Star mass:",
+                    egui::DragValue::new(&mut figure.mass)
+                        .range(0.01..=50.0)
+                        .speed(0.01)
+                        .suffix(" M☉"),
+                );
+                row(
+                    "Scale:",
+                    egui::DragValue::new(&mut figure.scale)
+                        .range(0.01..=1000.0)
+                        .speed(0.01)
+                        .suffix(" AU"),
+                );
+                row(
+                    "Perturbation:",
+                    egui::DragValue::new(&mut figure.perturbation)
+                        .range(0.0..=0.5)
+                        .speed(0.001),
+                );
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_scenario(path: &str, physics: Option<&PhysicsSimulation>) -> AstrariaResult<usize> {
+        let Some(physics) = physics else {
+            return Err(crate::AstrariaError::Physics(
+                "No simulation is running".to_string(),
+            ));
+        };
+        let simulation_time = physics.get_simulation_time()?;
+        let mut scenario =
+            crate::scenario::Scenario::from_bodies(&physics.get_bodies()?, simulation_time);
+        scenario.metadata = physics.scenario_metadata().clone();
+        if let Some(epoch) = &mut scenario.metadata.epoch {
+            *epoch += simulation_time / crate::math::SECONDS_PER_DAY;
+        }
+        scenario.integrator = physics.integrator_settings();
+        crate::scenario::ScenarioWriter::save(&scenario, std::path::Path::new(path))?;
+        Ok(scenario.bodies.len())
+    }
+
+    /// Carry the Satellites window's changes over to the renderer's satellite layer
+    fn apply_satellite_settings(settings: &mut SatelliteSettings, renderer: &mut Renderer) {
+        match settings.request.take() {
+            #[cfg(not(target_arch = "wasm32"))]
+            Some(SatelliteRequest::Load) => {
+                match crate::satellites::SatelliteLayer::load(&settings.tle_path) {
+                    Ok(layer) => {
+                        settings.status = format!("Loaded {} satellites", layer.len());
+                        renderer.set_satellites(Some(layer));
+                        return;
+                    }
+                    Err(e) => settings.status = format!("Load failed: {}", e),
+                }
+            }
+            Some(SatelliteRequest::Unload) => {
+                renderer.set_satellites(None);
+                settings.status.clear();
+                return;
+            }
+            _ => {}
+        }
+
+        let Some(layer) = renderer.satellites_mut() else {
+            return;
+        };
+        if settings.epoch != layer.epoch() {
+            layer.set_epoch(settings.epoch);
+        }
+        if layer.show_trails && !settings.show_trails {
+            layer.clear_trails();
+        }
+        layer.show_trails = settings.show_trails;
+        layer.trail_length = settings.trail_length;
+    }
+
+    /// Integrate forward from the current state on a worker thread, recording the light curve
+    fn compute_light_curve(
+        settings: &mut PhotometrySettings,
+        physics: &crate::physics::PhysicsSimulation,
+    ) {
+        settings.curve = None;
+        let Ok(bodies) = physics.get_bodies() else {
+            settings.status = "Physics state unavailable".to_string();
+            return;
+        };
+        let bodies: Vec<PhotometricBody> = bodies.iter().map(PhotometricBody::from_body).collect();
+        let observer = if settings.distant {
+            Observer::Direction(DVec3::from_array(settings.direction))
+        } else {
+            Observer::Body(settings.observer_body)
+        };
+        let limb_darkening = settings.limb_darkening;
+        let start_time = physics.get_simulation_time().unwrap_or(0.0);
+        let duration = settings.duration_days * 86400.0;
+        let (time_step, sample_interval) = (settings.time_step, settings.sample_interval);
+        settings.status.clear();
+        settings.task.start(move || {
+            LightCurve::compute(
+                &bodies,
+                observer,
+                &limb_darkening,
+                start_time,
+                duration,
+                time_step,
+                sample_interval,
+            )
+        });
+    }
+
+    fn finish_light_curve(settings: &mut PhotometrySettings, result: AstrariaResult<LightCurve>) {
+        match result {
+            Ok(curve) => {
+                let minimum = curve
+                    .samples
+                    .iter()
+                    .map(|sample| sample.relative_flux)
+                    .fold(1.0, f64::min);
+                settings.status = format!(
+                    "{} events, minimum relative flux {:.6}",
+                    curve.events.len(),
+                    minimum
+                );
+                settings.curve = Some(curve);
+            }
+            Err(e) => settings.status = e.to_string(),
+        }
+    }
+
+    /// Scatter plot of angles (radians) against time on a fixed ±180° axis
+    fn draw_angle_series(ui: &mut egui::Ui, points: &[[f64; 2]], color: egui::Color32) {
+        let (rect, _) = ui.allocate_exact_size(
+            egui::vec2(ui.available_width().max(200.0), 70.0),
+            egui::Sense::hover(),
+        );
+        let painter = ui.painter_at(rect);
+        painter.rect_stroke(
+            rect,
+            2.0,
+            egui::Stroke::new(1.0, egui::Color32::DARK_GRAY),
+            egui::StrokeKind::Inside,
+        );
+        painter.hline(
+            rect.x_range(),
+            rect.center().y,
+            egui::Stroke::new(0.5, egui::Color32::DARK_GRAY),
+        );
+        let (Some(first), Some(last)) = (points.first(), points.last()) else {
+            return;
+        };
+        let (x_min, x_max) = (first[0], last[0]);
+
+        for [x, y] in points.iter().copied() {
+            let fraction = if x_max > x_min {
+                ((x - x_min) / (x_max - x_min)) as f32
+            } else {
+                0.5
+            };
+            let position = egui::pos2(
+                rect.left() + fraction * rect.width(),
+                rect.center().y - (y / std::f64::consts::PI) as f32 * rect.height() / 2.0,
+            );
+            painter.circle_filled(position, 1.0, color);
+        }
+
+        let font = egui::FontId::monospace(10.0);
+        painter.text(
+            rect.left_top() + egui::vec2(3.0, 2.0),
+            egui::Align2::LEFT_TOP,
+            "180°",
+            font.clone(),
+            egui::Color32::GRAY,
+        );
+        painter.text(
+            rect.left_bottom() + egui::vec2(3.0, -2.0),
+            egui::Align2::LEFT_BOTTOM,
+            "-180°",
+            font,
+            egui::Color32::GRAY,
+        );
+    }
+
+    /// Draw a simple line plot of (x, y) points scaled to fit
+    fn draw_time_series(ui: &mut egui::Ui, points: &[[f64; 2]], color: egui::Color32) {
+        let (rect, _) = ui.allocate_exact_size(
+            egui::vec2(ui.available_width().max(200.0), 70.0),
+            egui::Sense::hover(),
+        );
+        let painter = ui.painter_at(rect);
+        painter.rect_stroke(
+            rect,
+            2.0,
+            egui::Stroke::new(1.0, egui::Color32::DARK_GRAY),
+            egui::StrokeKind::Inside,
+        );
+        if points.len() < 2 {
+            return;
+        }
+
+        let (mut x_min, mut x_max) = (f64::INFINITY, f64::NEG_INFINITY);
+        let (mut y_min, mut y_max) = (f64::INFINITY, f64::NEG_INFINITY);
+        for [x, y] in points
+            .iter()
+            .copied()
+            .filter(|[x, y]| x.is_finite() && y.is_finite())
+        {
+            x_min = x_min.min(x);
+            x_max = x_max.max(x);
+            y_min = y_min.min(y);
+            y_max = y_max.max(y);
+        }
+        if x_max <= x_min {
+            return;
+        }
+        if y_max <= y_min {
+            // Flat line: centre it
+            let pad = y_min.abs().max(1e-300);
+            y_min -= pad;
+            y_max += pad;
+        }
+
+        let to_screen = |[x, y]: [f64; 2]| {
+            egui::pos2(
+                rect.left() + ((x - x_min) / (x_max - x_min)) as f32 * rect.width(),
+                rect.bottom() - ((y - y_min) / (y_max - y_min)) as f32 * rect.height(),
+            )
+        };
+        let line: Vec<egui::Pos2> = points
+            .iter()
+            .copied()
+            .filter(|[x, y]| x.is_finite() && y.is_finite())
+            .map(to_screen)
+            .collect();
+        painter.add(egui::Shape::line(line, egui::Stroke::new(1.5, color)));
+
+        let font = egui::FontId::monospace(10.0);
+        painter.text(
+            rect.left_top() + egui::vec2(3.0, 2.0),
+            egui::Align2::LEFT_TOP,
+            format!("{:.2e}", y_max),
+            font.clone(),
+            egui::Color32::GRAY,
+        );
+        painter.text(
+            rect.left_bottom() + egui::vec2(3.0, -2.0),
+            egui::Align2::LEFT_BOTTOM,
+            format!("{:.2e}", y_min),
+            font,
+            egui::Color32::GRAY,
+        );
+    }
+
+    /// Porkchop plot of transfer delta-v between two bodies orbiting the same body
+    fn render_porkchop_window(
+        ctx: &egui::Context,
+        porkchop: &mut PorkchopSettings,
+        bodies: &[crate::math::Body],
+        hierarchy: &OrbitalHierarchy,
+        physics: Option<&crate::physics::PhysicsSimulation>,
+        pending_actions: &mut Vec<UiAction>,
+    ) {
+        if let Some((plot, context, central_name)) = porkchop.task.take_finished() {
+            Self::finish_porkchop(ctx, porkchop, plot, context, &central_name);
+        }
+        let mut open = porkchop.show_window;
+        egui::Window::new("Transfer Planner")
+            .open(&mut open)
+            .default_pos([580.0, 300.0])
+            .default_size([340.0, 520.0])
+            .show(ctx, |ui| {
+                Self::body_picker(
+                    ui,
+                    "porkchop_departure",
+                    "From:",
+                    &mut porkchop.departure_body,
+                    bodies,
+                );
+                Self::body_picker(
+                    ui,
+                    "porkchop_arrival",
+                    "To:",
+                    &mut porkchop.arrival_body,
+                    bodies,
+                );
+
+                for (label, range) in [
+                    ("Departure (days):", &mut porkchop.departure_days),
+                    ("Arrival (days):", &mut porkchop.arrival_days),
+                ] {
+                    ui.horizontal(|ui| {
+                        ui.label(label);
+                        ui.add(
+                            egui::DragValue::new(&mut range.0)
+                                .speed(1.0)
+                                .range(0.0..=f64::MAX),
+                        );
+                        ui.label("to");
+                        ui.add(
+                            egui::DragValue::new(&mut range.1)
+                                .speed(1.0)
+                                .range(range.0..=f64::MAX),
+                        );
+                    });
+                }
+
+                let idle = !porkchop.task.is_running();
+                if ui.add_enabled(idle, egui::Button::new("Compute")).clicked()
+                    && let Some(physics) = physics
+                {
+                    Self::compute_porkchop(porkchop, hierarchy, physics);
+                }
+                if porkchop.task.is_running() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.small("Sweeping transfers...");
+                    });
+                    ctx.request_repaint();
+                } else if !porkchop.status.is_empty() {
+                    ui.small(&porkchop.status);
+                }
+
+                let (Some((plot, context)), Some(texture)) = (&porkchop.plot, &porkchop.texture)
+                else {
+                    return;
+                };
+
+                // Departure runs left to right, arrival bottom to top
+                let size = egui::vec2(300.0, 300.0);
+                let response = ui.add(
+                    egui::Image::new(texture)
+                        .fit_to_exact_size(size)
+                        .sense(egui::Sense::click()),
+                );
+                let columns = plot.departure_times.len();
+                let rows = plot.arrival_times.len();
+                if response.clicked()
+                    && let Some(pointer) = response.interact_pointer_pos()
+                {
+                    let local = (pointer - response.rect.min) / response.rect.size();
+                    let column = ((local.x * columns as f32) as usize).min(columns - 1);
+                    let row = ((local.y * rows as f32) as usize).min(rows - 1);
+                    porkchop.selected = Some((column, rows - 1 - row));
+                }
+                if let Some((column, row)) = porkchop.selected {
+                    let marker = response.rect.min
+                        + egui::vec2(
+                            (column as f32 + 0.5) / columns as f32 * size.x,
+                            (rows as f32 - row as f32 - 0.5) / rows as f32 * size.y,
+                        );
+                    ui.painter().circle_stroke(
+                        marker,
+                        4.0,
+                        egui::Stroke::new(2.0, egui::Color32::WHITE),
+                    );
+                }
+
+                if let Some((_, _, minimum)) = plot.minimum() {
+                    ui.small(format!(
+                        "Minimum total delta-v: {:.2} km/s",
+                        minimum / 1000.0
+                    ));
+                }
+                ui.separator();
+
+                let Some((column, row)) = porkchop.selected else {
+                    ui.label("Click the plot to pick a transfer");
+                    return;
+                };
+                let departure = plot.departure_times[column];
+                let arrival = plot.arrival_times[row];
+                let Some(transfer) = Transfer::plan(
+                    context.mu,
+                    context.departure_state,
+                    context.arrival_state,
+                    departure,
+                    arrival - departure,
+                ) else {
+                    ui.label("No transfer for this departure/arrival pair");
+                    return;
+                };
+
+                ui.label(format!(
+                    "Depart day {:.1}, arrive day {:.1} ({:.1} days)",
+                    departure / 86400.0,
+                    arrival / 86400.0,
+                    transfer.time_of_flight / 86400.0
+                ));
+                ui.label(format!(
+                    "Departure Δv: {:.2} km/s, arrival Δv: {:.2} km/s",
+                    transfer.departure_delta_v.length() / 1000.0,
+                    transfer.arrival_delta_v.length() / 1000.0
+                ));
+                ui.small(format!(
+                    "Transfer orbit: a = {:.3e} m, e = {:.3}",
+                    transfer.semi_major_axis(),
+                    transfer.eccentricity()
+                ));
+
+                if ui.button("Spawn Transfer Body").clicked()
+                    && let Some(physics) = physics
+                {
+                    Self::spawn_transfer_body(&transfer, context, physics, bodies, pending_actions);
+                }
+            });
+        porkchop.show_window = open;
+    }
+
+    /// Sample the chosen bodies and sweep the porkchop grid on a worker thread
+    fn compute_porkchop(
+        porkchop: &mut PorkchopSettings,
+        hierarchy: &OrbitalHierarchy,
+        physics: &crate::physics::PhysicsSimulation,
+    ) {
+        porkchop.plot = None;
+        porkchop.texture = None;
+        porkchop.selected = None;
+
+        let Ok(bodies) = physics.get_bodies() else {
+            porkchop.status = "Physics state unavailable".to_string();
+            return;
+        };
+        if bodies.len() != hierarchy.len() {
+            porkchop.status = "Bodies changed, try again".to_string();
+            return;
+        }
+        let (departure, arrival) = (porkchop.departure_body, porkchop.arrival_body);
+        let central = hierarchy.parent(departure);
+        if departure == arrival || central.is_none() || central != hierarchy.parent(arrival) {
+            porkchop.status = "Both bodies must orbit the same body".to_string();
+            return;
+        }
+        let central = central.unwrap_or_default();
+
+        let relative = |index: usize| {
+            (
+                bodies[index].position - bodies[central].position,
+                bodies[index].velocity - bodies[central].velocity,
+            )
+        };
+        let context = PorkchopContext {
+            departure_body: departure,
+            arrival_body: arrival,
+            central_body: central,
+            mu: GRAVITATIONAL_CONSTANT * bodies[central].mass,
+            departure_state: relative(departure),
+            arrival_state: relative(arrival),
+            epoch: physics.get_simulation_time().unwrap_or(0.0),
+        };
+
+        let day = 86400.0;
+        let departure_window = (
+            porkchop.departure_days.0 * day,
+            porkchop.departure_days.1 * day,
+        );
+        let arrival_window = (porkchop.arrival_days.0 * day, porkchop.arrival_days.1 * day);
+        let central_name = bodies[central].name.clone();
+        porkchop.status.clear();
+        porkchop.task.start(move || {
+            let plot = PorkchopPlot::compute(
+                context.mu,
+                context.departure_state,
+                context.arrival_state,
+                departure_window,
+                arrival_window,
+                PORKCHOP_STEPS,
+            );
+            (plot, context, central_name)
+        });
+    }
+
+    /// Show a finished sweep, with its cheapest transfer selected
+    fn finish_porkchop(
+        ctx: &egui::Context,
+        porkchop: &mut PorkchopSettings,
+        plot: PorkchopPlot,
+        context: PorkchopContext,
+        central_name: &str,
+    ) {
+        let Some((column, row, _)) = plot.minimum() else {
+            porkchop.status = "No transfers found in these windows".to_string();
+            return;
+        };
+        porkchop.status = format!("Transfers around {}", central_name);
+        porkchop.selected = Some((column, row));
+        porkchop.texture = Some(ctx.load_texture(
+            "porkchop_plot",
+            Self::porkchop_image(&plot),
+            egui::TextureOptions::NEAREST,
+        ));
+        porkchop.plot = Some((plot, context));
+    }
+
+    /// Colour-map the delta-v grid, with contour lines every 10% above the minimum
+    fn porkchop_image(plot: &PorkchopPlot) -> egui::ColorImage {
+        let columns = plot.departure_times.len();
+        let rows = plot.arrival_times.len();
+        let minimum = plot.minimum().map_or(1.0, |(_, _, value)| value.max(1.0));
+
+        // Contour band index of a cell, None where no transfer exists
+        let level = |column: usize, row: usize| {
+            plot.get(column, row)
+                .map(|value| ((value / minimum - 1.0) / 0.1).floor() as i64)
+        };
+
+        let mut pixels = Vec::with_capacity(columns * rows);
+        for image_row in 0..rows {
+            let row = rows - 1 - image_row;
+            for column in 0..columns {
+                let Some(value) = plot.get(column, row) else {
+                    pixels.push(egui::Color32::from_gray(40));
+                    continue;
+                };
+
+                let here = level(column, row);
+                let on_contour = (column + 1 < columns && level(column + 1, row) != here)
+                    || (row + 1 < rows && level(column, row + 1) != here);
+                if on_contour && here.is_some_and(|band| band < 20) {
+                    pixels.push(egui::Color32::BLACK);
+                    continue;
+                }
+
+                // Blue (cheap) through green and yellow to red at three times the minimum
+                let t = ((value / minimum).ln() / 3.0_f64.ln()).clamp(0.0, 1.0) as f32;
+                let color = if t < 0.5 {
+                    egui::Color32::from_rgb(0, (510.0 * t) as u8, (255.0 * (1.0 - 2.0 * t)) as u8)
+                } else {
+                    egui::Color32::from_rgb(
+                        (510.0 * (t - 0.5)) as u8,
+                        (255.0 * (2.0 - 2.0 * t)) as u8,
+                        0,
+                    )
+                };
+                pixels.push(color);
+            }
+        }
+
+        egui::ColorImage::new([columns, rows], pixels)
+    }
+
+    /// Place a spacecraft on the transfer orbit at the current simulation time
+    fn spawn_transfer_body(
+        transfer: &Transfer,
+        context: &PorkchopContext,
+        physics: &crate::physics::PhysicsSimulation,
+        bodies: &[crate::math::Body],
+        pending_actions: &mut Vec<UiAction>,
+    ) {
+        let Ok(inertial) = physics.get_bodies() else {
+            return;
+        };
+        let Some(central) = inertial.get(context.central_body) else {
+            return;
+        };
+        let now = physics.get_simulation_time().unwrap_or(context.epoch) - context.epoch;
+        let (position, velocity) = transfer.state_at(now);
+
+        let name_of = |index: usize| bodies.get(index).map_or("?", |body| body.name.as_str());
+        let body = Body::new_with_properties(
+            1.0e3,
+            central.position + position,
+            central.velocity + velocity,
+            format!(
+                "{} -> {} transfer",
+                name_of(context.departure_body),
+                name_of(context.arrival_body)
+            ),
+            crate::scenario::BodyType::Spacecraft {
+                radius: 10.0,
+                maneuvers: Vec::new(),
+            },
+            [0.4, 0.9, 1.0, 1.0],
+            (0.0, 0.0, 0.0, 0.0),
+        );
+        pending_actions.push(UiAction::SpawnBody(Box::new(body)));
+    }
+
+    /// Add, edit and delete the maneuver nodes of one spacecraft
+    fn render_maneuver_planner(
+        ctx: &egui::Context,
+        index: usize,
+        maneuvers: &[Maneuver],
+        bodies: &[crate::math::Body],
+        hierarchy: &OrbitalHierarchy,
+        simulation_time: f64,
+        pending_actions: &mut Vec<UiAction>,
+    ) {
+        let mut edited = maneuvers.to_vec();
+        let mut changed = false;
+
+        egui::Window::new("Maneuver Planner")
+            .default_pos([580.0, 260.0])
+            .default_size([320.0, 260.0])
+            .show(ctx, |ui| {
+                ui.label(format!("Craft: {}", bodies[index].name));
+                ui.label(format!("Simulation time: {:.1} s", simulation_time));
+                ui.separator();
+
+                let mut delete = None;
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        for (node, maneuver) in edited.iter_mut().enumerate() {
+                            ui.push_id(node, |ui| {
+                                ui.horizontal(|ui| {
+                                    ui.strong(format!("Node {}", node + 1));
+                                    if maneuver.executed {
+                                        ui.label("(executed)");
+                                    }
+                                    if ui.small_button("Delete").clicked() {
+                                        delete = Some(node);
+                                    }
+                                });
+
+                                // Burns that already happened can only be deleted
+                                ui.add_enabled_ui(!maneuver.executed, |ui| {
+                                    ui.horizontal(|ui| {
+                                        ui.label("Time:");
+                                        changed |= ui
+                                            .add(
+                                                // Earlier than now would fire at once
+                                                egui::DragValue::new(&mut maneuver.time)
+                                                    .range(simulation_time..=f64::MAX)
+                                                    .clamp_existing_to_range(false)
+                                                    .speed(10.0)
+                                                    .suffix(" s"),
+                                            )
+                                            .changed();
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Relative to:");
+                                        egui::ComboBox::from_id_salt("maneuver_reference")
+                                            .selected_text(&maneuver.reference_body)
+                                            .show_ui(ui, |ui| {
+                                                for (candidate, body) in bodies.iter().enumerate() {
+                                                    if candidate != index {
+                                                        changed |= ui
+                                                            .selectable_value(
+                                                                &mut maneuver.reference_body,
+                                                                body.name.clone(),
+                                                                &body.name,
+                                                            )
+                                                            .changed();
+                                                    }
+                                                }
+                                            });
+                                    });
+                                    for (label, value) in [
+                                        ("Prograde:", &mut maneuver.prograde),
+                                        ("Normal:", &mut maneuver.normal),
+                                        ("Radial:", &mut maneuver.radial),
+                                    ] {
+                                        ui.horizontal(|ui| {
+                                            ui.label(label);
+                                            changed |= ui
+                                                .add(
+                                                    egui::DragValue::new(value)
+                                                        .speed(0.1)
+                                                        .suffix(" m/s"),
+                                                )
+                                                .changed();
+                                        });
+                                    }
+                                });
+                                ui.separator();
+                            });
+                        }
+                    });
+
+                if let Some(node) = delete {
+                    edited.remove(node);
+                    changed = true;
+                }
+
+                if ui.button("Add Node").clicked() {
+                    // Default to burning against whatever the craft orbits, ten minutes from now
+                    let reference = hierarchy
+                        .parent(index)
+                        .or_else(|| (0..bodies.len()).find(|&other| other != index))
+                        .map(|other| bodies[other].name.clone())
+                        .unwrap_or_default();
+                    edited.push(Maneuver::new(
+                        simulation_time + 600.0,
+                        reference,
+                        0.0,
+                        0.0,
+                        0.0,
+                    ));
+                    changed = true;
+                }
+            });
+
+        if changed {
+            pending_actions.push(UiAction::SetManeuvers {
+                body_index: index,
+                maneuvers: edited,
+            });
+        }
+    }
+
+    /// Frame selector: inertial, barycentric, body-centred or co-rotating with a pair
+    fn render_reference_frame_controls(
+        ui: &mut egui::Ui,
+        reference_frame: &mut ReferenceFrame,
+        bodies: &[crate::math::Body],
+        hierarchy: &OrbitalHierarchy,
+    ) {
+        // Sensible defaults when switching kind: the selected pair is a body and its parent
+        let default_body = hierarchy.roots().first().copied().unwrap_or(0);
+        let default_secondary = (0..bodies.len())
+            .find(|&index| hierarchy.parent(index).is_some())
+            .unwrap_or(0);
+        let default_primary = hierarchy.parent(default_secondary).unwrap_or(default_body);
+
+        egui::ComboBox::from_id_salt("reference_frame_kind")
+            .selected_text(reference_frame.label())
+            .show_ui(ui, |ui| {
+                let options = [
+                    ReferenceFrame::Inertial,
+                    ReferenceFrame::Barycentric,
+                    ReferenceFrame::BodyCentred { body: default_body },
+                    ReferenceFrame::CoRotating {
+                        primary: default_primary,
+                        secondary: default_secondary,
+                    },
+                ];
+                for option in options {
+                    let is_current =
+                        std::mem::discriminant(reference_frame) == std::mem::discriminant(&option);
+                    if ui.selectable_label(is_current, option.label()).clicked() && !is_current {
+                        *reference_frame = option;
+                    }
+                }
+            });
+
+        match reference_frame {
+            ReferenceFrame::BodyCentred { body } => {
+                Self::body_picker(ui, "reference_frame_body", "Centre:", body, bodies);
+            }
+            ReferenceFrame::CoRotating { primary, secondary } => {
+                Self::body_picker(ui, "reference_frame_primary", "Primary:", primary, bodies);
+                Self::body_picker(
+                    ui,
+                    "reference_frame_secondary",
+                    "Secondary:",
+                    secondary,
+                    bodies,
+                );
+            }
+            ReferenceFrame::Inertial | ReferenceFrame::Barycentric => {}
+        }
+    }
+
+    /// Labelled combo box choosing one body by index
+    fn body_picker(
+        ui: &mut egui::Ui,
+        id: &str,
+        label: &str,
+        index: &mut usize,
+        bodies: &[crate::math::Body],
+    ) {
+        let selected_text = bodies
+            .get(*index)
+            .map_or_else(|| format!("#{}", index), |body| body.name.clone());
+        ui.horizontal(|ui| {
+            ui.label(label);
+            egui::ComboBox::from_id_salt(id)
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    for (candidate, body) in bodies.iter().enumerate() {
+                        ui.selectable_value(index, candidate, &body.name);
+                    }
+                });
+        });
+    }
+
+    /// Draw L1-L5 labels over the scene at the markers' projected screen positions
+    fn paint_lagrange_labels(ctx: &egui::Context, positions: &[DVec3], view_projection: DMat4) {
+        let screen = ctx.content_rect();
+        let painter = ctx.layer_painter(egui::LayerId::background());
+        let color = egui::Color32::from_rgb(230, 220, 90);
+
+        for (position, name) in positions.iter().zip(LAGRANGE_POINT_NAMES) {
+            let clip = view_projection * DVec4::new(position.x, position.y, position.z, 1.0);
+            if clip.w <= 0.0 {
+                continue; // Behind the camera
+            }
+            let ndc_x = clip.x / clip.w;
+            let ndc_y = clip.y / clip.w;
+            if ndc_x.abs() > 1.0 || ndc_y.abs() > 1.0 {
+                continue;
+            }
+
+            let screen_position = egui::pos2(
+                screen.min.x + (ndc_x as f32 + 1.0) * 0.5 * screen.width(),
+                screen.min.y + (1.0 - ndc_y as f32) * 0.5 * screen.height(),
+            );
+            painter.circle_stroke(screen_position, 4.0, egui::Stroke::new(1.5, color));
+            painter.text(
+                screen_position + egui::vec2(6.0, -6.0),
+                egui::Align2::LEFT_BOTTOM,
+                name,
+                egui::FontId::proportional(12.0),
+                color,
+            );
+        }
+    }
+
+    /// Show the outcome of reloading the scenario from disk.
+    /// The window opens when the reload failed or found problems, and closes once it is clean.
+    pub fn report_scenario_reload(
+        &mut self,
+        status: String,
+        diagnostics: Vec<String>,
+        failed: bool,
+    ) {
+        self.panels.scenario_reload.show_window = failed || !diagnostics.is_empty();
+        self.panels.scenario_reload.status = status;
+        self.panels.scenario_reload.diagnostics = diagnostics;
+    }
+
+    /// Body selected in the object list
+    pub fn selected_object(&self) -> Option<usize> {
+        self.selected_object_index
+    }
+
+    pub fn select_object(&mut self, index: Option<usize>) {
+        self.selected_object_index = index;
+    }
+
+    /// Show a viewing frame that was changed outside the UI
+    pub fn set_reference_frame(&mut self, frame: ReferenceFrame) {
+        self.reference_frame = frame;
+    }
+
     pub fn resize(&mut self, _new_size: PhysicalSize<u32>) -> AstrariaResult<()> {
         // egui handles resize automatically
         Ok(())
@@ -576,7 +3131,7 @@ impl UserInterface {
     /// Get orbital path rendering settings (like Java Options.drawOrbits)
     pub fn get_orbital_path_settings(&self) -> (bool, usize, f64) {
         (
-            self.show_orbital_paths,
+            self.panels.show_orbital_paths,
             self.orbital_path_history_length as usize,
             (self.orbital_path_segment_distance * 1000.0) as f64, // Convert km to meters
         )
@@ -584,6 +3139,6 @@ impl UserInterface {
 
     /// Check if orbital paths should be rendered (like Java Options.drawOrbits)
     pub fn should_show_orbital_paths(&self) -> bool {
-        self.show_orbital_paths
+        self.panels.show_orbital_paths
     }
 }